@@ -0,0 +1,22 @@
+use gray_tree::{expr::Expr, Result};
+use std::collections::HashMap;
+
+fn main() -> Result<()> {
+    let expr: Expr = "(1 + 2) * x ^ 2 - -y / 4".parse()?;
+    println!("{}", expr);
+    println!("{}", expr.root());
+
+    let mut vars = HashMap::new();
+    vars.insert("x".to_owned(), 3.0);
+    vars.insert("y".to_owned(), 8.0);
+    println!("= {}", expr.eval_with(&vars)?);
+
+    let expr = expr.substitute("x", &Expr::parse("y + 1")?);
+    println!("{}", expr);
+
+    if let Err(err) = Expr::parse("1 + * 2") {
+        println!("{}", err);
+    }
+
+    Ok(())
+}
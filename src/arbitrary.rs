@@ -0,0 +1,255 @@
+//! Trees are generated with a uniformly random shape from [`gen::uniform`]
+//! and shrink by removing subtrees with [`gen::shrink`]. The containers are
+//! filled from such a tree, so they shrink by dropping whole runs of
+//! entries.
+
+use crate::binary_tree::bst::BinarySearchTree;
+use crate::binary_tree::Node;
+use crate::gen;
+use crate::tree_map::TreeMap;
+use crate::tree_multiset::TreeMultiSet;
+use crate::tree_set::TreeSet;
+
+#[cfg(feature = "proptest")]
+pub use self::proptest_impls::{node, NodeStrategy, NodeValueTree};
+
+#[cfg(feature = "proptest")]
+mod proptest_impls {
+    use super::*;
+    use proptest::arbitrary::{any_with, Arbitrary};
+    use proptest::collection::SizeRange;
+    use proptest::option::{self, OptionStrategy};
+    use proptest::prelude::Rng;
+    use proptest::strategy::{Map, NewTree, Strategy, ValueTree};
+    use proptest::test_runner::TestRunner;
+    use std::fmt;
+    use std::iter::FromIterator;
+    use std::mem;
+
+    /// Create a strategy for trees of `size` nodes, at least one, holding
+    /// values of `element`.
+    pub fn node<S: Strategy>(element: S, size: impl Into<SizeRange>) -> NodeStrategy<S> {
+        NodeStrategy {
+            element,
+            size: size.into(),
+        }
+    }
+
+    /// Strategy for trees, see [`node`].
+    #[derive(Debug, Clone)]
+    pub struct NodeStrategy<S> {
+        element: S,
+        size: SizeRange,
+    }
+
+    impl<S: Strategy> Strategy for NodeStrategy<S>
+    where
+        S::Value: Clone,
+    {
+        type Tree = NodeValueTree<S::Value>;
+        type Value = Node<S::Value>;
+
+        fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+            let (min, max) = self.size.start_end_incl();
+            let len = (min.max(1)..=max.max(1)).new_tree(runner)?.current();
+            let shape =
+                gen::uniform(len, &mut || runner.rng().next_u64()).expect("the tree is not empty");
+            let mut data = Vec::with_capacity(len);
+            for _ in 0..len {
+                data.push(self.element.new_tree(runner)?.current());
+            }
+            let mut data = data.into_iter();
+            Ok(NodeValueTree {
+                current: shape.mid_order_map(|_| data.next().expect("one value per node")),
+                failing: None,
+                candidates: None,
+            })
+        }
+    }
+
+    /// Value tree of a [`NodeStrategy`], simplifying by removing subtrees.
+    #[derive(Debug, Clone)]
+    pub struct NodeValueTree<T> {
+        current: Node<T>,
+        /// The last tree known to fail, while `current` is one of its
+        /// candidates.
+        failing: Option<Node<T>>,
+        /// The candidates not tried yet, the next one last.
+        candidates: Option<Vec<Node<T>>>,
+    }
+
+    impl<T: Clone + fmt::Debug> ValueTree for NodeValueTree<T> {
+        type Value = Node<T>;
+
+        fn current(&self) -> Node<T> {
+            self.current.clone()
+        }
+
+        fn simplify(&mut self) -> bool {
+            // The current tree fails too, so move on to its candidates.
+            if self.failing.take().is_some() || self.candidates.is_none() {
+                let mut candidates: Vec<_> = gen::shrink(&self.current).collect();
+                candidates.reverse();
+                self.candidates = Some(candidates);
+            }
+            match self.candidates.as_mut().and_then(Vec::pop) {
+                Some(candidate) => {
+                    self.failing = Some(mem::replace(&mut self.current, candidate));
+                    true
+                }
+                None => false,
+            }
+        }
+
+        fn complicate(&mut self) -> bool {
+            match self.failing.take() {
+                Some(failing) => {
+                    self.current = failing;
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    impl<T: Arbitrary + Clone> Arbitrary for Node<T> {
+        type Parameters = (SizeRange, T::Parameters);
+        type Strategy = NodeStrategy<T::Strategy>;
+
+        fn arbitrary_with((size, element): Self::Parameters) -> Self::Strategy {
+            node(any_with::<T>(element), size)
+        }
+    }
+
+    /// Strategy for a container filled from a tree, empty once in ten.
+    type Filled<T, C> =
+        Map<OptionStrategy<NodeStrategy<<T as Arbitrary>::Strategy>>, fn(Option<Node<T>>) -> C>;
+
+    fn filled<T, C>((size, element): (SizeRange, T::Parameters)) -> Filled<T, C>
+    where
+        T: Arbitrary + Clone,
+        C: FromIterator<T> + fmt::Debug,
+    {
+        let collect: fn(Option<Node<T>>) -> C = |node| node.into_iter().flatten().collect();
+        option::weighted(0.9, node(any_with::<T>(element), size)).prop_map(collect)
+    }
+
+    impl<T: Arbitrary + Clone + Ord> Arbitrary for BinarySearchTree<T> {
+        type Parameters = (SizeRange, T::Parameters);
+        type Strategy = Filled<T, Self>;
+
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+            filled(args)
+        }
+    }
+
+    impl<T: Arbitrary + Clone + Ord> Arbitrary for TreeSet<T> {
+        type Parameters = (SizeRange, T::Parameters);
+        type Strategy = Filled<T, Self>;
+
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+            filled(args)
+        }
+    }
+
+    impl<T: Arbitrary + Clone + Ord> Arbitrary for TreeMultiSet<T> {
+        type Parameters = (SizeRange, T::Parameters);
+        type Strategy = Filled<T, Self>;
+
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+            filled(args)
+        }
+    }
+
+    impl<K, V> Arbitrary for TreeMap<K, V>
+    where
+        K: Arbitrary + Clone + Ord,
+        V: Arbitrary + Clone,
+    {
+        type Parameters = (SizeRange, <(K, V) as Arbitrary>::Parameters);
+        type Strategy = Filled<(K, V), Self>;
+
+        fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+            filled(args)
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impls {
+    use super::*;
+    use quickcheck::{empty_shrinker, Arbitrary, Gen};
+    use std::iter::{self, FromIterator};
+
+    impl<T: Arbitrary> Arbitrary for Node<T> {
+        /// Generate a tree of at most `g.size()` nodes, at least one.
+        fn arbitrary(g: &mut Gen) -> Self {
+            let len = 1 + usize::arbitrary(g) % g.size().max(1);
+            let shape =
+                gen::uniform(len, &mut || u64::arbitrary(g)).expect("the tree is not empty");
+            shape.mid_order_map(|_| T::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(gen::shrink(self).collect::<Vec<_>>().into_iter())
+        }
+    }
+
+    /// Get the containers filled from the candidates of shrinking `node`,
+    /// the empty one first.
+    fn shrink_filled<T, C>(node: Option<Node<T>>) -> Box<dyn Iterator<Item = C>>
+    where
+        T: Clone,
+        C: FromIterator<T> + 'static,
+    {
+        let node = match node {
+            Some(node) => node,
+            None => return empty_shrinker(),
+        };
+        let candidates: Vec<C> = iter::once(C::from_iter(None))
+            .chain(gen::shrink(&node).map(|node| node.into_iter().collect()))
+            .collect();
+        Box::new(candidates.into_iter())
+    }
+
+    impl<T: Arbitrary + Ord> Arbitrary for BinarySearchTree<T> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Vec::<T>::arbitrary(g).into_iter().collect()
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_filled(self.root().cloned())
+        }
+    }
+
+    impl<T: Arbitrary + Ord> Arbitrary for TreeSet<T> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Vec::<T>::arbitrary(g).into_iter().collect()
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_filled(Node::from_sorted_vec(self.iter().cloned().collect()))
+        }
+    }
+
+    impl<T: Arbitrary + Ord> Arbitrary for TreeMultiSet<T> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Vec::<T>::arbitrary(g).into_iter().collect()
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            shrink_filled(Node::from_sorted_vec(self.iter().cloned().collect()))
+        }
+    }
+
+    impl<K: Arbitrary + Ord, V: Arbitrary> Arbitrary for TreeMap<K, V> {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Vec::<(K, V)>::arbitrary(g).into_iter().collect()
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            let entries = self.iter().map(|(key, value)| (key.clone(), value.clone()));
+            shrink_filled(Node::from_sorted_vec(entries.collect()))
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::view;
+use super::Node;
+use std::fmt;
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct ArcInner<T> {
+    data: T,
+    left: Option<ArcNode<T>>,
+    right: Option<ArcNode<T>>,
+}
+
+/// Immutable binary tree node with structural sharing.
+///
+/// "Modifications" return new trees that share every unchanged subtree
+/// with the original, so snapshots are cheap and trees can be shared
+/// between threads. Cloning a node only bumps a reference count.
+pub struct ArcNode<T>(Arc<ArcInner<T>>);
+
+impl<T> Clone for ArcNode<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArcNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArcNode")
+            .field("data", &self.0.data)
+            .field("left", &self.0.left)
+            .field("right", &self.0.right)
+            .finish()
+    }
+}
+
+impl<T> ArcNode<T> {
+    /// Create a node with no links.
+    pub fn new(data: T) -> Self {
+        Self::with_children(data, None, None)
+    }
+
+    /// Create a node with the given children.
+    pub fn with_children(data: T, left: Option<ArcNode<T>>, right: Option<ArcNode<T>>) -> Self {
+        Self(Arc::new(ArcInner { data, left, right }))
+    }
+
+    /// Get the ref of the containing data.
+    pub fn data(&self) -> &T {
+        &self.0.data
+    }
+
+    /// Get the ref of left child.
+    pub fn left(&self) -> Option<&ArcNode<T>> {
+        self.0.left.as_ref()
+    }
+
+    /// Get the ref of right child.
+    pub fn right(&self) -> Option<&ArcNode<T>> {
+        self.0.right.as_ref()
+    }
+
+    /// Return `true` if both nodes are the same allocation.
+    pub fn ptr_eq(&self, other: &ArcNode<T>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Return a copy of this node with the left child replaced,
+    /// sharing the right subtree.
+    pub fn with_left(&self, left: Option<ArcNode<T>>) -> Self
+    where
+        T: Clone,
+    {
+        Self::with_children(self.0.data.clone(), left, self.0.right.clone())
+    }
+
+    /// Return a copy of this node with the right child replaced,
+    /// sharing the left subtree.
+    pub fn with_right(&self, right: Option<ArcNode<T>>) -> Self
+    where
+        T: Clone,
+    {
+        Self::with_children(self.0.data.clone(), self.0.left.clone(), right)
+    }
+
+    /// Return a copy of this node with the data replaced,
+    /// sharing both subtrees.
+    pub fn with_data(&self, data: T) -> Self {
+        Self::with_children(data, self.0.left.clone(), self.0.right.clone())
+    }
+
+    /// Get the number of nodes in the tree.
+    pub fn len(&self) -> usize {
+        view::size(self)
+    }
+
+    /// Return `false`; a tree always holds its root.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Pre order map into a new, unshared tree.
+    pub fn map<U, F>(&self, f: F) -> ArcNode<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        view::rebuild(self, f, ArcNode::with_children)
+    }
+
+    /// Copy the tree into a boxed tree.
+    pub fn to_node(&self) -> Node<T>
+    where
+        T: Clone,
+    {
+        view::map(self, T::clone)
+    }
+
+    /// Convert into a boxed tree, moving the data out of nodes that are
+    /// not shared and cloning the rest.
+    pub fn into_node(self) -> Node<T>
+    where
+        T: Clone,
+    {
+        let mut order = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            let inner = Arc::try_unwrap(node.0).unwrap_or_else(|shared| ArcInner {
+                data: shared.data.clone(),
+                left: shared.left.clone(),
+                right: shared.right.clone(),
+            });
+            let (has_left, has_right) = (inner.left.is_some(), inner.right.is_some());
+            stack.extend(inner.right);
+            stack.extend(inner.left);
+            order.push((inner.data, has_left, has_right));
+        }
+        let mut built: Vec<Node<T>> = Vec::new();
+        for (data, has_left, has_right) in order.into_iter().rev() {
+            let left = if has_left { built.pop() } else { None };
+            let right = if has_right { built.pop() } else { None };
+            built.push(Node {
+                data,
+                left: left.map(Node::boxed),
+                right: right.map(Node::boxed),
+            });
+        }
+        built.pop().expect("the root is built last")
+    }
+}
+
+/// A node waiting to be flattened, with its parent index and side.
+type Pending<'a, T> = (&'a ArcNode<T>, Option<(usize, bool)>);
+
+/// Traversal order of the copy-on-write maps.
+#[derive(Clone, Copy)]
+enum Order {
+    Pre,
+    Mid,
+    Post,
+}
+
+impl<T: Clone> ArcNode<T> {
+    /// Pre order copy-on-write map.
+    ///
+    /// `f` returns the new data of a node, or `None` to keep it. Only the
+    /// changed nodes and their ancestors are copied; every untouched
+    /// subtree stays shared with `self`.
+    pub fn pre_order_map_cow<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        self.map_cow(Order::Pre, f)
+    }
+
+    /// Mid order copy-on-write map, see [`ArcNode::pre_order_map_cow`].
+    pub fn mid_order_map_cow<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        self.map_cow(Order::Mid, f)
+    }
+
+    /// Post order copy-on-write map, see [`ArcNode::pre_order_map_cow`].
+    pub fn post_order_map_cow<F>(&self, f: F) -> Self
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        self.map_cow(Order::Post, f)
+    }
+
+    fn map_cow<F>(&self, order: Order, mut f: F) -> Self
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        // Flatten in pre order with child indices.
+        let mut nodes: Vec<(&ArcNode<T>, Option<usize>, Option<usize>)> = Vec::new();
+        let mut stack: Vec<Pending<'_, T>> = vec![(self, None)];
+        while let Some((node, link)) = stack.pop() {
+            let idx = nodes.len();
+            if let Some((parent, is_right)) = link {
+                let entry = &mut nodes[parent];
+                if is_right {
+                    entry.2 = Some(idx);
+                } else {
+                    entry.1 = Some(idx);
+                }
+            }
+            nodes.push((node, None, None));
+            stack.extend(node.right().map(|right| (right, Some((idx, true)))));
+            stack.extend(node.left().map(|left| (left, Some((idx, false)))));
+        }
+
+        let mut updates: Vec<Option<T>> = nodes.iter().map(|_| None).collect();
+        match order {
+            Order::Pre => {
+                for (idx, (node, _, _)) in nodes.iter().enumerate() {
+                    updates[idx] = f(node.data());
+                }
+            }
+            Order::Mid => {
+                let mut stack = Vec::new();
+                let mut next = Some(0);
+                loop {
+                    while let Some(idx) = next {
+                        stack.push(idx);
+                        next = nodes[idx].1;
+                    }
+                    match stack.pop() {
+                        Some(idx) => {
+                            updates[idx] = f(nodes[idx].0.data());
+                            next = nodes[idx].2;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            Order::Post => {
+                let mut stack = vec![(0, false)];
+                while let Some((idx, expanded)) = stack.pop() {
+                    if expanded {
+                        updates[idx] = f(nodes[idx].0.data());
+                    } else {
+                        stack.push((idx, true));
+                        stack.extend(nodes[idx].2.map(|right| (right, false)));
+                        stack.extend(nodes[idx].1.map(|left| (left, false)));
+                    }
+                }
+            }
+        }
+
+        // Rebuild children first, copying only along changed paths.
+        let mut built: Vec<Option<(ArcNode<T>, bool)>> = nodes.iter().map(|_| None).collect();
+        for idx in (0..nodes.len()).rev() {
+            let (node, left, right) = nodes[idx];
+            let mut take = |child: Option<usize>| {
+                child.map(|child| built[child].take().expect("children are built first"))
+            };
+            let left = take(left);
+            let right = take(right);
+            let update = updates[idx].take();
+            let changed = update.is_some()
+                || left.as_ref().is_some_and(|(_, changed)| *changed)
+                || right.as_ref().is_some_and(|(_, changed)| *changed);
+            let rebuilt = if changed {
+                let data = update.unwrap_or_else(|| node.data().clone());
+                ArcNode::with_children(data, left.map(|(n, _)| n), right.map(|(n, _)| n))
+            } else {
+                node.clone()
+            };
+            built[idx] = Some((rebuilt, changed));
+        }
+        built[0].take().expect("the root is built last").0
+    }
+}
+
+impl<T> From<Node<T>> for ArcNode<T> {
+    fn from(node: Node<T>) -> Self {
+        // Flatten in pre order, then build children first.
+        let mut order = Vec::new();
+        let mut stack = vec![node];
+        while let Some(mut node) = stack.pop() {
+            let left = node.left.take();
+            let right = node.right.take();
+            order.push((node.into_data(), left.is_some(), right.is_some()));
+            stack.extend(right.map(|node| *node));
+            stack.extend(left.map(|node| *node));
+        }
+        let mut built: Vec<ArcNode<T>> = Vec::new();
+        for (data, has_left, has_right) in order.into_iter().rev() {
+            let left = if has_left { built.pop() } else { None };
+            let right = if has_right { built.pop() } else { None };
+            built.push(ArcNode::with_children(data, left, right));
+        }
+        built.pop().expect("the root is built last")
+    }
+}
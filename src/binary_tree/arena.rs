@@ -0,0 +1,367 @@
+use super::view;
+use super::Node;
+use crate::datasize::DataSize;
+use crate::{Error, Result};
+
+/// Handle of a node in an [`Arena`].
+///
+/// Handles carry a generation, so a handle to a removed node never refers
+/// to a node later stored in the same slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId {
+    index: usize,
+    generation: u32,
+}
+
+#[derive(Debug, Clone)]
+struct ArenaNode<T> {
+    data: T,
+    parent: Option<NodeId>,
+    left: Option<NodeId>,
+    right: Option<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+struct Slot<T> {
+    generation: u32,
+    node: Option<ArenaNode<T>>,
+}
+
+/// Arena of binary tree nodes with parent links.
+///
+/// Nodes live in a `Vec` and refer to each other by [`NodeId`]; removed
+/// slots are reused through a free list.
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Create an empty arena.
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Create an empty arena with room for `capacity` nodes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Get the number of live nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no live nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create a detached node, returning its handle.
+    pub fn insert(&mut self, data: T) -> NodeId {
+        let node = ArenaNode {
+            data,
+            parent: None,
+            left: None,
+            right: None,
+        };
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index];
+                slot.node = Some(node);
+                NodeId {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                NodeId {
+                    index: self.slots.len() - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    /// Return `true` if `id` refers to a live node.
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_ok()
+    }
+
+    /// Get the ref of the data of a node.
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).ok().map(|node| &node.data)
+    }
+
+    /// Get the mutable ref of the data of a node.
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.node_mut(id).ok().map(|node| &mut node.data)
+    }
+
+    /// Get the parent of a node.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.parent
+    }
+
+    /// Get the left child of a node.
+    pub fn left(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.left
+    }
+
+    /// Get the right child of a node.
+    pub fn right(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.right
+    }
+
+    /// Get the root of the tree containing a node.
+    pub fn root(&self, mut id: NodeId) -> NodeId {
+        while let Some(parent) = self.parent(id) {
+            id = parent;
+        }
+        id
+    }
+
+    /// Replace the left child of `parent`, detaching `child` from its
+    /// current parent first. Return the old left child, now detached.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `child` is `parent` or one of its ancestors.
+    pub fn set_left(&mut self, parent: NodeId, child: Option<NodeId>) -> Result<Option<NodeId>> {
+        self.set_child(parent, child, false)
+    }
+
+    /// Replace the right child of `parent`, detaching `child` from its
+    /// current parent first. Return the old right child, now detached.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `child` is `parent` or one of its ancestors.
+    pub fn set_right(&mut self, parent: NodeId, child: Option<NodeId>) -> Result<Option<NodeId>> {
+        self.set_child(parent, child, true)
+    }
+
+    /// Unlink a node, with its subtree, from its parent.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn detach(&mut self, id: NodeId) -> Result<()> {
+        if let Some(parent) = self.node_mut(id)?.parent.take() {
+            let parent = self.node_mut(parent)?;
+            if parent.left == Some(id) {
+                parent.left = None;
+            } else {
+                parent.right = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove a single node in `O(1)`, returning its data.
+    /// Its children are left as detached roots.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn remove(&mut self, id: NodeId) -> Result<T> {
+        self.detach(id)?;
+        let node = self.release(id);
+        for child in node.left.into_iter().chain(node.right) {
+            self.node_mut(child)?.parent = None;
+        }
+        Ok(node.data)
+    }
+
+    /// Remove a node with its whole subtree, returning the data
+    /// in pre order.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn remove_subtree(&mut self, id: NodeId) -> Result<Vec<T>> {
+        self.detach(id)?;
+        let mut data = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let node = self.release(current);
+            stack.extend(node.right);
+            stack.extend(node.left);
+            data.push(node.data);
+        }
+        Ok(data)
+    }
+
+    /// Move a boxed tree into the arena, returning the handle of its root.
+    pub fn insert_tree(&mut self, node: Node<T>) -> NodeId {
+        let mut stack = vec![(None, false, node)];
+        let mut root = None;
+        while let Some((parent, is_right, mut node)) = stack.pop() {
+            let left = node.left.take();
+            let right = node.right.take();
+            let id = self.insert(node.into_data());
+            match parent {
+                Some(parent) => {
+                    self.node_mut(id).expect("inserted nodes are live").parent = Some(parent);
+                    let parent = self.node_mut(parent).expect("inserted nodes are live");
+                    if is_right {
+                        parent.right = Some(id);
+                    } else {
+                        parent.left = Some(id);
+                    }
+                }
+                None => root = Some(id),
+            }
+            stack.extend(right.map(|right| (Some(id), true, *right)));
+            stack.extend(left.map(|left| (Some(id), false, *left)));
+        }
+        root.expect("the root is inserted first")
+    }
+
+    /// Move the subtree of a node out of the arena as a boxed tree.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn take_tree(&mut self, id: NodeId) -> Result<Node<T>> {
+        self.detach(id)?;
+        // Release in pre order, then build children first.
+        let mut order = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            let node = self.release(current);
+            stack.extend(node.right);
+            stack.extend(node.left);
+            order.push(node);
+        }
+        // The roots of both subtrees of a node are on top of the stack,
+        // left above right, when the node itself is reached.
+        let mut built: Vec<Node<T>> = Vec::new();
+        for node in order.into_iter().rev() {
+            let left = node
+                .left
+                .map(|_| built.pop().expect("children are built first"));
+            let right = node
+                .right
+                .map(|_| built.pop().expect("children are built first"));
+            built.push(Node {
+                data: node.data,
+                left: left.map(Node::boxed),
+                right: right.map(Node::boxed),
+            });
+        }
+        Ok(built.pop().expect("the root is built last"))
+    }
+
+    /// Create a pre order iterator over the subtree of a node.
+    pub fn pre_order(&self, id: NodeId) -> PreOrder<'_, T> {
+        PreOrder {
+            inner: self.node(id).ok().map(|_| view::pre_order((self, id))),
+        }
+    }
+
+    fn node(&self, id: NodeId) -> Result<&ArenaNode<T>> {
+        self.slots
+            .get(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_ref())
+            .ok_or(Error::RemovedNode)
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut ArenaNode<T>> {
+        self.slots
+            .get_mut(id.index)
+            .filter(|slot| slot.generation == id.generation)
+            .and_then(|slot| slot.node.as_mut())
+            .ok_or(Error::RemovedNode)
+    }
+
+    /// Free the slot of a live node.
+    fn release(&mut self, id: NodeId) -> ArenaNode<T> {
+        let slot = &mut self.slots[id.index];
+        let node = slot.node.take().expect("released nodes are live");
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(id.index);
+        self.len -= 1;
+        node
+    }
+
+    fn set_child(
+        &mut self,
+        parent: NodeId,
+        child: Option<NodeId>,
+        is_right: bool,
+    ) -> Result<Option<NodeId>> {
+        self.node(parent)?;
+        if let Some(child) = child {
+            self.node(child)?;
+            if parent == child || self.is_ancestor(child, parent) {
+                return Err(Error::WouldCycle);
+            }
+            self.detach(child)?;
+            self.node_mut(child)?.parent = Some(parent);
+        }
+        let node = self.node_mut(parent)?;
+        let slot = if is_right {
+            &mut node.right
+        } else {
+            &mut node.left
+        };
+        let old = std::mem::replace(slot, child);
+        if let Some(old) = old {
+            self.node_mut(old)?.parent = None;
+        }
+        Ok(old)
+    }
+
+    /// Return `true` if `ancestor` is on the path from `id` to its root.
+    fn is_ancestor(&self, ancestor: NodeId, mut id: NodeId) -> bool {
+        while let Some(parent) = self.parent(id) {
+            if parent == ancestor {
+                return true;
+            }
+            id = parent;
+        }
+        false
+    }
+}
+
+impl<T: DataSize> DataSize for Arena<T> {
+    /// Count the slots and the free list at their capacity, and the heap
+    /// data of the live nodes.
+    fn heap_size(&self) -> usize {
+        self.slots.capacity() * std::mem::size_of::<Slot<T>>()
+            + self.free.heap_size()
+            + self
+                .slots
+                .iter()
+                .filter_map(|slot| slot.node.as_ref())
+                .map(|node| node.data.heap_size())
+                .sum::<usize>()
+    }
+}
+
+/// Pre order iterator over the handles of a subtree.
+#[derive(Debug)]
+pub struct PreOrder<'a, T> {
+    inner: Option<view::PreOrder<(&'a Arena<T>, NodeId)>>,
+}
+
+impl<'a, T> Iterator for PreOrder<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.as_mut()?.next().map(|(_, id)| id)
+    }
+}
@@ -0,0 +1,541 @@
+use super::Node;
+use crate::monoid::Measure;
+use crate::{Error, Result};
+use std::cmp::Ordering;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Add, Range};
+use std::ptr;
+
+/// An aggregate of a subtree, computed from the data of its root and the
+/// aggregates of its children.
+pub trait Augment<T>: Sized {
+    /// Compute the aggregate of a node from its data and the aggregates of
+    /// its children.
+    fn augment(data: &T, left: Option<&Self>, right: Option<&Self>) -> Self;
+}
+
+/// Number of nodes in a subtree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(pub usize);
+
+impl<T> Augment<T> for Size {
+    fn augment(_data: &T, left: Option<&Self>, right: Option<&Self>) -> Self {
+        Size(1 + left.map_or(0, |left| left.0) + right.map_or(0, |right| right.0))
+    }
+}
+
+/// Height of a subtree, a leaf being `1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Height(pub usize);
+
+impl<T> Augment<T> for Height {
+    fn augment(_data: &T, left: Option<&Self>, right: Option<&Self>) -> Self {
+        Height(
+            1 + left
+                .map_or(0, |left| left.0)
+                .max(right.map_or(0, |right| right.0)),
+        )
+    }
+}
+
+/// Sum of the data in a subtree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sum<T>(pub T);
+
+impl<T: Clone + Add<Output = T>> Augment<T> for Sum<T> {
+    fn augment(data: &T, left: Option<&Self>, right: Option<&Self>) -> Self {
+        let mut sum = data.clone();
+        if let Some(left) = left {
+            sum = left.0.clone() + sum;
+        }
+        if let Some(right) = right {
+            sum = sum + right.0.clone();
+        }
+        Sum(sum)
+    }
+}
+
+/// Smallest and largest data in a subtree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MinMax<T> {
+    /// The smallest data.
+    pub min: T,
+    /// The largest data.
+    pub max: T,
+}
+
+impl<T: Ord + Clone> Augment<T> for MinMax<T> {
+    fn augment(data: &T, left: Option<&Self>, right: Option<&Self>) -> Self {
+        let mut min_max = MinMax {
+            min: data.clone(),
+            max: data.clone(),
+        };
+        for child in left.into_iter().chain(right) {
+            if child.min < min_max.min {
+                min_max.min = child.min.clone();
+            }
+            if child.max > min_max.max {
+                min_max.max = child.max.clone();
+            }
+        }
+        min_max
+    }
+}
+
+/// Largest end of the intervals in a subtree, which lets an interval tree
+/// skip subtrees ending before a query point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaxEnd<T>(pub T);
+
+impl<T: Ord + Clone> Augment<Range<T>> for MaxEnd<T> {
+    fn augment(data: &Range<T>, left: Option<&Self>, right: Option<&Self>) -> Self {
+        let mut end = &data.end;
+        for child in left.into_iter().chain(right) {
+            if child.0.cmp(end) == Ordering::Greater {
+                end = &child.0;
+            }
+        }
+        MaxEnd(end.clone())
+    }
+}
+
+/// Any [`Measure`] folded over a subtree in mid order, e.g. a user-defined
+/// [`Monoid`](crate::monoid::Monoid) whose combination does not commute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Folded<M>(pub M);
+
+impl<T, M: Measure<T>> Augment<T> for Folded<M> {
+    fn augment(data: &T, left: Option<&Self>, right: Option<&Self>) -> Self {
+        let mut acc = M::measure(data);
+        if let Some(left) = left {
+            acc = left.0.combine(&acc);
+        }
+        if let Some(right) = right {
+            acc = acc.combine(&right.0);
+        }
+        Folded(acc)
+    }
+}
+
+type Link<T, A> = Option<Box<AugmentedNode<T, A>>>;
+
+/// Binary tree node caching the aggregate `A` of its subtree.
+///
+/// The aggregates are refreshed by every mutating method, so they stay up
+/// to date as long as the tree is only changed through this API or a
+/// [`Cursor`].
+#[derive(Debug)]
+pub struct AugmentedNode<T, A> {
+    data: T,
+    aggregate: A,
+    left: Link<T, A>,
+    right: Link<T, A>,
+}
+
+impl<T, A> AugmentedNode<T, A> {
+    /// Get the ref of the containing data.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Get the ref of the aggregate of the subtree.
+    pub fn aggregate(&self) -> &A {
+        &self.aggregate
+    }
+
+    /// Get the ref of left child.
+    pub fn left(&self) -> Option<&Self> {
+        self.left.as_deref()
+    }
+
+    /// Get the ref of right child.
+    pub fn right(&self) -> Option<&Self> {
+        self.right.as_deref()
+    }
+
+    /// Convert into the containing data, dropping the children.
+    pub fn into_data(mut self) -> T {
+        drop(self.left.take());
+        drop(self.right.take());
+        let node = ManuallyDrop::new(self);
+        // SAFETY: `node` is never dropped and its links are already empty,
+        // so the data and the aggregate are moved out exactly once and
+        // nothing is leaked.
+        unsafe {
+            drop(ptr::read(&node.aggregate));
+            ptr::read(&node.data)
+        }
+    }
+
+    /// Convert into a plain [`Node`], dropping the aggregates, without
+    /// recursion.
+    pub fn into_node(self) -> Node<T> {
+        convert(
+            self,
+            |data, left: Option<Node<T>>, right: Option<Node<T>>| Node {
+                data,
+                left: left.map(Node::boxed),
+                right: right.map(Node::boxed),
+            },
+        )
+    }
+
+    /// Create a cursor focused on this node, to move around and mutate the
+    /// tree.
+    pub fn into_cursor(self) -> Cursor<T, A> {
+        Cursor {
+            focus: Box::new(self),
+            path: Vec::new(),
+        }
+    }
+}
+
+impl<T, A: Augment<T>> AugmentedNode<T, A> {
+    /// Create a node with no links.
+    pub fn new(data: T) -> Self {
+        Self::with_children(data, None, None)
+    }
+
+    /// Create a node with the given children.
+    pub fn with_children(data: T, left: Option<Self>, right: Option<Self>) -> Self {
+        let left = left.map(Box::new);
+        let right = right.map(Box::new);
+        Self {
+            aggregate: A::augment(
+                &data,
+                left.as_deref().map(Self::aggregate),
+                right.as_deref().map(Self::aggregate),
+            ),
+            data,
+            left,
+            right,
+        }
+    }
+
+    /// Recompute the aggregate from the data and the children.
+    fn update(&mut self) {
+        self.aggregate = A::augment(
+            &self.data,
+            self.left.as_deref().map(Self::aggregate),
+            self.right.as_deref().map(Self::aggregate),
+        );
+    }
+
+    /// Replace the containing data, returning the old one.
+    pub fn set_data(&mut self, data: T) -> T {
+        let old = mem::replace(&mut self.data, data);
+        self.update();
+        old
+    }
+
+    /// Modify the containing data with `f`.
+    pub fn modify<F: FnOnce(&mut T)>(&mut self, f: F) {
+        f(&mut self.data);
+        self.update();
+    }
+
+    /// Replace the left child, returning the old one.
+    pub fn set_left(&mut self, node: Option<Self>) -> Option<Self> {
+        let old = mem::replace(&mut self.left, node.map(Box::new));
+        self.update();
+        old.map(|node| *node)
+    }
+
+    /// Replace the right child, returning the old one.
+    pub fn set_right(&mut self, node: Option<Self>) -> Option<Self> {
+        let old = mem::replace(&mut self.right, node.map(Box::new));
+        self.update();
+        old.map(|node| *node)
+    }
+
+    /// Rotate the subtree left, making the right child its root.
+    /// # Errors
+    /// Return `MissingChild` Error when there is no right child.
+    pub fn rotate_left(&mut self) -> Result<()> {
+        let mut pivot = self.right.take().ok_or(Error::MissingChild)?;
+        mem::swap(self, &mut pivot);
+        pivot.right = self.left.take();
+        pivot.update();
+        self.left = Some(pivot);
+        self.update();
+        Ok(())
+    }
+
+    /// Rotate the subtree right, making the left child its root.
+    /// # Errors
+    /// Return `MissingChild` Error when there is no left child.
+    pub fn rotate_right(&mut self) -> Result<()> {
+        let mut pivot = self.left.take().ok_or(Error::MissingChild)?;
+        mem::swap(self, &mut pivot);
+        pivot.left = self.right.take();
+        pivot.update();
+        self.right = Some(pivot);
+        self.update();
+        Ok(())
+    }
+
+    /// Check that every cached aggregate equals the one computed afresh.
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first stale aggregate.
+    pub fn validate(&self) -> Result<()>
+    where
+        A: PartialEq,
+    {
+        let mut stack = vec![(Vec::new(), self)];
+        while let Some((path, node)) = stack.pop() {
+            let expected = A::augment(
+                &node.data,
+                node.left.as_deref().map(Self::aggregate),
+                node.right.as_deref().map(Self::aggregate),
+            );
+            if expected != node.aggregate {
+                return Err(Error::InvariantViolation {
+                    path,
+                    reason: "cached aggregate is stale".to_string(),
+                });
+            }
+            for (idx, child) in [&node.left, &node.right].iter().enumerate() {
+                if let Some(child) = child {
+                    let mut child_path = path.clone();
+                    child_path.push(idx);
+                    stack.push((child_path, child));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> AugmentedNode<T, Size> {
+    /// Get the `k`-th node in mid order, counting from 0, in `O(h)` by
+    /// descending along the cached subtree sizes. For a binary search tree
+    /// this is the `k`-th smallest value.
+    pub fn kth_smallest(&self, k: usize) -> Option<&T> {
+        let mut node = self;
+        let mut k = k;
+        if k >= node.aggregate.0 {
+            return None;
+        }
+        loop {
+            let left = node.left.as_deref().map_or(0, |left| left.aggregate.0);
+            match k.cmp(&left) {
+                Ordering::Less => node = node.left.as_deref().expect("`k` is in the left subtree"),
+                Ordering::Equal => return Some(&node.data),
+                Ordering::Greater => {
+                    k -= left + 1;
+                    node = node.right.as_deref().expect("`k` is in the right subtree");
+                }
+            }
+        }
+    }
+
+    /// Get the `k`-th node in reverse mid order, counting from 0, in
+    /// `O(h)`. For a binary search tree this is the `k`-th largest value.
+    pub fn kth_largest(&self, k: usize) -> Option<&T> {
+        let len = self.aggregate.0;
+        k.checked_add(1)
+            .and_then(|rank| len.checked_sub(rank))
+            .and_then(|k| self.kth_smallest(k))
+    }
+}
+
+impl<T, A: Augment<T>> From<Node<T>> for AugmentedNode<T, A> {
+    /// Compute the aggregates bottom-up, without recursion.
+    fn from(node: Node<T>) -> Self {
+        let mut stack = Vec::new();
+        let mut done = Vec::new();
+        stack.push(Frame::Enter(node));
+        while let Some(frame) = stack.pop() {
+            match frame {
+                Frame::Enter(mut node) => {
+                    let left = node.left.take();
+                    let right = node.right.take();
+                    stack.push(Frame::Exit(
+                        node.into_data(),
+                        left.is_some(),
+                        right.is_some(),
+                    ));
+                    stack.extend(right.map(|node| Frame::Enter(*node)));
+                    stack.extend(left.map(|node| Frame::Enter(*node)));
+                }
+                Frame::Exit(data, has_left, has_right) => {
+                    let right = if has_right { done.pop() } else { None };
+                    let left = if has_left { done.pop() } else { None };
+                    done.push(Self::with_children(data, left, right));
+                }
+            }
+        }
+        done.pop().expect("the root is converted last")
+    }
+}
+
+impl<T, A> Drop for AugmentedNode<T, A> {
+    /// Unlink the descendants into a worklist, so that dropping a
+    /// degenerate tree does not recurse once per level.
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Self>> = Vec::new();
+        stack.extend(self.left.take());
+        stack.extend(self.right.take());
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+/// A step of a post order conversion: a node still to take apart, or the
+/// data of one whose converted children, if present, are on top of the
+/// output stack.
+enum Frame<N, T> {
+    Enter(N),
+    Exit(T, bool, bool),
+}
+
+/// Convert an augmented tree in post order, without recursion.
+fn convert<T, A, U>(
+    node: AugmentedNode<T, A>,
+    mut build: impl FnMut(T, Option<U>, Option<U>) -> U,
+) -> U {
+    let mut stack = vec![Frame::Enter(Box::new(node))];
+    let mut done = Vec::new();
+    while let Some(frame) = stack.pop() {
+        match frame {
+            Frame::Enter(mut node) => {
+                let left = node.left.take();
+                let right = node.right.take();
+                stack.push(Frame::Exit(
+                    node.into_data(),
+                    left.is_some(),
+                    right.is_some(),
+                ));
+                stack.extend(right.map(Frame::Enter));
+                stack.extend(left.map(Frame::Enter));
+            }
+            Frame::Exit(data, has_left, has_right) => {
+                let right = if has_right { done.pop() } else { None };
+                let left = if has_left { done.pop() } else { None };
+                done.push(build(data, left, right));
+            }
+        }
+    }
+    done.pop().expect("the root is converted last")
+}
+
+/// Side of a parent on which the cursor went down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Left,
+    Right,
+}
+
+/// Cursor over an [`AugmentedNode`] tree, holding the tree apart along the
+/// path from the root to the focused node.
+///
+/// Mutations recompute the aggregate of the focused node at once, and
+/// those of its ancestors as the cursor goes back up, so each move takes
+/// `O(1)`.
+#[derive(Debug)]
+pub struct Cursor<T, A> {
+    focus: Box<AugmentedNode<T, A>>,
+    /// The ancestors of the focused node, each missing the child on the
+    /// recorded side.
+    path: Vec<(Side, Box<AugmentedNode<T, A>>)>,
+}
+
+impl<T, A> Cursor<T, A> {
+    /// Get the ref of the focused node.
+    pub fn node(&self) -> &AugmentedNode<T, A> {
+        &self.focus
+    }
+
+    /// Get the ref of the data of the focused node.
+    pub fn data(&self) -> &T {
+        &self.focus.data
+    }
+
+    /// Get the ref of the aggregate of the focused subtree.
+    pub fn aggregate(&self) -> &A {
+        &self.focus.aggregate
+    }
+
+    /// Get the depth of the focused node, the root being at `0`.
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Return `true` if the cursor is at the root.
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Move to the left child.
+    /// Return `false`, staying put, when there is none.
+    pub fn go_left(&mut self) -> bool {
+        self.go_down(Side::Left)
+    }
+
+    /// Move to the right child.
+    /// Return `false`, staying put, when there is none.
+    pub fn go_right(&mut self) -> bool {
+        self.go_down(Side::Right)
+    }
+
+    fn go_down(&mut self, side: Side) -> bool {
+        let child = match side {
+            Side::Left => self.focus.left.take(),
+            Side::Right => self.focus.right.take(),
+        };
+        match child {
+            Some(child) => {
+                let parent = mem::replace(&mut self.focus, child);
+                self.path.push((side, parent));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<T, A: Augment<T>> Cursor<T, A> {
+    /// Move to the parent, refreshing its aggregate.
+    /// Return `false`, staying put, at the root.
+    pub fn go_up(&mut self) -> bool {
+        let (side, parent) = match self.path.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+        let child = mem::replace(&mut self.focus, parent);
+        match side {
+            Side::Left => self.focus.left = Some(child),
+            Side::Right => self.focus.right = Some(child),
+        }
+        self.focus.update();
+        true
+    }
+
+    /// Replace the data of the focused node, returning the old one.
+    pub fn set_data(&mut self, data: T) -> T {
+        self.focus.set_data(data)
+    }
+
+    /// Modify the data of the focused node with `f`.
+    pub fn modify<F: FnOnce(&mut T)>(&mut self, f: F) {
+        self.focus.modify(f);
+    }
+
+    /// Replace the left child of the focused node, returning the old one.
+    pub fn set_left(&mut self, node: Option<AugmentedNode<T, A>>) -> Option<AugmentedNode<T, A>> {
+        self.focus.set_left(node)
+    }
+
+    /// Replace the right child of the focused node, returning the old one.
+    pub fn set_right(&mut self, node: Option<AugmentedNode<T, A>>) -> Option<AugmentedNode<T, A>> {
+        self.focus.set_right(node)
+    }
+
+    /// Go back up to the root and return the tree.
+    pub fn into_root(mut self) -> AugmentedNode<T, A> {
+        while self.go_up() {}
+        *self.focus
+    }
+}
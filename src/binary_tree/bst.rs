@@ -0,0 +1,624 @@
+use super::pool::{Global, NodeAlloc};
+use super::{construct, BoxedNode, Link, Node};
+use crate::{Error, Result};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+/// Binary search tree over [`Node`]s, without rebalancing.
+///
+/// Values are kept in strictly increasing mid order; inserting a value
+/// already present leaves the tree unchanged. Inserted and removed nodes
+/// go through the allocator `A`, see [`NodeAlloc`].
+#[derive(Debug, Clone)]
+pub struct BinarySearchTree<T, A = Global> {
+    root: Link<T>,
+    len: usize,
+    alloc: A,
+}
+
+impl<T, A: Default> Default for BinarySearchTree<T, A> {
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
+impl<T> BinarySearchTree<T> {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, A> BinarySearchTree<T, A> {
+    /// Create an empty tree allocating its nodes from `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            alloc,
+        }
+    }
+
+    /// Get the ref of the allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the underlying tree, e.g. for printing.
+    pub fn root(&self) -> Option<&Node<T>> {
+        self.root.as_deref()
+    }
+
+    /// Convert into the underlying tree.
+    pub fn into_node(self) -> Option<Node<T>> {
+        self.root.map(|node| *node)
+    }
+
+    /// Create an iterator over the values in increasing order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.root())
+    }
+
+    /// Rebuild the tree height-balanced in `O(n)`, e.g. after many
+    /// insertions in increasing order. See [`Node::rebalanced`].
+    pub fn rebalance(&mut self) {
+        self.root = self.root.take().map(|root| root.rebalanced().boxed());
+    }
+
+    /// Get the smallest value.
+    pub fn first(&self) -> Option<&T> {
+        let mut node = self.root()?;
+        while let Some(left) = node.left() {
+            node = left;
+        }
+        Some(node.data())
+    }
+
+    /// Get the largest value.
+    pub fn last(&self) -> Option<&T> {
+        let mut node = self.root()?;
+        while let Some(right) = node.right() {
+            node = right;
+        }
+        Some(node.data())
+    }
+
+    /// Get the `k`-th smallest value, counting from 0.
+    ///
+    /// The tree does not cache subtree sizes, so this walks in order from
+    /// the nearer end, in `O(h + min(k, n - k))`; an
+    /// [`AugmentedNode`](super::augment::AugmentedNode) with
+    /// [`Size`](super::augment::Size) answers in `O(h)`.
+    pub fn kth_smallest(&self, k: usize) -> Option<&T> {
+        if k >= self.len {
+            return None;
+        }
+        if k <= self.len / 2 {
+            self.walk(k, false)
+        } else {
+            self.walk(self.len - 1 - k, true)
+        }
+    }
+
+    /// Get the `k`-th largest value, counting from 0. See
+    /// [`BinarySearchTree::kth_smallest`].
+    pub fn kth_largest(&self, k: usize) -> Option<&T> {
+        let rank = self.len.checked_sub(k.checked_add(1)?)?;
+        self.kth_smallest(rank)
+    }
+
+    /// Get the `k`-th value in mid order, or in reverse mid order if
+    /// `reverse`, stopping there.
+    fn walk(&self, k: usize, reverse: bool) -> Option<&T> {
+        let mut stack = Vec::new();
+        let mut next = self.root();
+        let mut k = k;
+        loop {
+            while let Some(node) = next {
+                stack.push(node);
+                next = if reverse { node.right() } else { node.left() };
+            }
+            let node = stack.pop()?;
+            if k == 0 {
+                return Some(node.data());
+            }
+            k -= 1;
+            next = if reverse { node.left() } else { node.right() };
+        }
+    }
+}
+
+impl<T: Ord> BinarySearchTree<T> {
+    /// Build a height-balanced tree from strictly increasing values in
+    /// `O(n)`, cloning them. See the `TryFrom<Vec<T>>` implementation to
+    /// take ownership instead.
+    /// # Errors
+    /// Return `Unsorted` Error when the values are not strictly increasing.
+    pub fn from_sorted(values: &[T]) -> Result<Self>
+    where
+        T: Clone,
+    {
+        Self::from_sorted_in(values, Global)
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>>> BinarySearchTree<T, A> {
+    /// Build a height-balanced tree from strictly increasing values in
+    /// `O(n)`, cloning them and boxing the nodes through `alloc`.
+    /// # Errors
+    /// Return `Unsorted` Error when the values are not strictly increasing.
+    pub fn from_sorted_in(values: &[T], alloc: A) -> Result<Self>
+    where
+        T: Clone,
+    {
+        Self::from_sorted_vec_in(values.to_vec(), alloc)
+    }
+
+    fn from_sorted_vec_in(values: Vec<T>, mut alloc: A) -> Result<Self> {
+        if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(Error::Unsorted);
+        }
+        Ok(Self {
+            len: values.len(),
+            root: construct::balanced_in(values, &mut alloc),
+            alloc,
+        })
+    }
+
+    /// Merge two trees into a height-balanced one in `O(n + m)`, by
+    /// merging their values in order and bulk loading the result. A value
+    /// present in both is kept from `self`.
+    ///
+    /// The nodes of both trees go back to their allocators, and those of
+    /// the result come from the allocator of `self`.
+    pub fn merge(mut self, mut other: Self) -> Self {
+        let (mut left, mut right) = (
+            into_values(self.root.take(), self.len, &mut self.alloc)
+                .into_iter()
+                .peekable(),
+            into_values(other.root.take(), other.len, &mut other.alloc)
+                .into_iter()
+                .peekable(),
+        );
+        let mut values = Vec::with_capacity(left.len() + right.len());
+        loop {
+            let ord = match (left.peek(), right.peek()) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+            match ord {
+                Ordering::Less => values.extend(left.next()),
+                Ordering::Greater => values.extend(right.next()),
+                Ordering::Equal => {
+                    values.extend(left.next());
+                    right.next();
+                }
+            }
+        }
+        self.len = values.len();
+        self.root = construct::balanced_in(values, &mut self.alloc);
+        self
+    }
+
+    /// Split the tree into the values less than `key` and the others, in
+    /// `O(height)` plus the time to count the smaller part.
+    ///
+    /// The nodes are relinked along the search path of `key`, so neither
+    /// part is rebalanced and both are at most as high as `self`. The
+    /// second part gets a clone of the allocator.
+    pub fn split(self, key: &T) -> (Self, Self)
+    where
+        A: Clone,
+    {
+        let mut less: Link<T> = None;
+        let mut greater: Link<T> = None;
+        // The empty links where the next nodes of each part hang.
+        let mut less_hook = &mut less;
+        let mut greater_hook = &mut greater;
+        let mut link = self.root;
+        while let Some(mut node) = link {
+            if node.data < *key {
+                link = node.right.take();
+                less_hook = &mut less_hook.insert(node).right;
+            } else {
+                link = node.left.take();
+                greater_hook = &mut greater_hook.insert(node).left;
+            }
+        }
+        // Count both parts in step until the smaller one runs out.
+        let (mut small, mut large) = (Iter::new(less.as_deref()), Iter::new(greater.as_deref()));
+        let mut counted = 0;
+        let less_len = loop {
+            match (small.next(), large.next()) {
+                (Some(_), Some(_)) => counted += 1,
+                (None, _) => break counted,
+                (Some(_), None) => break self.len - counted,
+            }
+        };
+        (
+            Self {
+                root: less,
+                len: less_len,
+                alloc: self.alloc.clone(),
+            },
+            Self {
+                root: greater,
+                len: self.len - less_len,
+                alloc: self.alloc,
+            },
+        )
+    }
+}
+
+impl<T, A: NodeAlloc<Node<T>>> BinarySearchTree<T, A> {
+    /// Keep only the values for which `f` holds, calling it in increasing
+    /// order.
+    ///
+    /// The tree is pruned in place in a single traversal: a rejected node
+    /// is replaced by the join of its pruned subtrees, with the smallest
+    /// node of the right one on top, and its box goes back to the
+    /// allocator. This takes `O(n)` plus `O(height)` per rejected node with
+    /// two children, and the tree gets no higher.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        // Nodes on the path from the root, with the verdict of `f` once
+        // their left subtree is pruned.
+        let mut stack: Vec<(BoxedNode<T>, Option<bool>)> = Vec::new();
+        let mut link = self.root.take();
+        let mut removed = 0;
+        loop {
+            while let Some(mut node) = link {
+                link = node.left.take();
+                stack.push((node, None));
+            }
+            // The pruned subtree just finished.
+            let mut done: Link<T> = None;
+            loop {
+                match stack.last_mut() {
+                    None => {
+                        self.root = done;
+                        self.len -= removed;
+                        return;
+                    }
+                    Some((node, keep @ None)) => {
+                        node.left = done;
+                        *keep = Some(f(&node.data));
+                        link = node.right.take();
+                        break;
+                    }
+                    Some((_, Some(_))) => {
+                        let (mut node, keep) = stack.pop().expect("the stack is not empty");
+                        if keep == Some(true) {
+                            node.right = done;
+                            done = Some(node);
+                        } else {
+                            let left = node.left.take();
+                            self.alloc.free(node);
+                            removed += 1;
+                            done = join(left, done);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>>> BinarySearchTree<T, A> {
+    /// Return `true` if the tree holds `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        let mut link = self.root();
+        while let Some(node) = link {
+            link = match value.cmp(node.data()) {
+                Ordering::Less => node.left(),
+                Ordering::Greater => node.right(),
+                Ordering::Equal => return true,
+            };
+        }
+        false
+    }
+
+    /// Create an iterator over the values not less than `value`, in
+    /// increasing order, found in `O(height)`.
+    pub fn iter_from(&self, value: &T) -> Iter<'_, T> {
+        let mut stack = Vec::new();
+        let mut node = self.root();
+        while let Some(current) = node {
+            if current.data() < value {
+                node = current.right();
+            } else {
+                stack.push(current);
+                node = current.left();
+            }
+        }
+        Iter { stack }
+    }
+
+    /// Insert a value, in `O(height)`.
+    /// Return `false` if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        let link = find_link(&mut self.root, &value);
+        if link.is_some() {
+            return false;
+        }
+        *link = Some(self.alloc.alloc(Node::new(value)));
+        self.len += 1;
+        true
+    }
+
+    /// Remove a value, in `O(height)`, returning it if it was present.
+    pub fn remove(&mut self, value: &T) -> Option<T> {
+        let link = find_link(&mut self.root, value);
+        let mut node = link.take()?;
+        let value = match (node.left.take(), node.right.take()) {
+            (None, child) | (child, None) => {
+                *link = child;
+                self.alloc.free(node).into_data()
+            }
+            (Some(left), Some(right)) => {
+                // Replace the value with its successor.
+                let mut right = Some(right);
+                let successor =
+                    take_min(&mut right, &mut self.alloc).expect("the right subtree is not empty");
+                node.left = Some(left);
+                node.right = right;
+                let value = std::mem::replace(&mut node.data, successor);
+                *link = Some(node);
+                value
+            }
+        };
+        self.len -= 1;
+        Some(value)
+    }
+}
+
+/// Find the link holding `value`, or the empty link where it belongs.
+fn find_link<'a, T: Ord>(mut link: &'a mut Link<T>, value: &T) -> &'a mut Link<T> {
+    loop {
+        match link.as_ref().map(|node| value.cmp(&node.data)) {
+            Some(Ordering::Less) => link = &mut link.as_mut().expect("the link is not empty").left,
+            Some(Ordering::Greater) => {
+                link = &mut link.as_mut().expect("the link is not empty").right
+            }
+            _ => return link,
+        }
+    }
+}
+
+/// Remove the smallest value under `link`.
+fn take_min<T, A: NodeAlloc<Node<T>>>(link: &mut Link<T>, alloc: &mut A) -> Option<T> {
+    take_min_node(link).map(|node| alloc.free(node).into_data())
+}
+
+/// Unlink the node of the smallest value under `link`, without children.
+fn take_min_node<T>(mut link: &mut Link<T>) -> Option<BoxedNode<T>> {
+    while link.as_ref()?.left.is_some() {
+        link = &mut link.as_mut().expect("the link is not empty").left;
+    }
+    let mut node = link.take()?;
+    *link = node.right.take();
+    Some(node)
+}
+
+/// Join two trees, every value of `left` being less than those of `right`,
+/// under the smallest node of `right`.
+fn join<T>(left: Link<T>, mut right: Link<T>) -> Link<T> {
+    if left.is_none() {
+        return right;
+    }
+    match take_min_node(&mut right) {
+        Some(mut root) => {
+            root.left = left;
+            root.right = right;
+            Some(root)
+        }
+        None => left,
+    }
+}
+
+impl<T, A> From<BinarySearchTree<T, A>> for Option<Node<T>> {
+    fn from(tree: BinarySearchTree<T, A>) -> Self {
+        tree.into_node()
+    }
+}
+
+impl<T, A> From<BinarySearchTree<T, A>> for Vec<T> {
+    /// Collect the values in increasing order.
+    fn from(tree: BinarySearchTree<T, A>) -> Self {
+        into_values(tree.root, tree.len, &mut Global)
+    }
+}
+
+/// Take apart a tree in mid order, without recursion, collecting the
+/// values and handing the boxes back to `alloc`.
+fn into_values<T, A: NodeAlloc<Node<T>>>(
+    mut link: Link<T>,
+    capacity: usize,
+    alloc: &mut A,
+) -> Vec<T> {
+    let mut values = Vec::with_capacity(capacity);
+    let mut stack = Vec::new();
+    loop {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            stack.push(node);
+        }
+        match stack.pop() {
+            Some(mut node) => {
+                link = node.right.take();
+                values.push(alloc.free(node).into_data());
+            }
+            None => break,
+        }
+    }
+    values
+}
+
+impl<T: Ord, A> BinarySearchTree<T, A> {
+    /// Check that the values are strictly increasing in mid order and that
+    /// the length matches, which may break with an inconsistent `Ord`.
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first node not greater than
+    /// its mid order predecessor, or at the root when the length is wrong.
+    pub fn validate(&self) -> Result<()> {
+        let mut len = 0;
+        let mut prev: Option<&T> = None;
+        let mut stack: Vec<(&Node<T>, Vec<usize>)> = Vec::new();
+        let mut next = self.root().map(|root| (root, Vec::new()));
+        loop {
+            while let Some((node, path)) = next.take() {
+                next = node.left().map(|left| (left, child_path(&path, 0)));
+                stack.push((node, path));
+            }
+            let (node, path) = match stack.pop() {
+                Some(top) => top,
+                None => break,
+            };
+            if prev.is_some_and(|prev| prev >= node.data()) {
+                return Err(Error::InvariantViolation {
+                    path,
+                    reason: "value is not greater than its mid order predecessor".to_string(),
+                });
+            }
+            prev = Some(node.data());
+            len += 1;
+            next = node.right().map(|right| (right, child_path(&path, 1)));
+        }
+        if len != self.len {
+            return Err(Error::InvariantViolation {
+                path: Vec::new(),
+                reason: format!("tree holds {} values but the length is {}", len, self.len),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Extend `path` by the child `idx`.
+fn child_path(path: &[usize], idx: usize) -> Vec<usize> {
+    let mut child = path.to_vec();
+    child.push(idx);
+    child
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>> + Default> TryFrom<Vec<T>> for BinarySearchTree<T, A> {
+    type Error = Error;
+
+    /// Build a height-balanced tree from strictly increasing values in `O(n)`.
+    /// # Errors
+    /// Return `Unsorted` Error when the values are not strictly increasing.
+    fn try_from(values: Vec<T>) -> Result<Self> {
+        Self::from_sorted_vec_in(values, A::default())
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>> + Default> TryFrom<Node<T>> for BinarySearchTree<T, A> {
+    type Error = Error;
+
+    /// Wrap a tree whose mid order traversal is strictly increasing,
+    /// boxing the root through the allocator; the other nodes keep their
+    /// boxes.
+    /// # Errors
+    /// Return `Unsorted` Error when the mid order traversal is not strictly
+    /// increasing.
+    fn try_from(node: Node<T>) -> Result<Self> {
+        let mut len = 0;
+        let mut prev: Option<&T> = None;
+        for value in Iter::new(Some(&node)) {
+            if prev.is_some_and(|prev| prev >= value) {
+                return Err(Error::Unsorted);
+            }
+            prev = Some(value);
+            len += 1;
+        }
+        let mut alloc = A::default();
+        Ok(Self {
+            root: Some(alloc.alloc(node)),
+            len,
+            alloc,
+        })
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>> + Default> FromIterator<T> for BinarySearchTree<T, A> {
+    /// Build a height-balanced tree from the values, which are bulk loaded
+    /// in `O(n)` when strictly increasing and sorted first otherwise.
+    /// Repeated values are kept once, the first one winning as with
+    /// [`BinarySearchTree::insert`]. The nodes are boxed through the
+    /// allocator.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values: Vec<T> = iter.into_iter().collect();
+        if values.windows(2).any(|pair| pair[0] >= pair[1]) {
+            values.sort();
+            values.dedup();
+        }
+        Self::from_sorted_vec_in(values, A::default()).expect("the values are sorted")
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>>> Extend<T> for BinarySearchTree<T, A> {
+    /// Insert the values one by one, without rebalancing.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.insert(value);
+        });
+    }
+}
+
+impl<'a, T: Ord + Copy, A: NodeAlloc<Node<T>>> Extend<&'a T> for BinarySearchTree<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a, T, A> IntoIterator for &'a BinarySearchTree<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Mid order iterator over the values of a binary search tree.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(mut node: Option<&'a Node<T>>) -> Self {
+        let mut stack = Vec::new();
+        while let Some(current) = node {
+            stack.push(current);
+            node = current.left();
+        }
+        Self { stack }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        let mut next = node.right();
+        while let Some(current) = next {
+            self.stack.push(current);
+            next = current.left();
+        }
+        Some(node.data())
+    }
+}
@@ -0,0 +1,147 @@
+use super::Node;
+use crate::{Error, Result};
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+/// Data with a binary encoding for [`Node::write_to`] and [`Node::read_from`].
+pub trait BinaryData: Sized {
+    /// Write the encoding of the value.
+    /// # Errors
+    /// Return the error of `writer`.
+    fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()>;
+
+    /// Read a value written by [`BinaryData::write_binary`].
+    /// # Errors
+    /// Return the error of `reader`, or `InvalidData` when the bytes do
+    /// not encode a value.
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self>;
+}
+
+macro_rules! binary_number {
+    ($($ty:ty),*) => {
+        $(
+            impl BinaryData for $ty {
+                fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&self.to_le_bytes())
+                }
+
+                fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+                    let mut bytes = [0; std::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+binary_number!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64);
+
+/// Written as a `u64` so that the encoding does not depend on the platform.
+impl BinaryData for usize {
+    fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u64).write_binary(writer)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let value = u64::read_binary(reader)?;
+        usize::try_from(value).map_err(|_| invalid_data("length does not fit in usize"))
+    }
+}
+
+/// Written as an `i64` so that the encoding does not depend on the platform.
+impl BinaryData for isize {
+    fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as i64).write_binary(writer)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let value = i64::read_binary(reader)?;
+        isize::try_from(value).map_err(|_| invalid_data("value does not fit in isize"))
+    }
+}
+
+impl BinaryData for bool {
+    fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        (*self as u8).write_binary(writer)
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        match u8::read_binary(reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+/// Written as its length in bytes followed by its UTF-8 bytes.
+impl BinaryData for String {
+    fn write_binary<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.len().write_binary(writer)?;
+        writer.write_all(self.as_bytes())
+    }
+
+    fn read_binary<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let len = usize::read_binary(reader)?;
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8"))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl<T: BinaryData> Node<T> {
+    /// Write the tree in a compact binary form, without recursion.
+    ///
+    /// The encoding is the number of nodes as a little endian `u64`, then
+    /// two bits per node in pre order telling whether it has a left and a
+    /// right child (packed from the low bit of every byte), then the data
+    /// of the nodes in pre order.
+    /// # Errors
+    /// Return `Io` Error when writing fails.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        let nodes = self.to_pre_order_shape();
+        let mut bits = vec![0u8; (2 * nodes.len()).div_ceil(8)];
+        for (idx, &(_, has_left, has_right)) in nodes.iter().enumerate() {
+            let bit = 2 * idx;
+            bits[bit / 8] |= (has_left as u8) << (bit % 8);
+            bits[bit / 8] |= (has_right as u8) << (bit % 8 + 1);
+        }
+        nodes.len().write_binary(&mut writer)?;
+        writer.write_all(&bits)?;
+        for (data, _, _) in nodes {
+            data.write_binary(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Read a tree written by [`Node::write_to`], without recursion.
+    /// # Errors
+    /// Return `Io` Error when reading fails or some data is invalid, and
+    /// `MalformedTree` Error when the structure bits do not describe
+    /// exactly one tree.
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self> {
+        let len = usize::read_binary(&mut reader)?;
+        let bytes = len.checked_mul(2).ok_or(Error::MalformedTree)?.div_ceil(8);
+        let mut bits = Vec::new();
+        (&mut reader).take(bytes as u64).read_to_end(&mut bits)?;
+        if bits.len() != bytes {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let mut nodes = Vec::with_capacity(bits.len() * 4);
+        for idx in 0..len {
+            let bit = 2 * idx;
+            let has_left = bits[bit / 8] >> (bit % 8) & 1 == 1;
+            let has_right = bits[bit / 8] >> (bit % 8 + 1) & 1 == 1;
+            nodes.push((T::read_binary(&mut reader)?, has_left, has_right));
+        }
+        Node::from_pre_order_shape(nodes)
+    }
+}
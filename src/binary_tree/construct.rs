@@ -0,0 +1,552 @@
+use super::pool::NodeAlloc;
+use super::{BoxedNode, Link, Node, Order};
+use crate::{Error, Result};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Child indices of every item in an index-shaped tree.
+struct Links {
+    root: usize,
+    left: Vec<Option<usize>>,
+    right: Vec<Option<usize>>,
+}
+
+impl Links {
+    /// Move `items` into nodes shaped by the links, children first.
+    fn assemble<T>(self, items: Vec<T>) -> Node<T> {
+        self.assemble_with(items, Node::boxed)
+    }
+
+    /// Move `items` into nodes shaped by the links as [`Links::assemble`]
+    /// does, boxing the children with `boxed`.
+    fn assemble_with<T, F>(self, items: Vec<T>, mut boxed: F) -> Node<T>
+    where
+        F: FnMut(Node<T>) -> BoxedNode<T>,
+    {
+        let mut items: Vec<_> = items.into_iter().map(Some).collect();
+        let mut built: Vec<Option<Node<T>>> = items.iter().map(|_| None).collect();
+        let mut stack = vec![(self.root, false)];
+        while let Some((idx, expanded)) = stack.pop() {
+            if expanded {
+                let data = items[idx].take().expect("every item is used once");
+                let left = self.left[idx].and_then(|l| built[l].take()).map(&mut boxed);
+                let right = self.right[idx]
+                    .and_then(|r| built[r].take())
+                    .map(&mut boxed);
+                built[idx] = Some(Node { data, left, right });
+            } else {
+                stack.push((idx, true));
+                stack.extend(self.left[idx].map(|l| (l, false)));
+                stack.extend(self.right[idx].map(|r| (r, false)));
+            }
+        }
+        built[self.root].take().expect("the root is built last")
+    }
+
+    /// Get the links of the mirror image, items being numbered backwards.
+    fn mirror(self) -> Links {
+        let last = self.left.len() - 1;
+        let flip = |idx: usize| last - idx;
+        Links {
+            root: flip(self.root),
+            left: (0..=last)
+                .map(|idx| self.right[flip(idx)].map(flip))
+                .collect(),
+            right: (0..=last)
+                .map(|idx| self.left[flip(idx)].map(flip))
+                .collect(),
+        }
+    }
+
+    /// Return `true` if mid order traversal visits every item in order.
+    fn is_mid_order(&self) -> bool {
+        let mut next = 0;
+        let mut stack = Vec::new();
+        let mut link = Some(self.root);
+        loop {
+            while let Some(idx) = link {
+                stack.push(idx);
+                link = self.left[idx];
+            }
+            match stack.pop() {
+                Some(idx) if idx == next => {
+                    next += 1;
+                    link = self.right[idx];
+                }
+                Some(_) => return false,
+                None => return next == self.left.len(),
+            }
+        }
+    }
+}
+
+/// Map the values of a mid order traversal to their positions.
+/// # Errors
+/// Return `InconsistentTraversals` Error when a value is repeated.
+fn positions<T: Eq + Hash>(inorder: &[T]) -> Result<HashMap<&T, usize>> {
+    let mut positions = HashMap::with_capacity(inorder.len());
+    for (idx, value) in inorder.iter().enumerate() {
+        if positions.insert(value, idx).is_some() {
+            return Err(Error::InconsistentTraversals);
+        }
+    }
+    Ok(positions)
+}
+
+/// Build the links, over positions in `inorder`, of the tree traversed
+/// in pre order as `order`, or in post order when `post` is set.
+///
+/// Post order read backwards is the pre order of the mirror image, whose
+/// mid order is `inorder` backwards, so both share the same pass.
+/// # Errors
+/// Return `InconsistentTraversals` Error when no such tree exists.
+fn pre_in_links<T: Eq + Hash>(order: &[T], inorder: &[T], post: bool) -> Result<Links> {
+    if order.len() != inorder.len() {
+        return Err(Error::InconsistentTraversals);
+    }
+    let positions = positions(inorder)?;
+    let len = inorder.len();
+    let mut values = order.iter();
+    let mut next_value = || {
+        if post {
+            values.next_back()
+        } else {
+            values.next()
+        }
+    };
+    let mut left = vec![None; len];
+    let mut right = vec![None; len];
+    let mut used = vec![false; len];
+    // The stack holds the path of nodes still waiting for a right child;
+    // `next` is the position of the first of them to close in mid order.
+    let mut stack: Vec<usize> = Vec::new();
+    let mut next = 0;
+    let mut root = None;
+    while let Some(value) = next_value() {
+        let mut idx = *positions.get(value).ok_or(Error::InconsistentTraversals)?;
+        if post {
+            idx = len - 1 - idx;
+        }
+        if std::mem::replace(&mut used[idx], true) {
+            return Err(Error::InconsistentTraversals);
+        }
+        if let Some(&top) = stack.last() {
+            if top != next {
+                left[top] = Some(idx);
+            } else {
+                let mut last = top;
+                while stack.last() == Some(&next) {
+                    last = next;
+                    stack.pop();
+                    next += 1;
+                }
+                right[last] = Some(idx);
+            }
+        } else {
+            root = Some(idx);
+        }
+        stack.push(idx);
+    }
+    let root = root.ok_or(Error::InconsistentTraversals)?;
+    let links = Links { root, left, right };
+    if !links.is_mid_order() {
+        Err(Error::InconsistentTraversals)
+    } else if post {
+        Ok(links.mirror())
+    } else {
+        Ok(links)
+    }
+}
+
+/// Build the links, over positions in `inorder`, of the tree traversed
+/// in level order as `level`.
+///
+/// Every node splits the range of positions of its subtree in two, and
+/// the ranges are taken by the nodes in level order, so the next value
+/// must fall within the first nonempty range in the queue.
+/// # Errors
+/// Return `InconsistentTraversals` Error when no such tree exists.
+fn level_in_links<T: Eq + Hash>(level: &[T], inorder: &[T]) -> Result<Links> {
+    if level.len() != inorder.len() {
+        return Err(Error::InconsistentTraversals);
+    }
+    let positions = positions(inorder)?;
+    let len = inorder.len();
+    let mut left = vec![None; len];
+    let mut right = vec![None; len];
+    let mut root = None;
+    let mut values = level.iter();
+    let mut queue = VecDeque::new();
+    queue.push_back((None, 0, len));
+    while let Some((parent, lo, hi)) = queue.pop_front() {
+        if lo == hi {
+            continue;
+        }
+        let idx = values
+            .next()
+            .and_then(|value| positions.get(value))
+            .copied()
+            .filter(|idx| (lo..hi).contains(idx))
+            .ok_or(Error::InconsistentTraversals)?;
+        match parent {
+            None => root = Some(idx),
+            Some((parent, false)) => left[parent] = Some(idx),
+            Some((parent, true)) => right[parent] = Some(idx),
+        }
+        queue.push_back((Some((idx, false)), lo, idx));
+        queue.push_back((Some((idx, true)), idx + 1, hi));
+    }
+    let root = root.ok_or(Error::InconsistentTraversals)?;
+    Ok(Links { root, left, right })
+}
+
+/// Build the cartesian tree links of `values` with a monotone stack.
+fn cartesian_links<T: Ord>(values: &[T]) -> Option<Links> {
+    let mut left = vec![None; values.len()];
+    let mut right = vec![None; values.len()];
+    let mut stack: Vec<usize> = Vec::new();
+    for (idx, value) in values.iter().enumerate() {
+        let mut last = None;
+        while let Some(&top) = stack.last() {
+            if values[top] <= *value {
+                break;
+            }
+            last = stack.pop();
+        }
+        left[idx] = last;
+        if let Some(&top) = stack.last() {
+            right[top] = Some(idx);
+        }
+        stack.push(idx);
+    }
+    Some(Links {
+        root: *stack.first()?,
+        left,
+        right,
+    })
+}
+
+impl<T> Node<T> {
+    /// Build a tree from its nodes in pre order, each given with whether it
+    /// has a left and a right child, without recursion.
+    ///
+    /// This flat shape is the interchange form of a tree: it is what
+    /// [`Node::into_pre_order_shape`] produces and what serializers emit,
+    /// so deep trees round-trip without overflowing the stack.
+    /// # Errors
+    /// Return `MalformedTree` Error when the flags do not describe exactly
+    /// one tree.
+    pub fn from_pre_order_shape(nodes: Vec<(T, bool, bool)>) -> Result<Node<T>> {
+        // Going backwards, the roots of both subtrees of a node are on top
+        // of the stack, left above right, when the node itself is reached.
+        let mut built: Vec<Node<T>> = Vec::new();
+        for (data, has_left, has_right) in nodes.into_iter().rev() {
+            let mut child = |present: bool| {
+                if present {
+                    built
+                        .pop()
+                        .map(|node| Some(node.boxed()))
+                        .ok_or(Error::MalformedTree)
+                } else {
+                    Ok(None)
+                }
+            };
+            let left = child(has_left)?;
+            let right = child(has_right)?;
+            built.push(Node { data, left, right });
+        }
+        match (built.pop(), built.is_empty()) {
+            (Some(root), true) => Ok(root),
+            _ => Err(Error::MalformedTree),
+        }
+    }
+
+    /// Convert into the nodes in pre order, each with whether it has a left
+    /// and a right child, without recursion.
+    /// See [`Node::from_pre_order_shape`].
+    pub fn into_pre_order_shape(self) -> Vec<(T, bool, bool)> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(mut node) = stack.pop() {
+            let (left, right) = (node.left.take(), node.right.take());
+            nodes.push((node.into_data(), left.is_some(), right.is_some()));
+            stack.extend(right.map(|node| *node));
+            stack.extend(left.map(|node| *node));
+        }
+        nodes
+    }
+
+    /// Get the refs of the nodes in pre order, each with whether it has a
+    /// left and a right child. See [`Node::from_pre_order_shape`].
+    pub fn to_pre_order_shape(&self) -> Vec<(&T, bool, bool)> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            nodes.push((&node.data, node.left.is_some(), node.right.is_some()));
+            stack.extend(node.right());
+            stack.extend(node.left());
+        }
+        nodes
+    }
+
+    /// Get the refs of the nodes in pre order and the edges between their
+    /// indices, each as the parent, the child and whether it is a right
+    /// child. Graph libraries such as `petgraph` build a directed graph
+    /// from such an edge list. See [`Node::from_edge_list`].
+    pub fn to_edge_list(&self) -> (Vec<&T>, Vec<(usize, usize, bool)>) {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        let mut stack = vec![(self, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let idx = nodes.len();
+            nodes.push(&node.data);
+            edges.extend(parent.map(|(parent, is_right)| (parent, idx, is_right)));
+            stack.extend(node.right().map(|right| (right, Some((idx, true)))));
+            stack.extend(node.left().map(|left| (left, Some((idx, false)))));
+        }
+        (nodes, edges)
+    }
+
+    /// Build a tree from its nodes and the edges between their indices,
+    /// given as by [`Node::to_edge_list`] but in any order, without
+    /// recursion.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when an edge names a missing node,
+    /// and `MalformedTree` Error when the edges do not form exactly one
+    /// binary tree over all the nodes.
+    pub fn from_edge_list(nodes: Vec<T>, edges: &[(usize, usize, bool)]) -> Result<Node<T>> {
+        let len = nodes.len();
+        let mut links = Links {
+            root: 0,
+            left: vec![None; len],
+            right: vec![None; len],
+        };
+        let mut has_parent = vec![false; len];
+        for &(parent, child, is_right) in edges {
+            if let Some(&index) = [parent, child].iter().find(|&&idx| idx >= len) {
+                return Err(Error::IndexOutOfRange { index, len });
+            }
+            let slot = if is_right {
+                &mut links.right[parent]
+            } else {
+                &mut links.left[parent]
+            };
+            if slot.is_some() || has_parent[child] {
+                return Err(Error::MalformedTree);
+            }
+            *slot = Some(child);
+            has_parent[child] = true;
+        }
+        let mut roots = (0..len).filter(|&idx| !has_parent[idx]);
+        links.root = match (roots.next(), roots.next()) {
+            (Some(root), None) => root,
+            _ => return Err(Error::MalformedTree),
+        };
+        // With one root and one parent for every other node, the edges form
+        // a tree exactly when every node is reachable from the root.
+        let mut reached = 0;
+        let mut stack = vec![links.root];
+        while let Some(idx) = stack.pop() {
+            reached += 1;
+            stack.extend(links.left[idx]);
+            stack.extend(links.right[idx]);
+        }
+        if reached != len {
+            return Err(Error::MalformedTree);
+        }
+        Ok(links.assemble(nodes))
+    }
+
+    /// Build a tree top-down from a seed, without recursion: `f` turns a
+    /// seed into the data of a node and the seeds of its left and right
+    /// children, `None` for missing ones. Seeds are expanded in pre order.
+    ///
+    /// `f` must eventually stop returning seeds; see
+    /// [`Node::unfold_to_depth`] to cap the depth instead.
+    pub fn unfold<S, F>(seed: S, f: F) -> Node<T>
+    where
+        F: FnMut(S) -> (T, Option<S>, Option<S>),
+    {
+        Self::unfold_inner(seed, None, f)
+    }
+
+    /// Build a tree as [`Node::unfold`] does, dropping the seeds of
+    /// children below depth `max_depth`, the root having depth 0.
+    pub fn unfold_to_depth<S, F>(seed: S, max_depth: usize, f: F) -> Node<T>
+    where
+        F: FnMut(S) -> (T, Option<S>, Option<S>),
+    {
+        Self::unfold_inner(seed, Some(max_depth), f)
+    }
+
+    fn unfold_inner<S, F>(seed: S, max_depth: Option<usize>, mut f: F) -> Node<T>
+    where
+        F: FnMut(S) -> (T, Option<S>, Option<S>),
+    {
+        let mut nodes = Vec::new();
+        let mut stack = vec![(seed, 0)];
+        while let Some((seed, depth)) = stack.pop() {
+            let (data, mut left, mut right) = f(seed);
+            if max_depth.is_some_and(|max| depth >= max) {
+                left = None;
+                right = None;
+            }
+            nodes.push((data, left.is_some(), right.is_some()));
+            stack.extend(right.map(|right| (right, depth + 1)));
+            stack.extend(left.map(|left| (left, depth + 1)));
+        }
+        Node::from_pre_order_shape(nodes).expect("the shape is well-formed")
+    }
+
+    /// Build the cartesian tree of `values` in `O(n)`.
+    ///
+    /// Mid order traversal yields `values` in order, and every node holds
+    /// the minimum of its subtree (the leftmost one on ties).
+    /// Return `None` if `values` is empty.
+    pub fn cartesian_tree(values: &[T]) -> Option<Node<&T>>
+    where
+        T: Ord,
+    {
+        Some(cartesian_links(values)?.assemble(values.iter().collect()))
+    }
+
+    /// Build the cartesian tree of `values`, taking ownership of them.
+    /// See [`Node::cartesian_tree`].
+    pub fn cartesian_tree_from_vec(values: Vec<T>) -> Option<Node<T>>
+    where
+        T: Ord,
+    {
+        Some(cartesian_links(&values)?.assemble(values))
+    }
+
+    /// Build a height-balanced tree over `values` in `O(n)`, without
+    /// recursion.
+    ///
+    /// Mid order traversal yields `values` in order, so sorted values give
+    /// a balanced binary search tree; the order is not checked.
+    /// Return `None` if `values` is empty.
+    pub fn from_sorted(values: &[T]) -> Option<Node<T>>
+    where
+        T: Clone,
+    {
+        balanced(values.to_vec())
+    }
+
+    /// Build a height-balanced tree over `values`, taking ownership of
+    /// them. See [`Node::from_sorted`].
+    pub fn from_sorted_vec(values: Vec<T>) -> Option<Node<T>> {
+        balanced(values)
+    }
+
+    /// Rebuild the tree height-balanced with the same mid order, in `O(n)`
+    /// time and space and without recursion.
+    pub fn rebalanced(self) -> Node<T> {
+        balanced(self.flatten(Order::Mid)).expect("a tree holds its root")
+    }
+
+    /// Rebuild the tree traversed as `pre` in pre order and as `inorder`
+    /// in mid order, in `O(n)` and without recursion. The values must be
+    /// distinct.
+    /// # Errors
+    /// Return `InconsistentTraversals` Error when the traversals are empty,
+    /// hold repeated values or describe no common tree.
+    pub fn from_pre_in(pre: &[T], inorder: &[T]) -> Result<Node<T>>
+    where
+        T: Eq + Hash + Clone,
+    {
+        Ok(pre_in_links(pre, inorder, false)?.assemble(inorder.to_vec()))
+    }
+
+    /// Rebuild the tree traversed as `post` in post order and as `inorder`
+    /// in mid order, in `O(n)` and without recursion. The values must be
+    /// distinct.
+    /// # Errors
+    /// Return `InconsistentTraversals` Error when the traversals are empty,
+    /// hold repeated values or describe no common tree.
+    pub fn from_post_in(post: &[T], inorder: &[T]) -> Result<Node<T>>
+    where
+        T: Eq + Hash + Clone,
+    {
+        Ok(pre_in_links(post, inorder, true)?.assemble(inorder.to_vec()))
+    }
+
+    /// Rebuild the tree traversed as `level` in level order and as
+    /// `inorder` in mid order, in `O(n)` and without recursion. The values
+    /// must be distinct.
+    /// # Errors
+    /// Return `InconsistentTraversals` Error when the traversals are empty,
+    /// hold repeated values or describe no common tree.
+    pub fn from_level_in(level: &[T], inorder: &[T]) -> Result<Node<T>>
+    where
+        T: Eq + Hash + Clone,
+    {
+        Ok(level_in_links(level, inorder)?.assemble(inorder.to_vec()))
+    }
+}
+
+/// Build the links of a height-balanced tree over `len` items in mid order.
+fn balanced_links(len: usize) -> Option<Links> {
+    if len == 0 {
+        return None;
+    }
+    let mut left = vec![None; len];
+    let mut right = vec![None; len];
+    let root = len / 2;
+    let mut stack = vec![(0, root, len)];
+    while let Some((lo, mid, hi)) = stack.pop() {
+        if lo < mid {
+            let child = lo + (mid - lo) / 2;
+            left[mid] = Some(child);
+            stack.push((lo, child, mid));
+        }
+        if mid + 1 < hi {
+            let child = mid + 1 + (hi - mid - 1) / 2;
+            right[mid] = Some(child);
+            stack.push((mid + 1, child, hi));
+        }
+    }
+    Some(Links { root, left, right })
+}
+
+/// Build a height-balanced tree whose mid order traversal yields `items`.
+pub(super) fn balanced<T>(items: Vec<T>) -> Option<Node<T>> {
+    Some(balanced_links(items.len())?.assemble(items))
+}
+
+/// Build a height-balanced tree whose mid order traversal yields `items`,
+/// boxing every node through `alloc`.
+pub(super) fn balanced_in<T, A: NodeAlloc<Node<T>>>(items: Vec<T>, alloc: &mut A) -> Link<T> {
+    let links = balanced_links(items.len())?;
+    let root = links.assemble_with(items, |node| alloc.alloc(node));
+    Some(alloc.alloc(root))
+}
+
+/// Serialize as the pre order shape, a sequence of `(data, has left, has
+/// right)` tuples, see [`Node::from_pre_order_shape`].
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Node<T> {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let shape = self.to_pre_order_shape();
+        let mut seq = serializer.serialize_seq(Some(shape.len()))?;
+        for node in shape {
+            seq.serialize_element(&node)?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserialize from the pre order shape, rejecting sequences that do not
+/// describe exactly one tree.
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Node<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let shape: Vec<(T, bool, bool)> = serde::Deserialize::deserialize(deserializer)?;
+        Node::from_pre_order_shape(shape).map_err(serde::de::Error::custom)
+    }
+}
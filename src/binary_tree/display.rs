@@ -0,0 +1,577 @@
+use super::Node;
+use crate::diagram::Diagram;
+use crate::layout;
+use crate::width::{char_width, str_width, truncate};
+use std::fmt;
+
+/// Characters drawing the connectors of a [`TreeFormatter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Glyphs {
+    /// Connector of a child with siblings after it.
+    pub branch: char,
+    /// Connector of the last child.
+    pub last: char,
+    /// Line continuing past the children of an earlier sibling.
+    pub vertical: char,
+    /// Line leading to a label.
+    pub horizontal: char,
+    /// Shown in place of the missing child of a node with a single child.
+    pub empty: &'static str,
+}
+
+impl Glyphs {
+    /// `├──`, `└──` and `│`, like the `tree` command.
+    pub const UNICODE: Glyphs = Glyphs {
+        branch: '├',
+        last: '└',
+        vertical: '│',
+        horizontal: '─',
+        empty: "∅",
+    };
+
+    /// `├──` and `╰──` with a rounded corner.
+    pub const ROUNDED: Glyphs = Glyphs {
+        branch: '├',
+        last: '╰',
+        vertical: '│',
+        horizontal: '─',
+        empty: "∅",
+    };
+
+    /// `|--` and `` `-- `` for terminals without Unicode.
+    pub const ASCII: Glyphs = Glyphs {
+        branch: '|',
+        last: '`',
+        vertical: '|',
+        horizontal: '-',
+        empty: "()",
+    };
+}
+
+/// Options of the box-drawing tree renderer, used through
+/// [`Node::display_with`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TreeFormatter {
+    glyphs: Glyphs,
+    max_depth: Option<usize>,
+    max_per_level: Option<usize>,
+    indent: usize,
+    padding: usize,
+    elision: String,
+    max_label_width: Option<usize>,
+}
+
+impl Default for TreeFormatter {
+    fn default() -> Self {
+        Self {
+            glyphs: Glyphs::UNICODE,
+            max_depth: None,
+            max_per_level: None,
+            indent: 2,
+            padding: 1,
+            elision: "…".to_owned(),
+            max_label_width: None,
+        }
+    }
+}
+
+impl TreeFormatter {
+    /// Create the default formatter: Unicode glyphs, `├── ` connectors and
+    /// no limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the glyphs.
+    pub fn glyphs(mut self, glyphs: Glyphs) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Show the nodes down to depth `depth` only, the root having depth 0.
+    /// The children of the deepest nodes shown are replaced by the elision
+    /// marker.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Show the first `count` nodes in pre order on every level only.
+    /// Later ones are replaced by the elision marker, once per parent.
+    pub fn max_per_level(mut self, count: Option<usize>) -> Self {
+        self.max_per_level = count;
+        self
+    }
+
+    /// Set the number of horizontal glyphs in a connector.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Set the number of spaces between a connector and its label.
+    pub fn padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the marker standing for the nodes not shown.
+    pub fn elision(mut self, marker: &str) -> Self {
+        self.elision = marker.to_owned();
+        self
+    }
+
+    /// Cut every line of the labels to `width` columns, ending the cut
+    /// ones with `…`. Without it, the precision of the format string is
+    /// used, such as `{:.8}`.
+    pub fn max_label_width(mut self, width: Option<usize>) -> Self {
+        self.max_label_width = width;
+        self
+    }
+}
+
+/// Display adapter printing a tree vertically with connectors, like the
+/// `tree` command. Created by [`Node::display_compact`] and
+/// [`Node::display_with`].
+///
+/// The left child is listed before the right one, and the missing child of
+/// a node with a single child is shown with the `empty` glyph. The later
+/// lines of a multi-line label are aligned with the first one, or with the
+/// labels of the children when the line to them passes below.
+#[derive(Debug, Clone)]
+pub struct Formatted<'a, T> {
+    node: &'a Node<T>,
+    format: TreeFormatter,
+}
+
+impl<T> Node<T> {
+    /// Get an adapter displaying the tree with box-drawing connectors,
+    /// one node per line. Same as formatting the node with `{:#}`.
+    pub fn display_compact(&self) -> Formatted<'_, T> {
+        self.display_with(TreeFormatter::default())
+    }
+
+    /// Get an adapter displaying the tree with connectors as configured
+    /// by `format`.
+    pub fn display_with(&self, format: TreeFormatter) -> Formatted<'_, T> {
+        Formatted { node: self, format }
+    }
+}
+
+/// What a line of the drawing shows.
+enum Line<'a, T> {
+    Node(&'a Node<T>),
+    Empty,
+    Elided,
+}
+
+impl<T: fmt::Display> fmt::Display for Formatted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        compact(self.node, &self.format, f, |_| None)
+    }
+}
+
+/// Draw the tree with connectors, every line of a label being wrapped in
+/// the escape sequence `paint` gives for its node.
+fn compact<T, P>(
+    node: &Node<T>,
+    format: &TreeFormatter,
+    f: &mut fmt::Formatter<'_>,
+    paint: P,
+) -> fmt::Result
+where
+    T: fmt::Display,
+    P: Fn(&Node<T>) -> Option<String>,
+{
+    let glyphs = format.glyphs;
+    let horizontal: String = std::iter::repeat_n(glyphs.horizontal, format.indent).collect();
+    let padding = " ".repeat(format.padding);
+    let blank = " ".repeat(format.indent + format.padding);
+    let max_width = format.max_label_width.or(f.precision());
+    let write_prefix = |f: &mut fmt::Formatter<'_>, open: &[bool]| {
+        for &more in open {
+            if more {
+                write!(f, "{}{}", glyphs.vertical, blank)?;
+            } else {
+                write!(f, " {}", blank)?;
+            }
+        }
+        Ok(())
+    };
+
+    // Whether every ancestor below the root still has siblings to come,
+    // which decides between a vertical line and a blank in the prefix.
+    let mut open: Vec<bool> = Vec::new();
+    let mut shown: Vec<usize> = Vec::new();
+    let mut stack = vec![(Line::Node(node), 0, true)];
+    while let Some((mut line, depth, mut last)) = stack.pop() {
+        if shown.len() <= depth {
+            shown.resize(depth + 1, 0);
+        }
+        if let Line::Node(_) = line {
+            if format.max_per_level.is_some_and(|max| shown[depth] >= max) {
+                line = Line::Elided;
+                // Elide the right sibling along with the left child.
+                if let Some((Line::Node(_), sibling_depth, true)) = stack.last() {
+                    if *sibling_depth == depth && !last {
+                        stack.pop();
+                        last = true;
+                    }
+                }
+            } else {
+                shown[depth] += 1;
+            }
+        }
+
+        if depth > 0 {
+            open.truncate(depth - 1);
+            write_prefix(f, &open)?;
+            let corner = if last { glyphs.last } else { glyphs.branch };
+            write!(f, "{}{}{}", corner, horizontal, padding)?;
+            open.push(!last);
+        }
+        let node = match line {
+            Line::Node(node) => node,
+            Line::Empty => {
+                writeln!(f, "{}", glyphs.empty)?;
+                continue;
+            }
+            Line::Elided => {
+                writeln!(f, "{}", format.elision)?;
+                continue;
+            }
+        };
+        let parent = node.left.is_some() || node.right.is_some();
+        let label = truncate(&node.data.to_string(), max_width);
+        let escape = paint(node);
+        for (idx, text) in label.split('\n').enumerate() {
+            if idx > 0 {
+                write_prefix(f, &open)?;
+                if parent {
+                    write!(f, "{}{}", glyphs.vertical, blank)?;
+                }
+            }
+            match &escape {
+                Some(escape) => writeln!(f, "{}{}\x1b[0m", escape, text)?,
+                None => writeln!(f, "{}", text)?,
+            }
+        }
+        if !parent {
+            continue;
+        }
+        if format.max_depth.is_some_and(|max| depth >= max) {
+            stack.push((Line::Elided, depth + 1, true));
+            continue;
+        }
+        let right = node.right().map_or(Line::Empty, Line::Node);
+        let left = node.left().map_or(Line::Empty, Line::Node);
+        stack.push((right, depth + 1, true));
+        stack.push((left, depth + 1, false));
+    }
+    Ok(())
+}
+
+/// Foreground color of a label in a terminal supporting ANSI escape
+/// sequences.
+#[cfg(feature = "color")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// Black.
+    Black,
+    /// Red.
+    Red,
+    /// Green.
+    Green,
+    /// Yellow.
+    Yellow,
+    /// Blue.
+    Blue,
+    /// Magenta.
+    Magenta,
+    /// Cyan.
+    Cyan,
+    /// White.
+    White,
+    /// Color of the 256-color palette.
+    Fixed(u8),
+    /// 24-bit color.
+    Rgb(u8, u8, u8),
+}
+
+#[cfg(feature = "color")]
+impl Color {
+    /// Get the escape sequence selecting the color.
+    fn escape(self) -> String {
+        match self {
+            Color::Black => "\x1b[30m".to_owned(),
+            Color::Red => "\x1b[31m".to_owned(),
+            Color::Green => "\x1b[32m".to_owned(),
+            Color::Yellow => "\x1b[33m".to_owned(),
+            Color::Blue => "\x1b[34m".to_owned(),
+            Color::Magenta => "\x1b[35m".to_owned(),
+            Color::Cyan => "\x1b[36m".to_owned(),
+            Color::White => "\x1b[37m".to_owned(),
+            Color::Fixed(idx) => format!("\x1b[38;5;{}m", idx),
+            Color::Rgb(r, g, b) => format!("\x1b[38;2;{};{};{}m", r, g, b),
+        }
+    }
+}
+
+/// Display adapter printing a tree with connectors like [`Formatted`],
+/// every label colored by a closure. Created by [`Formatted::colored`] and
+/// [`Node::display_colored`].
+///
+/// The closure is given the node of the label, so it may color by the node
+/// data as well as by the shape of its subtree. Labels it gives no color
+/// to, connectors and markers are left uncolored.
+#[cfg(feature = "color")]
+#[derive(Clone)]
+pub struct Colored<'a, T, F> {
+    node: &'a Node<T>,
+    format: TreeFormatter,
+    paint: F,
+}
+
+#[cfg(feature = "color")]
+impl<T: fmt::Debug, F> fmt::Debug for Colored<'_, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Colored")
+            .field("node", &self.node)
+            .field("format", &self.format)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "color")]
+impl<'a, T> Formatted<'a, T> {
+    /// Color every label with the color `paint` gives for its node.
+    pub fn colored<F>(self, paint: F) -> Colored<'a, T, F>
+    where
+        F: Fn(&Node<T>) -> Option<Color>,
+    {
+        Colored {
+            node: self.node,
+            format: self.format,
+            paint,
+        }
+    }
+}
+
+#[cfg(feature = "color")]
+impl<T> Node<T> {
+    /// Get an adapter displaying the tree with box-drawing connectors, one
+    /// node per line, every label colored with the color `paint` gives for
+    /// its node.
+    pub fn display_colored<F>(&self, paint: F) -> Colored<'_, T, F>
+    where
+        F: Fn(&Node<T>) -> Option<Color>,
+    {
+        self.display_compact().colored(paint)
+    }
+}
+
+#[cfg(feature = "color")]
+impl<T, F> fmt::Display for Colored<'_, T, F>
+where
+    T: fmt::Display,
+    F: Fn(&Node<T>) -> Option<Color>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        compact(self.node, &self.format, f, |node| {
+            (self.paint)(node).map(Color::escape)
+        })
+    }
+}
+
+/// Display adapter printing a tree sideways, the root at the left margin
+/// and depth growing rightward. Created by [`Node::display_horizontal`].
+///
+/// Right subtrees are drawn above their parent with `┌──` and left
+/// subtrees below it with `└──`, so reading from the bottom up gives the
+/// nodes in mid order. The later lines of a multi-line label are indented
+/// one step past the first one, leaving room for the line down to the left
+/// subtree, and a precision such as `{:.8}` cuts every line of the labels
+/// to that many columns.
+#[derive(Debug)]
+pub struct Horizontal<'a, T> {
+    node: &'a Node<T>,
+}
+
+impl<T> Clone for Horizontal<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Horizontal<'_, T> {}
+
+impl<T> Node<T> {
+    /// Get an adapter displaying the tree sideways, one node per line,
+    /// which keeps tall and skinny trees narrow.
+    pub fn display_horizontal(&self) -> Horizontal<'_, T> {
+        Horizontal { node: self }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Horizontal<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The prefix of a node at depth `d` holds `d - 1` segments, every
+        // one telling whether a vertical line passes on that level. The
+        // segments of the ancestors are left untouched by the subtrees
+        // drawn in between, so every entry only restores its own.
+        let mut segments: Vec<bool> = Vec::new();
+        let mut stack = vec![(self.node, 0, false, false, false)];
+        while let Some((node, depth, left, segment, emit)) = stack.pop() {
+            match depth {
+                0 => {}
+                1 => segments.clear(),
+                _ => {
+                    segments.truncate(depth - 2);
+                    segments.push(segment);
+                }
+            }
+            if !emit {
+                // A line passes between a node and its children on the
+                // side facing its parent.
+                if let Some(child) = node.left() {
+                    stack.push((child, depth + 1, true, !left, false));
+                }
+                stack.push((node, depth, left, segment, true));
+                if let Some(child) = node.right() {
+                    stack.push((child, depth + 1, false, left, false));
+                }
+                continue;
+            }
+            let label = truncate(&node.data.to_string(), f.precision());
+            for (idx, text) in label.split('\n').enumerate() {
+                if depth > 0 {
+                    for &line in &segments {
+                        f.write_str(if line { "│   " } else { "    " })?;
+                    }
+                    if idx == 0 {
+                        f.write_str(if left { "└── " } else { "┌── " })?;
+                    } else {
+                        f.write_str(if left { "    " } else { "│   " })?;
+                    }
+                }
+                if idx > 0 {
+                    // The left subtree hangs below the label.
+                    f.write_str(if node.left.is_some() {
+                        "│   "
+                    } else {
+                        "    "
+                    })?;
+                }
+                writeln!(f, "{}", text)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Draw the tree top-down, every level on a line below the connectors to
+/// its parents. Subtrees are packed with the Reingold–Tilford layout, so
+/// the width grows with the number of nodes rather than with the depth.
+///
+/// Labels are sized in terminal columns, wide characters taking two of
+/// them. The lines of a multi-line label are stacked, and the labels of a
+/// level are aligned on their last lines, from where the connectors leave.
+/// A precision such as `{:.8}` cuts every line of the labels to that many
+/// columns.
+pub(super) fn top_down<T: fmt::Display>(node: &Node<T>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let diagram = Diagram::binary(node);
+    let labels: Vec<Vec<String>> = diagram
+        .labels
+        .iter()
+        .map(|label| {
+            truncate(label, f.precision())
+                .split('\n')
+                .map(str::to_owned)
+                .collect()
+        })
+        .collect();
+    // An empty label still takes a column, so the connectors of its parent
+    // have somewhere to point.
+    let widths: Vec<i64> = labels
+        .iter()
+        .map(|lines| {
+            lines
+                .iter()
+                .map(|line| str_width(line))
+                .max()
+                .unwrap_or(0)
+                .max(1) as i64
+        })
+        .collect();
+    let layout = layout::tidy(&diagram.children, &widths, 1);
+    let width = layout.width as usize;
+    let start = |idx: usize| (layout.x[idx] - widths[idx] / 2) as usize;
+
+    // Every level takes as many lines as its tallest label, with a line of
+    // connectors below it, except the last level.
+    let mut heights = vec![1; layout.height];
+    for (idx, lines) in labels.iter().enumerate() {
+        let height = &mut heights[layout.depth[idx]];
+        *height = lines.len().max(*height);
+    }
+    let mut bottom = Vec::with_capacity(layout.height);
+    let mut top = 0;
+    for height in &heights {
+        bottom.push(top + height - 1);
+        top += height + 1;
+    }
+
+    // A cell holds what is printed in one column: a wide character fills
+    // the next cell with an empty string, and zero-width ones are attached
+    // to the character before them.
+    let mut rows = vec![vec![String::from(" "); width]; top - 1];
+    for (idx, lines) in labels.iter().enumerate() {
+        let first = bottom[layout.depth[idx]] + 1 - lines.len();
+        for (row, line) in rows[first..].iter_mut().zip(lines) {
+            let begin = start(idx) + (widths[idx] as usize - str_width(line)) / 2;
+            let mut col = begin;
+            for c in line.chars() {
+                match char_width(c) {
+                    0 if col > begin && !c.is_control() => row[col - 1].push(c),
+                    0 => {}
+                    wide => {
+                        row[col] = c.to_string();
+                        for cell in &mut row[col + 1..col + wide] {
+                            cell.clear();
+                        }
+                        col += wide;
+                    }
+                }
+            }
+        }
+    }
+    // Connectors only fill blank cells, so labels are never overwritten.
+    let mut put = |row: usize, col: usize, c: char| {
+        if rows[row][col] == " " {
+            rows[row][col] = c.to_string();
+        }
+    };
+    for (parent, child, side) in diagram.edges() {
+        let row = bottom[layout.depth[parent]];
+        let (begin, end) = (start(parent), start(parent) + widths[parent] as usize);
+        let x = layout.x[child] as usize;
+        if side == Some("L") {
+            if x + 1 < begin {
+                put(row + 1, x + 1, '/');
+                (x + 2..begin).for_each(|col| put(row, col, '_'));
+            } else {
+                put(row + 1, x, '/');
+            }
+        } else if x > end {
+            put(row + 1, x - 1, '\\');
+            (end..x - 1).for_each(|col| put(row, col, '_'));
+        } else {
+            put(row + 1, x, '\\');
+        }
+    }
+    for row in rows {
+        let line: String = row.concat();
+        writeln!(f, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
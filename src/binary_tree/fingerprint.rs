@@ -0,0 +1,53 @@
+use super::Node;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::vec;
+
+impl<T: Hash> Node<T> {
+    /// Hash the shape and the data of the tree, without recursion.
+    ///
+    /// Every node hashes its data with the hashes of its children, as in a
+    /// Merkle tree, so equal trees get equal fingerprints and different
+    /// fingerprints prove the trees different. The hash is not
+    /// cryptographic and may differ across Rust versions.
+    pub fn fingerprint(&self) -> u64 {
+        let (hash, _) = self
+            .subtree_hashes()
+            .next_back()
+            .expect("the root is hashed last");
+        hash
+    }
+
+    /// Get the fingerprint of every subtree with its root, in post order.
+    /// See [`Node::fingerprint`].
+    ///
+    /// Grouping the subtrees by fingerprint finds the repeated ones in
+    /// linear time, candidates with equal fingerprints being equal but for
+    /// a hash collision.
+    pub fn subtree_hashes(&self) -> vec::IntoIter<(u64, &Node<T>)> {
+        let mut hashes: Vec<(u64, &Node<T>)> = Vec::new();
+        // The hashes of the subtrees whose parent is not hashed yet, the
+        // right child of a node being on top of the left one when the node
+        // is reached for the second time.
+        let mut pending: Vec<u64> = Vec::new();
+        let mut stack = vec![(self, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if !expanded {
+                stack.push((node, true));
+                stack.extend(node.right().map(|right| (right, false)));
+                stack.extend(node.left().map(|left| (left, false)));
+                continue;
+            }
+            let right = node.right().and_then(|_| pending.pop());
+            let left = node.left().and_then(|_| pending.pop());
+            let mut hasher = DefaultHasher::new();
+            node.data.hash(&mut hasher);
+            left.hash(&mut hasher);
+            right.hash(&mut hasher);
+            let hash = hasher.finish();
+            pending.push(hash);
+            hashes.push((hash, node));
+        }
+        hashes.into_iter()
+    }
+}
@@ -0,0 +1,340 @@
+use super::view;
+use super::Node;
+use crate::datasize::DataSize;
+use crate::{Error, Result};
+use std::convert::TryFrom;
+
+/// Binary tree stored in heap-array order.
+///
+/// The root is at index `0` and the children of index `i` are at `2i + 1`
+/// and `2i + 2`, with `None` marking missing nodes. Complete trees are
+/// stored without holes; a tree of height `h` may need up to `2^h - 1`
+/// slots in general.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlatTree<T> {
+    slots: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> Default for FlatTree<T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> FlatTree<T> {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a complete tree holding `items` in level order.
+    pub fn from_complete(items: Vec<T>) -> Self {
+        Self {
+            len: items.len(),
+            slots: items.into_iter().map(Some).collect(),
+        }
+    }
+
+    /// Create a tree from heap-array slots, trimming trailing `None`s.
+    /// # Errors
+    /// Return `MalformedTree` Error when a node has no parent.
+    pub fn from_slots(mut slots: Vec<Option<T>>) -> Result<Self> {
+        while let Some(None) = slots.last() {
+            slots.pop();
+        }
+        let mut len = 0;
+        for idx in 0..slots.len() {
+            if slots[idx].is_some() {
+                if idx > 0 && slots[(idx - 1) / 2].is_none() {
+                    return Err(Error::MalformedTree);
+                }
+                len += 1;
+            }
+        }
+        Ok(Self { slots, len })
+    }
+
+    /// Get the heap-array slots.
+    pub fn slots(&self) -> &[Option<T>] {
+        &self.slots
+    }
+
+    /// Convert into the heap-array slots.
+    pub fn into_slots(self) -> Vec<Option<T>> {
+        self.slots
+    }
+
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the tree is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the ref of the data at the root.
+    pub fn root(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Get the ref of the data at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.slots.get(index)?.as_ref()
+    }
+
+    /// Get the mutable ref of the data at `index`.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.slots.get_mut(index)?.as_mut()
+    }
+
+    /// Get the index of the left child of `index`.
+    pub fn left(&self, index: usize) -> Option<usize> {
+        self.occupied(index.checked_mul(2)?.checked_add(1)?)
+    }
+
+    /// Get the index of the right child of `index`.
+    pub fn right(&self, index: usize) -> Option<usize> {
+        self.occupied(index.checked_mul(2)?.checked_add(2)?)
+    }
+
+    /// Get the index of the parent of `index`.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        if index == 0 {
+            return None;
+        }
+        self.occupied((index - 1) / 2)
+    }
+
+    /// Create a level order traverse iterator.
+    pub fn level_order_iter(&self) -> LevelOrderIter<'_, T> {
+        LevelOrderIter {
+            slots: self.slots.iter().enumerate(),
+        }
+    }
+
+    /// Pre order map.
+    pub fn pre_order_map<U, F>(self, f: F) -> FlatTree<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let order = self.pre_order_indices();
+        self.map_in(order, f)
+    }
+
+    /// Mid order map.
+    pub fn mid_order_map<U, F>(self, f: F) -> FlatTree<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let order = self.mid_order_indices();
+        self.map_in(order, f)
+    }
+
+    /// Post order map.
+    pub fn post_order_map<U, F>(self, f: F) -> FlatTree<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let order = self.post_order_indices();
+        self.map_in(order, f)
+    }
+
+    fn occupied(&self, index: usize) -> Option<usize> {
+        self.get(index).map(|_| index)
+    }
+
+    /// Apply `f` to the nodes in the given order of indices.
+    fn map_in<U, F>(mut self, order: Vec<usize>, mut f: F) -> FlatTree<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let mut slots: Vec<Option<U>> = self.slots.iter().map(|_| None).collect();
+        for idx in order {
+            let data = self.slots[idx].take().expect("every node is mapped once");
+            slots[idx] = Some(f(data));
+        }
+        FlatTree {
+            slots,
+            len: self.len,
+        }
+    }
+
+    fn pre_order_indices(&self) -> Vec<usize> {
+        self.indices(view::pre_order)
+    }
+
+    fn mid_order_indices(&self) -> Vec<usize> {
+        self.indices(view::mid_order)
+    }
+
+    fn post_order_indices(&self) -> Vec<usize> {
+        self.indices(view::post_order)
+    }
+
+    /// Collect the indices of the nodes in the order of a traversal.
+    fn indices<'a, I, F>(&'a self, traversal: F) -> Vec<usize>
+    where
+        I: Iterator<Item = (&'a FlatTree<T>, usize)>,
+        F: FnOnce((&'a FlatTree<T>, usize)) -> I,
+    {
+        match self.occupied(0) {
+            Some(root) => traversal((self, root)).map(|(_, idx)| idx).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<T: DataSize> DataSize for FlatTree<T> {
+    /// Count the slots at their capacity, holes included, and the heap
+    /// data of the nodes.
+    fn heap_size(&self) -> usize {
+        self.slots.heap_size()
+    }
+}
+
+impl<T> TryFrom<Node<T>> for FlatTree<T> {
+    type Error = Error;
+
+    /// Lay out a tree in heap-array order.
+    ///
+    /// Degenerate trees need exponentially many slots.
+    /// # Errors
+    /// Return `CapacityOverflow` Error when the tree is too deep for the
+    /// slots to be indexed or allocated.
+    fn try_from(node: Node<T>) -> Result<Self> {
+        let mut slots = empty_slots(last_heap_index(&node)?)?;
+        let mut len = 0;
+        let mut stack = vec![(0, node)];
+        while let Some((idx, mut node)) = stack.pop() {
+            // The indices were checked above.
+            if let Some(left) = node.left.take() {
+                stack.push((2 * idx + 1, *left));
+            }
+            if let Some(right) = node.right.take() {
+                stack.push((2 * idx + 2, *right));
+            }
+            slots[idx] = Some(node.into_data());
+            len += 1;
+        }
+        Ok(Self { slots, len })
+    }
+}
+
+impl<T> From<FlatTree<T>> for Option<Node<T>> {
+    fn from(tree: FlatTree<T>) -> Self {
+        build(tree.slots)
+    }
+}
+
+/// Get the index of a child in heap-array order, `side` being `1` for the
+/// left child and `2` for the right one.
+/// # Errors
+/// Return `CapacityOverflow` Error when the index does not fit in `usize`.
+fn child_index(idx: usize, side: usize) -> Result<usize> {
+    idx.checked_mul(2)
+        .and_then(|idx| idx.checked_add(side))
+        .ok_or(Error::CapacityOverflow)
+}
+
+/// Get the highest heap-array index of the nodes, without recursion, so
+/// that a deep tree fails before its slots are allocated.
+/// # Errors
+/// Return `CapacityOverflow` Error when an index does not fit in `usize`.
+fn last_heap_index<T>(node: &Node<T>) -> Result<usize> {
+    let mut last = 0;
+    let mut stack = vec![(0, node)];
+    while let Some((idx, node)) = stack.pop() {
+        last = last.max(idx);
+        if let Some(left) = node.left() {
+            stack.push((child_index(idx, 1)?, left));
+        }
+        if let Some(right) = node.right() {
+            stack.push((child_index(idx, 2)?, right));
+        }
+    }
+    Ok(last)
+}
+
+/// Allocate `None` slots up to index `last`.
+/// # Errors
+/// Return `CapacityOverflow` Error when the slots cannot be allocated.
+fn empty_slots<T>(last: usize) -> Result<Vec<Option<T>>> {
+    let len = last.checked_add(1).ok_or(Error::CapacityOverflow)?;
+    let mut slots = Vec::new();
+    slots
+        .try_reserve_exact(len)
+        .map_err(|_| Error::CapacityOverflow)?;
+    slots.resize_with(len, || None);
+    Ok(slots)
+}
+
+/// Link heap-array slots into a tree, dropping the nodes without parent.
+fn build<T>(slots: Vec<Option<T>>) -> Option<Node<T>> {
+    let mut built: Vec<Option<Node<T>>> = slots.iter().map(|_| None).collect();
+    for (idx, data) in slots.into_iter().enumerate().rev() {
+        if let Some(data) = data {
+            let mut take = |child: usize| built.get_mut(child)?.take().map(Node::boxed);
+            let left = take(2 * idx + 1);
+            let right = take(2 * idx + 2);
+            built[idx] = Some(Node { data, left, right });
+        }
+    }
+    built.into_iter().next().flatten()
+}
+
+impl<T> Node<T> {
+    /// Create a tree from a level order array with holes, the heap-array
+    /// layout of [`FlatTree`]: the children of index `i` are at `2i + 1`
+    /// and `2i + 2`, and `None` marks a missing node.
+    ///
+    /// Return `None` when the root is missing. Values whose parent is
+    /// missing are dropped.
+    pub fn from_level_order(slots: Vec<Option<T>>) -> Option<Self> {
+        build(slots)
+    }
+}
+
+impl<T: Clone> Node<T> {
+    /// Get the tree as a level order array with holes, the inverse of
+    /// [`Node::from_level_order`]. The array ends at the last node, with
+    /// no trailing `None`.
+    ///
+    /// Degenerate trees need exponentially many slots.
+    /// # Errors
+    /// Return `CapacityOverflow` Error when the tree is too deep for the
+    /// slots to be indexed or allocated.
+    pub fn to_level_order_vec(&self) -> Result<Vec<Option<T>>> {
+        let mut slots = empty_slots(last_heap_index(self)?)?;
+        let mut stack = vec![(0, self)];
+        while let Some((idx, node)) = stack.pop() {
+            // The indices were checked above.
+            stack.extend(node.left().map(|left| (2 * idx + 1, left)));
+            stack.extend(node.right().map(|right| (2 * idx + 2, right)));
+            slots[idx] = Some(node.data.clone());
+        }
+        Ok(slots)
+    }
+}
+
+/// Level order traverse iterator of a [`FlatTree`].
+#[derive(Debug)]
+pub struct LevelOrderIter<'a, T> {
+    slots: std::iter::Enumerate<std::slice::Iter<'a, Option<T>>>,
+}
+
+impl<'a, T> Iterator for LevelOrderIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.find_map(|(idx, slot)| {
+            let level = (usize::BITS - 1 - (idx + 1).leading_zeros()) as usize;
+            slot.as_ref().map(|data| (level, data))
+        })
+    }
+}
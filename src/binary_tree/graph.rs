@@ -0,0 +1,38 @@
+use super::Node;
+use crate::Result;
+use petgraph::graph::{Graph, NodeIndex};
+
+impl<T> Node<T> {
+    /// Build a directed `petgraph` graph of the refs of the nodes, indexed
+    /// in pre order, with an edge from every parent to each of its
+    /// children weighted by whether it is a right child.
+    ///
+    /// See [`Node::to_edge_list`].
+    pub fn to_graph(&self) -> Graph<&T, bool> {
+        let (nodes, edges) = self.to_edge_list();
+        let mut graph = Graph::with_capacity(nodes.len(), edges.len());
+        for data in nodes {
+            graph.add_node(data);
+        }
+        for (parent, child, is_right) in edges {
+            graph.add_edge(NodeIndex::new(parent), NodeIndex::new(child), is_right);
+        }
+        graph
+    }
+
+    /// Build a tree from a directed `petgraph` graph with edges from
+    /// parents to children, weighted by whether they are right children
+    /// as in [`Node::to_graph`], without recursion.
+    /// # Errors
+    /// Return `MalformedTree` Error when the graph is not exactly one
+    /// binary tree over all its nodes. See [`Node::from_edge_list`].
+    pub fn try_from_graph(graph: Graph<T, bool>) -> Result<Node<T>> {
+        let (nodes, edges) = graph.into_nodes_edges();
+        let edges: Vec<_> = edges
+            .iter()
+            .map(|edge| (edge.source().index(), edge.target().index(), edge.weight))
+            .collect();
+        let nodes = nodes.into_iter().map(|node| node.weight).collect();
+        Self::from_edge_list(nodes, &edges)
+    }
+}
@@ -1,27 +1,25 @@
-use super::Node;
+use super::view::{self, LevelOrder, MidOrder, PostOrder, PreOrder};
+use super::{Node, Order};
 use std::collections::VecDeque;
-use std::marker::PhantomData;
+use std::ptr;
 
 /// Level order traverse iterator.
 #[derive(Debug)]
 pub struct LevelOrderIter<'a, T> {
-    last: *const Node<T>,
-    queue: VecDeque<*const Node<T>>,
-    marker: PhantomData<&'a Node<T>>,
+    last: &'a Node<T>,
+    queue: VecDeque<&'a Node<T>>,
     level: usize,
 }
 
 impl<'a, T> LevelOrderIter<'a, T> {
     /// Create a level order traverse iter.
     pub fn new(node: &'a Node<T>) -> Self {
-        let ptr = node as *const _;
         let mut queue = VecDeque::new();
-        queue.push_back(ptr);
+        queue.push_back(node);
         Self {
-            last: ptr,
+            last: node,
             queue,
             level: 0,
-            marker: PhantomData::default(),
         }
     }
 
@@ -36,32 +34,190 @@ impl<'a, T> Iterator for LevelOrderIter<'a, T> {
     type Item = (usize, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(ptr) = self.queue.pop_front() {
-            unsafe {
-                if let Some(node) = ptr.as_ref() {
-                    if let Some(left) = node.left() {
-                        self.queue.push_back(left as *const _);
-                    }
-                    if let Some(right) = node.right() {
-                        self.queue.push_back(right as *const _);
-                    }
-
-                    let level = self.level;
-
-                    // update the last pointer.
-                    if self.last == ptr {
-                        if let Some(last) = self.queue.back() {
-                            self.last = *last;
-                        }
-                        self.level += 1;
-                    }
-                    Some((level, node.data()))
-                } else {
-                    None
-                }
+        let node = self.queue.pop_front()?;
+        if let Some(left) = node.left() {
+            self.queue.push_back(left);
+        }
+        if let Some(right) = node.right() {
+            self.queue.push_back(right);
+        }
+
+        let level = self.level;
+
+        // update the last pointer.
+        if ptr::eq(self.last, node) {
+            if let Some(last) = self.queue.back() {
+                self.last = last;
             }
-        } else {
-            None
+            self.level += 1;
+        }
+        Some((level, node.data()))
+    }
+}
+
+/// Mid order iterator over the data of a tree.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    /// Create a mid order iterator over the tree rooted at `node`.
+    pub fn new(node: &'a Node<T>) -> Self {
+        Self {
+            stack: Vec::new(),
+            next: Some(node),
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            self.next = node.left();
+            self.stack.push(node);
+        }
+        let node = self.stack.pop()?;
+        self.next = node.right();
+        Some(node.data())
+    }
+}
+
+/// Mid order iterator over the mutable data of a tree.
+#[derive(Debug)]
+pub struct IterMut<'a, T> {
+    /// The data of every node on the path waiting to be visited, with its
+    /// right subtree.
+    stack: Vec<(&'a mut T, Option<&'a mut Node<T>>)>,
+    next: Option<&'a mut Node<T>>,
+}
+
+impl<'a, T> IterMut<'a, T> {
+    /// Create a mid order iterator over the tree rooted at `node`.
+    pub fn new(node: &'a mut Node<T>) -> Self {
+        Self {
+            stack: Vec::new(),
+            next: Some(node),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            let Node { data, left, right } = node;
+            self.next = left.as_deref_mut();
+            self.stack.push((data, right.as_deref_mut()));
+        }
+        let (data, right) = self.stack.pop()?;
+        self.next = right;
+        Some(data)
+    }
+}
+
+/// Mid order iterator moving the data out of a tree.
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    stack: Vec<Node<T>>,
+    next: Option<Node<T>>,
+}
+
+impl<T> IntoIter<T> {
+    /// Create a mid order iterator consuming the tree rooted at `node`.
+    pub fn new(node: Node<T>) -> Self {
+        Self {
+            stack: Vec::new(),
+            next: Some(node),
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(mut node) = self.next.take() {
+            self.next = node.left.take().map(|left| *left);
+            self.stack.push(node);
+        }
+        let mut node = self.stack.pop()?;
+        self.next = node.right.take().map(|right| *right);
+        Some(node.into_data())
+    }
+}
+
+/// Pre order iterator that does not descend below a depth.
+#[derive(Debug)]
+pub struct DepthLimitedIter<'a, T> {
+    stack: Vec<(usize, &'a Node<T>)>,
+    max_depth: usize,
+}
+
+impl<'a, T> DepthLimitedIter<'a, T> {
+    /// Create a pre order iterator over the nodes of the tree rooted at
+    /// `node` at most `max_depth` levels below it.
+    pub fn new(node: &'a Node<T>, max_depth: usize) -> Self {
+        Self {
+            stack: vec![(0, node)],
+            max_depth,
+        }
+    }
+}
+
+impl<'a, T> Iterator for DepthLimitedIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+        if depth < self.max_depth {
+            self.stack
+                .extend(node.right().map(|right| (depth + 1, right)));
+            self.stack.extend(node.left().map(|left| (depth + 1, left)));
+        }
+        Some((depth, node.data()))
+    }
+}
+
+/// Iterator over the subtrees of a tree, i.e. the refs of its nodes, in a
+/// chosen [`Order`].
+#[derive(Debug)]
+pub struct Subtrees<'a, T>(SubtreesInner<'a, T>);
+
+#[derive(Debug)]
+enum SubtreesInner<'a, T> {
+    Pre(PreOrder<&'a Node<T>>),
+    Mid(MidOrder<&'a Node<T>>),
+    Post(PostOrder<&'a Node<T>>),
+    Level(LevelOrder<&'a Node<T>>),
+}
+
+impl<'a, T> Subtrees<'a, T> {
+    /// Create an iterator over the subtrees of the tree rooted at `node`,
+    /// the whole tree included.
+    pub fn new(node: &'a Node<T>, order: Order) -> Self {
+        Self(match order {
+            Order::Pre => SubtreesInner::Pre(view::pre_order(node)),
+            Order::Mid => SubtreesInner::Mid(view::mid_order(node)),
+            Order::Post => SubtreesInner::Post(view::post_order(node)),
+            Order::Level => SubtreesInner::Level(view::level_order(node)),
+        })
+    }
+}
+
+impl<'a, T> Iterator for Subtrees<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            SubtreesInner::Pre(iter) => iter.next(),
+            SubtreesInner::Mid(iter) => iter.next(),
+            SubtreesInner::Post(iter) => iter.next(),
+            SubtreesInner::Level(iter) => iter.next().map(|(_, node)| node),
         }
     }
 }
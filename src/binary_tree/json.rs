@@ -0,0 +1,424 @@
+use super::{Link, Node};
+use crate::{Error, Result};
+
+/// Data that can be written as a JSON scalar and read back.
+pub trait JsonData: Sized {
+    /// Append the JSON literal of the value to `out`.
+    fn write_json(&self, out: &mut String);
+
+    /// Read a value from a JSON literal, quotes included for strings.
+    /// Return `None` if the literal does not describe a value.
+    fn read_json(literal: &str) -> Option<Self>;
+}
+
+macro_rules! json_number {
+    ($($ty:ty),*) => {
+        $(
+            impl JsonData for $ty {
+                fn write_json(&self, out: &mut String) {
+                    out.push_str(&self.to_string());
+                }
+
+                fn read_json(literal: &str) -> Option<Self> {
+                    literal.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+json_number!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! json_float {
+    ($($ty:ty),*) => {
+        $(
+            impl JsonData for $ty {
+                /// Non-finite values have no JSON literal and are written as `null`.
+                fn write_json(&self, out: &mut String) {
+                    if self.is_finite() {
+                        out.push_str(&format!("{:?}", self));
+                    } else {
+                        out.push_str("null");
+                    }
+                }
+
+                fn read_json(literal: &str) -> Option<Self> {
+                    let first = literal.chars().next()?;
+                    if first == '-' || first.is_ascii_digit() {
+                        literal.parse().ok()
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+json_float!(f32, f64);
+
+impl JsonData for bool {
+    fn write_json(&self, out: &mut String) {
+        out.push_str(if *self { "true" } else { "false" });
+    }
+
+    fn read_json(literal: &str) -> Option<Self> {
+        match literal {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl JsonData for String {
+    fn write_json(&self, out: &mut String) {
+        out.push('"');
+        for c in self.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+
+    fn read_json(literal: &str) -> Option<Self> {
+        let inner = literal.strip_prefix('"')?.strip_suffix('"')?;
+        let mut value = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            let escaped = match chars.next()? {
+                '"' => '"',
+                '\\' => '\\',
+                '/' => '/',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'u' => {
+                    let high = hex4(&mut chars)?;
+                    if (0xd800..0xdc00).contains(&high) {
+                        if chars.next()? != '\\' || chars.next()? != 'u' {
+                            return None;
+                        }
+                        let low = hex4(&mut chars)?;
+                        if !(0xdc00..0xe000).contains(&low) {
+                            return None;
+                        }
+                        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))?
+                    } else {
+                        char::from_u32(high)?
+                    }
+                }
+                _ => return None,
+            };
+            value.push(escaped);
+        }
+        Some(value)
+    }
+}
+
+/// Read the four hex digits of a `\u` escape.
+fn hex4(chars: &mut std::str::Chars<'_>) -> Option<u32> {
+    let digits: String = chars.take(4).collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+enum Step<'a, T> {
+    Value(Option<&'a Node<T>>, usize),
+    Key(&'static str, usize),
+    Close(usize),
+}
+
+/// Write `node` without recursion, indenting by two spaces if `pretty`.
+fn write<T: JsonData>(node: &Node<T>, pretty: bool) -> String {
+    let mut out = String::new();
+    let newline = |out: &mut String, depth: usize| {
+        if pretty {
+            out.push('\n');
+            out.push_str(&"  ".repeat(depth));
+        }
+    };
+    let colon = if pretty { ": " } else { ":" };
+    let mut stack = vec![Step::Value(Some(node), 0)];
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Value(None, _) => out.push_str("null"),
+            Step::Value(Some(node), depth) => {
+                out.push('{');
+                newline(&mut out, depth + 1);
+                out.push_str("\"data\"");
+                out.push_str(colon);
+                node.data.write_json(&mut out);
+                stack.push(Step::Close(depth));
+                stack.push(Step::Value(node.right(), depth + 1));
+                stack.push(Step::Key("right", depth + 1));
+                stack.push(Step::Value(node.left(), depth + 1));
+                stack.push(Step::Key("left", depth + 1));
+            }
+            Step::Key(key, depth) => {
+                out.push(',');
+                newline(&mut out, depth);
+                out.push('"');
+                out.push_str(key);
+                out.push('"');
+                out.push_str(colon);
+            }
+            Step::Close(depth) => {
+                newline(&mut out, depth);
+                out.push('}');
+            }
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Key {
+    Data,
+    Left,
+    Right,
+}
+
+/// An object being read.
+struct Frame<T> {
+    start: usize,
+    data: Option<T>,
+    left: Option<Link<T>>,
+    right: Option<Link<T>>,
+    /// The child the nested object being read belongs to.
+    pending: Key,
+}
+
+impl<T> Frame<T> {
+    fn new(start: usize) -> Self {
+        Self {
+            start,
+            data: None,
+            left: None,
+            right: None,
+            pending: Key::Data,
+        }
+    }
+
+    fn has(&self, key: Key) -> bool {
+        match key {
+            Key::Data => self.data.is_some(),
+            Key::Left => self.left.is_some(),
+            Key::Right => self.right.is_some(),
+        }
+    }
+
+    fn set_child(&mut self, key: Key, child: Link<T>) {
+        match key {
+            Key::Left => self.left = Some(child),
+            Key::Right => self.right = Some(child),
+            Key::Data => unreachable!("data is never a nested object"),
+        }
+    }
+}
+
+/// Reader of JSON-like input, keeping open objects on a stack rather
+/// than recursing.
+pub(super) struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(super) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn read<T: JsonData>(mut self) -> Result<Node<T>> {
+        self.expect('{')?;
+        let mut frames = vec![Frame::new(self.position - 1)];
+        let mut first = true;
+        loop {
+            let frame = frames.last_mut().expect("an object is open");
+            let closed = if first {
+                self.eat('}')
+            } else if self.eat(',') {
+                false
+            } else if self.eat('}') {
+                true
+            } else {
+                return Err(self.error("expected `,` or `}`"));
+            };
+            first = false;
+
+            if closed {
+                let frame = frames.pop().expect("an object is open");
+                let start = frame.start;
+                let data = frame.data.ok_or_else(|| Error::Parse {
+                    position: start,
+                    message: "missing `data`".to_owned(),
+                })?;
+                let node = Node {
+                    data,
+                    left: frame.left.flatten(),
+                    right: frame.right.flatten(),
+                };
+                match frames.last_mut() {
+                    Some(parent) => {
+                        let key = parent.pending;
+                        parent.set_child(key, Some(node.boxed()));
+                    }
+                    None => {
+                        self.finish()?;
+                        return Ok(node);
+                    }
+                }
+                continue;
+            }
+
+            self.skip_whitespace();
+            let key_start = self.position;
+            let key = match self.literal()? {
+                "\"data\"" => Key::Data,
+                "\"left\"" => Key::Left,
+                "\"right\"" => Key::Right,
+                _ => {
+                    self.position = key_start;
+                    return Err(self.error("expected `data`, `left` or `right`"));
+                }
+            };
+            if frame.has(key) {
+                self.position = key_start;
+                return Err(self.error("duplicate key"));
+            }
+            self.expect(':')?;
+            self.skip_whitespace();
+            let value_start = self.position;
+            if key == Key::Data {
+                let literal = self.literal()?;
+                let data = T::read_json(literal).ok_or_else(|| Error::Parse {
+                    position: value_start,
+                    message: "invalid data".to_owned(),
+                })?;
+                frame.data = Some(data);
+            } else if self.eat('{') {
+                frame.pending = key;
+                frames.push(Frame::new(value_start));
+                first = true;
+            } else if self.literal()? == "null" {
+                frame.set_child(key, None);
+            } else {
+                self.position = value_start;
+                return Err(self.error("expected an object or `null`"));
+            }
+        }
+    }
+
+    /// Make sure only whitespace is left.
+    pub(super) fn finish(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        if self.position < self.input.len() {
+            Err(self.error("unexpected trailing input"))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn position(&self) -> usize {
+        self.position
+    }
+
+    pub(super) fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip whitespace and consume `c` if it comes next.
+    pub(super) fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.input[self.position..].starts_with(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(super) fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", c)))
+        }
+    }
+
+    /// Consume a string literal or a bare scalar such as a number.
+    pub(super) fn literal(&mut self) -> Result<&'a str> {
+        let input = self.input;
+        let rest = &input[self.position..];
+        let len = if let Some(string) = rest.strip_prefix('"') {
+            let mut escaped = false;
+            let end = string.find(|c| {
+                let end = !escaped && c == '"';
+                escaped = !escaped && c == '\\';
+                end
+            });
+            match end {
+                Some(end) => end + 2,
+                None => return Err(self.error("unterminated string")),
+            }
+        } else {
+            rest.find(|c: char| c.is_whitespace() || ",:{}[]\"".contains(c))
+                .unwrap_or(rest.len())
+        };
+        if len == 0 {
+            return Err(self.error("expected a value"));
+        }
+        self.position += len;
+        Ok(&rest[..len])
+    }
+
+    pub(super) fn error(&self, message: &str) -> Error {
+        Error::Parse {
+            position: self.position,
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl<T: JsonData> Node<T> {
+    /// Write the tree as compact nested JSON, without recursion.
+    ///
+    /// Every node is an object `{"data":...,"left":...,"right":...}` with
+    /// keys in this order, a missing child being `null`.
+    pub fn to_json(&self) -> String {
+        write(self, false)
+    }
+
+    /// Write the tree as nested JSON indented by two spaces per level.
+    /// See [`Node::to_json`].
+    pub fn to_json_pretty(&self) -> String {
+        write(self, true)
+    }
+
+    /// Read a tree from nested JSON as written by [`Node::to_json`] or
+    /// [`Node::to_json_pretty`], without recursion.
+    ///
+    /// Keys may come in any order and `left` and `right` may be omitted.
+    /// # Errors
+    /// Return `Parse` Error when the input is not such an object, or some
+    /// data is not a valid literal of `T`.
+    pub fn from_json(input: &str) -> Result<Self> {
+        Reader::new(input).read()
+    }
+}
@@ -0,0 +1,119 @@
+use super::json::{JsonData, Reader};
+use super::Node;
+use crate::{Error, Result};
+use std::collections::VecDeque;
+
+impl<T: JsonData> Node<T> {
+    /// Read a tree from its LeetCode form such as `"[1,2,3,null,5]"`: the
+    /// level order list of the children of every present node, a missing
+    /// child being `null` and trailing `null`s being optional.
+    /// Return `Ok(None)` for the empty tree `"[]"`.
+    /// # Errors
+    /// Return `Parse` Error when the input is not such a list, the root is
+    /// `null`, or some value other than `null` is left without a parent.
+    pub fn from_leetcode(input: &str) -> Result<Option<Self>> {
+        let mut reader = Reader::new(input);
+        reader.expect('[')?;
+        let mut values = Vec::new();
+        if !reader.eat(']') {
+            loop {
+                reader.skip_whitespace();
+                let position = reader.position();
+                let literal = reader.literal()?;
+                let value = if literal == "null" {
+                    None
+                } else {
+                    let data = T::read_json(literal).ok_or_else(|| Error::Parse {
+                        position,
+                        message: "invalid data".to_owned(),
+                    })?;
+                    Some(data)
+                };
+                values.push((value, position));
+                if reader.eat(']') {
+                    break;
+                }
+                reader.expect(',')?;
+            }
+        }
+        reader.finish()?;
+
+        let mut values = values.into_iter();
+        let root = match values.next() {
+            Some((Some(root), _)) => root,
+            Some((None, position)) => {
+                return Err(Error::Parse {
+                    position,
+                    message: "the root cannot be `null`".to_owned(),
+                })
+            }
+            None => return Ok(None),
+        };
+
+        // Present nodes are numbered in level order, which is also the
+        // order in which they receive their children.
+        let mut items = vec![Some(root)];
+        let mut children: Vec<(Option<usize>, Option<usize>)> = vec![(None, None)];
+        let (mut parent, mut right) = (0, false);
+        for (value, position) in values {
+            if parent == items.len() {
+                if value.is_none() {
+                    continue;
+                }
+                return Err(Error::Parse {
+                    position,
+                    message: "value has no parent".to_owned(),
+                });
+            }
+            if let Some(value) = value {
+                let child = Some(items.len());
+                if right {
+                    children[parent].1 = child;
+                } else {
+                    children[parent].0 = child;
+                }
+                items.push(Some(value));
+                children.push((None, None));
+            }
+            if right {
+                parent += 1;
+            }
+            right = !right;
+        }
+
+        let mut nodes = Vec::with_capacity(items.len());
+        let mut stack = vec![0];
+        while let Some(idx) = stack.pop() {
+            let (left, right) = children[idx];
+            let data = items[idx].take().expect("every node is visited once");
+            nodes.push((data, left.is_some(), right.is_some()));
+            stack.extend(right);
+            stack.extend(left);
+        }
+        Node::from_pre_order_shape(nodes).map(Some)
+    }
+
+    /// Write the tree in its LeetCode form, without trailing `null`s.
+    /// See [`Node::from_leetcode`].
+    pub fn to_leetcode(&self) -> String {
+        let mut values = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(Some(self));
+        while let Some(node) = queue.pop_front() {
+            let mut value = String::new();
+            match node {
+                Some(node) => {
+                    node.data.write_json(&mut value);
+                    queue.push_back(node.left());
+                    queue.push_back(node.right());
+                }
+                None => value.push_str("null"),
+            }
+            values.push(value);
+        }
+        while values.last().is_some_and(|value| value == "null") {
+            values.pop();
+        }
+        format!("[{}]", values.join(","))
+    }
+}
@@ -1,15 +1,69 @@
+use crate::datasize::DataSize;
 use crate::{Error, Result};
 use std::collections::VecDeque;
 use std::fmt;
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
 
 /// Binary tree iter.
 pub mod iter;
 
+/// Binary search tree.
+pub mod bst;
+
+/// Set operations on binary search trees.
+pub mod set_ops;
+
+/// Binary trees with cached subtree aggregates.
+pub mod augment;
+
+/// Arena-backed binary tree.
+pub mod arena;
+
+/// Heap-array binary tree layout.
+pub mod flat;
+
+/// Shared-ownership binary tree.
+pub mod rc;
+
+/// Persistent binary tree with structural sharing.
+pub mod arc;
+
+/// Storage-independent traversals.
+pub mod view;
+
+/// Node box recycling.
+pub mod pool;
+
+/// Nested JSON format.
+pub mod json;
+
+/// Compact binary format.
+pub mod compact;
+
+mod leetcode;
+
+/// Display adapters.
+pub mod display;
+
+/// Shape statistics.
+pub mod stats;
+
+mod construct;
+
+mod fingerprint;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "petgraph")]
+mod graph;
+
 type Link<T> = Option<BoxedNode<T>>;
 type BoxedNode<T> = Box<Node<T>>;
 
 /// Binary tree node.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Node<T> {
     data: T,
     left: Link<T>,
@@ -51,157 +105,452 @@ impl<T> Node<T> {
         &self.data
     }
 
+    /// Get the mutable ref of left child.
+    pub fn left_mut(&mut self) -> Option<&mut Node<T>> {
+        self.left.as_deref_mut()
+    }
+
+    /// Get the mutable ref of right child.
+    pub fn right_mut(&mut self) -> Option<&mut Node<T>> {
+        self.right.as_deref_mut()
+    }
+
+    /// Get the mutable ref of the containing data.
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Get the mutable refs of the data and both children at once.
+    pub fn parts_mut(&mut self) -> (&mut T, Option<&mut Node<T>>, Option<&mut Node<T>>) {
+        (
+            &mut self.data,
+            self.left.as_deref_mut(),
+            self.right.as_deref_mut(),
+        )
+    }
+
+    /// Convert into the containing data, dropping the children.
+    pub fn into_data(mut self) -> T {
+        drop(self.left.take());
+        drop(self.right.take());
+        let node = ManuallyDrop::new(self);
+        // SAFETY: `node` is never dropped and its links are already empty,
+        // so the data is moved out exactly once and nothing is leaked.
+        unsafe { ptr::read(&node.data) }
+    }
+
+    /// Replace the left child, returning the old one.
+    pub fn set_left(&mut self, node: Option<Node<T>>) -> Option<Node<T>> {
+        std::mem::replace(&mut self.left, node.map(Node::boxed)).map(|node| *node)
+    }
+
+    /// Replace the right child, returning the old one.
+    pub fn set_right(&mut self, node: Option<Node<T>>) -> Option<Node<T>> {
+        std::mem::replace(&mut self.right, node.map(Node::boxed)).map(|node| *node)
+    }
+
+    /// Replace the left child with an already boxed node,
+    /// returning the old one without unboxing it.
+    pub fn set_left_boxed(&mut self, node: Option<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        std::mem::replace(&mut self.left, node)
+    }
+
+    /// Replace the right child with an already boxed node,
+    /// returning the old one without unboxing it.
+    pub fn set_right_boxed(&mut self, node: Option<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        std::mem::replace(&mut self.right, node)
+    }
+
     /// Create a level order traverse iterator
     /// use this node as root.
-    pub fn level_order_iter(&self) -> iter::LevelOrderIter<T> {
+    pub fn level_order_iter(&self) -> iter::LevelOrderIter<'_, T> {
         iter::LevelOrderIter::new(self)
     }
+
+    /// Create a pre order iterator over the data with its depth, not
+    /// descending more than `max_depth` levels below this node.
+    pub fn iter_to_depth(&self, max_depth: usize) -> iter::DepthLimitedIter<'_, T> {
+        iter::DepthLimitedIter::new(self, max_depth)
+    }
+
+    /// Create an iterator over the subtrees, i.e. the refs of this node and
+    /// of all its descendants, in `order` and without recursion.
+    pub fn subtrees(&self, order: Order) -> iter::Subtrees<'_, T> {
+        iter::Subtrees::new(self, order)
+    }
+
+    /// Create a mid order iterator over the data, the order used by
+    /// `for` loops over a tree.
+    pub fn iter(&self) -> iter::Iter<'_, T> {
+        iter::Iter::new(self)
+    }
+
+    /// Create a mid order iterator over the mutable data.
+    pub fn iter_mut(&mut self) -> iter::IterMut<'_, T> {
+        iter::IterMut::new(self)
+    }
 }
 
-impl<T> Node<T> {
-    /// Pre order map.
-    pub fn pre_order_map<U, F>(self, mut f: F) -> Node<U>
-    where
-        F: FnMut(T) -> U,
-    {
-        self.pre_order_map_inner(&mut f)
+impl<T> IntoIterator for Node<T> {
+    type Item = T;
+    type IntoIter = iter::IntoIter<T>;
+
+    /// Move the data out in mid order.
+    fn into_iter(self) -> Self::IntoIter {
+        iter::IntoIter::new(self)
     }
+}
 
-    fn pre_order_map_inner<U, F>(self, f: &mut F) -> Node<U>
-    where
-        F: FnMut(T) -> U,
-    {
-        Node {
-            data: f(self.data),
-            left: self.left.map(|node| node.pre_order_map_inner(f).boxed()),
-            right: self.right.map(|node| node.pre_order_map_inner(f).boxed()),
-        }
+impl<'a, T> IntoIterator for &'a Node<T> {
+    type Item = &'a T;
+    type IntoIter = iter::Iter<'a, T>;
+
+    /// Iterate over the data in mid order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Node<T> {
+    type Item = &'a mut T;
+    type IntoIter = iter::IterMut<'a, T>;
+
+    /// Iterate over the mutable data in mid order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
+/// Order in which a traversal visits the nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Every node before its left then its right subtree.
+    Pre,
+    /// The left subtree, then the node, then the right subtree.
+    Mid,
+    /// The left then the right subtree before the node.
+    Post,
+    /// Level by level from the root, from left to right.
+    Level,
+}
+
+/// A node waiting to be flattened, with its parent index and side.
+type Pending<T> = (Node<T>, Option<(usize, bool)>);
+
 impl<T> Node<T> {
-    /// Mid order map.
-    pub fn mid_order_map<U, F>(self, mut f: F) -> Node<U>
+    /// Pre order map.
+    pub fn pre_order_map<U, F>(self, f: F) -> Node<U>
     where
         F: FnMut(T) -> U,
     {
-        self.mid_order_map_inner(&mut f)
+        self.map_in(Order::Pre, f)
     }
 
-    fn mid_order_map_inner<U, F>(self, f: &mut F) -> Node<U>
+    /// Mid order map.
+    pub fn mid_order_map<U, F>(self, f: F) -> Node<U>
     where
         F: FnMut(T) -> U,
     {
-        Node {
-            left: self.left.map(|node| node.mid_order_map_inner(f).boxed()),
-            data: f(self.data),
-            right: self.right.map(|node| node.mid_order_map_inner(f).boxed()),
-        }
+        self.map_in(Order::Mid, f)
     }
-}
 
-impl<T> Node<T> {
     /// Post order map.
-    pub fn post_order_map<U, F>(self, mut f: F) -> Node<U>
+    pub fn post_order_map<U, F>(self, f: F) -> Node<U>
     where
         F: FnMut(T) -> U,
     {
-        self.post_order_map_inner(&mut f)
+        self.map_in(Order::Post, f)
     }
 
-    fn post_order_map_inner<U, F>(self, f: &mut F) -> Node<U>
+    /// Map with explicit stacks, so that degenerate trees do not recurse
+    /// once per level.
+    fn map_in<U, F>(self, order: Order, mut f: F) -> Node<U>
     where
         F: FnMut(T) -> U,
     {
-        Node {
-            left: self.left.map(|node| node.post_order_map_inner(f).boxed()),
-            right: self.right.map(|node| node.post_order_map_inner(f).boxed()),
-            data: f(self.data),
+        // Flatten in pre order, recording the children of every node.
+        let mut items = Vec::new();
+        let mut children: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+        let mut stack: Vec<Pending<T>> = vec![(self, None)];
+        while let Some((mut node, parent)) = stack.pop() {
+            let idx = items.len();
+            match parent {
+                Some((parent, true)) => children[parent].1 = Some(idx),
+                Some((parent, false)) => children[parent].0 = Some(idx),
+                None => {}
+            }
+            let (left, right) = (node.left.take(), node.right.take());
+            stack.extend(right.map(|right| (*right, Some((idx, true)))));
+            stack.extend(left.map(|left| (*left, Some((idx, false)))));
+            items.push(Some(node.into_data()));
+            children.push((None, None));
         }
-    }
-}
 
-impl<T: fmt::Display> fmt::Display for Node<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let ptr = self as *const _;
-        let mut last = ptr;
-        let mut back = ptr;
-        let mut max_width = 1;
-        let mut queue: VecDeque<Option<*const Self>> = VecDeque::new();
-        let mut levels = Vec::new();
-        let mut level = Vec::new();
-        queue.push_back(Some(ptr));
-
-        while let Some(ptr) = queue.pop_front() {
-            if let Some(ptr) = ptr {
-                unsafe {
-                    if let Some(node) = ptr.as_ref() {
-                        queue.push_back(node.left().map(|node| {
-                            let ptr = node as *const _;
-                            back = ptr;
-                            ptr
-                        }));
-                        queue.push_back(node.right().map(|node| {
-                            let ptr = node as *const _;
-                            back = ptr;
-                            ptr
-                        }));
-                        let c = format!("{}", node.data);
-                        if c.len() > max_width {
-                            max_width = c.len();
-                        }
-                        level.push(c);
-                        if last == ptr {
-                            last = back;
-                            levels.push(level);
-                            level = Vec::new();
+        let mut mapped: Vec<Option<U>> = items.iter().map(|_| None).collect();
+        let mut apply = |idx: usize| {
+            let data = items[idx].take().expect("every node is mapped once");
+            mapped[idx] = Some(f(data));
+        };
+        match order {
+            Order::Pre => (0..children.len()).for_each(&mut apply),
+            Order::Level => {
+                let mut queue = VecDeque::new();
+                queue.push_back(0);
+                while let Some(idx) = queue.pop_front() {
+                    apply(idx);
+                    queue.extend(children[idx].0);
+                    queue.extend(children[idx].1);
+                }
+            }
+            Order::Mid => {
+                let mut stack = Vec::new();
+                let mut next = Some(0);
+                loop {
+                    while let Some(idx) = next {
+                        stack.push(idx);
+                        next = children[idx].0;
+                    }
+                    match stack.pop() {
+                        Some(idx) => {
+                            apply(idx);
+                            next = children[idx].1;
                         }
+                        None => break,
+                    }
+                }
+            }
+            Order::Post => {
+                let mut stack = vec![(0, false)];
+                while let Some((idx, expanded)) = stack.pop() {
+                    if expanded {
+                        apply(idx);
+                    } else {
+                        stack.push((idx, true));
+                        stack.extend(children[idx].1.map(|right| (right, false)));
+                        stack.extend(children[idx].0.map(|left| (left, false)));
                     }
                 }
-            } else {
-                level.push(" ".to_owned());
             }
         }
-        let len = levels.len();
-        let blank = " ".repeat(max_width);
-        for (idx, level) in levels.iter().enumerate() {
-            let left = (1 << (len - idx - 1)) - 1;
-            let mid = (1 << (len - idx)) - 1;
-            if idx > 0 {
-                for i in (0..(1 << (len - idx - 1))).rev() {
-                    let left_space = blank.repeat(left + i);
-                    let mid_space = blank.repeat(mid - 2 * i);
-                    let right_space = blank.repeat(mid + 2 * i);
-                    write!(f, "{}", left_space)?;
-                    for (idx, c) in level.iter().enumerate() {
-                        let branch = if idx % 2 == 0 {
-                            let tag = if c != " " { "/" } else { " " };
-                            format!("{:^width$}", tag, width = max_width)
-                        } else {
-                            let tag = if c != " " { r"\" } else { " " };
-                            format!(
-                                "{}{:^width$}{}",
-                                mid_space,
-                                tag,
-                                right_space,
-                                width = max_width
-                            )
-                        };
-                        write!(f, "{}", branch)?;
+
+        let nodes = mapped
+            .into_iter()
+            .zip(children)
+            .map(|(data, (left, right))| {
+                let data = data.expect("every node is mapped");
+                (data, left.is_some(), right.is_some())
+            })
+            .collect();
+        Node::from_pre_order_shape(nodes).expect("the shape is well-formed")
+    }
+
+    /// Move the data out in the given order, without recursion.
+    pub fn flatten(self, order: Order) -> Vec<T> {
+        match order {
+            Order::Pre => self
+                .into_pre_order_shape()
+                .into_iter()
+                .map(|(data, _, _)| data)
+                .collect(),
+            Order::Mid => self.into_iter().collect(),
+            Order::Post => {
+                let mut items = Vec::new();
+                let mut stack = vec![(self, false)];
+                while let Some((mut node, expanded)) = stack.pop() {
+                    if expanded {
+                        items.push(node.into_data());
+                        continue;
                     }
-                    writeln!(f)?;
+                    let (left, right) = (node.left.take(), node.right.take());
+                    stack.push((node, true));
+                    stack.extend(right.map(|right| (*right, false)));
+                    stack.extend(left.map(|left| (*left, false)));
+                }
+                items
+            }
+            Order::Level => {
+                let mut items = Vec::new();
+                let mut queue = VecDeque::new();
+                queue.push_back(self);
+                while let Some(mut node) = queue.pop_front() {
+                    queue.extend(node.left.take().map(|left| *left));
+                    queue.extend(node.right.take().map(|right| *right));
+                    items.push(node.into_data());
                 }
+                items
             }
+        }
+    }
 
-            let left_space = blank.repeat(left);
-            let space = blank.repeat(mid);
-            write!(f, "{}", left_space)?;
-            for c in level.iter() {
-                write!(f, "{:^width$}{}", c, space, width = max_width)?;
+    /// Copy the first `n` nodes in level order into a new tree, e.g. to
+    /// preview a huge tree, without visiting the others. Return `None` if
+    /// `n` is `0`.
+    pub fn take_nodes(&self, n: usize) -> Option<Node<T>>
+    where
+        T: Clone,
+    {
+        // Copy in level order with parent links, then build bottom-up.
+        let mut taken: Vec<(T, Option<(usize, bool)>)> = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((self, None));
+        while let Some((node, parent)) = queue.pop_front() {
+            if taken.len() == n {
+                break;
             }
-            writeln!(f)?;
+            let idx = taken.len();
+            taken.push((node.data.clone(), parent));
+            queue.extend(node.left().map(|left| (left, Some((idx, false)))));
+            queue.extend(node.right().map(|right| (right, Some((idx, true)))));
+        }
+        // Children come after their parent, so going backwards every node
+        // is complete when it is attached.
+        let mut links: Vec<(Link<T>, Link<T>)> = taken.iter().map(|_| (None, None)).collect();
+        let mut root = None;
+        for (idx, (data, parent)) in taken.into_iter().enumerate().rev() {
+            let (left, right) = mem::take(&mut links[idx]);
+            let node = Node { data, left, right };
+            match parent {
+                Some((parent, false)) => links[parent].0 = Some(node.boxed()),
+                Some((parent, true)) => links[parent].1 = Some(node.boxed()),
+                None => root = Some(node),
+            }
+        }
+        root
+    }
+
+    /// Reduce the tree bottom-up: `f` gets the data of every node with the
+    /// results of its left and right subtrees, `None` for missing ones,
+    /// and the result of the root is returned. Runs without recursion,
+    /// visiting the nodes in post order.
+    pub fn fold_tree<R, F>(&self, mut f: F) -> R
+    where
+        F: FnMut(&T, Option<R>, Option<R>) -> R,
+    {
+        let mut results: Vec<R> = Vec::new();
+        let mut stack = vec![(self, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if expanded {
+                let right = node.right.as_ref().and_then(|_| results.pop());
+                let left = node.left.as_ref().and_then(|_| results.pop());
+                results.push(f(&node.data, left, right));
+            } else {
+                stack.push((node, true));
+                stack.extend(node.right().map(|right| (right, false)));
+                stack.extend(node.left().map(|left| (left, false)));
+            }
+        }
+        results.pop().expect("the root is folded last")
+    }
+
+    /// Rewire the tree in place into a chain of right children holding the
+    /// data in pre order, in `O(n)` time without allocating.
+    ///
+    /// Every left subtree is moved between its parent and the right
+    /// subtree, hanging the latter off the last node of its right spine.
+    pub fn flatten_to_right_spine(&mut self) {
+        let mut node = self;
+        loop {
+            if let Some(mut left) = node.left.take() {
+                let mut tail = &mut *left;
+                while tail.right.is_some() {
+                    tail = tail.right.as_deref_mut().expect("checked above");
+                }
+                tail.right = node.right.take();
+                node.right = Some(left);
+            }
+            match node.right.as_deref_mut() {
+                Some(right) => node = right,
+                None => break,
+            }
+        }
+    }
+
+    /// Rotate the tree left in place: the right child becomes the root,
+    /// with the old root as its left child taking over its left subtree.
+    /// The mid order is kept and no data is cloned.
+    /// # Errors
+    /// Return `MissingChild` Error when there is no right child.
+    pub fn rotate_left(&mut self) -> Result<()> {
+        let mut pivot = self.right.take().ok_or(Error::MissingChild)?;
+        self.right = pivot.left.take();
+        mem::swap(self, &mut *pivot);
+        self.left = Some(pivot);
+        Ok(())
+    }
+
+    /// Rotate the tree right in place: the left child becomes the root,
+    /// with the old root as its right child taking over its right subtree.
+    /// The mid order is kept and no data is cloned.
+    /// # Errors
+    /// Return `MissingChild` Error when there is no left child.
+    pub fn rotate_right(&mut self) -> Result<()> {
+        let mut pivot = self.left.take().ok_or(Error::MissingChild)?;
+        self.left = pivot.right.take();
+        mem::swap(self, &mut *pivot);
+        self.right = Some(pivot);
+        Ok(())
+    }
+}
+
+impl<T: Clone> Clone for Node<T> {
+    /// Clone the tree with an explicit stack, so that cloning a degenerate
+    /// tree does not recurse once per level.
+    fn clone(&self) -> Self {
+        let nodes = self
+            .to_pre_order_shape()
+            .into_iter()
+            .map(|(data, has_left, has_right)| (data.clone(), has_left, has_right))
+            .collect();
+        Node::from_pre_order_shape(nodes).expect("the shape is well-formed")
+    }
+}
+
+impl<T: DataSize> DataSize for Node<T> {
+    /// Count the boxes of the descendants and the heap data of every node,
+    /// without recursion. The node itself is not counted.
+    fn heap_size(&self) -> usize {
+        let mut size = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            size += node.data.heap_size();
+            for child in node.left().into_iter().chain(node.right()) {
+                size += mem::size_of::<Node<T>>();
+                stack.push(child);
+            }
+        }
+        size
+    }
+}
+
+impl<T> Drop for Node<T> {
+    /// Unlink the descendants into a worklist, so that dropping a
+    /// degenerate tree does not recurse once per level.
+    fn drop(&mut self) {
+        if self.left.is_none() && self.right.is_none() {
+            return;
+        }
+        let mut stack: Vec<BoxedNode<T>> = Vec::new();
+        stack.extend(self.left.take());
+        stack.extend(self.right.take());
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Node<T> {
+    /// Draw the tree top-down with a tidy layout, or with box-drawing
+    /// connectors when the alternate flag `{:#}` is set (see
+    /// [`Node::display_compact`]). A precision such as `{:.8}` cuts every
+    /// label to that many columns.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let format = display::TreeFormatter::new().max_label_width(f.precision());
+            write!(f, "{}", self.display_with(format))
+        } else {
+            display::top_down(self, f)
         }
-        write!(f, "")
     }
 }
 
@@ -0,0 +1,106 @@
+use super::{Node, Order};
+
+impl<T: Send> Node<T> {
+    /// Map the data, processing independent subtrees on the rayon thread
+    /// pool.
+    ///
+    /// The top levels of the tree are split with `rayon::join` into about
+    /// two tasks per pool thread, each mapping its subtree without
+    /// recursion, and the results are joined at every split node. Only
+    /// worth it when `f` is expensive; the order in which the data is
+    /// mapped is unspecified.
+    pub fn par_map<U, F>(self, f: F) -> Node<U>
+    where
+        U: Send,
+        F: Fn(T) -> U + Sync,
+    {
+        map_split(self, &f, split_depth())
+    }
+}
+
+impl<T: Sync> Node<T> {
+    /// Fold the tree bottom-up, processing independent subtrees on the
+    /// rayon thread pool.
+    ///
+    /// `f` gets the data of every node with the folds of its left and right
+    /// subtrees, `None` for a missing child, and the fold of the root is
+    /// returned. The work is split as in [`Node::par_map`].
+    pub fn par_fold<A, F>(&self, f: F) -> A
+    where
+        A: Send,
+        F: Fn(&T, Option<A>, Option<A>) -> A + Sync,
+    {
+        fold_split(self, &f, split_depth())
+    }
+}
+
+/// Get the number of levels to split, giving about two subtrees per
+/// thread of the current pool.
+fn split_depth() -> usize {
+    let threads = rayon::current_num_threads();
+    if threads == 1 {
+        0
+    } else {
+        (usize::BITS - (threads - 1).leading_zeros()) as usize + 1
+    }
+}
+
+fn map_split<T, U, F>(mut node: Node<T>, f: &F, depth: usize) -> Node<U>
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Sync,
+{
+    if depth == 0 {
+        return node.map_in(Order::Pre, f);
+    }
+    let (left, right) = (node.left.take(), node.right.take());
+    let (left, right) = rayon::join(
+        || left.map(|left| map_split(*left, f, depth - 1)),
+        || right.map(|right| map_split(*right, f, depth - 1)),
+    );
+    Node {
+        data: f(node.into_data()),
+        left: left.map(Node::boxed),
+        right: right.map(Node::boxed),
+    }
+}
+
+fn fold_split<T, A, F>(node: &Node<T>, f: &F, depth: usize) -> A
+where
+    T: Sync,
+    A: Send,
+    F: Fn(&T, Option<A>, Option<A>) -> A + Sync,
+{
+    if depth == 0 {
+        return fold(node, f);
+    }
+    let (left, right) = rayon::join(
+        || node.left().map(|left| fold_split(left, f, depth - 1)),
+        || node.right().map(|right| fold_split(right, f, depth - 1)),
+    );
+    f(node.data(), left, right)
+}
+
+/// Fold bottom-up without recursion.
+fn fold<T, A, F>(node: &Node<T>, f: &F) -> A
+where
+    F: Fn(&T, Option<A>, Option<A>) -> A,
+{
+    // The folds of the subtrees whose parent is not folded yet, the right
+    // one on top of the left one.
+    let mut folded: Vec<A> = Vec::new();
+    let mut stack = vec![(node, false)];
+    while let Some((node, expanded)) = stack.pop() {
+        if !expanded {
+            stack.push((node, true));
+            stack.extend(node.right().map(|right| (right, false)));
+            stack.extend(node.left().map(|left| (left, false)));
+            continue;
+        }
+        let right = node.right().and_then(|_| folded.pop());
+        let left = node.left().and_then(|_| folded.pop());
+        folded.push(f(node.data(), left, right));
+    }
+    folded.pop().expect("the root is folded last")
+}
@@ -0,0 +1,134 @@
+use super::{BoxedNode, Node};
+use std::mem::MaybeUninit;
+
+/// Source of the node boxes of a tree container.
+///
+/// [`BinarySearchTree`](super::bst::BinarySearchTree),
+/// [`TreeHeap`](crate::heap::TreeHeap) and
+/// [`TreeMap`](crate::tree_map::TreeMap) box every node they create through
+/// `alloc` on insertion and hand the box back through `free` when they
+/// remove a single entry. A `BinarySearchTree` also boxes the nodes it
+/// builds from sorted input, an iterator, a merge or a set operation
+/// through its allocator, and hands back the nodes it merges or retains
+/// away. A `TreeMap` hands back the nodes it retains, drains or clears
+/// away, and those a set operation leaves out. The other bulk operations,
+/// such as cloning or dropping, use the global allocator.
+pub trait NodeAlloc<N> {
+    /// Box a node.
+    fn alloc(&mut self, node: N) -> Box<N>;
+
+    /// Take back a box, returning the node it held.
+    fn free(&mut self, node: Box<N>) -> N;
+}
+
+/// The global allocator, through `Box`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Global;
+
+impl<N> NodeAlloc<N> for Global {
+    fn alloc(&mut self, node: N) -> Box<N> {
+        Box::new(node)
+    }
+
+    fn free(&mut self, node: Box<N>) -> N {
+        *node
+    }
+}
+
+impl<N, A: NodeAlloc<N> + ?Sized> NodeAlloc<N> for &mut A {
+    fn alloc(&mut self, node: N) -> Box<N> {
+        (**self).alloc(node)
+    }
+
+    fn free(&mut self, node: Box<N>) -> N {
+        (**self).free(node)
+    }
+}
+
+/// Recycling allocator for node boxes.
+///
+/// Freed boxes are kept empty and reused by later [`NodeAlloc::alloc`]
+/// calls instead of going through the global allocator, so workloads that
+/// repeatedly build and discard nodes allocate only until the pool is
+/// warm. Pass `&mut pool` to a container to share one pool among several.
+#[derive(Debug)]
+pub struct NodePool<N> {
+    free: Vec<Box<MaybeUninit<N>>>,
+}
+
+impl<N> Default for NodePool<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> NodePool<N> {
+    /// Create an empty pool.
+    pub fn new() -> Self {
+        Self { free: Vec::new() }
+    }
+
+    /// Get the number of boxes ready for reuse.
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Return `true` if no box is ready for reuse.
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+
+    /// Drop every box held by the pool.
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+
+    /// Keep the box of a node, dropping the node.
+    fn keep(&mut self, node: Box<N>) {
+        // SAFETY: `MaybeUninit<N>` has the layout of `N`, so the box may
+        // be reinterpreted, and the node is dropped in place exactly once
+        // before its memory is treated as uninitialized.
+        let raw = Box::into_raw(node);
+        unsafe {
+            raw.drop_in_place();
+            self.free.push(Box::from_raw(raw.cast::<MaybeUninit<N>>()));
+        }
+    }
+}
+
+impl<N> NodeAlloc<N> for NodePool<N> {
+    fn alloc(&mut self, node: N) -> Box<N> {
+        match self.free.pop() {
+            Some(slot) => Box::write(slot, node),
+            None => Box::new(node),
+        }
+    }
+
+    fn free(&mut self, node: Box<N>) -> N {
+        // SAFETY: the node is read out exactly once, and the box it leaves
+        // is only kept as uninitialized memory.
+        let raw = Box::into_raw(node);
+        unsafe {
+            let node = raw.read();
+            self.free.push(Box::from_raw(raw.cast::<MaybeUninit<N>>()));
+            node
+        }
+    }
+}
+
+impl<T> NodePool<Node<T>> {
+    /// Create a boxed node with no links, reusing a box if possible.
+    pub fn alloc_node(&mut self, data: T) -> BoxedNode<T> {
+        self.alloc(Node::new(data))
+    }
+
+    /// Take back every node box of a tree, dropping the data.
+    pub fn reclaim(&mut self, node: BoxedNode<T>) {
+        let mut stack = vec![node];
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+            self.keep(node);
+        }
+    }
+}
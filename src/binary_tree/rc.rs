@@ -0,0 +1,251 @@
+use super::view;
+use super::Node;
+use crate::datasize::DataSize;
+use crate::{Error, Result};
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt;
+use std::mem;
+use std::rc::{Rc, Weak};
+
+struct RcInner<T> {
+    data: T,
+    parent: Weak<RefCell<RcInner<T>>>,
+    left: Option<RcNode<T>>,
+    right: Option<RcNode<T>>,
+}
+
+/// Shared handle to a binary tree node with interior mutability.
+///
+/// Parents own their children and children point back through `Weak`
+/// links, so any number of handles may point into the same tree.
+/// Cloning a handle does not clone the node.
+pub struct RcNode<T>(Rc<RefCell<RcInner<T>>>);
+
+/// Non-owning handle to a node, see [`RcNode::downgrade`].
+pub struct WeakNode<T>(Weak<RefCell<RcInner<T>>>);
+
+impl<T> Clone for RcNode<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Clone for WeakNode<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RcNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.0.borrow();
+        f.debug_struct("RcNode")
+            .field("data", &inner.data)
+            .field("left", &inner.left)
+            .field("right", &inner.right)
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for WeakNode<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(WeakNode)")
+    }
+}
+
+impl<T> RcNode<T> {
+    /// Create a node with no links.
+    pub fn new(data: T) -> Self {
+        Self(Rc::new(RefCell::new(RcInner {
+            data,
+            parent: Weak::new(),
+            left: None,
+            right: None,
+        })))
+    }
+
+    /// Borrow the containing data.
+    ///
+    /// # Panics
+    /// Panics if the data is currently mutably borrowed.
+    pub fn data(&self) -> Ref<'_, T> {
+        Ref::map(self.0.borrow(), |inner| &inner.data)
+    }
+
+    /// Mutably borrow the containing data.
+    ///
+    /// # Panics
+    /// Panics if the data is currently borrowed.
+    pub fn data_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.0.borrow_mut(), |inner| &mut inner.data)
+    }
+
+    /// Get a handle to the left child.
+    pub fn left(&self) -> Option<RcNode<T>> {
+        self.0.borrow().left.clone()
+    }
+
+    /// Get a handle to the right child.
+    pub fn right(&self) -> Option<RcNode<T>> {
+        self.0.borrow().right.clone()
+    }
+
+    /// Get a handle to the parent.
+    pub fn parent(&self) -> Option<RcNode<T>> {
+        self.0.borrow().parent.upgrade().map(RcNode)
+    }
+
+    /// Create an iterator over the ancestors of this node, parent first,
+    /// following the parent links.
+    pub fn ancestors(&self) -> Ancestors<T> {
+        Ancestors {
+            next: self.parent(),
+        }
+    }
+
+    /// Get the number of edges between this node and the root of its
+    /// tree, in `O(depth)`.
+    pub fn depth(&self) -> usize {
+        self.ancestors().count()
+    }
+
+    /// Get a handle to the root of the tree containing this node.
+    pub fn root(&self) -> RcNode<T> {
+        self.ancestors().last().unwrap_or_else(|| self.clone())
+    }
+
+    /// Return `true` if both handles point to the same node.
+    pub fn ptr_eq(&self, other: &RcNode<T>) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Create a non-owning handle.
+    pub fn downgrade(&self) -> WeakNode<T> {
+        WeakNode(Rc::downgrade(&self.0))
+    }
+
+    /// Replace the left child, detaching `node` from its current parent
+    /// first. Return the old left child, now detached.
+    /// # Errors
+    /// Return `WouldCycle` Error when `node` is this node or one of its
+    /// ancestors.
+    pub fn set_left(&self, node: Option<RcNode<T>>) -> Result<Option<RcNode<T>>> {
+        self.set_child(node, false)
+    }
+
+    /// Replace the right child, detaching `node` from its current parent
+    /// first. Return the old right child, now detached.
+    /// # Errors
+    /// Return `WouldCycle` Error when `node` is this node or one of its
+    /// ancestors.
+    pub fn set_right(&self, node: Option<RcNode<T>>) -> Result<Option<RcNode<T>>> {
+        self.set_child(node, true)
+    }
+
+    /// Unlink this node, with its subtree, from its parent.
+    pub fn detach(&self) {
+        let parent = match self.parent() {
+            Some(parent) => parent,
+            None => return,
+        };
+        let mut parent = parent.0.borrow_mut();
+        if parent.left.as_ref().is_some_and(|left| left.ptr_eq(self)) {
+            parent.left = None;
+        } else {
+            parent.right = None;
+        }
+        self.0.borrow_mut().parent = Weak::new();
+    }
+
+    fn set_child(&self, node: Option<RcNode<T>>, is_right: bool) -> Result<Option<RcNode<T>>> {
+        if let Some(node) = &node {
+            if self.ptr_eq(node) || self.ancestors().any(|ancestor| ancestor.ptr_eq(node)) {
+                return Err(Error::WouldCycle);
+            }
+            node.detach();
+            node.0.borrow_mut().parent = Rc::downgrade(&self.0);
+        }
+        let mut inner = self.0.borrow_mut();
+        let slot = if is_right {
+            &mut inner.right
+        } else {
+            &mut inner.left
+        };
+        let old = std::mem::replace(slot, node);
+        if let Some(old) = &old {
+            old.0.borrow_mut().parent = Weak::new();
+        }
+        Ok(old)
+    }
+}
+
+impl<T: Clone> RcNode<T> {
+    /// Copy the subtree of this node into a boxed tree.
+    ///
+    /// # Panics
+    /// Panics if a node of the subtree is currently mutably borrowed.
+    pub fn to_node(&self) -> Node<T> {
+        view::map(self.clone(), T::clone)
+    }
+}
+
+impl<T: DataSize> DataSize for RcNode<T> {
+    /// Count the shared allocations of this node and its descendants, with
+    /// their reference counts, and the heap data of every node, without
+    /// recursion.
+    ///
+    /// # Panics
+    /// Panics if a node of the subtree is currently mutably borrowed.
+    fn heap_size(&self) -> usize {
+        let allocation = 2 * mem::size_of::<usize>() + mem::size_of::<RefCell<RcInner<T>>>();
+        view::pre_order(self.clone())
+            .map(|node| allocation + node.data().heap_size())
+            .sum()
+    }
+}
+
+impl<T> From<Node<T>> for RcNode<T> {
+    fn from(node: Node<T>) -> Self {
+        let mut stack: Vec<(Option<RcNode<T>>, bool, Node<T>)> = vec![(None, false, node)];
+        let mut root = None;
+        while let Some((parent, is_right, mut node)) = stack.pop() {
+            let left = node.left.take();
+            let right = node.right.take();
+            let handle = RcNode::new(node.into_data());
+            match parent {
+                Some(parent) => {
+                    parent
+                        .set_child(Some(handle.clone()), is_right)
+                        .expect("fresh nodes form no cycle");
+                }
+                None => root = Some(handle.clone()),
+            }
+            stack.extend(right.map(|right| (Some(handle.clone()), true, *right)));
+            stack.extend(left.map(|left| (Some(handle.clone()), false, *left)));
+        }
+        root.expect("the root is visited first")
+    }
+}
+
+impl<T> WeakNode<T> {
+    /// Get an owning handle if the node is still alive.
+    pub fn upgrade(&self) -> Option<RcNode<T>> {
+        self.0.upgrade().map(RcNode)
+    }
+}
+
+/// Iterator over the ancestors of an [`RcNode`].
+#[derive(Debug)]
+pub struct Ancestors<T> {
+    next: Option<RcNode<T>>,
+}
+
+impl<T> Iterator for Ancestors<T> {
+    type Item = RcNode<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next.take()?;
+        self.next = node.parent();
+        Some(node)
+    }
+}
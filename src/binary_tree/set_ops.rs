@@ -0,0 +1,143 @@
+use super::bst::{BinarySearchTree, Iter};
+use super::pool::NodeAlloc;
+use super::Node;
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+/// Walk two trees in step, pairing up equal values.
+#[derive(Debug)]
+struct Merged<'a, T> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+}
+
+impl<'a, T: Ord> Merged<'a, T> {
+    fn new(a: Iter<'a, T>, b: Iter<'a, T>) -> Self {
+        Self {
+            a: a.peekable(),
+            b: b.peekable(),
+        }
+    }
+}
+
+impl<'a, T: Ord> Iterator for Merged<'a, T> {
+    /// The value from either tree or both.
+    type Item = (Option<&'a T>, Option<&'a T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ord = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        Some(match ord {
+            Ordering::Less => (self.a.next(), None),
+            Ordering::Greater => (None, self.b.next()),
+            Ordering::Equal => (self.a.next(), self.b.next()),
+        })
+    }
+}
+
+macro_rules! set_iter {
+    ($(#[$doc:meta])* $name:ident, |$a:ident, $b:ident| $pick:expr) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<'a, T> {
+            merged: Merged<'a, T>,
+        }
+
+        impl<'a, T: Ord> Iterator for $name<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.merged.find_map(|($a, $b)| $pick)
+            }
+        }
+    };
+}
+
+set_iter!(
+    /// Lazy union of two trees, see [`BinarySearchTree::union`].
+    Union,
+    |a, b| a.or(b)
+);
+
+set_iter!(
+    /// Lazy intersection of two trees, see
+    /// [`BinarySearchTree::intersection`].
+    Intersection,
+    |a, b| b.and(a)
+);
+
+set_iter!(
+    /// Lazy difference of two trees, see [`BinarySearchTree::difference`].
+    Difference,
+    |a, b| if b.is_none() { a } else { None }
+);
+
+set_iter!(
+    /// Lazy symmetric difference of two trees, see
+    /// [`BinarySearchTree::symmetric_difference`].
+    SymmetricDifference,
+    |a, b| if a.is_some() != b.is_some() { a.or(b) } else { None }
+);
+
+impl<T: Ord, A> BinarySearchTree<T, A> {
+    /// Iterate in increasing order over the values in `self` or `other`,
+    /// in `O(n + m)` without allocating. Collecting the iterator bulk loads
+    /// a balanced tree in linear time.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T> {
+        Union {
+            merged: Merged::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterate in increasing order over the values in both `self` and
+    /// `other`, see [`BinarySearchTree::union`].
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T> {
+        Intersection {
+            merged: Merged::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterate in increasing order over the values in `self` but not in
+    /// `other`, see [`BinarySearchTree::union`].
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T> {
+        Difference {
+            merged: Merged::new(self.iter(), other.iter()),
+        }
+    }
+
+    /// Iterate in increasing order over the values in exactly one of
+    /// `self` and `other`, see [`BinarySearchTree::union`].
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T> {
+        SymmetricDifference {
+            merged: Merged::new(self.iter(), other.iter()),
+        }
+    }
+}
+
+macro_rules! set_op {
+    ($trait:ident, $method:ident, $iter:ident) => {
+        impl<T, A> $trait<&BinarySearchTree<T, A>> for &BinarySearchTree<T, A>
+        where
+            T: Ord + Clone,
+            A: NodeAlloc<Node<T>> + Default,
+        {
+            type Output = BinarySearchTree<T, A>;
+
+            /// Build a balanced tree of the result in `O(n + m)`, through a
+            /// default allocator.
+            fn $method(self, other: &BinarySearchTree<T, A>) -> Self::Output {
+                self.$iter(other).cloned().collect()
+            }
+        }
+    };
+}
+
+set_op!(BitOr, bitor, union);
+set_op!(BitAnd, bitand, intersection);
+set_op!(Sub, sub, difference);
+set_op!(BitXor, bitxor, symmetric_difference);
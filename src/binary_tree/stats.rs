@@ -0,0 +1,80 @@
+use super::view::{self, TreeNode};
+use super::Node;
+
+/// Shape statistics of a binary tree, to compare balancing strategies.
+///
+/// Depths count the edges from the root, which has depth 0. Built in one
+/// level order pass by [`TreeStats::of`] for any [`TreeNode`] handle, or by
+/// [`Node::stats`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TreeStats {
+    /// Number of nodes.
+    pub size: usize,
+    /// Number of nodes without children.
+    pub leaves: usize,
+    /// Number of nodes at every depth, from the root down.
+    pub levels: Vec<usize>,
+    /// Sum of the depths of all nodes, known as the internal path length.
+    pub path_length: u64,
+}
+
+impl TreeStats {
+    /// Compute the statistics of the tree under `root`, without recursion.
+    pub fn of<N: TreeNode>(root: N) -> Self {
+        let mut stats = Self::default();
+        for (depth, node) in view::level_order(root) {
+            if stats.levels.len() == depth {
+                stats.levels.push(0);
+            }
+            stats.levels[depth] += 1;
+            stats.size += 1;
+            stats.path_length += depth as u64;
+            if node.left().is_none() && node.right().is_none() {
+                stats.leaves += 1;
+            }
+        }
+        stats
+    }
+
+    /// Get the height, a single node having height 1.
+    pub fn height(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Get the average depth of the nodes, `0.0` for an empty tree.
+    pub fn average_depth(&self) -> f64 {
+        if self.size == 0 {
+            0.0
+        } else {
+            self.path_length as f64 / self.size as f64
+        }
+    }
+}
+
+/// Sum `weight(data) * depth` over the tree under `root`, without
+/// recursion, the root having depth 0.
+///
+/// With access frequencies as weights, this is the cost of the lookups
+/// beyond the root, the quantity an optimal binary search tree minimizes.
+pub fn weighted_path_length<N, F>(root: N, weight: F) -> u64
+where
+    N: TreeNode,
+    F: Fn(&N::Data) -> u64,
+{
+    view::level_order(root)
+        .map(|(depth, node)| node.with_data(&weight) * depth as u64)
+        .sum()
+}
+
+impl<T> Node<T> {
+    /// Compute the shape statistics of the tree. See [`TreeStats::of`].
+    pub fn stats(&self) -> TreeStats {
+        TreeStats::of(self)
+    }
+
+    /// Sum `weight(data) * depth` over the tree, the root having depth 0.
+    /// See [`weighted_path_length`].
+    pub fn weighted_path_length<F: Fn(&T) -> u64>(&self, weight: F) -> u64 {
+        weighted_path_length(self, weight)
+    }
+}
@@ -0,0 +1,281 @@
+use super::arc::ArcNode;
+use super::arena::{Arena, NodeId};
+use super::flat::FlatTree;
+use super::rc::RcNode;
+use super::Node;
+use std::collections::VecDeque;
+
+/// Handle to a node of a binary tree, whatever its storage.
+///
+/// Implemented for `&Node<T>`, `&ArcNode<T>`, `RcNode<T>`,
+/// `(&Arena<T>, NodeId)` and `(&FlatTree<T>, usize)`, so the traversals
+/// in this module work across owned, shared and arena-backed trees.
+pub trait TreeNode: Clone {
+    /// The data held by every node.
+    type Data;
+
+    /// Call `f` with the data of the node.
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Self::Data) -> R;
+
+    /// Get a handle to the left child.
+    fn left(&self) -> Option<Self>;
+
+    /// Get a handle to the right child.
+    fn right(&self) -> Option<Self>;
+}
+
+impl<T> TreeNode for &Node<T> {
+    type Data = T;
+
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(self.data())
+    }
+
+    fn left(&self) -> Option<Self> {
+        Node::left(self)
+    }
+
+    fn right(&self) -> Option<Self> {
+        Node::right(self)
+    }
+}
+
+impl<T> TreeNode for &ArcNode<T> {
+    type Data = T;
+
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(self.data())
+    }
+
+    fn left(&self) -> Option<Self> {
+        ArcNode::left(self)
+    }
+
+    fn right(&self) -> Option<Self> {
+        ArcNode::right(self)
+    }
+}
+
+impl<T> TreeNode for RcNode<T> {
+    type Data = T;
+
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(&self.data())
+    }
+
+    fn left(&self) -> Option<Self> {
+        RcNode::left(self)
+    }
+
+    fn right(&self) -> Option<Self> {
+        RcNode::right(self)
+    }
+}
+
+impl<T> TreeNode for (&Arena<T>, NodeId) {
+    type Data = T;
+
+    /// # Panics
+    /// Panics if the node has been removed.
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(self.0.get(self.1).expect("the node is live"))
+    }
+
+    fn left(&self) -> Option<Self> {
+        Some((self.0, self.0.left(self.1)?))
+    }
+
+    fn right(&self) -> Option<Self> {
+        Some((self.0, self.0.right(self.1)?))
+    }
+}
+
+impl<T> TreeNode for (&FlatTree<T>, usize) {
+    type Data = T;
+
+    /// # Panics
+    /// Panics if the slot is empty.
+    fn with_data<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        f(self.0.get(self.1).expect("the slot is occupied"))
+    }
+
+    fn left(&self) -> Option<Self> {
+        Some((self.0, self.0.left(self.1)?))
+    }
+
+    fn right(&self) -> Option<Self> {
+        Some((self.0, self.0.right(self.1)?))
+    }
+}
+
+/// Create a pre order iterator over the handles of a tree.
+pub fn pre_order<N: TreeNode>(root: N) -> PreOrder<N> {
+    PreOrder { stack: vec![root] }
+}
+
+/// Create a mid order iterator over the handles of a tree.
+pub fn mid_order<N: TreeNode>(root: N) -> MidOrder<N> {
+    MidOrder {
+        stack: Vec::new(),
+        next: Some(root),
+    }
+}
+
+/// Create a post order iterator over the handles of a tree.
+pub fn post_order<N: TreeNode>(root: N) -> PostOrder<N> {
+    PostOrder {
+        stack: vec![(root, false)],
+    }
+}
+
+/// Create a level order iterator over the handles of a tree,
+/// paired with their levels.
+pub fn level_order<N: TreeNode>(root: N) -> LevelOrder<N> {
+    let mut queue = VecDeque::new();
+    queue.push_back((0, root));
+    LevelOrder { queue }
+}
+
+/// Get the number of nodes of a tree.
+pub fn size<N: TreeNode>(root: N) -> usize {
+    pre_order(root).count()
+}
+
+/// Get the height of a tree, a single node having height 1.
+pub fn height<N: TreeNode>(root: N) -> usize {
+    level_order(root).last().map_or(0, |(level, _)| level + 1)
+}
+
+/// Copy a tree into a boxed tree, calling `f` on the data in pre order.
+pub fn map<N, U, F>(root: N, f: F) -> Node<U>
+where
+    N: TreeNode,
+    F: FnMut(&N::Data) -> U,
+{
+    rebuild(root, f, |data, left, right| Node {
+        data,
+        left: left.map(Node::boxed),
+        right: right.map(Node::boxed),
+    })
+}
+
+/// Copy a tree into any storage, calling `f` on the data in pre order and
+/// `build` on the new data and children of every node, children first.
+pub(crate) fn rebuild<N, U, B, F, G>(root: N, mut f: F, mut build: G) -> B
+where
+    N: TreeNode,
+    F: FnMut(&N::Data) -> U,
+    G: FnMut(U, Option<B>, Option<B>) -> B,
+{
+    let order: Vec<(U, bool, bool)> = pre_order(root)
+        .map(|node| {
+            let (has_left, has_right) = (node.left().is_some(), node.right().is_some());
+            (node.with_data(&mut f), has_left, has_right)
+        })
+        .collect();
+    // The roots of both subtrees of a node are on top of the stack,
+    // left above right, when the node itself is reached.
+    let mut built: Vec<B> = Vec::new();
+    for (data, has_left, has_right) in order.into_iter().rev() {
+        let left = if has_left { built.pop() } else { None };
+        let right = if has_right { built.pop() } else { None };
+        built.push(build(data, left, right));
+    }
+    built.pop().expect("the root is built last")
+}
+
+/// Pre order iterator over node handles.
+#[derive(Debug)]
+pub struct PreOrder<N> {
+    stack: Vec<N>,
+}
+
+impl<N: TreeNode> Iterator for PreOrder<N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.right());
+        self.stack.extend(node.left());
+        Some(node)
+    }
+}
+
+/// Mid order iterator over node handles.
+#[derive(Debug)]
+pub struct MidOrder<N> {
+    stack: Vec<N>,
+    next: Option<N>,
+}
+
+impl<N: TreeNode> Iterator for MidOrder<N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            self.next = node.left();
+            self.stack.push(node);
+        }
+        let node = self.stack.pop()?;
+        self.next = node.right();
+        Some(node)
+    }
+}
+
+/// Post order iterator over node handles.
+#[derive(Debug)]
+pub struct PostOrder<N> {
+    stack: Vec<(N, bool)>,
+}
+
+impl<N: TreeNode> Iterator for PostOrder<N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, expanded) = self.stack.pop()?;
+            if expanded {
+                return Some(node);
+            }
+            let (left, right) = (node.left(), node.right());
+            self.stack.push((node, true));
+            self.stack.extend(right.map(|right| (right, false)));
+            self.stack.extend(left.map(|left| (left, false)));
+        }
+    }
+}
+
+/// Level order iterator over node handles.
+#[derive(Debug)]
+pub struct LevelOrder<N> {
+    queue: VecDeque<(usize, N)>,
+}
+
+impl<N: TreeNode> Iterator for LevelOrder<N> {
+    type Item = (usize, N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (level, node) = self.queue.pop_front()?;
+        self.queue.extend(node.left().map(|left| (level + 1, left)));
+        self.queue
+            .extend(node.right().map(|right| (level + 1, right)));
+        Some((level, node))
+    }
+}
@@ -0,0 +1,76 @@
+/// Bit vector with constant time rank and logarithmic time select.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BitVec {
+    words: Vec<u64>,
+    /// Number of ones before every word.
+    ranks: Vec<usize>,
+    len: usize,
+}
+
+impl BitVec {
+    pub(crate) fn new(bits: impl Iterator<Item = bool>) -> Self {
+        let mut words: Vec<u64> = Vec::new();
+        let mut len = 0;
+        for bit in bits {
+            if len % 64 == 0 {
+                words.push(0);
+            }
+            if bit {
+                words[len / 64] |= 1 << (len % 64);
+            }
+            len += 1;
+        }
+        let mut ranks = Vec::with_capacity(words.len() + 1);
+        let mut ones = 0;
+        for word in &words {
+            ranks.push(ones);
+            ones += word.count_ones() as usize;
+        }
+        ranks.push(ones);
+        Self { words, ranks, len }
+    }
+
+    pub(crate) fn get(&self, idx: usize) -> bool {
+        self.words[idx / 64] >> (idx % 64) & 1 == 1
+    }
+
+    /// Count the ones in `0..end`.
+    pub(crate) fn rank1(&self, end: usize) -> usize {
+        let (word, bit) = (end / 64, end % 64);
+        if bit == 0 {
+            self.ranks[word]
+        } else {
+            self.ranks[word] + (self.words[word] & ((1 << bit) - 1)).count_ones() as usize
+        }
+    }
+
+    /// Count the zeros in `0..end`.
+    pub(crate) fn rank0(&self, end: usize) -> usize {
+        end - self.rank1(end)
+    }
+
+    /// Find the position of the `k`-th (0-based) `bit`.
+    pub(crate) fn select(&self, bit: bool, k: usize) -> Option<usize> {
+        let rank = |end| {
+            if bit {
+                self.rank1(end)
+            } else {
+                self.rank0(end)
+            }
+        };
+        if rank(self.len) <= k {
+            return None;
+        }
+        // The smallest `end` with `rank(end) > k` is one past the answer.
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if rank(mid + 1) > k {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(lo)
+    }
+}
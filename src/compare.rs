@@ -0,0 +1,37 @@
+use std::cmp::Ordering;
+
+/// Total order on keys used by the ordered containers in place of `Ord`.
+///
+/// Closures `Fn(&K, &K) -> Ordering` are comparators, so e.g.
+/// case-insensitive or keyed-by-field orders need no newtype over the
+/// keys.
+pub trait Comparator<K: ?Sized> {
+    /// Compare two keys.
+    fn compare(&self, a: &K, b: &K) -> Ordering;
+}
+
+/// The order of `Ord`, the default comparator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Natural;
+
+impl<K: Ord + ?Sized> Comparator<K> for Natural {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// The reverse order of another comparator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Reversed<C = Natural>(pub C);
+
+impl<K: ?Sized, C: Comparator<K>> Comparator<K> for Reversed<C> {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        self.0.compare(b, a)
+    }
+}
+
+impl<K: ?Sized, F: Fn(&K, &K) -> Ordering> Comparator<K> for F {
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        self(a, b)
+    }
+}
@@ -0,0 +1,290 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(feature = "concurrent")]
+mod read_mostly;
+
+#[cfg(feature = "concurrent")]
+pub use read_mostly::{ReadMostlyMap, Snapshot, SnapshotIter};
+
+type Link<T> = RwLock<Option<Arc<SharedNode<T>>>>;
+
+/// Life cycle of a node of a [`ConcurrentSearchTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// The value is in the set.
+    Present,
+    /// The value was removed but the node still routes searches.
+    Removed,
+    /// The node was cut out of the tree and must not be changed again.
+    Unlinked,
+}
+
+struct SharedNode<T> {
+    value: T,
+    state: Mutex<State>,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T> SharedNode<T> {
+    fn new(value: T) -> Arc<Self> {
+        Arc::new(Self {
+            value,
+            state: Mutex::new(State::Present),
+            left: RwLock::new(None),
+            right: RwLock::new(None),
+        })
+    }
+
+    /// Get the link to follow for a value ordered `ord` relative to this
+    /// node.
+    fn link(&self, ord: Ordering) -> &Link<T> {
+        if ord == Ordering::Less {
+            &self.left
+        } else {
+            &self.right
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn read<T>(link: &Link<T>) -> RwLockReadGuard<'_, Option<Arc<SharedNode<T>>>> {
+    link.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write<T>(link: &Link<T>) -> RwLockWriteGuard<'_, Option<Arc<SharedNode<T>>>> {
+    link.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The last node of a search with the direction taken from it.
+type Parent<T> = Option<(Arc<SharedNode<T>>, Ordering)>;
+
+/// Ordered set that many threads can update at once, as a binary search
+/// tree with a lock per link, without rebalancing.
+///
+/// Searches take each read lock only to step over it, so they never wait
+/// for each other and only briefly for writers. An insertion locks the
+/// link it fills, and a removal marks its node removed, then cuts it out
+/// of the tree when it has at most one child. A node removed with two
+/// children keeps routing searches and is reused if its value comes back.
+///
+/// Locks are always taken from the root down, the state of a node before
+/// its links, so operations cannot deadlock; an operation that finds the
+/// tree changed under it starts again from the root. Every operation is
+/// linearizable.
+pub struct ConcurrentSearchTree<T> {
+    root: Link<T>,
+    len: AtomicUsize,
+}
+
+impl<T> Default for ConcurrentSearchTree<T> {
+    fn default() -> Self {
+        Self {
+            root: RwLock::new(None),
+            len: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T> ConcurrentSearchTree<T> {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of values, which may be stale by the time it returns
+    /// if other threads are writing.
+    pub fn len(&self) -> usize {
+        self.len.load(atomic::Ordering::SeqCst)
+    }
+
+    /// Return `true` if the tree is empty, see [`ConcurrentSearchTree::len`].
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Ord> ConcurrentSearchTree<T> {
+    /// Walk down to the node holding `value`, with the node it was reached
+    /// from. Without such a node, return where `value` would be inserted.
+    fn find(&self, value: &T) -> (Parent<T>, Option<Arc<SharedNode<T>>>) {
+        let mut parent = None;
+        let mut next = read(&self.root).clone();
+        while let Some(node) = next {
+            let ord = value.cmp(&node.value);
+            if ord == Ordering::Equal {
+                return (parent, Some(node));
+            }
+            next = read(node.link(ord)).clone();
+            parent = Some((node, ord));
+        }
+        (parent, None)
+    }
+
+    /// Return `true` if the tree contains `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        match self.find(value) {
+            (_, Some(node)) => *node.state() == State::Present,
+            (_, None) => false,
+        }
+    }
+
+    /// Insert a value, returning `false` if it was already present.
+    pub fn insert(&self, value: T) -> bool {
+        loop {
+            let (parent, node) = self.find(&value);
+            if let Some(node) = node {
+                let mut state = node.state();
+                match *state {
+                    State::Present => return false,
+                    State::Removed => *state = State::Present,
+                    State::Unlinked => continue,
+                }
+                self.len.fetch_add(1, atomic::Ordering::SeqCst);
+                return true;
+            }
+            // Lock the state of the parent first, so that it cannot be cut
+            // out once the new node hangs from it.
+            let state;
+            let mut link = match &parent {
+                Some((parent, ord)) => {
+                    state = parent.state();
+                    if *state == State::Unlinked {
+                        continue;
+                    }
+                    write(parent.link(*ord))
+                }
+                None => write(&self.root),
+            };
+            if link.is_some() {
+                continue;
+            }
+            *link = Some(SharedNode::new(value));
+            self.len.fetch_add(1, atomic::Ordering::SeqCst);
+            return true;
+        }
+    }
+
+    /// Remove a value, returning `false` if it was not present.
+    pub fn remove(&self, value: &T) -> bool {
+        loop {
+            let (parent, node) = match self.find(value) {
+                (parent, Some(node)) => (parent, node),
+                (_, None) => return false,
+            };
+            {
+                let mut state = node.state();
+                match *state {
+                    State::Present => *state = State::Removed,
+                    State::Removed => return false,
+                    State::Unlinked => continue,
+                }
+            }
+            self.len.fetch_sub(1, atomic::Ordering::SeqCst);
+            self.unlink(parent, &node);
+            return true;
+        }
+    }
+
+    /// Cut a removed node with at most one child out of the tree, putting
+    /// the child in its place. Give up if anything changed since the node
+    /// was found: the node then stays to route searches.
+    fn unlink(&self, parent: Parent<T>, node: &Arc<SharedNode<T>>) {
+        let parent_state;
+        let mut link = match &parent {
+            Some((parent, ord)) => {
+                parent_state = parent.state();
+                if *parent_state == State::Unlinked {
+                    return;
+                }
+                write(parent.link(*ord))
+            }
+            None => write(&self.root),
+        };
+        if !link
+            .as_ref()
+            .is_some_and(|linked| Arc::ptr_eq(linked, node))
+        {
+            return;
+        }
+        let mut state = node.state();
+        if *state != State::Removed {
+            return;
+        }
+        let left = write(&node.left);
+        let right = write(&node.right);
+        let child = match (&*left, &*right) {
+            (Some(_), Some(_)) => return,
+            (Some(child), None) | (None, Some(child)) => Some(child.clone()),
+            (None, None) => None,
+        };
+        // The links of the node stay in place for searches still passing
+        // through it.
+        *state = State::Unlinked;
+        *link = child;
+    }
+}
+
+impl<T: Clone> ConcurrentSearchTree<T> {
+    /// Copy the values in increasing order, without recursion.
+    ///
+    /// The copy is not atomic: values inserted or removed by other threads
+    /// meanwhile may or may not be seen.
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len());
+        let mut stack = Vec::new();
+        let mut next = read(&self.root).clone();
+        loop {
+            while let Some(node) = next {
+                next = read(&node.left).clone();
+                stack.push(node);
+            }
+            let node = match stack.pop() {
+                Some(node) => node,
+                None => break,
+            };
+            if *node.state() == State::Present {
+                values.push(node.value.clone());
+            }
+            next = read(&node.right).clone();
+        }
+        values
+    }
+}
+
+impl<T: fmt::Debug + Clone> fmt::Debug for ConcurrentSearchTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.to_vec()).finish()
+    }
+}
+
+impl<T> Drop for ConcurrentSearchTree<T> {
+    /// Free the nodes without recursion.
+    fn drop(&mut self) {
+        let mut stack: Vec<_> = write(&self.root).take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            // Unlinked nodes are freed with the last operation passing
+            // through them, so every node has a single owner by now.
+            if let Ok(node) = Arc::try_unwrap(node) {
+                let into_inner =
+                    |link: Link<T>| link.into_inner().unwrap_or_else(PoisonError::into_inner);
+                stack.extend(into_inner(node.left));
+                stack.extend(into_inner(node.right));
+            }
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for ConcurrentSearchTree<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| {
+            self.insert(value);
+        });
+    }
+}
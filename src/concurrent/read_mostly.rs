@@ -0,0 +1,328 @@
+use crate::gen::{Rng, SplitMix64};
+use crossbeam_epoch::{self as epoch, Atomic, Guard, Owned};
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic;
+use std::sync::{Arc, Mutex, PoisonError};
+
+type MapLink<K, V> = Option<Arc<MapNode<K, V>>>;
+
+/// Immutable treap node, shared between the versions of a
+/// [`ReadMostlyMap`].
+struct MapNode<K, V> {
+    entry: Arc<(K, V)>,
+    priority: u64,
+    left: MapLink<K, V>,
+    right: MapLink<K, V>,
+}
+
+impl<K, V> MapNode<K, V> {
+    /// Copy the node with other children.
+    fn with_children(&self, left: MapLink<K, V>, right: MapLink<K, V>) -> MapLink<K, V> {
+        Some(Arc::new(Self {
+            entry: self.entry.clone(),
+            priority: self.priority,
+            left,
+            right,
+        }))
+    }
+}
+
+/// Keys less than a key, the entry of the key and keys greater than it.
+type Split<K, V> = (MapLink<K, V>, Option<Arc<(K, V)>>, MapLink<K, V>);
+
+/// Split a treap into the keys less than `key`, the entry of `key` and
+/// the keys greater than it, copying the nodes on the path only.
+///
+/// Recurses once per level, and a treap is logarithmically deep with high
+/// probability.
+fn split<K: Ord, V>(link: &MapLink<K, V>, key: &K) -> Split<K, V> {
+    let node = match link {
+        Some(node) => node,
+        None => return (None, None, None),
+    };
+    match key.cmp(&node.entry.0) {
+        Ordering::Less => {
+            let (less, entry, greater) = split(&node.left, key);
+            (less, entry, node.with_children(greater, node.right.clone()))
+        }
+        Ordering::Greater => {
+            let (less, entry, greater) = split(&node.right, key);
+            (node.with_children(node.left.clone(), less), entry, greater)
+        }
+        Ordering::Equal => (
+            node.left.clone(),
+            Some(node.entry.clone()),
+            node.right.clone(),
+        ),
+    }
+}
+
+/// Merge two treaps, every key of `less` being less than those of
+/// `greater`, copying the nodes on the merged spines only.
+fn merge<K, V>(less: MapLink<K, V>, greater: MapLink<K, V>) -> MapLink<K, V> {
+    match (less, greater) {
+        (None, link) | (link, None) => link,
+        (Some(less), Some(greater)) => {
+            if less.priority > greater.priority {
+                let right = merge(less.right.clone(), Some(greater));
+                less.with_children(less.left.clone(), right)
+            } else {
+                let left = merge(Some(less), greater.left.clone());
+                greater.with_children(left, greater.right.clone())
+            }
+        }
+    }
+}
+
+/// Immutable version of a [`ReadMostlyMap`].
+struct Version<K, V> {
+    root: MapLink<K, V>,
+    len: usize,
+}
+
+/// Ordered map for read-mostly workloads: readers never block, and
+/// writers take turns.
+///
+/// The map is a persistent treap. A writer builds a new version sharing
+/// all but `O(log n)` nodes with the current one, then publishes it with
+/// an atomic swap. Readers pin the current epoch and read the version
+/// without any lock or reference count; a replaced version is destroyed by
+/// [crossbeam-epoch](crossbeam_epoch) once no thread pinned before the
+/// swap is left, dropping the nodes no other version shares.
+pub struct ReadMostlyMap<K, V> {
+    current: Atomic<Version<K, V>>,
+    /// Serializes the writers, holding the source of treap priorities.
+    writer: Mutex<SplitMix64>,
+}
+
+impl<K, V> Default for ReadMostlyMap<K, V> {
+    fn default() -> Self {
+        Self {
+            current: Atomic::new(Version { root: None, len: 0 }),
+            writer: Mutex::new(SplitMix64::new(0x5eed)),
+        }
+    }
+}
+
+impl<K, V> ReadMostlyMap<K, V> {
+    /// Create an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the current version of the map.
+    ///
+    /// The snapshot keeps the thread pinned, holding back the reclamation
+    /// of every version replaced in the meantime, so it should not be kept
+    /// for long.
+    pub fn snapshot(&self) -> Snapshot<'_, K, V> {
+        let guard = epoch::pin();
+        let version = self.current.load(atomic::Ordering::Acquire, &guard);
+        Snapshot {
+            version: version.as_raw(),
+            _guard: guard,
+            _map: PhantomData,
+        }
+    }
+
+    /// Get the number of entries of the current version.
+    pub fn len(&self) -> usize {
+        self.snapshot().len()
+    }
+
+    /// Return `true` if the current version is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K, V> ReadMostlyMap<K, V>
+where
+    K: Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    /// Publish a new version, destroying the current one once the readers
+    /// are done with it.
+    fn publish(&self, version: Version<K, V>, guard: &Guard) {
+        let old = self
+            .current
+            .swap(Owned::new(version), atomic::Ordering::AcqRel, guard);
+        // Safety: the old version is unreachable from the map, and the
+        // threads still reading it are pinned.
+        unsafe { guard.defer_destroy(old) };
+    }
+}
+
+impl<K: Ord, V> ReadMostlyMap<K, V> {
+    /// Return `true` if the current version contains `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.snapshot().get(key).is_some()
+    }
+
+    /// Get a copy of the value of `key` in the current version.
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.snapshot().get(key).cloned()
+    }
+}
+
+impl<K, V> ReadMostlyMap<K, V>
+where
+    K: Ord + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    /// Insert or replace the value of `key`, returning `true` if the key
+    /// was not present.
+    pub fn insert(&self, key: K, value: V) -> bool {
+        let mut rng = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let guard = epoch::pin();
+        let current = self.load(&guard);
+        let (less, old, greater) = split(&current.root, &key);
+        let node = Some(Arc::new(MapNode {
+            entry: Arc::new((key, value)),
+            priority: rng.next_u64(),
+            left: None,
+            right: None,
+        }));
+        let root = merge(merge(less, node), greater);
+        let len = current.len + usize::from(old.is_none());
+        self.publish(Version { root, len }, &guard);
+        old.is_none()
+    }
+
+    /// Remove `key`, returning `true` if it was present.
+    pub fn remove(&self, key: &K) -> bool {
+        let _writer = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let guard = epoch::pin();
+        let current = self.load(&guard);
+        let (less, old, greater) = split(&current.root, key);
+        if old.is_none() {
+            return false;
+        }
+        let root = merge(less, greater);
+        let len = current.len - 1;
+        self.publish(Version { root, len }, &guard);
+        true
+    }
+
+    /// Get the current version, as seen by a writer.
+    fn load<'g>(&self, guard: &'g Guard) -> &'g Version<K, V> {
+        let current = self.current.load(atomic::Ordering::Acquire, guard);
+        // Safety: the current version is never null, and is only destroyed
+        // after the guard is dropped.
+        unsafe { current.deref() }
+    }
+}
+
+impl<K, V> Drop for ReadMostlyMap<K, V> {
+    fn drop(&mut self) {
+        // Safety: the map is not shared anymore, and the snapshots borrow
+        // it, so no thread can be reading the current version.
+        unsafe {
+            let current = self
+                .current
+                .load(atomic::Ordering::Relaxed, epoch::unprotected());
+            drop(current.into_owned());
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ReadMostlyMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.snapshot().fmt(f)
+    }
+}
+
+/// Version of a [`ReadMostlyMap`] at some point in time.
+///
+/// The version stays valid and unchanged however the map moves on, as
+/// long as the snapshot keeps the thread pinned.
+pub struct Snapshot<'a, K, V> {
+    version: *const Version<K, V>,
+    _guard: Guard,
+    _map: PhantomData<&'a ReadMostlyMap<K, V>>,
+}
+
+impl<K, V> Clone for Snapshot<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            version: self.version,
+            // The thread is already pinned, so the version outlives the
+            // new guard too.
+            _guard: epoch::pin(),
+            _map: PhantomData,
+        }
+    }
+}
+
+impl<K, V> Snapshot<'_, K, V> {
+    fn version(&self) -> &Version<K, V> {
+        // Safety: the version was current when the guard was pinned.
+        unsafe { &*self.version }
+    }
+
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.version().len
+    }
+
+    /// Return `true` if there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Create an iterator over the entries in increasing order of keys.
+    pub fn iter(&self) -> SnapshotIter<'_, K, V> {
+        SnapshotIter {
+            stack: Vec::new(),
+            next: self.version().root.as_deref(),
+        }
+    }
+}
+
+impl<K: Ord, V> Snapshot<'_, K, V> {
+    /// Get the ref of the value of `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut next = self.version().root.as_deref();
+        while let Some(node) = next {
+            let (node_key, value) = &*node.entry;
+            next = match key.cmp(node_key) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return Some(value),
+            };
+        }
+        None
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Snapshot<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Iterator over the entries of a [`Snapshot`] in increasing order of keys.
+pub struct SnapshotIter<'a, K, V> {
+    stack: Vec<&'a MapNode<K, V>>,
+    next: Option<&'a MapNode<K, V>>,
+}
+
+impl<'a, K, V> Iterator for SnapshotIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            self.next = node.left.as_deref();
+            self.stack.push(node);
+        }
+        let node = self.stack.pop()?;
+        self.next = node.right.as_deref();
+        let (key, value) = &*node.entry;
+        Some((key, value))
+    }
+}
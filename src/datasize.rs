@@ -0,0 +1,105 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::mem::size_of;
+
+/// Estimate of the heap memory owned by a value.
+///
+/// Implemented for the primitive and common `std` types, and for the trees
+/// of this crate, so that the storage backends can be compared on the same
+/// data. Allocator overhead and unused bytes rounded up by the allocator
+/// are not counted.
+pub trait DataSize {
+    /// Get the number of bytes allocated on the heap and owned by the
+    /// value, not counting the value itself.
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_without_heap {
+    ($($ty:ty),*) => {
+        $(
+            impl DataSize for $ty {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_without_heap!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+/// Borrowed data is not owned.
+impl<T: ?Sized> DataSize for &T {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl DataSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: DataSize> DataSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        size_of::<T>() + (**self).heap_size()
+    }
+}
+
+impl<T: DataSize> DataSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, DataSize::heap_size)
+    }
+}
+
+impl<T: DataSize> DataSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(DataSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: DataSize> DataSize for VecDeque<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(DataSize::heap_size).sum::<usize>()
+    }
+}
+
+/// Counts the entries and their heap data only, the node layout of
+/// `BTreeMap` being private.
+impl<K: DataSize, V: DataSize> DataSize for BTreeMap<K, V> {
+    fn heap_size(&self) -> usize {
+        self.iter()
+            .map(|(key, value)| size_of::<(K, V)>() + key.heap_size() + value.heap_size())
+            .sum()
+    }
+}
+
+impl<A: DataSize, B: DataSize> DataSize for (A, B) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl<A: DataSize, B: DataSize, C: DataSize> DataSize for (A, B, C) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size() + self.2.heap_size()
+    }
+}
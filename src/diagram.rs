@@ -0,0 +1,219 @@
+use crate::binary_tree::Node as BinaryNode;
+use crate::tree::Node;
+use std::fmt::{Display, Write};
+
+/// Nodes of a tree numbered in pre order with their labels and child
+/// slots, shared by the diagram exporters.
+pub(crate) struct Diagram {
+    pub(crate) labels: Vec<String>,
+    /// Child slots of every node, empty ones standing for missing children
+    /// of binary nodes.
+    pub(crate) children: Vec<Vec<Option<usize>>>,
+    /// Whether the slots are the left and right children of binary nodes.
+    pub(crate) binary: bool,
+}
+
+impl Diagram {
+    /// Collect a binary tree. Leaves have no slots, other nodes have a
+    /// left and a right one.
+    pub(crate) fn binary<T: Display>(root: &BinaryNode<T>) -> Self {
+        let mut diagram = Self {
+            labels: Vec::new(),
+            children: Vec::new(),
+            binary: true,
+        };
+        let mut stack: Vec<(_, Option<(usize, usize)>)> = vec![(root, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let idx = diagram.labels.len();
+            diagram.labels.push(node.data().to_string());
+            if let Some((parent, side)) = parent {
+                diagram.children[parent][side] = Some(idx);
+            }
+            let slots = if node.left().is_some() || node.right().is_some() {
+                vec![None, None]
+            } else {
+                Vec::new()
+            };
+            diagram.children.push(slots);
+            stack.extend(node.right().map(|right| (right, Some((idx, 1)))));
+            stack.extend(node.left().map(|left| (left, Some((idx, 0)))));
+        }
+        diagram
+    }
+
+    /// Collect an n-ary tree.
+    pub(crate) fn n_ary<T: Display>(root: &Node<T>) -> Self {
+        let mut diagram = Self {
+            labels: Vec::new(),
+            children: Vec::new(),
+            binary: false,
+        };
+        let mut stack: Vec<(_, Option<usize>)> = vec![(root, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let idx = diagram.labels.len();
+            diagram.labels.push(node.data().to_string());
+            if let Some(parent) = parent {
+                diagram.children[parent].push(Some(idx));
+            }
+            diagram
+                .children
+                .push(Vec::with_capacity(node.children().len()));
+            stack.extend(node.children().iter().rev().map(|child| (child, Some(idx))));
+        }
+        diagram
+    }
+
+    /// Iterate over the edges as parent, child and the side of binary
+    /// children (`L` or `R`).
+    pub(crate) fn edges(&self) -> impl Iterator<Item = (usize, usize, Option<&'static str>)> + '_ {
+        self.children
+            .iter()
+            .enumerate()
+            .flat_map(move |(parent, slots)| {
+                slots.iter().enumerate().filter_map(move |(slot, child)| {
+                    let side = if self.binary {
+                        Some(["L", "R"][slot])
+                    } else {
+                        None
+                    };
+                    child.map(|child| (parent, child, side))
+                })
+            })
+    }
+
+    /// Render as a Mermaid `graph TD` flowchart.
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph TD\n");
+        for (idx, label) in self.labels.iter().enumerate() {
+            writeln!(out, "    n{}[\"{}\"]", idx, escape_mermaid(label)).unwrap();
+        }
+        let mut edges: Vec<_> = self.edges().collect();
+        edges.sort_by_key(|&(_, child, _)| child);
+        for (parent, child, label) in edges {
+            match label {
+                Some(label) => writeln!(out, "    n{} -->|{}| n{}", parent, label, child),
+                None => writeln!(out, "    n{} --> n{}", parent, child),
+            }
+            .unwrap();
+        }
+        out
+    }
+
+    /// Render as nested `<ul>` lists, inner nodes being wrapped in open
+    /// `<details>` elements if `collapsible`. Missing children of binary
+    /// nodes are kept as empty items.
+    pub(crate) fn to_html(&self, collapsible: bool) -> String {
+        enum Step {
+            Item(Option<usize>),
+            Text(&'static str),
+        }
+        let mut out = String::from("<ul>");
+        let mut stack = vec![Step::Text("</ul>"), Step::Item(Some(0))];
+        while let Some(step) = stack.pop() {
+            let idx = match step {
+                Step::Text(text) => {
+                    out.push_str(text);
+                    continue;
+                }
+                Step::Item(None) => {
+                    out.push_str("<li></li>");
+                    continue;
+                }
+                Step::Item(Some(idx)) => idx,
+            };
+            let label = escape_html(&self.labels[idx]);
+            if self.children[idx].is_empty() {
+                write!(out, "<li>{}</li>", label).unwrap();
+                continue;
+            }
+            if collapsible {
+                write!(out, "<li><details open><summary>{}</summary><ul>", label).unwrap();
+                stack.push(Step::Text("</ul></details></li>"));
+            } else {
+                write!(out, "<li>{}<ul>", label).unwrap();
+                stack.push(Step::Text("</ul></li>"));
+            }
+            stack.extend(
+                self.children[idx]
+                    .iter()
+                    .rev()
+                    .map(|&child| Step::Item(child)),
+            );
+        }
+        out
+    }
+}
+
+/// Escape a label for HTML text or attribute values.
+fn escape_html(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a label for a quoted Mermaid node text using entity codes.
+fn escape_mermaid(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("#quot;"),
+            '#' => escaped.push_str("#35;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            '\n' => escaped.push_str("<br>"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl<T: Display> BinaryNode<T> {
+    /// Write the tree as a Mermaid `graph TD` flowchart, labelling the
+    /// edges to left and right children with `L` and `R`.
+    pub fn to_mermaid(&self) -> String {
+        Diagram::binary(self).to_mermaid()
+    }
+
+    /// Write the tree as nested `<ul>`/`<li>` lists with escaped labels,
+    /// without recursion. A node with a single child gets an empty item in
+    /// place of the missing one, so the sides stay apparent.
+    pub fn to_html(&self) -> String {
+        Diagram::binary(self).to_html(false)
+    }
+
+    /// Write the tree as [`BinaryNode::to_html`] does, every node with
+    /// children being a `<details open>` element that collapses them when
+    /// its `<summary>` label is clicked.
+    pub fn to_html_collapsible(&self) -> String {
+        Diagram::binary(self).to_html(true)
+    }
+}
+
+impl<T: Display> Node<T> {
+    /// Write the tree as a Mermaid `graph TD` flowchart.
+    pub fn to_mermaid(&self) -> String {
+        Diagram::n_ary(self).to_mermaid()
+    }
+
+    /// Write the tree as nested `<ul>`/`<li>` lists with escaped labels,
+    /// without recursion.
+    pub fn to_html(&self) -> String {
+        Diagram::n_ary(self).to_html(false)
+    }
+
+    /// Write the tree as [`Node::to_html`] does, every node with children
+    /// being a `<details open>` element that collapses them when its
+    /// `<summary>` label is clicked.
+    pub fn to_html_collapsible(&self) -> String {
+        Diagram::n_ary(self).to_html(true)
+    }
+}
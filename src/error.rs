@@ -6,6 +6,106 @@ pub enum Error {
     /// Missing data field error.
     #[error("missing data field")]
     MissingDataField,
+
+    /// Index out of range error.
+    #[error("index {index} out of range for length {len}")]
+    IndexOutOfRange {
+        /// The requested index.
+        index: usize,
+        /// The length of the collection.
+        len: usize,
+    },
+
+    /// Symbol not in the code table error.
+    #[error("symbol not in the code table")]
+    UnknownSymbol,
+
+    /// Bit stream ends in the middle of a code error.
+    #[error("bit stream ends in the middle of a code")]
+    TruncatedCode,
+
+    /// Bits that are not the prefix of any code error.
+    #[error("bit stream does not match any code")]
+    InvalidCode,
+
+    /// Symbol given more than once error.
+    #[error("symbol given more than once")]
+    DuplicateSymbol,
+
+    /// Unbound variable error.
+    #[error("unbound variable `{0}`")]
+    UnboundVariable(String),
+
+    /// Malformed expression tree error.
+    #[error("malformed expression tree")]
+    MalformedExpression,
+
+    /// Nodes already connected error.
+    #[error("nodes are already connected")]
+    AlreadyConnected,
+
+    /// Missing edge error.
+    #[error("no such edge")]
+    NoSuchEdge,
+
+    /// Malformed tree description error.
+    #[error("malformed tree")]
+    MalformedTree,
+
+    /// Removed node error.
+    #[error("node has been removed")]
+    RemovedNode,
+
+    /// Cyclic structure error.
+    #[error("operation would create a cycle")]
+    WouldCycle,
+
+    /// Missing child error.
+    #[error("node has no child on this side")]
+    MissingChild,
+
+    /// Unsorted values error.
+    #[error("values are not in strictly increasing order")]
+    Unsorted,
+
+    /// Inconsistent traversals error.
+    #[error("traversals do not describe the same tree")]
+    InconsistentTraversals,
+
+    /// Invalid node path error.
+    #[error("no node at this path")]
+    InvalidPath,
+
+    /// Capacity overflow error.
+    #[error("tree is too deep for an index-based layout")]
+    CapacityOverflow,
+
+    /// Unknown version error.
+    #[error("no such version")]
+    UnknownVersion,
+
+    /// Broken invariant error.
+    #[error("invariant violated at {path:?}: {reason}")]
+    InvariantViolation {
+        /// Indices of the children to follow from the root to the offending
+        /// node, `0` going left and `1` going right in binary trees.
+        path: Vec<usize>,
+        /// Description of the broken invariant.
+        reason: String,
+    },
+
+    /// Parse error.
+    #[error("parse error at {position}: {message}")]
+    Parse {
+        /// Byte offset in the input where the error occurs.
+        position: usize,
+        /// Description of the error.
+        message: String,
+    },
+
+    /// I/O error.
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// The result type.
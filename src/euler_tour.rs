@@ -0,0 +1,326 @@
+use crate::monoid::Monoid;
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+type Link = Option<usize>;
+
+#[derive(Debug, Clone)]
+struct TourNode<T> {
+    left: Link,
+    right: Link,
+    parent: Link,
+    priority: u64,
+    size: usize,
+    value: T,
+    sum: T,
+}
+
+/// Euler tour trees over a forest of vertices holding monoid values.
+///
+/// Every tree is stored as its Euler tour (one occurrence per vertex plus
+/// one per directed edge) in a treap, so `link`, `cut`, `connected` and
+/// subtree aggregates run in expected `O(log n)`. Tours are rotated when
+/// rerooting, so aggregates combine values in an unspecified order and the
+/// monoid should be commutative.
+#[derive(Debug, Clone, Default)]
+pub struct EulerTourTree<T> {
+    nodes: Vec<TourNode<T>>,
+    free: Vec<usize>,
+    vertices: Vec<usize>,
+    edges: HashMap<(usize, usize), usize>,
+    seed: u64,
+}
+
+impl<T: Monoid> EulerTourTree<T> {
+    /// Create an empty forest.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            vertices: Vec::new(),
+            edges: HashMap::new(),
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+
+    /// Get the number of vertices.
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Return `true` if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Add an isolated vertex, returning its index.
+    pub fn add(&mut self, value: T) -> usize {
+        let node = self.alloc(value);
+        self.vertices.push(node);
+        self.vertices.len() - 1
+    }
+
+    /// Get the value of a vertex.
+    ///
+    /// # Panics
+    /// Panics if `vertex` is out of range.
+    pub fn value(&self, vertex: usize) -> &T {
+        &self.nodes[self.vertices[vertex]].value
+    }
+
+    /// Replace the value of a vertex.
+    ///
+    /// # Panics
+    /// Panics if `vertex` is out of range.
+    pub fn set_value(&mut self, vertex: usize, value: T) {
+        let mut node = Some(self.vertices[vertex]);
+        self.nodes[self.vertices[vertex]].value = value;
+        while let Some(current) = node {
+            self.update(current);
+            node = self.nodes[current].parent;
+        }
+    }
+
+    /// Return `true` if `u` and `v` are in the same tree.
+    ///
+    /// # Panics
+    /// Panics if `u` or `v` is out of range.
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        self.root(self.vertices[u]) == self.root(self.vertices[v])
+    }
+
+    /// Add the edge `u - v`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `u` or `v` is not a vertex, and
+    /// `AlreadyConnected` Error when they are in the same tree.
+    pub fn link(&mut self, u: usize, v: usize) -> Result<()> {
+        self.check(u)?;
+        self.check(v)?;
+        if self.connected(u, v) {
+            return Err(Error::AlreadyConnected);
+        }
+        let tour_u = self.reroot(u);
+        let tour_v = self.reroot(v);
+        let uv = self.alloc(T::identity());
+        let vu = self.alloc(T::identity());
+        self.edges.insert((u, v), uv);
+        self.edges.insert((v, u), vu);
+        let tour = self.merge(tour_u, Some(uv));
+        let tour = self.merge(tour, tour_v);
+        self.merge(tour, Some(vu));
+        Ok(())
+    }
+
+    /// Remove the edge `u - v`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `u` or `v` is not a vertex, and
+    /// `NoSuchEdge` Error when they are not adjacent.
+    pub fn cut(&mut self, u: usize, v: usize) -> Result<()> {
+        self.check(u)?;
+        self.check(v)?;
+        let (uv, vu) = match (self.edges.get(&(u, v)), self.edges.get(&(v, u))) {
+            (Some(&uv), Some(&vu)) => (uv, vu),
+            _ => return Err(Error::NoSuchEdge),
+        };
+        let (first, second) = if self.position(uv) < self.position(vu) {
+            (uv, vu)
+        } else {
+            (vu, uv)
+        };
+        let root = self.root(first);
+        let (outer_left, rest) = self.split(Some(root), self.position(first));
+        let (_, rest) = self.split(rest, 1);
+        let (inner, rest) = self.split(rest, self.position(second));
+        let (_, outer_right) = self.split(rest, 1);
+        self.merge(outer_left, outer_right);
+        if let Some(inner) = inner {
+            self.nodes[inner].parent = None;
+        }
+        self.edges.remove(&(u, v));
+        self.edges.remove(&(v, u));
+        self.release(uv);
+        self.release(vu);
+        Ok(())
+    }
+
+    /// Aggregate the values of all vertices in the tree containing `vertex`.
+    ///
+    /// # Panics
+    /// Panics if `vertex` is out of range.
+    pub fn tree_aggregate(&self, vertex: usize) -> T {
+        self.nodes[self.root(self.vertices[vertex])].sum.clone()
+    }
+
+    /// Get the number of vertices in the tree containing `vertex`.
+    ///
+    /// # Panics
+    /// Panics if `vertex` is out of range.
+    pub fn tree_size(&self, vertex: usize) -> usize {
+        // A tour of `k` vertices has `k` vertex and `2(k - 1)` edge occurrences.
+        self.nodes[self.root(self.vertices[vertex])]
+            .size
+            .div_ceil(3)
+    }
+
+    /// Aggregate the values of the subtree of `vertex` when the tree is
+    /// rooted so that `parent` is the parent of `vertex`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `vertex` or `parent` is not a
+    /// vertex, and `NoSuchEdge` Error when they are not adjacent.
+    pub fn subtree_aggregate(&mut self, vertex: usize, parent: usize) -> Result<T> {
+        self.check(vertex)?;
+        self.check(parent)?;
+        let (enter, leave) = match (
+            self.edges.get(&(parent, vertex)),
+            self.edges.get(&(vertex, parent)),
+        ) {
+            (Some(&enter), Some(&leave)) => (enter, leave),
+            _ => return Err(Error::NoSuchEdge),
+        };
+        // The tour enters the subtree through `enter` and leaves through
+        // `leave`, wrapping around the end of the sequence if needed.
+        let root = self.root(enter);
+        let (start, end) = (self.position(enter), self.position(leave));
+        let (left, rest) = self.split(Some(root), start.min(end) + 1);
+        let (middle, right) = self.split(rest, start.max(end) - start.min(end) - 1);
+        let sum = if start < end {
+            self.sum(middle)
+        } else {
+            self.sum(left).combine(&self.sum(right))
+        };
+        let tour = self.merge(left, middle);
+        self.merge(tour, right);
+        Ok(sum)
+    }
+
+    /// Check that `vertex` is a vertex of the forest.
+    fn check(&self, vertex: usize) -> Result<()> {
+        if vertex < self.vertices.len() {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfRange {
+                index: vertex,
+                len: self.vertices.len(),
+            })
+        }
+    }
+
+    /// Rotate the tour of the tree containing `vertex` to start at it.
+    fn reroot(&mut self, vertex: usize) -> Link {
+        let node = self.vertices[vertex];
+        let root = self.root(node);
+        let (before, after) = self.split(Some(root), self.position(node));
+        self.merge(after, before)
+    }
+
+    fn alloc(&mut self, value: T) -> usize {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        let node = TourNode {
+            left: None,
+            right: None,
+            parent: None,
+            priority: self.seed,
+            size: 1,
+            sum: value.clone(),
+            value,
+        };
+        match self.free.pop() {
+            Some(idx) => {
+                self.nodes[idx] = node;
+                idx
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, node: usize) {
+        self.free.push(node);
+    }
+
+    fn root(&self, mut node: usize) -> usize {
+        while let Some(parent) = self.nodes[node].parent {
+            node = parent;
+        }
+        node
+    }
+
+    fn position(&self, mut node: usize) -> usize {
+        let mut position = self.size(self.nodes[node].left);
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].right == Some(node) {
+                position += self.size(self.nodes[parent].left) + 1;
+            }
+            node = parent;
+        }
+        position
+    }
+
+    fn size(&self, node: Link) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    fn sum(&self, node: Link) -> T {
+        node.map_or_else(T::identity, |node| self.nodes[node].sum.clone())
+    }
+
+    fn update(&mut self, node: usize) {
+        let TourNode { left, right, .. } = self.nodes[node];
+        let sum = self
+            .sum(left)
+            .combine(&self.nodes[node].value)
+            .combine(&self.sum(right));
+        self.nodes[node].size = self.size(left) + 1 + self.size(right);
+        self.nodes[node].sum = sum;
+        for child in left.into_iter().chain(right) {
+            self.nodes[child].parent = Some(node);
+        }
+    }
+
+    fn merge(&mut self, left: Link, right: Link) -> Link {
+        let (left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            (left, None) => return left,
+            (None, right) => return right,
+        };
+        let root = if self.nodes[left].priority > self.nodes[right].priority {
+            let merged = self.merge(self.nodes[left].right, Some(right));
+            self.nodes[left].right = merged;
+            left
+        } else {
+            let merged = self.merge(Some(left), self.nodes[right].left);
+            self.nodes[right].left = merged;
+            right
+        };
+        self.update(root);
+        self.nodes[root].parent = None;
+        Some(root)
+    }
+
+    /// Split off the first `count` nodes.
+    fn split(&mut self, node: Link, count: usize) -> (Link, Link) {
+        let node = match node {
+            Some(node) => node,
+            None => return (None, None),
+        };
+        let left_size = self.size(self.nodes[node].left);
+        let (left, right) = if count <= left_size {
+            let (left, right) = self.split(self.nodes[node].left, count);
+            self.nodes[node].left = right;
+            (left, Some(node))
+        } else {
+            let (left, right) = self.split(self.nodes[node].right, count - left_size - 1);
+            self.nodes[node].right = left;
+            (Some(node), right)
+        };
+        self.update(node);
+        for part in left.into_iter().chain(right) {
+            self.nodes[part].parent = None;
+        }
+        (left, right)
+    }
+}
@@ -0,0 +1,268 @@
+use crate::binary_tree::display::Glyphs;
+use crate::binary_tree::Node as BinaryNode;
+use crate::diagram::Diagram;
+use crate::tree::Node;
+use std::fmt::{self, Display};
+
+/// Line of an [`Explorer`] as shown on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// Index of the node in pre order.
+    pub node: usize,
+    /// Depth of the node, the root having depth 0.
+    pub depth: usize,
+    /// Connectors drawn before the label, such as `│   ├── `.
+    pub prefix: String,
+    /// Label of the node.
+    pub label: &'a str,
+    /// Whether the children are shown, `None` for a leaf.
+    pub expanded: Option<bool>,
+    /// Whether the cursor is on the node.
+    pub selected: bool,
+}
+
+/// State of an interactive tree explorer: which nodes are expanded, where
+/// the cursor is, and the lines to draw.
+///
+/// The explorer copies the labels of a tree, so it does not borrow it, and
+/// leaves drawing and key handling to the terminal front end, which lays
+/// out [`Explorer::lines`]. Nodes are identified by their index in pre
+/// order. Displaying it draws the visible lines, the selected one marked
+/// with `>`.
+#[derive(Debug, Clone)]
+pub struct Explorer {
+    labels: Vec<String>,
+    children: Vec<Vec<usize>>,
+    parent: Vec<Option<usize>>,
+    expanded: Vec<bool>,
+    cursor: usize,
+}
+
+impl Explorer {
+    fn new(diagram: Diagram) -> Self {
+        let children: Vec<Vec<usize>> = diagram
+            .children
+            .iter()
+            .map(|slots| slots.iter().flatten().copied().collect())
+            .collect();
+        let mut parent = vec![None; children.len()];
+        for (idx, own) in children.iter().enumerate() {
+            for &child in own {
+                parent[child] = Some(idx);
+            }
+        }
+        let mut expanded = vec![false; children.len()];
+        expanded[0] = true;
+        Self {
+            labels: diagram.labels,
+            children,
+            parent,
+            expanded,
+            cursor: 0,
+        }
+    }
+
+    /// Create an explorer of a binary tree, the root being expanded.
+    /// Missing children are not shown.
+    pub fn binary<T: Display>(root: &BinaryNode<T>) -> Self {
+        Self::new(Diagram::binary(root))
+    }
+
+    /// Create an explorer of an n-ary tree, the root being expanded.
+    pub fn n_ary<T: Display>(root: &Node<T>) -> Self {
+        Self::new(Diagram::n_ary(root))
+    }
+
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Return `false`, an explorer holding at least the root.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Get the label of a node.
+    pub fn label(&self, node: usize) -> Option<&str> {
+        self.labels.get(node).map(String::as_str)
+    }
+
+    /// Get the node under the cursor.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Return `true` if the children of `node` are shown.
+    pub fn is_expanded(&self, node: usize) -> bool {
+        self.expanded.get(node).copied().unwrap_or(false)
+    }
+
+    /// Get the visible nodes in pre order with their depths, skipping the
+    /// subtrees of collapsed nodes.
+    fn visible(&self) -> Vec<(usize, usize)> {
+        let mut visible = Vec::new();
+        let mut stack = vec![(0, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            visible.push((node, depth));
+            if self.expanded[node] {
+                stack.extend(
+                    self.children[node]
+                        .iter()
+                        .rev()
+                        .map(|&child| (child, depth + 1)),
+                );
+            }
+        }
+        visible
+    }
+
+    /// Get the visible lines, top to bottom, in `O(n)`.
+    pub fn lines(&self) -> Vec<Line<'_>> {
+        let glyphs = Glyphs::UNICODE;
+        // Whether every ancestor below the root still has siblings to
+        // come, which decides between a vertical line and a blank.
+        let mut open: Vec<bool> = Vec::new();
+        let mut lines = Vec::new();
+        for (node, depth) in self.visible() {
+            let mut prefix = String::new();
+            if depth > 0 {
+                open.truncate(depth - 1);
+                for &more in &open {
+                    prefix.push(if more { glyphs.vertical } else { ' ' });
+                    prefix.push_str("   ");
+                }
+                let siblings = &self.children[self.parent[node].expect("only the root is on top")];
+                let last = siblings.last() == Some(&node);
+                prefix.push(if last { glyphs.last } else { glyphs.branch });
+                prefix.push(glyphs.horizontal);
+                prefix.push(glyphs.horizontal);
+                prefix.push(' ');
+                open.push(!last);
+            }
+            lines.push(Line {
+                node,
+                depth,
+                prefix,
+                label: &self.labels[node],
+                expanded: if self.children[node].is_empty() {
+                    None
+                } else {
+                    Some(self.expanded[node])
+                },
+                selected: node == self.cursor,
+            });
+        }
+        lines
+    }
+
+    /// Move the cursor to the next visible line, returning `false` on the
+    /// last one.
+    pub fn go_down(&mut self) -> bool {
+        let visible = self.visible();
+        let at = visible.iter().position(|&(node, _)| node == self.cursor);
+        match at.and_then(|at| visible.get(at + 1)) {
+            Some(&(node, _)) => {
+                self.cursor = node;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the cursor to the previous visible line, returning `false` on
+    /// the first one.
+    pub fn go_up(&mut self) -> bool {
+        let visible = self.visible();
+        let at = visible.iter().position(|&(node, _)| node == self.cursor);
+        match at.and_then(|at| at.checked_sub(1)) {
+            Some(at) => {
+                self.cursor = visible[at].0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the cursor to the parent, returning `false` at the root.
+    pub fn go_parent(&mut self) -> bool {
+        match self.parent[self.cursor] {
+            Some(parent) => {
+                self.cursor = parent;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Show the children of the node under the cursor.
+    pub fn expand(&mut self) {
+        self.expanded[self.cursor] = true;
+    }
+
+    /// Hide the subtree of the node under the cursor.
+    pub fn collapse(&mut self) {
+        self.expanded[self.cursor] = false;
+    }
+
+    /// Expand the node under the cursor if collapsed, and collapse it
+    /// otherwise.
+    pub fn toggle(&mut self) {
+        self.expanded[self.cursor] = !self.expanded[self.cursor];
+    }
+
+    /// Expand every node.
+    pub fn expand_all(&mut self) {
+        self.expanded
+            .iter_mut()
+            .for_each(|expanded| *expanded = true);
+    }
+
+    /// Collapse every node but the root, moving the cursor to the root if
+    /// it gets hidden.
+    pub fn collapse_all(&mut self) {
+        self.expanded
+            .iter_mut()
+            .for_each(|expanded| *expanded = false);
+        self.expanded[0] = true;
+        if self.parent[self.cursor].is_some_and(|parent| parent != 0) {
+            self.cursor = 0;
+        }
+    }
+
+    /// Move the cursor to the next node in pre order whose label contains
+    /// `query`, wrapping around after the last one and expanding its
+    /// ancestors so it is visible. Return `false` if no other node matches.
+    pub fn search(&mut self, query: &str) -> bool {
+        let len = self.labels.len();
+        let found = (1..len)
+            .map(|offset| (self.cursor + offset) % len)
+            .find(|&node| self.labels[node].contains(query));
+        let found = match found {
+            Some(found) => found,
+            None => return false,
+        };
+        let mut ancestor = self.parent[found];
+        while let Some(node) = ancestor {
+            self.expanded[node] = true;
+            ancestor = self.parent[node];
+        }
+        self.cursor = found;
+        true
+    }
+}
+
+impl fmt::Display for Explorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines() {
+            let cursor = if line.selected { '>' } else { ' ' };
+            let marker = match line.expanded {
+                Some(true) => "▾ ",
+                Some(false) => "▸ ",
+                None => "",
+            };
+            writeln!(f, "{} {}{}{}", cursor, line.prefix, marker, line.label)?;
+        }
+        Ok(())
+    }
+}
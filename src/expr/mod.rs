@@ -0,0 +1,315 @@
+use crate::binary_tree::{Node, Order};
+use crate::{Error, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Infix expression parser.
+pub mod parser;
+
+/// Operators of an expression tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Addition.
+    Add,
+    /// Subtraction.
+    Sub,
+    /// Multiplication.
+    Mul,
+    /// Division.
+    Div,
+    /// Exponentiation.
+    Pow,
+    /// Unary negation, the operand is the left child.
+    Neg,
+}
+
+impl Op {
+    /// Get the binding power, higher binds tighter.
+    pub fn precedence(self) -> u8 {
+        match self {
+            Op::Add | Op::Sub => 1,
+            Op::Mul | Op::Div => 2,
+            Op::Neg => 3,
+            Op::Pow => 4,
+        }
+    }
+
+    /// Return `true` if the operator groups to the right.
+    pub fn is_right_assoc(self) -> bool {
+        matches!(self, Op::Pow | Op::Neg)
+    }
+
+    /// Return `true` if the operator takes a single operand.
+    pub fn is_unary(self) -> bool {
+        self == Op::Neg
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub | Op::Neg => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Pow => "^",
+        }
+    }
+
+    fn apply(self, lhs: f64, rhs: f64) -> f64 {
+        match self {
+            Op::Add => lhs + rhs,
+            Op::Sub => lhs - rhs,
+            Op::Mul => lhs * rhs,
+            Op::Div => lhs / rhs,
+            Op::Pow => lhs.powf(rhs),
+            Op::Neg => -lhs,
+        }
+    }
+}
+
+/// Data of an expression tree node.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprNode {
+    /// Numeric literal.
+    Num(f64),
+    /// Named variable.
+    Var(String),
+    /// Operator applied to the children.
+    Op(Op),
+}
+
+impl fmt::Display for ExprNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprNode::Num(value) => write!(f, "{}", value),
+            ExprNode::Var(name) => write!(f, "{}", name),
+            ExprNode::Op(op) => write!(f, "{}", op.symbol()),
+        }
+    }
+}
+
+/// Expression tree.
+///
+/// The `Display` impl prints the expression in infix notation
+/// with the minimal parentheses.
+#[derive(Debug, Clone)]
+pub struct Expr {
+    root: Node<ExprNode>,
+}
+
+impl Expr {
+    /// Create a numeric literal.
+    pub fn num(value: f64) -> Self {
+        Self {
+            root: Node::new(ExprNode::Num(value)),
+        }
+    }
+
+    /// Create a variable.
+    pub fn var(name: impl Into<String>) -> Self {
+        Self {
+            root: Node::new(ExprNode::Var(name.into())),
+        }
+    }
+
+    /// Create a binary operation.
+    ///
+    /// # Panics
+    /// Panic if `op` is unary.
+    pub fn binary(op: Op, lhs: Expr, rhs: Expr) -> Self {
+        assert!(!op.is_unary(), "`{:?}` is not a binary operator", op);
+        let mut root = Node::new(ExprNode::Op(op));
+        root.set_left(Some(lhs.root));
+        root.set_right(Some(rhs.root));
+        Self { root }
+    }
+
+    /// Create a negation.
+    pub fn negate(operand: Expr) -> Self {
+        let mut root = Node::new(ExprNode::Op(Op::Neg));
+        root.set_left(Some(operand.root));
+        Self { root }
+    }
+
+    /// Parse an infix expression such as `"(1+2)*x"`.
+    /// # Errors
+    /// Return `Parse` Error when the input is malformed.
+    pub fn parse(input: &str) -> Result<Self> {
+        parser::Parser::new(input).parse()
+    }
+
+    /// Create from a binary tree.
+    /// # Errors
+    /// Return `MalformedExpression` Error when an operator has the wrong
+    /// number of operands or a leaf has children.
+    pub fn from_node(root: Node<ExprNode>) -> Result<Self> {
+        let mut stack = vec![&root];
+        while let Some(node) = stack.pop() {
+            let arity_ok = match node.data() {
+                ExprNode::Op(Op::Neg) => node.left().is_some() && node.right().is_none(),
+                ExprNode::Op(_) => node.left().is_some() && node.right().is_some(),
+                _ => node.left().is_none() && node.right().is_none(),
+            };
+            if !arity_ok {
+                return Err(Error::MalformedExpression);
+            }
+            stack.extend(node.left());
+            stack.extend(node.right());
+        }
+        Ok(Self { root })
+    }
+
+    /// Get the underlying binary tree.
+    pub fn root(&self) -> &Node<ExprNode> {
+        &self.root
+    }
+
+    /// Convert into the underlying binary tree.
+    pub fn into_node(self) -> Node<ExprNode> {
+        self.root
+    }
+
+    /// Evaluate an expression without variables.
+    /// # Errors
+    /// Return `UnboundVariable` Error when the expression contains a variable.
+    pub fn eval(&self) -> Result<f64> {
+        self.eval_with(&HashMap::new())
+    }
+
+    /// Evaluate with the given variable bindings.
+    /// # Errors
+    /// Return `UnboundVariable` Error when a variable is not bound.
+    pub fn eval_with(&self, vars: &HashMap<String, f64>) -> Result<f64> {
+        eval_node(&self.root, vars)
+    }
+
+    /// Replace every occurrence of the variable `name` with `value`.
+    pub fn substitute(self, name: &str, value: &Expr) -> Self {
+        Self {
+            root: substitute_node(self.root, name, &value.root),
+        }
+    }
+}
+
+fn eval_node(root: &Node<ExprNode>, vars: &HashMap<String, f64>) -> Result<f64> {
+    // The operands of a node are evaluated before it, so their values are
+    // on top of the stack when it is reached.
+    let mut values = Vec::new();
+    for node in root.subtrees(Order::Post) {
+        let value = match node.data() {
+            ExprNode::Num(value) => *value,
+            ExprNode::Var(name) => vars
+                .get(name)
+                .copied()
+                .ok_or_else(|| Error::UnboundVariable(name.clone()))?,
+            ExprNode::Op(op) => {
+                operand(node.left())?;
+                let rhs = match node.right() {
+                    Some(_) => values.pop().ok_or(Error::MalformedExpression)?,
+                    None => 0.0,
+                };
+                let lhs = values.pop().ok_or(Error::MalformedExpression)?;
+                op.apply(lhs, rhs)
+            }
+        };
+        values.push(value);
+    }
+    values.pop().ok_or(Error::MalformedExpression)
+}
+
+fn operand(node: Option<&Node<ExprNode>>) -> Result<&Node<ExprNode>> {
+    node.ok_or(Error::MalformedExpression)
+}
+
+fn substitute_node(mut root: Node<ExprNode>, name: &str, value: &Node<ExprNode>) -> Node<ExprNode> {
+    let mut stack = vec![&mut root];
+    while let Some(node) = stack.pop() {
+        if matches!(node.data(), ExprNode::Var(var) if var == name) {
+            *node = value.clone();
+            continue;
+        }
+        let (_, left, right) = node.parts_mut();
+        stack.extend(left);
+        stack.extend(right);
+    }
+    root
+}
+
+/// Binding power of the node when printed, leaves bind tightest.
+fn precedence(node: &Node<ExprNode>) -> u8 {
+    match node.data() {
+        ExprNode::Op(op) => op.precedence(),
+        ExprNode::Num(value) if value.is_sign_negative() => Op::Neg.precedence(),
+        _ => u8::MAX,
+    }
+}
+
+/// What is left to write of an expression.
+enum Piece<'a> {
+    Node(&'a Node<ExprNode>),
+    Text(&'static str),
+}
+
+fn push_operand<'a>(stack: &mut Vec<Piece<'a>>, node: &'a Node<ExprNode>, parenthesize: bool) {
+    if parenthesize {
+        stack.push(Piece::Text(")"));
+        stack.push(Piece::Node(node));
+        stack.push(Piece::Text("("));
+    } else {
+        stack.push(Piece::Node(node));
+    }
+}
+
+fn write_node(f: &mut fmt::Formatter<'_>, root: &Node<ExprNode>) -> fmt::Result {
+    // The pieces are pushed in reverse, so the leftmost is written first.
+    let mut stack = vec![Piece::Node(root)];
+    while let Some(piece) = stack.pop() {
+        let node = match piece {
+            Piece::Node(node) => node,
+            Piece::Text(text) => {
+                f.write_str(text)?;
+                continue;
+            }
+        };
+        let op = match node.data() {
+            ExprNode::Op(op) => *op,
+            data => {
+                write!(f, "{}", data)?;
+                continue;
+            }
+        };
+        let prec = op.precedence();
+        let left = operand(node.left()).map_err(|_| fmt::Error)?;
+        if op.is_unary() {
+            f.write_str(op.symbol())?;
+            push_operand(&mut stack, left, precedence(left) < prec);
+            continue;
+        }
+        let right = operand(node.right()).map_err(|_| fmt::Error)?;
+        let left_prec = precedence(left);
+        let right_prec = precedence(right);
+        let (left_parens, right_parens) = if op.is_right_assoc() {
+            (left_prec <= prec, right_prec < prec)
+        } else {
+            (left_prec < prec, right_prec <= prec)
+        };
+        push_operand(&mut stack, right, right_parens);
+        stack.push(Piece::Text(op.symbol()));
+        push_operand(&mut stack, left, left_parens);
+    }
+    Ok(())
+}
+
+impl FromStr for Expr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_node(f, &self.root)
+    }
+}
@@ -0,0 +1,202 @@
+use super::{Expr, Op};
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(Op),
+    Open,
+    Close,
+    End,
+}
+
+/// Operator precedence parser for infix expressions.
+///
+/// Supports numbers, identifiers, `+ - * / ^`, unary minus and parentheses.
+/// `^` is right associative and binds tighter than unary minus.
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+    token: Token,
+    token_start: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Create a parser over `input`.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            position: 0,
+            token: Token::End,
+            token_start: 0,
+        }
+    }
+
+    /// Parse the whole input into an expression tree.
+    /// # Errors
+    /// Return `Parse` Error when the input is malformed.
+    pub fn parse(mut self) -> Result<Expr> {
+        self.advance()?;
+        // Operands and the operators waiting for their right operand are
+        // kept on stacks rather than in recursive calls, so deep nesting
+        // cannot overflow the call stack.
+        let mut operands = Vec::new();
+        let mut pending = Vec::new();
+        loop {
+            // Prefix operators and opening parentheses, then an operand.
+            loop {
+                match self.token.clone() {
+                    Token::Num(value) => {
+                        self.advance()?;
+                        operands.push(Expr::num(value));
+                        break;
+                    }
+                    Token::Ident(name) => {
+                        self.advance()?;
+                        operands.push(Expr::var(name));
+                        break;
+                    }
+                    Token::Open => {
+                        self.advance()?;
+                        pending.push(Pending::Open);
+                    }
+                    Token::Op(Op::Sub) => {
+                        self.advance()?;
+                        pending.push(Pending::Op(Op::Neg));
+                    }
+                    _ => return Err(self.error("expected an operand")),
+                }
+            }
+            // Closing parentheses, then an infix operator or the end.
+            loop {
+                match self.token {
+                    Token::Op(op) => {
+                        reduce(&mut operands, &mut pending, infix_bp(op).0);
+                        self.advance()?;
+                        pending.push(Pending::Op(op));
+                        break;
+                    }
+                    Token::Close => {
+                        reduce(&mut operands, &mut pending, 0);
+                        if pending.pop().is_none() {
+                            return Err(self.error("unexpected trailing input"));
+                        }
+                        self.advance()?;
+                    }
+                    Token::End => {
+                        reduce(&mut operands, &mut pending, 0);
+                        if !pending.is_empty() {
+                            return Err(self.error("expected `)`"));
+                        }
+                        return Ok(operands.pop().expect("an operand was parsed"));
+                    }
+                    _ => return Err(self.error("expected an operator")),
+                }
+            }
+        }
+    }
+
+    fn advance(&mut self) -> Result<()> {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.token_start = self.position;
+        let mut chars = self.input[self.position..].chars();
+        let c = match chars.next() {
+            Some(c) => c,
+            None => {
+                self.token = Token::End;
+                return Ok(());
+            }
+        };
+        self.token = match c {
+            '+' => Token::Op(Op::Add),
+            '-' => Token::Op(Op::Sub),
+            '*' => Token::Op(Op::Mul),
+            '/' => Token::Op(Op::Div),
+            '^' => Token::Op(Op::Pow),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_digit() || c == '.' => {
+                let len = self.scan(|c| c.is_ascii_digit() || c == '.');
+                let literal = &self.input[self.position..self.position + len];
+                let value = literal
+                    .parse()
+                    .map_err(|_| self.error("invalid number literal"))?;
+                self.position += len;
+                self.token = Token::Num(value);
+                return Ok(());
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = self.scan(|c| c.is_alphanumeric() || c == '_');
+                let name = self.input[self.position..self.position + len].to_owned();
+                self.position += len;
+                self.token = Token::Ident(name);
+                return Ok(());
+            }
+            _ => return Err(self.error("unexpected character")),
+        };
+        self.position += c.len_utf8();
+        Ok(())
+    }
+
+    fn scan<F>(&self, f: F) -> usize
+    where
+        F: Fn(char) -> bool,
+    {
+        let rest = &self.input[self.position..];
+        rest.find(|c| !f(c)).unwrap_or(rest.len())
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Parse {
+            position: self.token_start,
+            message: message.to_owned(),
+        }
+    }
+}
+
+/// An opening parenthesis or an operator waiting for its right operand.
+#[derive(Debug, Clone, Copy)]
+enum Pending {
+    Open,
+    Op(Op),
+}
+
+/// Apply the pending operators binding tighter than `left_bp`, down to
+/// the innermost open parenthesis.
+fn reduce(operands: &mut Vec<Expr>, pending: &mut Vec<Pending>, left_bp: u8) {
+    while let Some(&Pending::Op(op)) = pending.last() {
+        let right_bp = if op.is_unary() {
+            prefix_bp()
+        } else {
+            infix_bp(op).1
+        };
+        if left_bp >= right_bp {
+            break;
+        }
+        pending.pop();
+        let rhs = operands.pop().expect("an operator has an operand");
+        let expr = if op.is_unary() {
+            Expr::negate(rhs)
+        } else {
+            let lhs = operands.pop().expect("a binary operator has two operands");
+            Expr::binary(op, lhs, rhs)
+        };
+        operands.push(expr);
+    }
+}
+
+fn prefix_bp() -> u8 {
+    5
+}
+
+fn infix_bp(op: Op) -> (u8, u8) {
+    match op {
+        Op::Add | Op::Sub => (1, 2),
+        Op::Mul | Op::Div => (3, 4),
+        Op::Pow => (8, 7),
+        Op::Neg => unreachable!("negation is not an infix operator"),
+    }
+}
@@ -0,0 +1,86 @@
+use crate::monoid::Monoid;
+use crate::{Error, Result};
+
+/// Fenwick tree, or binary indexed tree, over a sequence of values of a
+/// commutative monoid.
+///
+/// Point updates and prefix aggregates run in `O(log n)` on a single
+/// array. Updates are folded into the covering cells out of order, so the
+/// monoid must be commutative; use a [`SegmentTree`] otherwise.
+///
+/// [`SegmentTree`]: crate::segment_tree::SegmentTree
+#[derive(Debug, Clone)]
+pub struct FenwickTree<M> {
+    /// `cells[i]` aggregates the values in `(i + 1 - lowbit(i + 1))..=i`.
+    cells: Vec<M>,
+}
+
+impl<M: Monoid> FenwickTree<M> {
+    /// Create a tree of `len` identity values.
+    pub fn new(len: usize) -> Self {
+        Self {
+            cells: vec![M::identity(); len],
+        }
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Return `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Combine `value` into the value at `index`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `index` is out of bounds.
+    pub fn add(&mut self, index: usize, value: &M) -> Result<()> {
+        if index >= self.len() {
+            return Err(Error::IndexOutOfRange {
+                index,
+                len: self.len(),
+            });
+        }
+        let mut i = index + 1;
+        while i <= self.len() {
+            self.cells[i - 1] = self.cells[i - 1].combine(value);
+            i += i & i.wrapping_neg();
+        }
+        Ok(())
+    }
+
+    /// Aggregate the values before `end`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `end` is past the length.
+    pub fn prefix(&self, end: usize) -> Result<M> {
+        if end > self.len() {
+            return Err(Error::IndexOutOfRange {
+                index: end,
+                len: self.len(),
+            });
+        }
+        let mut acc = M::identity();
+        let mut i = end;
+        while i > 0 {
+            acc = self.cells[i - 1].combine(&acc);
+            i -= i & i.wrapping_neg();
+        }
+        Ok(acc)
+    }
+}
+
+impl<M: Monoid> From<Vec<M>> for FenwickTree<M> {
+    /// Build the tree over `values` in `O(n)`.
+    fn from(values: Vec<M>) -> Self {
+        let mut cells = values;
+        for i in 1..=cells.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= cells.len() {
+                cells[parent - 1] = cells[parent - 1].combine(&cells[i - 1]);
+            }
+        }
+        Self { cells }
+    }
+}
@@ -0,0 +1,553 @@
+pub use crate::monoid::{Measure, Monoid};
+use std::iter::FromIterator;
+use std::mem;
+
+/// Number of items, turning a finger tree into an indexed sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Size(pub usize);
+
+impl Monoid for Size {
+    fn identity() -> Self {
+        Size(0)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Size(self.0 + other.0)
+    }
+}
+
+impl<T> Measure<T> for Size {
+    fn measure(_item: &T) -> Self {
+        Size(1)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Item<T, M> {
+    Leaf(T),
+    /// A 2-3 node with its cached measure.
+    Node(M, Vec<Item<T, M>>),
+}
+
+impl<T, M: Measure<T>> Item<T, M> {
+    fn node(children: Vec<Item<T, M>>) -> Self {
+        Item::Node(measure_all(&children), children)
+    }
+
+    fn measure(&self) -> M {
+        match self {
+            Item::Leaf(item) => M::measure(item),
+            Item::Node(measure, _) => measure.clone(),
+        }
+    }
+
+    fn into_leaf(self) -> T {
+        match self {
+            Item::Leaf(item) => item,
+            Item::Node(..) => unreachable!("top level items are leaves"),
+        }
+    }
+
+    fn into_children(self) -> Vec<Item<T, M>> {
+        match self {
+            Item::Node(_, children) => children,
+            Item::Leaf(_) => unreachable!("nested items are nodes"),
+        }
+    }
+}
+
+fn measure_all<T, M: Measure<T>>(items: &[Item<T, M>]) -> M {
+    items
+        .iter()
+        .fold(M::identity(), |acc, item| acc.combine(&item.measure()))
+}
+
+type Digit<T, M> = Vec<Item<T, M>>;
+
+#[derive(Debug, Clone, Default)]
+enum Tree<T, M> {
+    #[default]
+    Empty,
+    Single(Item<T, M>),
+    Deep(Box<Deep<T, M>>),
+}
+
+/// Digits hold 1 to 4 items, the middle tree holds nodes one level deeper.
+#[derive(Debug, Clone)]
+struct Deep<T, M> {
+    measure: M,
+    prefix: Vec<Item<T, M>>,
+    middle: Tree<T, M>,
+    suffix: Vec<Item<T, M>>,
+}
+
+impl<T, M: Measure<T>> Tree<T, M> {
+    fn deep(prefix: Vec<Item<T, M>>, middle: Self, suffix: Vec<Item<T, M>>) -> Self {
+        let measure = measure_all(&prefix)
+            .combine(&middle.measure())
+            .combine(&measure_all(&suffix));
+        Tree::Deep(Box::new(Deep {
+            measure,
+            prefix,
+            middle,
+            suffix,
+        }))
+    }
+
+    fn from_digit(items: Vec<Item<T, M>>) -> Self {
+        items
+            .into_iter()
+            .fold(Tree::Empty, |tree, item| tree.push_back(item))
+    }
+
+    /// Build a deep tree whose prefix may be empty.
+    fn deep_left(prefix: Vec<Item<T, M>>, middle: Self, suffix: Vec<Item<T, M>>) -> Self {
+        if !prefix.is_empty() {
+            return Self::deep(prefix, middle, suffix);
+        }
+        match middle.pop_front() {
+            Some((node, middle)) => Self::deep(node.into_children(), middle, suffix),
+            None => Self::from_digit(suffix),
+        }
+    }
+
+    /// Build a deep tree whose suffix may be empty.
+    fn deep_right(prefix: Vec<Item<T, M>>, middle: Self, suffix: Vec<Item<T, M>>) -> Self {
+        if !suffix.is_empty() {
+            return Self::deep(prefix, middle, suffix);
+        }
+        match middle.pop_back() {
+            Some((middle, node)) => Self::deep(prefix, middle, node.into_children()),
+            None => Self::from_digit(prefix),
+        }
+    }
+
+    fn measure(&self) -> M {
+        match self {
+            Tree::Empty => M::identity(),
+            Tree::Single(item) => item.measure(),
+            Tree::Deep(deep) => deep.measure.clone(),
+        }
+    }
+
+    fn push_front(self, item: Item<T, M>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(item),
+            Tree::Single(other) => Self::deep(vec![item], Tree::Empty, vec![other]),
+            Tree::Deep(deep) => {
+                let Deep {
+                    mut prefix,
+                    middle,
+                    suffix,
+                    ..
+                } = *deep;
+                if prefix.len() == 4 {
+                    let rest = prefix.split_off(1);
+                    prefix.insert(0, item);
+                    Self::deep(prefix, middle.push_front(Item::node(rest)), suffix)
+                } else {
+                    prefix.insert(0, item);
+                    Self::deep(prefix, middle, suffix)
+                }
+            }
+        }
+    }
+
+    fn push_back(self, item: Item<T, M>) -> Self {
+        match self {
+            Tree::Empty => Tree::Single(item),
+            Tree::Single(other) => Self::deep(vec![other], Tree::Empty, vec![item]),
+            Tree::Deep(deep) => {
+                let Deep {
+                    prefix,
+                    middle,
+                    mut suffix,
+                    ..
+                } = *deep;
+                if suffix.len() == 4 {
+                    let last = suffix.split_off(3);
+                    let rest = mem::replace(&mut suffix, last);
+                    suffix.push(item);
+                    Self::deep(prefix, middle.push_back(Item::node(rest)), suffix)
+                } else {
+                    suffix.push(item);
+                    Self::deep(prefix, middle, suffix)
+                }
+            }
+        }
+    }
+
+    fn pop_front(self) -> Option<(Item<T, M>, Self)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(item) => Some((item, Tree::Empty)),
+            Tree::Deep(deep) => {
+                let Deep {
+                    mut prefix,
+                    middle,
+                    suffix,
+                    ..
+                } = *deep;
+                let first = prefix.remove(0);
+                Some((first, Self::deep_left(prefix, middle, suffix)))
+            }
+        }
+    }
+
+    fn pop_back(self) -> Option<(Self, Item<T, M>)> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(item) => Some((Tree::Empty, item)),
+            Tree::Deep(deep) => {
+                let Deep {
+                    prefix,
+                    middle,
+                    mut suffix,
+                    ..
+                } = *deep;
+                let last = suffix.pop()?;
+                Some((Self::deep_right(prefix, middle, suffix), last))
+            }
+        }
+    }
+
+    fn front(&self) -> Option<&Item<T, M>> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(item) => Some(item),
+            Tree::Deep(deep) => deep.prefix.first(),
+        }
+    }
+
+    fn back(&self) -> Option<&Item<T, M>> {
+        match self {
+            Tree::Empty => None,
+            Tree::Single(item) => Some(item),
+            Tree::Deep(deep) => deep.suffix.last(),
+        }
+    }
+
+    /// Concatenate `left`, the loose `items` and `right`.
+    fn concat(left: Self, items: Vec<Item<T, M>>, right: Self) -> Self {
+        match (left, right) {
+            (Tree::Empty, right) => items
+                .into_iter()
+                .rev()
+                .fold(right, |tree, item| tree.push_front(item)),
+            (left, Tree::Empty) => items
+                .into_iter()
+                .fold(left, |tree, item| tree.push_back(item)),
+            (Tree::Single(item), right) => Self::concat(Tree::Empty, items, right).push_front(item),
+            (left, Tree::Single(item)) => Self::concat(left, items, Tree::Empty).push_back(item),
+            (Tree::Deep(left), Tree::Deep(right)) => {
+                let left = *left;
+                let right = *right;
+                let mut loose = left.suffix;
+                loose.extend(items);
+                loose.extend(right.prefix);
+                let middle = Self::concat(left.middle, nodes(loose), right.middle);
+                Self::deep(left.prefix, middle, right.suffix)
+            }
+        }
+    }
+
+    /// Split a non-empty tree around the first item at which `pred`
+    /// holds for the accumulated measure, where `pred(acc + measure)` holds.
+    fn split<P>(self, pred: &P, acc: M) -> (Self, Item<T, M>, Self)
+    where
+        P: Fn(&M) -> bool,
+    {
+        let deep = match self {
+            Tree::Empty => unreachable!("cannot split an empty tree"),
+            Tree::Single(item) => return (Tree::Empty, item, Tree::Empty),
+            Tree::Deep(deep) => *deep,
+        };
+        let Deep {
+            prefix,
+            middle,
+            suffix,
+            ..
+        } = deep;
+        let after_prefix = acc.combine(&measure_all(&prefix));
+        if pred(&after_prefix) {
+            let (left, item, right) = split_digit(prefix, pred, acc);
+            return (
+                Self::from_digit(left),
+                item,
+                Self::deep_left(right, middle, suffix),
+            );
+        }
+        let after_middle = after_prefix.combine(&middle.measure());
+        if pred(&after_middle) {
+            let (middle_left, node, middle_right) = middle.split(pred, after_prefix.clone());
+            let acc = after_prefix.combine(&middle_left.measure());
+            let (left, item, right) = split_digit(node.into_children(), pred, acc);
+            return (
+                Self::deep_right(prefix, middle_left, left),
+                item,
+                Self::deep_left(right, middle_right, suffix),
+            );
+        }
+        let (left, item, right) = split_digit(suffix, pred, after_middle);
+        (
+            Self::deep_right(prefix, middle, left),
+            item,
+            Self::from_digit(right),
+        )
+    }
+}
+
+/// Group 2 or more items into 2-3 nodes.
+fn nodes<T, M: Measure<T>>(mut items: Vec<Item<T, M>>) -> Vec<Item<T, M>> {
+    let mut result = Vec::new();
+    while items.len() > 4 {
+        let rest = items.split_off(3);
+        result.push(Item::node(mem::replace(&mut items, rest)));
+    }
+    if items.len() == 4 {
+        let rest = items.split_off(2);
+        result.push(Item::node(items));
+        result.push(Item::node(rest));
+    } else if !items.is_empty() {
+        result.push(Item::node(items));
+    }
+    result
+}
+
+/// Split a digit around the first item at which `pred` holds.
+fn split_digit<T, M, P>(
+    mut items: Vec<Item<T, M>>,
+    pred: &P,
+    mut acc: M,
+) -> (Digit<T, M>, Item<T, M>, Digit<T, M>)
+where
+    M: Measure<T>,
+    P: Fn(&M) -> bool,
+{
+    let mut idx = 0;
+    while idx + 1 < items.len() {
+        acc = acc.combine(&items[idx].measure());
+        if pred(&acc) {
+            break;
+        }
+        idx += 1;
+    }
+    let right = items.split_off(idx + 1);
+    let item = items.pop().expect("digits are not empty");
+    (items, item, right)
+}
+
+/// 2-3 finger tree annotated with a monoidal measure `M`.
+///
+/// Pushing and popping at either end are amortized `O(1)`,
+/// concatenation and splitting are `O(log n)`.
+#[derive(Debug, Clone)]
+pub struct FingerTree<T, M> {
+    tree: Tree<T, M>,
+}
+
+impl<T, M> Default for FingerTree<T, M> {
+    fn default() -> Self {
+        Self { tree: Tree::Empty }
+    }
+}
+
+impl<T, M: Measure<T>> FingerTree<T, M> {
+    /// Create an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `true` if the tree is empty.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.tree, Tree::Empty)
+    }
+
+    /// Get the measure of the whole tree.
+    pub fn measure(&self) -> M {
+        self.tree.measure()
+    }
+
+    /// Add an item at the front.
+    pub fn push_front(&mut self, item: T) {
+        self.tree = mem::take(&mut self.tree).push_front(Item::Leaf(item));
+    }
+
+    /// Add an item at the back.
+    pub fn push_back(&mut self, item: T) {
+        self.tree = mem::take(&mut self.tree).push_back(Item::Leaf(item));
+    }
+
+    /// Remove the item at the front.
+    pub fn pop_front(&mut self) -> Option<T> {
+        let (item, tree) = mem::take(&mut self.tree).pop_front()?;
+        self.tree = tree;
+        Some(item.into_leaf())
+    }
+
+    /// Remove the item at the back.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let (tree, item) = mem::take(&mut self.tree).pop_back()?;
+        self.tree = tree;
+        Some(item.into_leaf())
+    }
+
+    /// Get the item at the front.
+    pub fn front(&self) -> Option<&T> {
+        match self.tree.front()? {
+            Item::Leaf(item) => Some(item),
+            Item::Node(..) => None,
+        }
+    }
+
+    /// Get the item at the back.
+    pub fn back(&self) -> Option<&T> {
+        match self.tree.back()? {
+            Item::Leaf(item) => Some(item),
+            Item::Node(..) => None,
+        }
+    }
+
+    /// Move all items of `other` to the back of `self`.
+    pub fn append(&mut self, other: Self) {
+        self.tree = Tree::concat(mem::take(&mut self.tree), Vec::new(), other.tree);
+    }
+
+    /// Split the tree into the longest prefix whose accumulated measure
+    /// does not satisfy `pred` and the rest.
+    ///
+    /// `pred` must be monotone: once it holds for an accumulated measure,
+    /// it holds for every extension of it.
+    pub fn split<P>(self, pred: P) -> (Self, Self)
+    where
+        P: Fn(&M) -> bool,
+    {
+        if self.is_empty() || !pred(&self.measure()) {
+            return (self, Self::new());
+        }
+        let (left, item, right) = self.tree.split(&pred, M::identity());
+        (
+            Self { tree: left },
+            Self {
+                tree: right.push_front(item),
+            },
+        )
+    }
+
+    /// Create an iterator over the items from front to back.
+    pub fn iter(&self) -> Iter<'_, T, M> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_tree(&self.tree);
+        iter
+    }
+}
+
+impl<T> FingerTree<T, Size> {
+    /// Get the number of items.
+    pub fn len(&self) -> usize {
+        self.measure().0
+    }
+
+    /// Split the sequence at `index`, returning the items before and after it.
+    pub fn split_at(self, index: usize) -> (Self, Self) {
+        self.split(|size| size.0 > index)
+    }
+
+    /// Get the item at `index`.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let mut tree = &self.tree;
+        let mut index = index;
+        let mut items: &[Item<T, Size>];
+        loop {
+            match tree {
+                Tree::Empty => return None,
+                Tree::Single(item) => {
+                    items = std::slice::from_ref(item);
+                    break;
+                }
+                Tree::Deep(deep) => {
+                    let prefix = measure_all(&deep.prefix).0;
+                    let middle = deep.middle.measure().0;
+                    if index < prefix {
+                        items = &deep.prefix;
+                        break;
+                    } else if index < prefix + middle {
+                        index -= prefix;
+                        tree = &deep.middle;
+                    } else {
+                        index -= prefix + middle;
+                        items = &deep.suffix;
+                        break;
+                    }
+                }
+            }
+        }
+        loop {
+            let mut next = None;
+            for item in items {
+                let size = item.measure().0;
+                if index < size {
+                    next = Some(item);
+                    break;
+                }
+                index -= size;
+            }
+            match next? {
+                Item::Leaf(item) => return Some(item),
+                Item::Node(_, children) => items = children,
+            }
+        }
+    }
+}
+
+impl<T, M: Measure<T>> FromIterator<T> for FingerTree<T, M> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for item in iter {
+            tree.push_back(item);
+        }
+        tree
+    }
+}
+
+#[derive(Debug)]
+enum Frame<'a, T, M> {
+    Tree(&'a Tree<T, M>),
+    Item(&'a Item<T, M>),
+}
+
+/// Iterator over the items of a [`FingerTree`].
+#[derive(Debug)]
+pub struct Iter<'a, T, M> {
+    stack: Vec<Frame<'a, T, M>>,
+}
+
+impl<'a, T, M> Iter<'a, T, M> {
+    fn push_tree(&mut self, tree: &'a Tree<T, M>) {
+        match tree {
+            Tree::Empty => {}
+            Tree::Single(item) => self.stack.push(Frame::Item(item)),
+            Tree::Deep(deep) => {
+                self.stack.extend(deep.suffix.iter().rev().map(Frame::Item));
+                self.stack.push(Frame::Tree(&deep.middle));
+                self.stack.extend(deep.prefix.iter().rev().map(Frame::Item));
+            }
+        }
+    }
+}
+
+impl<'a, T, M> Iterator for Iter<'a, T, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.pop()? {
+                Frame::Tree(tree) => self.push_tree(tree),
+                Frame::Item(Item::Leaf(item)) => return Some(item),
+                Frame::Item(Item::Node(_, children)) => {
+                    self.stack.extend(children.iter().rev().map(Frame::Item));
+                }
+            }
+        }
+    }
+}
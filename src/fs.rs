@@ -0,0 +1,213 @@
+use crate::binary_tree::display::Glyphs;
+use crate::tree::Node;
+use crate::Result;
+use std::fmt;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// File or directory read by [`FsTree::read`].
+#[derive(Debug, Clone)]
+pub struct FsEntry {
+    /// Last component of the path, converted lossily to UTF-8; the path
+    /// itself for the root.
+    pub name: String,
+    /// Path of the entry, the root path joined with the names below it.
+    pub path: PathBuf,
+    /// Metadata of the entry itself, symbolic links not being followed.
+    pub metadata: Metadata,
+}
+
+impl FsEntry {
+    fn read(path: PathBuf, name: String) -> Result<Self> {
+        let metadata = fs::symlink_metadata(&path)?;
+        Ok(Self {
+            name,
+            path,
+            metadata,
+        })
+    }
+
+    /// Return `true` if the entry is a directory.
+    pub fn is_dir(&self) -> bool {
+        self.metadata.is_dir()
+    }
+}
+
+impl fmt::Display for FsEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+type Filter = Rc<dyn Fn(&FsEntry) -> bool>;
+
+/// Options of a directory walk, used through [`FsTree::read_with`].
+#[derive(Clone, Default)]
+pub struct WalkOptions {
+    max_depth: Option<usize>,
+    filter: Option<Filter>,
+}
+
+impl fmt::Debug for WalkOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalkOptions")
+            .field("max_depth", &self.max_depth)
+            .field("filter", &self.filter.is_some())
+            .finish()
+    }
+}
+
+impl WalkOptions {
+    /// Create the default options: no depth limit and no filter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the entries down to depth `depth` only, the root having depth
+    /// 0. Deeper directories are listed but not opened.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Keep only the entries below the root for which `filter` holds.
+    /// Directories left out are not opened.
+    pub fn filter<F: Fn(&FsEntry) -> bool + 'static>(mut self, filter: F) -> Self {
+        self.filter = Some(Rc::new(filter));
+        self
+    }
+}
+
+/// Directory tree read from the filesystem.
+///
+/// Every node holds an [`FsEntry`], and the children of a directory are
+/// sorted by name. Symbolic links are listed but never followed, so the
+/// walk ends on any filesystem. Displaying it draws the tree like the
+/// `tree` command.
+#[derive(Debug, Clone)]
+pub struct FsTree {
+    root: Node<FsEntry>,
+}
+
+impl FsTree {
+    /// Read the directory tree at `path` with the default options.
+    /// # Errors
+    /// Return `Io` Error when an entry or a directory cannot be read.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::read_with(path, &WalkOptions::default())
+    }
+
+    /// Read the directory tree at `path`, without recursion.
+    /// # Errors
+    /// Return `Io` Error when an entry or a directory cannot be read.
+    pub fn read_with<P: AsRef<Path>>(path: P, options: &WalkOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let root = FsEntry::read(path.to_path_buf(), path.display().to_string())?;
+        // Read in pre order with parent indices, then build bottom-up.
+        let mut entries: Vec<(FsEntry, Option<usize>)> = Vec::new();
+        let mut stack = vec![(root, 0, None)];
+        while let Some((entry, depth, parent)) = stack.pop() {
+            let idx = entries.len();
+            if entry.is_dir() && options.max_depth.is_none_or(|max| depth < max) {
+                let mut children = Vec::new();
+                for child in fs::read_dir(&entry.path)? {
+                    let child = child?;
+                    let name = child.file_name().to_string_lossy().into_owned();
+                    let child = FsEntry::read(child.path(), name)?;
+                    if options.filter.as_ref().is_none_or(|keep| keep(&child)) {
+                        children.push(child);
+                    }
+                }
+                children.sort_by(|a, b| a.name.cmp(&b.name));
+                stack.extend(
+                    children
+                        .into_iter()
+                        .rev()
+                        .map(|child| (child, depth + 1, Some(idx))),
+                );
+            }
+            entries.push((entry, parent));
+        }
+        // Children come after their parent, so going backwards every node
+        // gets all of its children, last first.
+        let mut children: Vec<Vec<Node<FsEntry>>> = entries.iter().map(|_| Vec::new()).collect();
+        let mut root = None;
+        for (idx, (entry, parent)) in entries.into_iter().enumerate().rev() {
+            let mut node = Node::new(entry);
+            let own = std::mem::take(&mut children[idx]);
+            node.children_mut().extend(own.into_iter().rev());
+            match parent {
+                Some(parent) => children[parent].push(node),
+                None => root = Some(node),
+            }
+        }
+        Ok(Self {
+            root: root.expect("the root is read first"),
+        })
+    }
+
+    /// Get the ref of the root node.
+    pub fn root(&self) -> &Node<FsEntry> {
+        &self.root
+    }
+
+    /// Convert into the root node.
+    pub fn into_node(self) -> Node<FsEntry> {
+        self.root
+    }
+
+    /// Count the directories and the other entries below the root.
+    pub fn counts(&self) -> (usize, usize) {
+        let dirs = self
+            .root
+            .pre_order_iter()
+            .skip(1)
+            .filter(|entry| entry.is_dir())
+            .count();
+        let all = self.root.pre_order_iter().count() - 1;
+        (dirs, all - dirs)
+    }
+}
+
+impl fmt::Display for FsTree {
+    /// Draw the tree with [`Glyphs::UNICODE`] connectors, followed by the
+    /// counts of directories and files.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let glyphs = Glyphs::UNICODE;
+        // Whether every ancestor below the root still has siblings to
+        // come, which decides between a vertical line and a blank.
+        let mut open: Vec<bool> = Vec::new();
+        let mut stack = vec![(&self.root, 0, true)];
+        while let Some((node, depth, last)) = stack.pop() {
+            if depth > 0 {
+                open.truncate(depth - 1);
+                for &more in &open {
+                    let line = if more { glyphs.vertical } else { ' ' };
+                    write!(f, "{}   ", line)?;
+                }
+                let corner = if last { glyphs.last } else { glyphs.branch };
+                write!(f, "{}{}{} ", corner, glyphs.horizontal, glyphs.horizontal)?;
+                open.push(!last);
+            }
+            writeln!(f, "{}", node.data())?;
+            let children = node.children();
+            stack.extend(
+                children
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(idx, child)| (child, depth + 1, idx + 1 == children.len())),
+            );
+        }
+        let (dirs, files) = self.counts();
+        write!(
+            f,
+            "\n{} director{}, {} file{}",
+            dirs,
+            if dirs == 1 { "y" } else { "ies" },
+            files,
+            if files == 1 { "" } else { "s" }
+        )
+    }
+}
@@ -0,0 +1,224 @@
+use crate::binary_tree::bst::BinarySearchTree;
+use crate::binary_tree::Node;
+
+/// Source of random numbers for the generators.
+///
+/// Implemented for closures returning `u64`, so any random number
+/// generator can be plugged in, e.g. `|| rng.next_u64()`.
+pub trait Rng {
+    /// Get the next uniformly distributed 64-bit number.
+    fn next_u64(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> Rng for F {
+    fn next_u64(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Small seeded generator, good enough for tests and benchmarks but not
+/// for cryptography.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    /// Create a generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Get a number in `0..n`, `n` being positive.
+fn below<R: Rng + ?Sized>(rng: &mut R, n: usize) -> usize {
+    ((u128::from(rng.next_u64()) * n as u128) >> 64) as usize
+}
+
+/// Get a fair coin flip.
+fn coin<R: Rng + ?Sized>(rng: &mut R) -> bool {
+    rng.next_u64() >> 63 == 1
+}
+
+/// Build the tree linked by `left` and `right` from `root`, numbering the
+/// nodes in mid order.
+fn from_links(root: usize, left: &[Option<usize>], right: &[Option<usize>]) -> Node<usize> {
+    let mut shape = Vec::new();
+    let mut stack = vec![root];
+    while let Some(idx) = stack.pop() {
+        shape.push(((), left[idx].is_some(), right[idx].is_some()));
+        stack.extend(right[idx]);
+        stack.extend(left[idx]);
+    }
+    let node = Node::from_pre_order_shape(shape).expect("the links form a tree");
+    number(node)
+}
+
+/// Number the nodes in mid order.
+fn number<T>(node: Node<T>) -> Node<usize> {
+    let mut next = 0;
+    node.mid_order_map(|_| {
+        next += 1;
+        next - 1
+    })
+}
+
+/// Generate a binary tree of `n` nodes uniformly among all of their
+/// shapes, with Rémy's algorithm in `O(n)`.
+///
+/// The nodes hold `0..n` in mid order, so the tree is also a binary search
+/// tree. Return `None` if `n` is `0`.
+pub fn uniform<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Option<Node<usize>> {
+    if n == 0 {
+        return None;
+    }
+    // Grow a full binary tree of `n` inner nodes by splitting a uniformly
+    // chosen node into a new inner node over it and a new leaf, then keep
+    // the inner nodes only.
+    let total = 2 * n + 1;
+    let mut left = vec![None; total];
+    let mut right = vec![None; total];
+    let mut parent: Vec<Option<usize>> = vec![None; total];
+    let mut root = 0;
+    for step in 0..n {
+        let (inner, leaf) = (2 * step + 1, 2 * step + 2);
+        let split = below(rng, inner);
+        match parent[split] {
+            Some(up) if left[up] == Some(split) => left[up] = Some(inner),
+            Some(up) => right[up] = Some(inner),
+            None => root = inner,
+        }
+        parent[inner] = parent[split];
+        parent[split] = Some(inner);
+        parent[leaf] = Some(inner);
+        if coin(rng) {
+            left[inner] = Some(split);
+            right[inner] = Some(leaf);
+        } else {
+            left[inner] = Some(leaf);
+            right[inner] = Some(split);
+        }
+    }
+    // Leaves are the even nodes.
+    let inner = |link: Option<usize>| link.filter(|idx| idx % 2 == 1);
+    let left: Vec<_> = left.into_iter().map(inner).collect();
+    let right: Vec<_> = right.into_iter().map(inner).collect();
+    Some(from_links(root, &left, &right))
+}
+
+/// Generate the binary search tree of inserting `0..n` in a uniformly
+/// random order, without rebalancing. Return `None` if `n` is `0`.
+pub fn random_bst<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Option<Node<usize>> {
+    let mut values: Vec<usize> = (0..n).collect();
+    for idx in (1..n).rev() {
+        values.swap(idx, below(rng, idx + 1));
+    }
+    let mut tree = BinarySearchTree::new();
+    tree.extend(values);
+    tree.into_node()
+}
+
+/// Generate the complete binary tree of `n` nodes, every level full but
+/// the last one, which is filled from the left.
+///
+/// The nodes hold `0..n` in mid order. Return `None` if `n` is `0`.
+pub fn complete(n: usize) -> Option<Node<usize>> {
+    Node::from_level_order(vec![Some(()); n]).map(number)
+}
+
+/// Generate the perfect binary tree of height `height`, with
+/// `2^height - 1` nodes holding them in mid order. Return `None` if
+/// `height` is `0`.
+pub fn perfect(height: u32) -> Option<Node<usize>> {
+    complete((1 << height) - 1)
+}
+
+/// Generate a degenerate tree of `n` nodes, every inner node having a
+/// single child on a random side.
+///
+/// The nodes hold `0..n` in mid order. Return `None` if `n` is `0`.
+pub fn degenerate<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Option<Node<usize>> {
+    if n == 0 {
+        return None;
+    }
+    let mut left = vec![None; n];
+    let mut right = vec![None; n];
+    for idx in 0..n - 1 {
+        if coin(rng) {
+            left[idx] = Some(idx + 1);
+        } else {
+            right[idx] = Some(idx + 1);
+        }
+    }
+    Some(from_links(0, &left, &right))
+}
+
+/// Generate the chain of `n` left children, holding `0..n` from the
+/// deepest node up. Return `None` if `n` is `0`.
+pub fn left_chain(n: usize) -> Option<Node<usize>> {
+    degenerate(n, &mut || u64::MAX)
+}
+
+/// Generate the chain of `n` right children, holding `0..n` from the root
+/// down. Return `None` if `n` is `0`.
+pub fn right_chain(n: usize) -> Option<Node<usize>> {
+    degenerate(n, &mut || 0)
+}
+
+/// Get the trees one step smaller than `node` for shrinking a failing
+/// property-based test case: its subtrees on their own first, then the tree
+/// with each subtree but the whole removed, in pre order.
+///
+/// The candidates are cloned lazily, one per item.
+pub fn shrink<T: Clone>(node: &Node<T>) -> impl Iterator<Item = Node<T>> + '_ {
+    // Paths from the root to every other node, `true` going left.
+    let mut paths = Vec::new();
+    let mut stack = vec![(Vec::new(), node)];
+    while let Some((path, node)) = stack.pop() {
+        let child_path = |left: bool| {
+            let mut child: Vec<bool> = path.clone();
+            child.push(left);
+            child
+        };
+        if let Some(right) = node.right() {
+            stack.push((child_path(false), right));
+        }
+        if let Some(left) = node.left() {
+            stack.push((child_path(true), left));
+        }
+        if !path.is_empty() {
+            paths.push(path);
+        }
+    }
+    let subtrees = node.left().into_iter().chain(node.right()).cloned();
+    let removed = paths.into_iter().map(move |path| {
+        let mut tree = node.clone();
+        let (&last, path) = path.split_last().expect("the root is skipped");
+        let mut parent = &mut tree;
+        for &left in path {
+            let child = if left {
+                parent.left_mut()
+            } else {
+                parent.right_mut()
+            };
+            parent = child.expect("the path leads to a node");
+        }
+        if last {
+            parent.set_left(None);
+        } else {
+            parent.set_right(None);
+        }
+        tree
+    });
+    subtrees.chain(removed)
+}
@@ -0,0 +1,283 @@
+use crate::binary_tree::pool::{Global, NodeAlloc};
+use crate::binary_tree::Node;
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::mem;
+
+/// Heap ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HeapKind {
+    /// The smallest item is at the root.
+    Min,
+    /// The largest item is at the root.
+    Max,
+}
+
+impl HeapKind {
+    /// Return `true` if `a` belongs above `b`.
+    fn above<T: Ord>(self, a: &T, b: &T) -> bool {
+        match self {
+            HeapKind::Min => a < b,
+            HeapKind::Max => a > b,
+        }
+    }
+}
+
+/// Binary heap stored as an explicit tree of [`Node`]s.
+///
+/// The tree is always complete: the `n`-th node (1-based, level order) is
+/// reached from the root by following the bits of `n` after the leading one,
+/// `0` going left and `1` going right. Nodes below the root go through
+/// the allocator `A`, see [`NodeAlloc`].
+#[derive(Debug, Clone)]
+pub struct TreeHeap<T, A = Global> {
+    root: Option<Node<T>>,
+    len: usize,
+    kind: HeapKind,
+    alloc: A,
+}
+
+impl<T: Ord> TreeHeap<T> {
+    /// Create an empty heap of the given kind.
+    pub fn new(kind: HeapKind) -> Self {
+        Self::new_in(kind, Global)
+    }
+
+    /// Create an empty min-heap.
+    pub fn min() -> Self {
+        Self::new(HeapKind::Min)
+    }
+
+    /// Create an empty max-heap.
+    pub fn max() -> Self {
+        Self::new(HeapKind::Max)
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>>> TreeHeap<T, A> {
+    /// Create an empty heap of the given kind allocating its nodes from
+    /// `alloc`.
+    pub fn new_in(kind: HeapKind, alloc: A) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            kind,
+            alloc,
+        }
+    }
+
+    /// Get the ref of the allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the heap ordering.
+    pub fn kind(&self) -> HeapKind {
+        self.kind
+    }
+
+    /// Get the number of items.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the heap is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the item at the root.
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(Node::data)
+    }
+
+    /// Get the underlying tree, e.g. for printing.
+    pub fn root(&self) -> Option<&Node<T>> {
+        self.root.as_ref()
+    }
+
+    /// Push an item, in `O(log n)`.
+    ///
+    /// The item travels down the path to the new slot, swapping places
+    /// with every node it belongs above.
+    pub fn push(&mut self, mut item: T) {
+        self.len += 1;
+        let kind = self.kind;
+        let mut node = match self.root.as_mut() {
+            Some(root) => root,
+            None => {
+                self.root = Some(Node::new(item));
+                return;
+            }
+        };
+        let mut path = path(self.len);
+        while let Some(right) = path.next() {
+            if kind.above(&item, node.data()) {
+                mem::swap(&mut item, node.data_mut());
+            }
+            if path.len() == 0 {
+                let leaf = Some(self.alloc.alloc(Node::new(item)));
+                if right {
+                    node.set_right_boxed(leaf);
+                } else {
+                    node.set_left_boxed(leaf);
+                }
+                return;
+            }
+            node = child_mut(node, right);
+        }
+    }
+
+    /// Remove the item at the root, in `O(log n)`.
+    pub fn pop(&mut self) -> Option<T> {
+        let last = self.take_last()?;
+        let mut node = match self.root.as_mut() {
+            Some(root) => root,
+            None => return Some(last.into_data()),
+        };
+        let top = mem::replace(node.data_mut(), last.into_data());
+        let kind = self.kind;
+        loop {
+            let (data, left, right) = node.parts_mut();
+            let child = match (left, right) {
+                (Some(left), Some(right)) if kind.above(right.data(), left.data()) => right,
+                (Some(left), _) => left,
+                _ => break,
+            };
+            if !kind.above(child.data(), data) {
+                break;
+            }
+            mem::swap(data, child.data_mut());
+            node = child;
+        }
+        Some(top)
+    }
+
+    fn take_last(&mut self) -> Option<Node<T>> {
+        if self.len <= 1 {
+            self.len = 0;
+            return self.root.take();
+        }
+        let mut path = path(self.len);
+        self.len -= 1;
+        let mut node = self.root.as_mut()?;
+        while let Some(right) = path.next() {
+            if path.len() == 0 {
+                let leaf = if right {
+                    node.set_right_boxed(None)
+                } else {
+                    node.set_left_boxed(None)
+                };
+                return leaf.map(|leaf| self.alloc.free(leaf));
+            }
+            node = child_mut(node, right);
+        }
+        None
+    }
+}
+
+impl<T: Ord, A> TreeHeap<T, A> {
+    /// Check the heap property, that the tree is complete and that the
+    /// length matches.
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first offending node in
+    /// level order, or at the root when the length is wrong.
+    pub fn validate(&self) -> Result<()> {
+        let violation = |path, reason: &str| Error::InvariantViolation {
+            path,
+            reason: reason.to_string(),
+        };
+        let mut len = 0;
+        // Once a child is missing, no later node in level order has any.
+        let mut gap = false;
+        let mut queue: VecDeque<(&Node<T>, Vec<usize>)> =
+            self.root.iter().map(|root| (root, Vec::new())).collect();
+        while let Some((node, path)) = queue.pop_front() {
+            len += 1;
+            for (idx, &child) in [node.left(), node.right()].iter().enumerate() {
+                let child = match child {
+                    Some(child) => child,
+                    None => {
+                        gap = true;
+                        continue;
+                    }
+                };
+                let mut child_path = path.clone();
+                child_path.push(idx);
+                if gap {
+                    return Err(violation(child_path, "tree is not complete"));
+                }
+                if self.kind.above(child.data(), node.data()) {
+                    return Err(violation(child_path, "item belongs above its parent"));
+                }
+                queue.push_back((child, child_path));
+            }
+        }
+        if len != self.len {
+            return Err(Error::InvariantViolation {
+                path: Vec::new(),
+                reason: format!("tree holds {} items but the length is {}", len, self.len),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T, A> TreeHeap<T, A> {
+    /// Convert into the underlying tree.
+    pub fn into_node(self) -> Option<Node<T>> {
+        self.root
+    }
+
+    /// Convert into the items in level order, i.e. the usual heap array.
+    pub fn into_vec(self) -> Vec<T> {
+        let mut items = Vec::with_capacity(self.len);
+        let mut queue: VecDeque<_> = self.root.into_iter().collect();
+        while let Some(mut node) = queue.pop_front() {
+            queue.extend(node.set_left(None));
+            queue.extend(node.set_right(None));
+            items.push(node.into_data());
+        }
+        items
+    }
+}
+
+impl<T, A> From<TreeHeap<T, A>> for Option<Node<T>> {
+    fn from(heap: TreeHeap<T, A>) -> Self {
+        heap.into_node()
+    }
+}
+
+impl<T, A> From<TreeHeap<T, A>> for Vec<T> {
+    /// Collect the items in level order.
+    fn from(heap: TreeHeap<T, A>) -> Self {
+        heap.into_vec()
+    }
+}
+
+impl<T: Ord, A: NodeAlloc<Node<T>>> Extend<T> for TreeHeap<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|item| self.push(item));
+    }
+}
+
+impl<'a, T: Ord + Copy, A: NodeAlloc<Node<T>>> Extend<&'a T> for TreeHeap<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+fn child_mut<T>(node: &mut Node<T>, right: bool) -> &mut Node<T> {
+    let child = if right {
+        node.right_mut()
+    } else {
+        node.left_mut()
+    };
+    child.expect("the heap is complete")
+}
+
+/// Directions from the root to the `n`-th node, `true` going right.
+fn path(n: usize) -> impl ExactSizeIterator<Item = bool> {
+    let depth = (usize::BITS - 1 - n.leading_zeros()) as usize;
+    (0..depth).rev().map(move |bit| n >> bit & 1 == 1)
+}
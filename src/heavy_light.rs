@@ -0,0 +1,247 @@
+use crate::monoid::Monoid;
+use crate::segment_tree::{Action, SegmentTree};
+use crate::{Error, Result};
+use std::ops::Range;
+
+/// Heavy-light decomposition of a rooted tree.
+///
+/// Vertices are numbered so that every heavy path and every subtree
+/// occupies a contiguous range of positions, and any vertex-to-vertex path
+/// crosses `O(log n)` heavy paths.
+#[derive(Debug, Clone)]
+pub struct HeavyLight {
+    parent: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    size: Vec<usize>,
+    head: Vec<usize>,
+    position: Vec<usize>,
+}
+
+impl HeavyLight {
+    /// Decompose the tree given by the parent of every vertex,
+    /// the root having no parent.
+    /// # Errors
+    /// Return `MalformedTree` Error when `parents` does not describe
+    /// exactly one tree.
+    pub fn new(parents: &[Option<usize>]) -> Result<Self> {
+        let len = parents.len();
+        let mut children = vec![Vec::new(); len];
+        let mut root = None;
+        for (vertex, parent) in parents.iter().enumerate() {
+            match *parent {
+                Some(parent) if parent < len => children[parent].push(vertex),
+                Some(_) => return Err(Error::MalformedTree),
+                None if root.is_none() => root = Some(vertex),
+                None => return Err(Error::MalformedTree),
+            }
+        }
+        let root = match root {
+            Some(root) => root,
+            None if len == 0 => 0,
+            None => return Err(Error::MalformedTree),
+        };
+
+        // Top-down order for depths, bottom-up for sizes.
+        let mut order = Vec::with_capacity(len);
+        let mut depth = vec![0; len];
+        if len > 0 {
+            order.push(root);
+        }
+        let mut idx = 0;
+        while idx < order.len() {
+            let vertex = order[idx];
+            for &child in &children[vertex] {
+                depth[child] = depth[vertex] + 1;
+                order.push(child);
+            }
+            idx += 1;
+        }
+        if order.len() != len {
+            return Err(Error::MalformedTree);
+        }
+        let mut size = vec![1; len];
+        for &vertex in order.iter().rev() {
+            if let Some(parent) = parents[vertex] {
+                size[parent] += size[vertex];
+            }
+        }
+
+        // Pre-order visiting the heavy child first.
+        let mut head = vec![0; len];
+        let mut position = vec![0; len];
+        let mut stack = Vec::new();
+        if len > 0 {
+            head[root] = root;
+            stack.push(root);
+        }
+        let mut next = 0;
+        while let Some(vertex) = stack.pop() {
+            position[vertex] = next;
+            next += 1;
+            let heavy = children[vertex].iter().copied().max_by_key(|&c| size[c]);
+            for &child in &children[vertex] {
+                if Some(child) != heavy {
+                    head[child] = child;
+                    stack.push(child);
+                }
+            }
+            if let Some(heavy) = heavy {
+                head[heavy] = head[vertex];
+                stack.push(heavy);
+            }
+        }
+
+        Ok(Self {
+            parent: parents.to_vec(),
+            depth,
+            size,
+            head,
+            position,
+        })
+    }
+
+    /// Get the number of vertices.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Return `true` if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Get the position of a vertex in the decomposition order.
+    pub fn position(&self, vertex: usize) -> usize {
+        self.position[vertex]
+    }
+
+    /// Get the depth of a vertex, the root having depth 0.
+    pub fn depth(&self, vertex: usize) -> usize {
+        self.depth[vertex]
+    }
+
+    /// Get the range of positions of the subtree of `vertex`.
+    pub fn subtree(&self, vertex: usize) -> Range<usize> {
+        self.position[vertex]..self.position[vertex] + self.size[vertex]
+    }
+
+    /// Return the lowest common ancestor of `u` and `v`.
+    pub fn lca(&self, mut u: usize, mut v: usize) -> usize {
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            u = self.parent[self.head[u]].expect("a path head below the root has a parent");
+        }
+        if self.depth[u] < self.depth[v] {
+            u
+        } else {
+            v
+        }
+    }
+
+    /// Get the ranges of positions covering the path between `u` and `v`,
+    /// `O(log n)` of them.
+    pub fn path(&self, mut u: usize, mut v: usize) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] < self.depth[self.head[v]] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            let head = self.head[u];
+            ranges.push(self.position[head]..self.position[u] + 1);
+            u = self.parent[head].expect("a path head below the root has a parent");
+        }
+        let (lo, hi) = if self.position[u] < self.position[v] {
+            (u, v)
+        } else {
+            (v, u)
+        };
+        ranges.push(self.position[lo]..self.position[hi] + 1);
+        ranges
+    }
+}
+
+/// Vertex values of a rooted tree supporting path and subtree queries
+/// and updates in `O(log² n)`.
+///
+/// Path aggregates combine heavy path segments in an unspecified order,
+/// so the monoid should be commutative.
+#[derive(Debug, Clone)]
+pub struct PathTree<M, A = ()> {
+    decomposition: HeavyLight,
+    values: SegmentTree<M, A>,
+}
+
+impl<M: Monoid, A: Action<M>> PathTree<M, A> {
+    /// Build from the parent and the value of every vertex.
+    /// # Errors
+    /// Return `MalformedTree` Error when `parents` does not describe
+    /// exactly one tree, or the lengths differ.
+    pub fn new(parents: &[Option<usize>], values: Vec<M>) -> Result<Self> {
+        if parents.len() != values.len() {
+            return Err(Error::MalformedTree);
+        }
+        let decomposition = HeavyLight::new(parents)?;
+        let mut ordered = vec![M::identity(); values.len()];
+        for (vertex, value) in values.into_iter().enumerate() {
+            ordered[decomposition.position(vertex)] = value;
+        }
+        Ok(Self {
+            decomposition,
+            values: SegmentTree::new(ordered),
+        })
+    }
+
+    /// Get the decomposition.
+    pub fn decomposition(&self) -> &HeavyLight {
+        &self.decomposition
+    }
+
+    /// Get the value of a vertex.
+    pub fn get(&mut self, vertex: usize) -> M {
+        let position = self.decomposition.position(vertex);
+        self.values.get(position).expect("positions are in range")
+    }
+
+    /// Replace the value of a vertex.
+    pub fn set(&mut self, vertex: usize, value: M) {
+        let position = self.decomposition.position(vertex);
+        self.values
+            .set(position, value)
+            .expect("positions are in range");
+    }
+
+    /// Aggregate the values on the path between `u` and `v`.
+    pub fn path_query(&mut self, u: usize, v: usize) -> M {
+        let mut sum = M::identity();
+        for range in self.decomposition.path(u, v) {
+            let part = self.values.query(range).expect("ranges are in bounds");
+            sum = sum.combine(&part);
+        }
+        sum
+    }
+
+    /// Apply `action` to every value on the path between `u` and `v`.
+    pub fn path_apply(&mut self, u: usize, v: usize, action: A) {
+        for range in self.decomposition.path(u, v) {
+            self.values
+                .apply(range, action.clone())
+                .expect("ranges are in bounds");
+        }
+    }
+
+    /// Aggregate the values in the subtree of `vertex`.
+    pub fn subtree_query(&mut self, vertex: usize) -> M {
+        let range = self.decomposition.subtree(vertex);
+        self.values.query(range).expect("ranges are in bounds")
+    }
+
+    /// Apply `action` to every value in the subtree of `vertex`.
+    pub fn subtree_apply(&mut self, vertex: usize, action: A) {
+        let range = self.decomposition.subtree(vertex);
+        self.values
+            .apply(range, action)
+            .expect("ranges are in bounds");
+    }
+}
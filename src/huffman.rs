@@ -0,0 +1,204 @@
+use crate::binary_tree::Node;
+use crate::{Error, Result};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt;
+
+/// Data of a Huffman tree node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HuffmanData<S> {
+    weight: u64,
+    symbol: Option<S>,
+}
+
+impl<S> HuffmanData<S> {
+    /// Get the total frequency of the subtree.
+    pub fn weight(&self) -> u64 {
+        self.weight
+    }
+
+    /// Get the symbol, `None` for internal nodes.
+    pub fn symbol(&self) -> Option<&S> {
+        self.symbol.as_ref()
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for HuffmanData<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.symbol {
+            Some(symbol) => write!(f, "{}:{}", symbol, self.weight),
+            None => write!(f, "{}", self.weight),
+        }
+    }
+}
+
+/// Optimal prefix-code tree.
+///
+/// A `false` bit goes to the left child and a `true` bit to the right child.
+#[derive(Debug, Clone)]
+pub struct HuffmanTree<S> {
+    root: Node<HuffmanData<S>>,
+    codes: BTreeMap<S, Vec<bool>>,
+}
+
+impl<S: Ord + Clone> HuffmanTree<S> {
+    /// Build the tree from `(symbol, frequency)` pairs.
+    /// Return `None` if there are no symbols.
+    ///
+    /// Ties are broken by the order of the input, so the result is deterministic.
+    /// # Errors
+    /// Return `DuplicateSymbol` Error when a symbol is given more than once.
+    pub fn new<I>(frequencies: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = (S, u64)>,
+    {
+        let frequencies: Vec<(S, u64)> = frequencies.into_iter().collect();
+        let mut seen = BTreeSet::new();
+        if !frequencies.iter().all(|(symbol, _)| seen.insert(symbol)) {
+            return Err(Error::DuplicateSymbol);
+        }
+        Ok(Self::build(frequencies))
+    }
+
+    fn build(frequencies: Vec<(S, u64)>) -> Option<Self> {
+        let mut nodes = Vec::new();
+        let mut heap = BinaryHeap::new();
+        for (symbol, weight) in frequencies {
+            heap.push(Reverse((weight, nodes.len())));
+            nodes.push(Some(Node::new(HuffmanData {
+                weight,
+                symbol: Some(symbol),
+            })));
+        }
+
+        while let Some(Reverse((weight, idx))) = heap.pop() {
+            let Reverse((other, jdx)) = match heap.pop() {
+                Some(entry) => entry,
+                None => {
+                    let root = nodes[idx].take()?;
+                    let codes = Self::build_codes(&root);
+                    return Some(Self { root, codes });
+                }
+            };
+            let weight = weight + other;
+            let mut node = Node::new(HuffmanData {
+                weight,
+                symbol: None,
+            });
+            node.set_left(nodes[idx].take());
+            node.set_right(nodes[jdx].take());
+            heap.push(Reverse((weight, nodes.len())));
+            nodes.push(Some(node));
+        }
+        None
+    }
+
+    /// Build the tree from the frequencies of the symbols in `data`.
+    pub fn from_data<I>(data: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut frequencies = BTreeMap::new();
+        for symbol in data {
+            *frequencies.entry(symbol).or_insert(0) += 1;
+        }
+        Self::build(frequencies.into_iter().collect())
+    }
+
+    fn build_codes(root: &Node<HuffmanData<S>>) -> BTreeMap<S, Vec<bool>> {
+        let mut codes = BTreeMap::new();
+        if let Some(symbol) = root.data().symbol() {
+            codes.insert(symbol.clone(), vec![false]);
+            return codes;
+        }
+        let mut stack = vec![(root, Vec::new())];
+        while let Some((node, code)) = stack.pop() {
+            if let Some(symbol) = node.data().symbol() {
+                codes.insert(symbol.clone(), code);
+                continue;
+            }
+            if let Some(right) = node.right() {
+                let mut code = code.clone();
+                code.push(true);
+                stack.push((right, code));
+            }
+            if let Some(left) = node.left() {
+                let mut code = code;
+                code.push(false);
+                stack.push((left, code));
+            }
+        }
+        codes
+    }
+
+    /// Get the code of `symbol`.
+    pub fn code(&self, symbol: &S) -> Option<&[bool]> {
+        self.codes.get(symbol).map(Vec::as_slice)
+    }
+
+    /// Get the code table.
+    pub fn codes(&self) -> &BTreeMap<S, Vec<bool>> {
+        &self.codes
+    }
+
+    /// Encode `symbols` into a bit stream.
+    /// # Errors
+    /// Return `UnknownSymbol` Error when a symbol is not in the code table.
+    pub fn encode<'a, I>(&self, symbols: I) -> Result<Vec<bool>>
+    where
+        I: IntoIterator<Item = &'a S>,
+        S: 'a,
+    {
+        let mut bits = Vec::new();
+        for symbol in symbols {
+            bits.extend_from_slice(self.code(symbol).ok_or(Error::UnknownSymbol)?);
+        }
+        Ok(bits)
+    }
+
+    /// Decode a bit stream into symbols.
+    /// # Errors
+    /// Return `TruncatedCode` Error when the stream ends in the middle of a code,
+    /// or `InvalidCode` Error when the bits do not lead to any symbol, e.g. a
+    /// `true` bit for a single-symbol code.
+    pub fn decode<I>(&self, bits: I) -> Result<Vec<S>>
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let mut symbols = Vec::new();
+        let mut node = &self.root;
+        let mut pending = false;
+        for bit in bits {
+            if node.data().symbol().is_none() {
+                let next = if bit { node.right() } else { node.left() };
+                node = next.ok_or(Error::InvalidCode)?;
+            } else if bit {
+                // A lone symbol is coded as a single `false` bit.
+                return Err(Error::InvalidCode);
+            }
+            pending = true;
+            if let Some(symbol) = node.data().symbol() {
+                symbols.push(symbol.clone());
+                node = &self.root;
+                pending = false;
+            }
+        }
+        if pending {
+            Err(Error::TruncatedCode)
+        } else {
+            Ok(symbols)
+        }
+    }
+}
+
+impl<S> HuffmanTree<S> {
+    /// Get the underlying binary tree.
+    pub fn root(&self) -> &Node<HuffmanData<S>> {
+        &self.root
+    }
+
+    /// Convert into the underlying binary tree.
+    pub fn into_node(self) -> Node<HuffmanData<S>> {
+        self.root
+    }
+}
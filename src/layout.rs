@@ -0,0 +1,113 @@
+/// Positions of the nodes of a tree drawn top-down.
+#[derive(Debug, Clone)]
+pub(crate) struct Layout {
+    /// Horizontal center of every node, the leftmost edge being at 0.
+    pub(crate) x: Vec<i64>,
+    /// Depth of every node, the root having depth 0.
+    pub(crate) depth: Vec<usize>,
+    /// Total width of the drawing.
+    pub(crate) width: i64,
+    /// Number of levels.
+    pub(crate) height: usize,
+}
+
+/// Left and right edges of a subtree on every level, relative to its root.
+#[derive(Debug, Clone, Default)]
+struct Contour {
+    left: Vec<i64>,
+    right: Vec<i64>,
+}
+
+/// Lay out a tree with the Reingold–Tilford algorithm, without recursion.
+///
+/// Nodes are numbered in pre order with `0` as the root, so every child
+/// comes after its parent. `children` lists the child slots of every node;
+/// an empty slot is drawn as a gap, which keeps a lone child of a binary
+/// node on its side. Subtrees are pushed together until `gap` separates
+/// them on every level, and every parent is centered over its slots.
+pub(crate) fn tidy(children: &[Vec<Option<usize>>], widths: &[i64], gap: i64) -> Layout {
+    let len = children.len();
+    let mut offset = vec![0; len];
+    let mut contours: Vec<Option<Contour>> = vec![None; len];
+
+    // Children come after their parents, so going backwards visits every
+    // subtree before its root.
+    for idx in (0..len).rev() {
+        let mut merged = Contour::default();
+        let mut positions = Vec::with_capacity(children[idx].len());
+        for slot in &children[idx] {
+            let contour = match slot {
+                Some(child) => contours[*child]
+                    .take()
+                    .expect("children are laid out first"),
+                None => Contour {
+                    left: vec![0],
+                    right: vec![0],
+                },
+            };
+            let shift = if positions.is_empty() {
+                0
+            } else {
+                merged
+                    .right
+                    .iter()
+                    .zip(&contour.left)
+                    .map(|(right, left)| right - left + gap)
+                    .max()
+                    .unwrap_or(0)
+            };
+            positions.push(shift);
+            for (level, (&left, &right)) in contour.left.iter().zip(&contour.right).enumerate() {
+                if level < merged.left.len() {
+                    merged.right[level] = right + shift;
+                } else {
+                    merged.left.push(left + shift);
+                    merged.right.push(right + shift);
+                }
+            }
+        }
+
+        let center = match (positions.first(), positions.last()) {
+            (Some(first), Some(last)) => (first + last) / 2,
+            _ => 0,
+        };
+        for (slot, position) in children[idx].iter().zip(positions) {
+            if let Some(child) = slot {
+                offset[*child] = position - center;
+            }
+        }
+        let half = widths[idx] / 2;
+        let mut contour = Contour {
+            left: vec![-half],
+            right: vec![widths[idx] - half],
+        };
+        contour
+            .left
+            .extend(merged.left.iter().map(|left| left - center));
+        contour
+            .right
+            .extend(merged.right.iter().map(|right| right - center));
+        contours[idx] = Some(contour);
+    }
+
+    let (mut min, mut max) = (0, 0);
+    if let Some(Some(root)) = contours.first() {
+        min = root.left.iter().copied().min().unwrap_or(0);
+        max = root.right.iter().copied().max().unwrap_or(0);
+    }
+    let mut x = vec![-min; len];
+    let mut depth = vec![0; len];
+    for (idx, slots) in children.iter().enumerate() {
+        for &child in slots.iter().flatten() {
+            x[child] = x[idx] + offset[child];
+            depth[child] = depth[idx] + 1;
+        }
+    }
+    let height = depth.iter().max().map_or(0, |depth| depth + 1);
+    Layout {
+        x,
+        depth,
+        width: max - min,
+        height,
+    }
+}
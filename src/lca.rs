@@ -0,0 +1,389 @@
+use crate::binary_tree::arena::{Arena, NodeId};
+use crate::binary_tree::Node as BinaryNode;
+use crate::tree::Node;
+use crate::visit::Path;
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// Numbering of the nodes of a rooted tree, shared by the LCA indexes.
+///
+/// Nodes become vertices `0..len`, in pre order when read from a tree, and
+/// the index maps them back to their [`Path`] from the root or, for an
+/// [`Arena`], to their [`NodeId`]. Every constructor runs in `O(n)`
+/// without recursion.
+#[derive(Debug, Clone, Default)]
+pub struct TreeIndex {
+    root: Option<usize>,
+    parent: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    // Child slots of every vertex, `[left, right]` in binary trees.
+    children: Vec<Vec<Option<usize>>>,
+    // Index of every vertex among the child slots of its parent.
+    slot: Vec<usize>,
+    ids: Vec<NodeId>,
+    vertices: HashMap<NodeId, usize>,
+}
+
+impl TreeIndex {
+    /// Index the tree given by the parent of every vertex, the root having
+    /// no parent. Vertices keep their numbers.
+    /// # Errors
+    /// Return `MalformedTree` Error when `parents` does not describe
+    /// exactly one tree.
+    pub fn new(parents: &[Option<usize>]) -> Result<Self> {
+        let len = parents.len();
+        let mut index = Self {
+            parent: parents.to_vec(),
+            depth: vec![0; len],
+            children: vec![Vec::new(); len],
+            slot: vec![0; len],
+            ..Self::default()
+        };
+        let mut root = None;
+        for (vertex, parent) in parents.iter().enumerate() {
+            match *parent {
+                Some(parent) if parent < len => {
+                    index.slot[vertex] = index.children[parent].len();
+                    index.children[parent].push(Some(vertex));
+                }
+                Some(_) => return Err(Error::MalformedTree),
+                None if root.is_none() => root = Some(vertex),
+                None => return Err(Error::MalformedTree),
+            }
+        }
+        let mut order: Vec<usize> = root.into_iter().collect();
+        let mut idx = 0;
+        while idx < order.len() {
+            let vertex = order[idx];
+            for child in index.children[vertex].iter().flatten() {
+                index.depth[*child] = index.depth[vertex] + 1;
+                order.push(*child);
+            }
+            idx += 1;
+        }
+        if order.len() != len {
+            return Err(Error::MalformedTree);
+        }
+        index.root = root;
+        Ok(index)
+    }
+
+    /// Number the nodes in pre order, `children` giving the child slots of
+    /// a node, and return the nodes by vertex.
+    fn build<N, F>(root: N, mut children: F) -> (Self, Vec<N>)
+    where
+        F: FnMut(&N) -> Vec<Option<N>>,
+    {
+        let mut index = Self {
+            root: Some(0),
+            ..Self::default()
+        };
+        let mut nodes = Vec::new();
+        let mut stack = vec![(root, None, 0)];
+        while let Some((node, parent, slot)) = stack.pop() {
+            let vertex = nodes.len();
+            let slots = children(&node);
+            index.children.push(vec![None; slots.len()]);
+            stack.extend(
+                slots
+                    .into_iter()
+                    .enumerate()
+                    .rev()
+                    .filter_map(|(slot, child)| Some((child?, Some(vertex), slot))),
+            );
+            if let Some(parent) = parent {
+                index.children[parent][slot] = Some(vertex);
+            }
+            index.parent.push(parent);
+            index
+                .depth
+                .push(parent.map_or(0, |parent| index.depth[parent] + 1));
+            index.slot.push(slot);
+            nodes.push(node);
+        }
+        (index, nodes)
+    }
+
+    /// Index a binary tree, `0` going left and `1` going right in paths.
+    pub fn binary<T>(root: &BinaryNode<T>) -> Self {
+        Self::build(root, |node| vec![node.left(), node.right()]).0
+    }
+
+    /// Index an n-ary tree.
+    pub fn n_ary<T>(root: &Node<T>) -> Self {
+        Self::build(root, |node| node.children().iter().map(Some).collect()).0
+    }
+
+    /// Index the subtree of `root` in an arena, keeping the [`NodeId`] of
+    /// every vertex.
+    /// # Errors
+    /// Return `RemovedNode` Error when `root` is not in the arena.
+    pub fn arena<T>(arena: &Arena<T>, root: NodeId) -> Result<Self> {
+        if !arena.contains(root) {
+            return Err(Error::RemovedNode);
+        }
+        let (mut index, ids) = Self::build(root, |&id| vec![arena.left(id), arena.right(id)]);
+        index.vertices = ids.iter().enumerate().map(|(idx, &id)| (id, idx)).collect();
+        index.ids = ids;
+        Ok(index)
+    }
+
+    /// Return the shallower of `u` and `v`, `u` at equal depths.
+    fn shallower(&self, u: usize, v: usize) -> usize {
+        if self.depth[v] < self.depth[u] {
+            v
+        } else {
+            u
+        }
+    }
+
+    /// Get the number of vertices.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Return `true` if there are no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Get the root vertex, `None` if there are no vertices.
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    /// Get the parent of a vertex.
+    pub fn parent(&self, vertex: usize) -> Option<usize> {
+        self.parent[vertex]
+    }
+
+    /// Get the depth of a vertex, the root having depth 0.
+    pub fn depth(&self, vertex: usize) -> usize {
+        self.depth[vertex]
+    }
+
+    /// Create an iterator over the children of a vertex, in order.
+    pub fn children(&self, vertex: usize) -> impl Iterator<Item = usize> + '_ {
+        self.children[vertex].iter().flatten().copied()
+    }
+
+    /// Get the vertex at `path` from the root.
+    pub fn vertex_at(&self, path: &[usize]) -> Option<usize> {
+        let mut vertex = self.root?;
+        for &slot in path {
+            vertex = (*self.children[vertex].get(slot)?)?;
+        }
+        Some(vertex)
+    }
+
+    /// Get the path from the root to a vertex.
+    pub fn path(&self, mut vertex: usize) -> Path {
+        let mut path = Vec::with_capacity(self.depth[vertex]);
+        while let Some(parent) = self.parent[vertex] {
+            path.push(self.slot[vertex]);
+            vertex = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Get the vertex of a node of the indexed arena.
+    pub fn vertex_of(&self, id: NodeId) -> Option<usize> {
+        self.vertices.get(&id).copied()
+    }
+
+    /// Get the node of a vertex, when indexed from an arena.
+    pub fn node_id(&self, vertex: usize) -> Option<NodeId> {
+        self.ids.get(vertex).copied()
+    }
+}
+
+/// Lowest common ancestor queries over a [`TreeIndex`].
+///
+/// Implementors only answer [`Lca::lca`] on vertices; distances and the
+/// queries by [`NodeId`] or by [`Path`] are built on it. Vertices out of
+/// range make the queries panic.
+pub trait Lca {
+    /// Get the ref of the indexed tree.
+    fn tree(&self) -> &TreeIndex;
+
+    /// Return the lowest common ancestor of `u` and `v`.
+    fn lca(&self, u: usize, v: usize) -> usize;
+
+    /// Get the number of edges on the path between `u` and `v`.
+    fn distance(&self, u: usize, v: usize) -> usize {
+        let tree = self.tree();
+        tree.depth(u) + tree.depth(v) - 2 * tree.depth(self.lca(u, v))
+    }
+
+    /// Return the lowest common ancestor of two nodes of the indexed
+    /// arena, `None` if either is not indexed.
+    fn lca_by_id(&self, a: NodeId, b: NodeId) -> Option<NodeId> {
+        let tree = self.tree();
+        let lca = self.lca(tree.vertex_of(a)?, tree.vertex_of(b)?);
+        tree.node_id(lca)
+    }
+
+    /// Get the distance between two nodes of the indexed arena, `None` if
+    /// either is not indexed.
+    fn distance_by_id(&self, a: NodeId, b: NodeId) -> Option<usize> {
+        let tree = self.tree();
+        Some(self.distance(tree.vertex_of(a)?, tree.vertex_of(b)?))
+    }
+
+    /// Get the path to the lowest common ancestor of the nodes at paths
+    /// `a` and `b`, `None` if either has no node.
+    fn lca_by_path(&self, a: &[usize], b: &[usize]) -> Option<Path> {
+        let tree = self.tree();
+        let lca = self.lca(tree.vertex_at(a)?, tree.vertex_at(b)?);
+        Some(tree.path(lca))
+    }
+
+    /// Get the distance between the nodes at paths `a` and `b`, `None` if
+    /// either has no node.
+    fn distance_by_path(&self, a: &[usize], b: &[usize]) -> Option<usize> {
+        let tree = self.tree();
+        Some(self.distance(tree.vertex_at(a)?, tree.vertex_at(b)?))
+    }
+}
+
+/// LCA index by binary lifting.
+///
+/// Every vertex keeps its ancestors `1, 2, 4, …` levels up, so the index
+/// takes `O(n log n)` time and space to build and answers queries in
+/// `O(log n)`, lifting both vertices by powers of two.
+#[derive(Debug, Clone)]
+pub struct LcaIndex {
+    tree: TreeIndex,
+    // `up[k][v]` is the ancestor `2^k` levels above `v`, or the root.
+    up: Vec<Vec<usize>>,
+}
+
+impl LcaIndex {
+    /// Build the index of a tree.
+    pub fn new(tree: TreeIndex) -> Self {
+        let first: Vec<usize> = (0..tree.len())
+            .map(|vertex| tree.parent(vertex).unwrap_or(vertex))
+            .collect();
+        let height = tree.depth.iter().max().map_or(0, |&depth| depth + 1);
+        let mut up = vec![first];
+        while 1 << up.len() < height {
+            let last = up.last().expect("the first level is set");
+            let next = last.iter().map(|&ancestor| last[ancestor]).collect();
+            up.push(next);
+        }
+        Self { tree, up }
+    }
+
+    /// Get the ancestor `levels` levels above `vertex`, `None` above the
+    /// root.
+    pub fn ancestor(&self, mut vertex: usize, levels: usize) -> Option<usize> {
+        if levels > self.tree.depth(vertex) {
+            return None;
+        }
+        for (k, up) in self.up.iter().enumerate() {
+            if levels >> k & 1 == 1 {
+                vertex = up[vertex];
+            }
+        }
+        Some(vertex)
+    }
+}
+
+impl Lca for LcaIndex {
+    fn tree(&self) -> &TreeIndex {
+        &self.tree
+    }
+
+    fn lca(&self, u: usize, v: usize) -> usize {
+        let (du, dv) = (self.tree.depth(u), self.tree.depth(v));
+        let (deep, shallow) = if du < dv { (v, u) } else { (u, v) };
+        let mut u = self
+            .ancestor(deep, du.abs_diff(dv))
+            .expect("the deeper vertex has that many ancestors");
+        let mut v = shallow;
+        if u == v {
+            return u;
+        }
+        for up in self.up.iter().rev() {
+            if up[u] != up[v] {
+                u = up[u];
+                v = up[v];
+            }
+        }
+        self.up[0][u]
+    }
+}
+
+/// LCA index by Euler tour and sparse table.
+///
+/// The tour lists a vertex every time the walk passes through it, and the
+/// LCA of two vertices is the shallowest vertex of the tour between their
+/// first visits, found in a table of minima over every power-of-two window.
+/// Building takes `O(n log n)` time and space, as for [`LcaIndex`], but
+/// queries take `O(1)`, at the cost of a table about twice as large.
+#[derive(Debug, Clone)]
+pub struct EulerLca {
+    tree: TreeIndex,
+    // Position of the first visit of every vertex in the tour.
+    first: Vec<usize>,
+    // `table[k][i]` is the shallowest vertex of the tour in `i..i + 2^k`.
+    table: Vec<Vec<usize>>,
+}
+
+impl EulerLca {
+    /// Build the index of a tree.
+    pub fn new(tree: TreeIndex) -> Self {
+        let mut first = vec![0; tree.len()];
+        let mut tour = Vec::with_capacity(2 * tree.len());
+        // Every vertex on the stack with the next child slot to visit.
+        let mut stack: Vec<(usize, usize)> = tree.root.map(|root| (root, 0)).into_iter().collect();
+        tour.extend(tree.root);
+        while let Some((vertex, next)) = stack.last_mut() {
+            let slots = &tree.children[*vertex];
+            match (*next..slots.len()).find(|&slot| slots[slot].is_some()) {
+                Some(slot) => {
+                    *next = slot + 1;
+                    let child = slots[slot].expect("the slot is not empty");
+                    first[child] = tour.len();
+                    tour.push(child);
+                    stack.push((child, 0));
+                }
+                None => {
+                    stack.pop();
+                    tour.extend(stack.last().map(|&(parent, _)| parent));
+                }
+            }
+        }
+        let mut table = vec![tour];
+        let mut width = 1;
+        while 2 * width <= table[0].len() {
+            let last = table.last().expect("the first level is set");
+            let next = (0..last.len() - width)
+                .map(|idx| tree.shallower(last[idx], last[idx + width]))
+                .collect();
+            table.push(next);
+            width *= 2;
+        }
+        Self { tree, first, table }
+    }
+
+    /// Get the tour, each vertex being listed once plus once per child.
+    pub fn tour(&self) -> &[usize] {
+        &self.table[0]
+    }
+}
+
+impl Lca for EulerLca {
+    fn tree(&self) -> &TreeIndex {
+        &self.tree
+    }
+
+    fn lca(&self, u: usize, v: usize) -> usize {
+        let (a, b) = (self.first[u], self.first[v]);
+        let (start, end) = (a.min(b), a.max(b) + 1);
+        let level = (usize::BITS - 1 - (end - start).leading_zeros()) as usize;
+        let row = &self.table[level];
+        self.tree.shallower(row[start], row[end - (1 << level)])
+    }
+}
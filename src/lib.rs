@@ -3,9 +3,124 @@
 
 //! A collections of tree data structure implements.
 
+mod macros;
+
 /// Binary tree.
 pub mod binary_tree;
 
+/// N-ary tree.
+pub mod tree;
+
+/// Suffix tree.
+pub mod suffix_tree;
+
+/// Trie and Aho-Corasick automaton.
+pub mod trie;
+
+/// Merkle tree.
+pub mod merkle;
+
+/// Huffman coding tree.
+pub mod huffman;
+
+/// Expression tree.
+pub mod expr;
+
+/// Rope for text editing.
+pub mod rope;
+
+/// Monoids shared by the aggregating trees.
+pub mod monoid;
+
+/// Finger tree.
+pub mod finger_tree;
+
+/// Pointer-based binary heap.
+pub mod heap;
+
+/// Comparators for the ordered containers.
+pub mod compare;
+
+/// Ordered map over an AVL tree or a treap.
+pub mod tree_map;
+
+/// Ordered set over an AVL tree or a treap.
+pub mod tree_set;
+
+/// Ordered multiset over an AVL tree.
+pub mod tree_multiset;
+
+/// Persistent ordered map with version handles.
+pub mod persistent;
+
+/// Link-cut tree.
+pub mod link_cut;
+
+/// Euler tour tree.
+pub mod euler_tour;
+
+/// Segment tree.
+pub mod segment_tree;
+
+/// Fenwick tree.
+pub mod fenwick;
+
+/// Heavy-light decomposition.
+pub mod heavy_light;
+
+/// Lowest common ancestor queries.
+pub mod lca;
+
+/// Rerooting dynamic programming.
+pub mod reroot;
+
+/// Wavelet tree.
+pub mod wavelet;
+
+/// Tournament tree for k-way merging.
+pub mod tournament;
+
+/// Random tree generators.
+pub mod gen;
+
+/// Concurrent search trees.
+pub mod concurrent;
+
+/// Pruned traversals and node paths.
+pub mod visit;
+
+/// Interactive tree explorer state.
+pub mod explorer;
+
+/// SVG rendering.
+#[cfg(feature = "svg")]
+pub mod svg;
+
+/// Filesystem directory trees.
+#[cfg(feature = "fs")]
+pub mod fs;
+
+/// Terminal tree explorer widget.
+#[cfg(feature = "tui")]
+pub mod tui;
+
+/// Property-based testing strategies.
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+pub mod arbitrary;
+
+mod newick;
+
+mod diagram;
+
+mod layout;
+
+mod width;
+
+mod bitvec;
+
+/// Heap memory usage estimation.
+pub mod datasize;
+
 /// Error definitions.
 pub mod error;
 
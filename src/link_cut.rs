@@ -0,0 +1,308 @@
+use crate::monoid::Monoid;
+use crate::{Error, Result};
+use std::mem;
+
+#[derive(Debug, Clone)]
+struct LctNode<T> {
+    children: [Option<usize>; 2],
+    parent: Option<usize>,
+    reversed: bool,
+    value: T,
+    /// Aggregate of the splay subtree in path order.
+    sum: T,
+    /// Aggregate of the splay subtree in reversed path order.
+    rev_sum: T,
+}
+
+/// Link-cut tree over a forest of nodes holding monoid values.
+///
+/// Every preferred path is kept in a splay tree ordered by depth, so
+/// `link`, `cut`, `find_root` and path aggregates run in amortized
+/// `O(log n)`. Nodes are referred to by the index returned from [`add`].
+///
+/// [`add`]: LinkCutTree::add
+#[derive(Debug, Clone, Default)]
+pub struct LinkCutTree<T> {
+    nodes: Vec<LctNode<T>>,
+}
+
+impl<T: Monoid> LinkCutTree<T> {
+    /// Create an empty forest.
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return `true` if there are no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Add an isolated node, returning its index.
+    pub fn add(&mut self, value: T) -> usize {
+        self.nodes.push(LctNode {
+            children: [None, None],
+            parent: None,
+            reversed: false,
+            sum: value.clone(),
+            rev_sum: value.clone(),
+            value,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Get the value of a node.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of range.
+    pub fn value(&self, node: usize) -> &T {
+        &self.nodes[node].value
+    }
+
+    /// Replace the value of a node.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of range.
+    pub fn set_value(&mut self, node: usize, value: T) {
+        self.access(node);
+        self.nodes[node].value = value;
+        self.update(node);
+    }
+
+    /// Return the root of the tree containing `node`.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of range.
+    pub fn find_root(&mut self, node: usize) -> usize {
+        self.access(node);
+        let mut root = node;
+        loop {
+            self.push(root);
+            match self.nodes[root].children[0] {
+                Some(left) => root = left,
+                None => break,
+            }
+        }
+        self.splay(root);
+        root
+    }
+
+    /// Return `true` if `u` and `v` are in the same tree.
+    ///
+    /// # Panics
+    /// Panics if `u` or `v` is out of range.
+    pub fn connected(&mut self, u: usize, v: usize) -> bool {
+        u == v || self.find_root(u) == self.find_root(v)
+    }
+
+    /// Add the edge `u - v`, making `v` the parent of `u`.
+    /// The tree of `u` is rerooted at `u` first.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `u` or `v` is not a node, and
+    /// `AlreadyConnected` Error when they are in the same tree.
+    pub fn link(&mut self, u: usize, v: usize) -> Result<()> {
+        self.check(u)?;
+        self.check(v)?;
+        if self.connected(u, v) {
+            return Err(Error::AlreadyConnected);
+        }
+        self.make_root(u);
+        self.nodes[u].parent = Some(v);
+        Ok(())
+    }
+
+    /// Remove the edge `u - v`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `u` or `v` is not a node, and
+    /// `NoSuchEdge` Error when they are not adjacent.
+    pub fn cut(&mut self, u: usize, v: usize) -> Result<()> {
+        self.check(u)?;
+        self.check(v)?;
+        if u == v || !self.connected(u, v) {
+            return Err(Error::NoSuchEdge);
+        }
+        self.make_root(u);
+        self.access(v);
+        self.push(u);
+        if self.nodes[v].children[0] != Some(u) || self.nodes[u].children[1].is_some() {
+            return Err(Error::NoSuchEdge);
+        }
+        self.nodes[v].children[0] = None;
+        self.nodes[u].parent = None;
+        self.update(v);
+        Ok(())
+    }
+
+    /// Remove the edge between `node` and its parent, returning the parent.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of range.
+    pub fn cut_parent(&mut self, node: usize) -> Option<usize> {
+        self.access(node);
+        let mut parent = self.nodes[node].children[0]?;
+        loop {
+            self.push(parent);
+            match self.nodes[parent].children[1] {
+                Some(right) => parent = right,
+                None => break,
+            }
+        }
+        self.access(node);
+        let left = self.nodes[node].children[0].take()?;
+        self.nodes[left].parent = None;
+        self.update(node);
+        Some(parent)
+    }
+
+    /// Make `node` the root of its tree.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of range.
+    pub fn make_root(&mut self, node: usize) {
+        self.access(node);
+        self.reverse(node);
+    }
+
+    /// Aggregate the values on the path from `u` to `v`, in path order.
+    /// Return `None` if they are not connected.
+    ///
+    /// The tree of `u` is rerooted at `u`.
+    ///
+    /// # Panics
+    /// Panics if `u` or `v` is out of range.
+    pub fn path_aggregate(&mut self, u: usize, v: usize) -> Option<T> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        self.make_root(u);
+        self.access(v);
+        Some(self.nodes[v].sum.clone())
+    }
+
+    /// Check that `node` is a node of the forest.
+    fn check(&self, node: usize) -> Result<()> {
+        if node < self.nodes.len() {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfRange {
+                index: node,
+                len: self.nodes.len(),
+            })
+        }
+    }
+
+    fn is_splay_root(&self, node: usize) -> bool {
+        match self.nodes[node].parent {
+            Some(parent) => !self.nodes[parent].children.contains(&Some(node)),
+            None => true,
+        }
+    }
+
+    fn reverse(&mut self, node: usize) {
+        let node = &mut self.nodes[node];
+        node.children.swap(0, 1);
+        mem::swap(&mut node.sum, &mut node.rev_sum);
+        node.reversed ^= true;
+    }
+
+    fn push(&mut self, node: usize) {
+        if self.nodes[node].reversed {
+            let [left, right] = self.nodes[node].children;
+            for child in left.into_iter().chain(right) {
+                self.reverse(child);
+            }
+            self.nodes[node].reversed = false;
+        }
+    }
+
+    fn update(&mut self, node: usize) {
+        let [left, right] = self.nodes[node].children;
+        let value = &self.nodes[node].value;
+        let mut sum = value.clone();
+        let mut rev_sum = value.clone();
+        if let Some(left) = left {
+            sum = self.nodes[left].sum.combine(&sum);
+            rev_sum = rev_sum.combine(&self.nodes[left].rev_sum);
+        }
+        if let Some(right) = right {
+            sum = sum.combine(&self.nodes[right].sum);
+            rev_sum = self.nodes[right].rev_sum.combine(&rev_sum);
+        }
+        self.nodes[node].sum = sum;
+        self.nodes[node].rev_sum = rev_sum;
+    }
+
+    fn rotate(&mut self, node: usize) {
+        let parent = self.nodes[node]
+            .parent
+            .expect("rotated nodes have a parent");
+        let grandparent = self.nodes[parent].parent;
+        let dir = (self.nodes[parent].children[1] == Some(node)) as usize;
+        if !self.is_splay_root(parent) {
+            let grandparent = grandparent.expect("non-root nodes have a parent");
+            let side = (self.nodes[grandparent].children[1] == Some(parent)) as usize;
+            self.nodes[grandparent].children[side] = Some(node);
+        }
+        self.nodes[node].parent = grandparent;
+        let inner = self.nodes[node].children[1 - dir];
+        self.nodes[parent].children[dir] = inner;
+        if let Some(inner) = inner {
+            self.nodes[inner].parent = Some(parent);
+        }
+        self.nodes[node].children[1 - dir] = Some(parent);
+        self.nodes[parent].parent = Some(node);
+        self.update(parent);
+        self.update(node);
+    }
+
+    fn splay(&mut self, node: usize) {
+        let mut path = vec![node];
+        let mut top = node;
+        while !self.is_splay_root(top) {
+            top = self.nodes[top]
+                .parent
+                .expect("non-root nodes have a parent");
+            path.push(top);
+        }
+        for &node in path.iter().rev() {
+            self.push(node);
+        }
+        while !self.is_splay_root(node) {
+            let parent = self.nodes[node]
+                .parent
+                .expect("non-root nodes have a parent");
+            if !self.is_splay_root(parent) {
+                let grandparent = self.nodes[parent]
+                    .parent
+                    .expect("non-root nodes have a parent");
+                let zig_zig = (self.nodes[grandparent].children[0] == Some(parent))
+                    == (self.nodes[parent].children[0] == Some(node));
+                if zig_zig {
+                    self.rotate(parent);
+                } else {
+                    self.rotate(node);
+                }
+            }
+            self.rotate(node);
+        }
+    }
+
+    /// Make the root-to-`node` path preferred, leaving `node` at the root
+    /// of its splay tree with no deeper path attached.
+    fn access(&mut self, node: usize) {
+        let mut last = None;
+        let mut current = Some(node);
+        while let Some(splayed) = current {
+            self.splay(splayed);
+            self.nodes[splayed].children[1] = last;
+            self.update(splayed);
+            last = Some(splayed);
+            current = self.nodes[splayed].parent;
+        }
+        self.splay(node);
+    }
+}
@@ -0,0 +1,52 @@
+/// Create a [`binary_tree::Node`](crate::binary_tree::Node) from a literal
+/// tree.
+///
+/// A node is written as its data, followed by `=> { left, right }` when it
+/// has children. Either child may be left out, keeping its comma when the
+/// right one is given: `tree!{1 => {2, 3 => {, 4}}}` builds a root `1`
+/// with the leaf `2` on its left and, on its right, the node `3` whose only
+/// child `4` is on the right. `{2,}` and `{2}` both give a lone left child.
+#[macro_export]
+macro_rules! tree {
+    ($data:expr => { $($children:tt)* }) => {{
+        #[allow(unused_mut)]
+        let mut node = $crate::binary_tree::Node::new($data);
+        $crate::__tree_left!(node; $($children)*);
+        node
+    }};
+    ($data:expr) => {
+        $crate::binary_tree::Node::new($data)
+    };
+}
+
+/// Set the left child of a node being built by [`tree!`], then hand the
+/// rest over to [`__tree_right!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tree_left {
+    ($node:ident;) => {};
+    ($node:ident; , $($rest:tt)*) => {
+        $crate::__tree_right!($node; $($rest)*);
+    };
+    ($node:ident; $data:expr => { $($children:tt)* } $(, $($rest:tt)*)?) => {
+        $node.set_left(Some($crate::tree!($data => { $($children)* })));
+        $crate::__tree_right!($node; $($($rest)*)?);
+    };
+    ($node:ident; $data:expr $(, $($rest:tt)*)?) => {
+        $node.set_left(Some($crate::tree!($data)));
+        $crate::__tree_right!($node; $($($rest)*)?);
+    };
+}
+
+/// Set the right child of a node being built by [`tree!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tree_right {
+    ($node:ident;) => {};
+    ($node:ident; $data:expr => { $($children:tt)* } $(,)?) => {
+        $node.set_right(Some($crate::tree!($data => { $($children)* })));
+    };
+    ($node:ident; $data:expr $(,)?) => {
+        $node.set_right(Some($crate::tree!($data)));
+    };
+}
@@ -0,0 +1,221 @@
+use crate::{Error, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Hash function used to build a [`MerkleTree`].
+pub trait MerkleHasher {
+    /// The digest type.
+    type Hash: Clone + Eq;
+
+    /// Hash a leaf.
+    fn hash_leaf(&self, data: &[u8]) -> Self::Hash;
+
+    /// Hash the concatenation of two child digests.
+    fn hash_node(&self, left: &Self::Hash, right: &Self::Hash) -> Self::Hash;
+}
+
+/// Non-cryptographic hasher based on [`DefaultHasher`].
+///
+/// Suitable for change detection, not for adversarial settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdHasher;
+
+impl MerkleHasher for StdHasher {
+    type Hash = u64;
+
+    fn hash_leaf(&self, data: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        0u8.hash(&mut hasher);
+        data.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_node(&self, left: &u64, right: &u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        1u8.hash(&mut hasher);
+        left.hash(&mut hasher);
+        right.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// The side on which a sibling digest is combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The sibling is the left operand.
+    Left,
+    /// The sibling is the right operand.
+    Right,
+}
+
+/// Authentication path of a leaf, from the leaf level up to the root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof<H> {
+    index: usize,
+    path: Vec<(Side, H)>,
+}
+
+impl<H> Proof<H> {
+    /// Get the index of the proven leaf.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the sibling digests from the leaf level up to the root.
+    pub fn path(&self) -> &[(Side, H)] {
+        &self.path
+    }
+
+    /// Return `true` if `leaf` hashes up to `root` along this path.
+    pub fn verify<M>(&self, hasher: &M, root: &H, leaf: &[u8]) -> bool
+    where
+        M: MerkleHasher<Hash = H>,
+        H: Eq,
+    {
+        let hash =
+            self.path
+                .iter()
+                .fold(hasher.hash_leaf(leaf), |hash, (side, sibling)| match side {
+                    Side::Left => hasher.hash_node(sibling, &hash),
+                    Side::Right => hasher.hash_node(&hash, sibling),
+                });
+        hash == *root
+    }
+}
+
+/// Binary hash tree over a sequence of leaves.
+///
+/// A node without a sibling at the end of a level is promoted unchanged.
+#[derive(Debug, Clone)]
+pub struct MerkleTree<H: MerkleHasher> {
+    hasher: H,
+    levels: Vec<Vec<H::Hash>>,
+}
+
+impl<H: MerkleHasher> MerkleTree<H> {
+    /// Build the tree over `leaves`.
+    pub fn new<I>(hasher: H, leaves: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let leaves = leaves
+            .into_iter()
+            .map(|leaf| hasher.hash_leaf(leaf.as_ref()))
+            .collect::<Vec<_>>();
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hasher.hash_node(left, right),
+                    [single] => single.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { hasher, levels }
+    }
+
+    /// Get the hasher.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Get the number of leaves.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Return `true` if the tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the root hash, or `None` if the tree is empty.
+    pub fn root(&self) -> Option<&H::Hash> {
+        self.levels.last().and_then(|level| level.first())
+    }
+
+    /// Get the hash of the leaf at `index`.
+    pub fn leaf(&self, index: usize) -> Option<&H::Hash> {
+        self.levels[0].get(index)
+    }
+
+    /// Get the authentication path of the leaf at `index`.
+    pub fn proof(&self, index: usize) -> Option<Proof<H::Hash>> {
+        if index >= self.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                let side = if sibling < position {
+                    Side::Left
+                } else {
+                    Side::Right
+                };
+                path.push((side, hash.clone()));
+            }
+            position /= 2;
+        }
+        Some(Proof { index, path })
+    }
+
+    /// Replace the leaf at `index`, recomputing only the hashes on its path.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `index` is not a leaf.
+    pub fn update_leaf(&mut self, index: usize, data: &[u8]) -> Result<()> {
+        let len = self.len();
+        if index >= len {
+            return Err(Error::IndexOutOfRange { index, len });
+        }
+        self.levels[0][index] = self.hasher.hash_leaf(data);
+        self.rehash(index);
+        Ok(())
+    }
+
+    /// Append a leaf, recomputing only the hashes on its path.
+    pub fn push(&mut self, data: &[u8]) {
+        let hash = self.hasher.hash_leaf(data);
+        self.levels[0].push(hash);
+        self.rehash(self.len() - 1);
+    }
+
+    fn rehash(&mut self, mut position: usize) {
+        let mut depth = 0;
+        while self.levels[depth].len() > 1 {
+            let parent = position / 2;
+            let level = &self.levels[depth];
+            let hash = match level.get(2 * parent + 1) {
+                Some(right) => self.hasher.hash_node(&level[2 * parent], right),
+                None => level[2 * parent].clone(),
+            };
+            if depth + 1 == self.levels.len() {
+                self.levels.push(Vec::new());
+            }
+            let next = &mut self.levels[depth + 1];
+            if parent < next.len() {
+                next[parent] = hash;
+            } else {
+                next.push(hash);
+            }
+            position = parent;
+            depth += 1;
+        }
+    }
+}
+
+impl<H: MerkleHasher + Default> MerkleTree<H> {
+    /// Build the tree over `leaves` with the default hasher.
+    pub fn from_leaves<I>(leaves: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        Self::new(H::default(), leaves)
+    }
+}
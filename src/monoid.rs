@@ -0,0 +1,97 @@
+/// A type with an identity and an associative combining operation.
+pub trait Monoid: Clone {
+    /// The identity element.
+    fn identity() -> Self;
+
+    /// Combine two elements, `self` on the left.
+    fn combine(&self, other: &Self) -> Self;
+}
+
+/// A monoid that measures items of type `T`.
+pub trait Measure<T>: Monoid {
+    /// Measure a single item.
+    fn measure(item: &T) -> Self;
+}
+
+impl Monoid for () {
+    fn identity() -> Self {}
+
+    fn combine(&self, _other: &Self) -> Self {}
+}
+
+impl<T> Measure<T> for () {
+    fn measure(_item: &T) -> Self {}
+}
+
+impl<A: Monoid, B: Monoid> Monoid for (A, B) {
+    fn identity() -> Self {
+        (A::identity(), B::identity())
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        (self.0.combine(&other.0), self.1.combine(&other.1))
+    }
+}
+
+impl<T, A: Measure<T>, B: Measure<T>> Measure<T> for (A, B) {
+    fn measure(item: &T) -> Self {
+        (A::measure(item), B::measure(item))
+    }
+}
+
+impl<M: Monoid> Monoid for Option<M> {
+    /// `None`, so that a combination over nothing can be told apart.
+    fn identity() -> Self {
+        None
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.combine(b)),
+            (a, None) => a.clone(),
+            (None, b) => b.clone(),
+        }
+    }
+}
+
+/// Sum monoid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sum(pub i64);
+
+impl Monoid for Sum {
+    fn identity() -> Self {
+        Sum(0)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Sum(self.0 + other.0)
+    }
+}
+
+/// Maximum monoid, `i64::MIN` being the identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Max(pub i64);
+
+impl Monoid for Max {
+    fn identity() -> Self {
+        Max(i64::MIN)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Max(self.0.max(other.0))
+    }
+}
+
+/// Minimum monoid, `i64::MAX` being the identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Min(pub i64);
+
+impl Monoid for Min {
+    fn identity() -> Self {
+        Min(i64::MAX)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Min(self.0.min(other.0))
+    }
+}
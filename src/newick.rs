@@ -0,0 +1,289 @@
+use crate::binary_tree::Node as BinaryNode;
+use crate::tree::Node;
+use crate::{Error, Result};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Characters that end an unquoted label.
+const DELIMITERS: &str = "(),;:'[]";
+
+/// A label as read from the input.
+struct Label<'a> {
+    text: Cow<'a, str>,
+    quoted: bool,
+    position: usize,
+}
+
+impl Label<'_> {
+    fn parse<T: FromStr>(&self) -> Result<T> {
+        self.text.parse().map_err(|_| Error::Parse {
+            position: self.position,
+            message: "invalid label".to_owned(),
+        })
+    }
+}
+
+/// Parser of Newick strings keeping open subtrees on a stack rather than
+/// recursing. Subtrees are handed to `leaf` and `inner` bottom-up.
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn parse<N, L, I>(mut self, mut leaf: L, mut inner: I) -> Result<N>
+    where
+        L: FnMut(Label<'a>) -> Result<N>,
+        I: FnMut(Label<'a>, Vec<N>) -> Result<N>,
+    {
+        let mut open: Vec<Vec<N>> = Vec::new();
+        loop {
+            while self.eat('(') {
+                open.push(Vec::new());
+            }
+            let mut node = leaf(self.label()?)?;
+            loop {
+                let children = match open.last_mut() {
+                    Some(children) => children,
+                    None => {
+                        self.eat(';');
+                        self.skip_whitespace();
+                        if self.position < self.input.len() {
+                            return Err(self.error("unexpected trailing input"));
+                        }
+                        return Ok(node);
+                    }
+                };
+                children.push(node);
+                if self.eat(',') {
+                    break;
+                }
+                if !self.eat(')') {
+                    return Err(self.error("expected `,` or `)`"));
+                }
+                let children = open.pop().expect("a subtree is open");
+                node = inner(self.label()?, children)?;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip whitespace and consume `c` if it comes next.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.input[self.position..].starts_with(c) {
+            self.position += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume a possibly empty label, quoted with `'` or bare.
+    fn label(&mut self) -> Result<Label<'a>> {
+        self.skip_whitespace();
+        let input = self.input;
+        let position = self.position;
+        let rest = &input[position..];
+        let label = if let Some(quoted) = rest.strip_prefix('\'') {
+            // A quote inside a quoted label is written twice.
+            let mut text = String::new();
+            let mut chars = quoted.char_indices();
+            loop {
+                match chars.next() {
+                    Some((idx, '\'')) => {
+                        if quoted[idx + 1..].starts_with('\'') {
+                            chars.next();
+                            text.push('\'');
+                        } else {
+                            self.position += idx + 2;
+                            break;
+                        }
+                    }
+                    Some((_, c)) => text.push(c),
+                    None => return Err(self.error("unterminated quoted label")),
+                }
+            }
+            Label {
+                text: Cow::Owned(text),
+                quoted: true,
+                position,
+            }
+        } else {
+            let len = rest
+                .find(|c: char| c.is_whitespace() || DELIMITERS.contains(c))
+                .unwrap_or(rest.len());
+            self.position += len;
+            Label {
+                text: Cow::Borrowed(&rest[..len]),
+                quoted: false,
+                position,
+            }
+        };
+        self.skip_whitespace();
+        match self.input[self.position..].chars().next() {
+            Some(':') => Err(self.error("branch lengths are not supported")),
+            Some('[') => Err(self.error("comments are not supported")),
+            _ => Ok(label),
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::Parse {
+            position: self.position,
+            message: message.to_owned(),
+        }
+    }
+}
+
+/// Write `data` as a label, quoting it when it is empty or would not read
+/// back as a single bare label.
+fn write_label<T: Display>(out: &mut String, data: &T) {
+    let text = data.to_string();
+    let bare = !text.is_empty()
+        && !text
+            .chars()
+            .any(|c| c.is_whitespace() || DELIMITERS.contains(c));
+    if bare {
+        out.push_str(&text);
+    } else {
+        out.push('\'');
+        out.push_str(&text.replace('\'', "''"));
+        out.push('\'');
+    }
+}
+
+enum Step<N> {
+    Enter(N),
+    Text(&'static str),
+    Label(N),
+}
+
+impl<T: FromStr> BinaryNode<T> {
+    /// Read a tree from Newick notation such as `((4,(8,)5)2,((,9)6,7)3)1`,
+    /// without recursion.
+    ///
+    /// Every inner node lists exactly its left and right subtrees, an empty
+    /// bare label standing for a missing child. Labels are read with
+    /// [`FromStr`], may be quoted with `'`, and the final `;` is optional.
+    /// # Errors
+    /// Return `Parse` Error when the input is malformed, some node has
+    /// other than two subtrees, or some label is not a valid `T`.
+    pub fn from_newick(input: &str) -> Result<Self> {
+        let root = Parser::new(input).parse(
+            |label| {
+                if label.text.is_empty() && !label.quoted {
+                    Ok(None)
+                } else {
+                    Ok(Some(BinaryNode::new(label.parse()?)))
+                }
+            },
+            |label, children| {
+                if children.len() != 2 {
+                    return Err(Error::Parse {
+                        position: label.position,
+                        message: "expected a left and a right subtree".to_owned(),
+                    });
+                }
+                let mut children = children.into_iter();
+                let mut node = BinaryNode::new(label.parse()?);
+                node.set_left(children.next().flatten());
+                node.set_right(children.next().flatten());
+                Ok(Some(node))
+            },
+        )?;
+        root.ok_or_else(|| Error::Parse {
+            position: 0,
+            message: "empty tree".to_owned(),
+        })
+    }
+}
+
+impl<T: Display> BinaryNode<T> {
+    /// Write the tree in Newick notation, without the final `;` and
+    /// without recursion. See [`BinaryNode::from_newick`].
+    pub fn to_newick(&self) -> String {
+        let mut out = String::new();
+        let mut stack = vec![Step::Enter(Some(self))];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(None) => {}
+                Step::Enter(Some(node)) if node.left().is_none() && node.right().is_none() => {
+                    write_label(&mut out, node.data());
+                }
+                Step::Enter(Some(node)) => {
+                    out.push('(');
+                    stack.push(Step::Label(Some(node)));
+                    stack.push(Step::Text(")"));
+                    stack.push(Step::Enter(node.right()));
+                    stack.push(Step::Text(","));
+                    stack.push(Step::Enter(node.left()));
+                }
+                Step::Text(text) => out.push_str(text),
+                Step::Label(node) => {
+                    let node = node.expect("only present nodes are labelled");
+                    write_label(&mut out, node.data());
+                }
+            }
+        }
+        out
+    }
+}
+
+impl<T: FromStr> Node<T> {
+    /// Read a tree from Newick notation such as `((b,c)a,d)root`, without
+    /// recursion.
+    ///
+    /// Labels are read with [`FromStr`], may be quoted with `'`, and the
+    /// final `;` is optional.
+    /// # Errors
+    /// Return `Parse` Error when the input is malformed or some label is
+    /// not a valid `T`.
+    pub fn from_newick(input: &str) -> Result<Self> {
+        Parser::new(input).parse(
+            |label| Ok(Node::new(label.parse()?)),
+            |label, children| {
+                let mut node = Node::new(label.parse()?);
+                *node.children_mut() = children;
+                Ok(node)
+            },
+        )
+    }
+}
+
+impl<T: Display> Node<T> {
+    /// Write the tree in Newick notation, without the final `;` and
+    /// without recursion. See [`Node::from_newick`].
+    pub fn to_newick(&self) -> String {
+        let mut out = String::new();
+        let mut stack = vec![Step::Enter(self)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(node) if node.is_leaf() => write_label(&mut out, node.data()),
+                Step::Enter(node) => {
+                    out.push('(');
+                    stack.push(Step::Label(node));
+                    stack.push(Step::Text(")"));
+                    for (idx, child) in node.children().iter().enumerate().rev() {
+                        stack.push(Step::Enter(child));
+                        if idx > 0 {
+                            stack.push(Step::Text(","));
+                        }
+                    }
+                }
+                Step::Text(text) => out.push_str(text),
+                Step::Label(node) => write_label(&mut out, node.data()),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,645 @@
+use crate::{Error, Result};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+type Link<K, V> = Option<Arc<PathNode<K, V>>>;
+
+/// Immutable AVL node. Entries sit behind their own `Arc`, so copying a
+/// node on a path copies no key or value.
+#[derive(Debug)]
+struct PathNode<K, V> {
+    entry: Arc<(K, V)>,
+    /// Height of the subtree, a leaf being `1`.
+    height: u8,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+fn height<K, V>(link: &Link<K, V>) -> u8 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn node<K, V>(entry: Arc<(K, V)>, left: Link<K, V>, right: Link<K, V>) -> Arc<PathNode<K, V>> {
+    Arc::new(PathNode {
+        entry,
+        height: 1 + height(&left).max(height(&right)),
+        left,
+        right,
+    })
+}
+
+/// Build a node from subtrees whose heights differ by at most two,
+/// rotating new nodes into balance.
+fn balance<K, V>(entry: Arc<(K, V)>, left: Link<K, V>, right: Link<K, V>) -> Arc<PathNode<K, V>> {
+    let (left_height, right_height) = (height(&left), height(&right));
+    if left_height > right_height + 1 {
+        let left = left.expect("a taller subtree is not empty");
+        if height(&left.left) >= height(&left.right) {
+            let right = node(entry, left.right.clone(), right);
+            node(left.entry.clone(), left.left.clone(), Some(right))
+        } else {
+            let inner = left.right.as_ref().expect("a taller subtree is not empty");
+            let new_left = node(left.entry.clone(), left.left.clone(), inner.left.clone());
+            let new_right = node(entry, inner.right.clone(), right);
+            node(inner.entry.clone(), Some(new_left), Some(new_right))
+        }
+    } else if right_height > left_height + 1 {
+        let right = right.expect("a taller subtree is not empty");
+        if height(&right.right) >= height(&right.left) {
+            let left = node(entry, left, right.left.clone());
+            node(right.entry.clone(), Some(left), right.right.clone())
+        } else {
+            let inner = right.left.as_ref().expect("a taller subtree is not empty");
+            let new_left = node(entry, left, inner.left.clone());
+            let new_right = node(
+                right.entry.clone(),
+                inner.right.clone(),
+                right.right.clone(),
+            );
+            node(inner.entry.clone(), Some(new_left), Some(new_right))
+        }
+    } else {
+        node(entry, left, right)
+    }
+}
+
+/// Insert into a subtree by copying the search path, returning the new
+/// subtree and whether the key is new.
+fn insert<K: Ord, V>(link: &Link<K, V>, key: K, value: V) -> (Arc<PathNode<K, V>>, bool) {
+    let current = match link {
+        Some(current) => current,
+        None => return (node(Arc::new((key, value)), None, None), true),
+    };
+    match key.cmp(&current.entry.0) {
+        Ordering::Less => {
+            let (left, added) = insert(&current.left, key, value);
+            (
+                balance(current.entry.clone(), Some(left), current.right.clone()),
+                added,
+            )
+        }
+        Ordering::Greater => {
+            let (right, added) = insert(&current.right, key, value);
+            (
+                balance(current.entry.clone(), current.left.clone(), Some(right)),
+                added,
+            )
+        }
+        Ordering::Equal => (
+            node(
+                Arc::new((key, value)),
+                current.left.clone(),
+                current.right.clone(),
+            ),
+            false,
+        ),
+    }
+}
+
+/// Remove from a subtree by copying the search path, or return `None` if
+/// the key is absent so the subtree stays shared.
+fn remove<K, V, Q>(link: &Link<K, V>, key: &Q) -> Option<Link<K, V>>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let current = link.as_ref()?;
+    Some(match key.cmp(current.entry.0.borrow()) {
+        Ordering::Less => {
+            let left = remove(&current.left, key)?;
+            Some(balance(current.entry.clone(), left, current.right.clone()))
+        }
+        Ordering::Greater => {
+            let right = remove(&current.right, key)?;
+            Some(balance(current.entry.clone(), current.left.clone(), right))
+        }
+        Ordering::Equal => match (&current.left, &current.right) {
+            (None, child) | (child, None) => child.clone(),
+            (left, Some(right)) => {
+                let (rest, min) = remove_min(right);
+                Some(balance(min, left.clone(), rest))
+            }
+        },
+    })
+}
+
+/// Remove the smallest entry of a subtree by copying its left spine.
+fn remove_min<K, V>(current: &Arc<PathNode<K, V>>) -> (Link<K, V>, Arc<(K, V)>) {
+    match &current.left {
+        Some(left) => {
+            let (rest, min) = remove_min(left);
+            (
+                Some(balance(current.entry.clone(), rest, current.right.clone())),
+                min,
+            )
+        }
+        None => (current.right.clone(), current.entry.clone()),
+    }
+}
+
+/// Handle of a version of a [`PersistentMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(usize);
+
+impl Version {
+    /// Get the position of the version in the registry, the empty initial
+    /// version being `0`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+struct Record<K, V> {
+    root: Link<K, V>,
+    len: usize,
+    parent: Option<Version>,
+}
+
+/// Persistent ordered map keeping every version.
+///
+/// Each insertion or removal applies to a given version and returns the
+/// handle of a new one, copying only the `O(log n)` nodes on the path to
+/// the changed key over an AVL tree; all other nodes are shared with the
+/// base version. Old versions stay queryable, and [`Snapshot`]s of them can
+/// be sent to other threads.
+pub struct PersistentMap<K, V> {
+    versions: Vec<Record<K, V>>,
+}
+
+impl<K, V> Default for PersistentMap<K, V> {
+    fn default() -> Self {
+        Self {
+            versions: vec![Record {
+                root: None,
+                len: 0,
+                parent: None,
+            }],
+        }
+    }
+}
+
+impl<K, V> PersistentMap<K, V> {
+    /// Create a registry holding only the empty initial version.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the empty initial version.
+    pub fn initial(&self) -> Version {
+        Version(0)
+    }
+
+    /// Get the most recently created version.
+    pub fn latest(&self) -> Version {
+        Version(self.versions.len() - 1)
+    }
+
+    /// Get the number of versions.
+    pub fn version_count(&self) -> usize {
+        self.versions.len()
+    }
+
+    /// Create an iterator over the versions, oldest first.
+    pub fn versions(&self) -> impl ExactSizeIterator<Item = Version> {
+        (0..self.versions.len()).map(Version)
+    }
+
+    fn record(&self, version: Version) -> Result<&Record<K, V>> {
+        self.versions.get(version.0).ok_or(Error::UnknownVersion)
+    }
+
+    /// Get the version a version was derived from, `None` for the initial
+    /// one.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `version` is not in the registry.
+    pub fn parent(&self, version: Version) -> Result<Option<Version>> {
+        Ok(self.record(version)?.parent)
+    }
+
+    /// Get a snapshot of a version, sharing its nodes.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `version` is not in the registry.
+    pub fn snapshot(&self, version: Version) -> Result<Snapshot<K, V>> {
+        let record = self.record(version)?;
+        Ok(Snapshot {
+            root: record.root.clone(),
+            len: record.len,
+        })
+    }
+
+    /// Get the number of entries of a version.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `version` is not in the registry.
+    pub fn len(&self, version: Version) -> Result<usize> {
+        Ok(self.record(version)?.len)
+    }
+
+    /// Count the nodes over all versions, with and without sharing.
+    pub fn stats(&self) -> SharingStats {
+        let mut seen = HashSet::new();
+        let mut stack: Vec<&Arc<PathNode<K, V>>> = Vec::new();
+        for record in &self.versions {
+            stack.extend(&record.root);
+            while let Some(node) = stack.pop() {
+                if seen.insert(Arc::as_ptr(node)) {
+                    stack.extend(&node.left);
+                    stack.extend(&node.right);
+                }
+            }
+        }
+        SharingStats {
+            versions: self.versions.len(),
+            logical_nodes: self.versions.iter().map(|record| record.len).sum(),
+            allocated_nodes: seen.len(),
+        }
+    }
+
+    fn push(&mut self, root: Link<K, V>, len: usize, parent: Version) -> Version {
+        self.versions.push(Record {
+            root,
+            len,
+            parent: Some(parent),
+        });
+        self.latest()
+    }
+}
+
+impl<K: Ord, V> PersistentMap<K, V> {
+    /// Get the ref of the value of `key` in a version.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `version` is not in the registry.
+    pub fn get<Q>(&self, version: Version, key: &Q) -> Result<Option<&V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Ok(find(&self.record(version)?.root, key))
+    }
+
+    /// Insert an entry into a version, returning the new version. The base
+    /// version is left untouched.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `base` is not in the registry.
+    pub fn insert(&mut self, base: Version, key: K, value: V) -> Result<Version> {
+        let record = self.record(base)?;
+        let (root, added) = insert(&record.root, key, value);
+        let len = record.len + usize::from(added);
+        Ok(self.push(Some(root), len, base))
+    }
+
+    /// Remove `key` from a version, returning the new version, which
+    /// shares the whole tree of `base` if the key is absent.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `base` is not in the registry.
+    pub fn remove<Q>(&mut self, base: Version, key: &Q) -> Result<Version>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let record = self.record(base)?;
+        let (root, len) = match remove(&record.root, key) {
+            Some(root) => (root, record.len - 1),
+            None => (record.root.clone(), record.len),
+        };
+        Ok(self.push(root, len, base))
+    }
+}
+
+impl<K, V> fmt::Debug for PersistentMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistentMap")
+            .field("versions", &self.versions.len())
+            .finish()
+    }
+}
+
+fn find<'a, K, V, Q>(mut link: &'a Link<K, V>, key: &Q) -> Option<&'a V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    while let Some(node) = link {
+        link = match key.cmp(node.entry.0.borrow()) {
+            Ordering::Less => &node.left,
+            Ordering::Greater => &node.right,
+            Ordering::Equal => return Some(&node.entry.1),
+        };
+    }
+    None
+}
+
+/// Node counts of a [`PersistentMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SharingStats {
+    /// Number of versions.
+    pub versions: usize,
+    /// Total number of entries over all versions, i.e. the nodes full
+    /// copies would take.
+    pub logical_nodes: usize,
+    /// Number of distinct nodes actually allocated.
+    pub allocated_nodes: usize,
+}
+
+impl SharingStats {
+    /// Get how many logical nodes each allocated node stands for, `1.0`
+    /// when there are none.
+    pub fn sharing_ratio(&self) -> f64 {
+        if self.allocated_nodes == 0 {
+            1.0
+        } else {
+            self.logical_nodes as f64 / self.allocated_nodes as f64
+        }
+    }
+}
+
+/// Read-only view of one version of a [`PersistentMap`].
+pub struct Snapshot<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> Clone for Snapshot<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V> Snapshot<K, V> {
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the version is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the height of the tree, `0` when empty.
+    pub fn height(&self) -> usize {
+        usize::from(height(&self.root))
+    }
+
+    /// Create an iterator over the entries in increasing order of keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            len: self.len,
+        };
+        iter.push_left(&self.root);
+        iter
+    }
+}
+
+impl<K: Ord, V> Snapshot<K, V> {
+    /// Get the ref of the value of `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        find(&self.root, key)
+    }
+
+    /// Return `true` if the version contains `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Snapshot<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a Snapshot<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Linear undo/redo over a [`PersistentMap`], with branching.
+///
+/// Edits are staged on a working copy and recorded as a new version by
+/// [`commit`](History::commit), whose parent is the head. Undoing moves
+/// the head to its parent and redoing moves it back, while
+/// [`checkout`](History::checkout) moves it to any version, so that the
+/// next commit starts a new branch. Every version stays in the registry.
+pub struct History<K, V> {
+    map: PersistentMap<K, V>,
+    head: Version,
+    working: Snapshot<K, V>,
+    /// Versions undone from the head, the next one to redo last.
+    redo: Vec<Version>,
+}
+
+impl<K, V> Default for History<K, V> {
+    fn default() -> Self {
+        let map = PersistentMap::new();
+        let head = map.initial();
+        Self {
+            working: Snapshot { root: None, len: 0 },
+            map,
+            head,
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl<K, V> History<K, V> {
+    /// Create a history whose head is the empty initial version.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the ref of the registry of versions.
+    pub fn versions(&self) -> &PersistentMap<K, V> {
+        &self.map
+    }
+
+    /// Get the version the working copy is based on.
+    pub fn head(&self) -> Version {
+        self.head
+    }
+
+    /// Get the ref of the working copy, with the staged edits.
+    pub fn working(&self) -> &Snapshot<K, V> {
+        &self.working
+    }
+
+    /// Return `true` if there are staged edits, i.e. the working copy no
+    /// longer shares the tree of the head.
+    pub fn is_dirty(&self) -> bool {
+        let head = &self.map.versions[self.head.0].root;
+        match (&self.working.root, head) {
+            (Some(working), Some(head)) => !Arc::ptr_eq(working, head),
+            (working, head) => working.is_some() != head.is_some(),
+        }
+    }
+
+    /// Record the working copy as a child version of the head and move the
+    /// head to it, clearing the redo stack. Nothing is recorded if there
+    /// are no staged edits.
+    pub fn commit(&mut self) -> Version {
+        if self.is_dirty() {
+            let root = self.working.root.clone();
+            self.head = self.map.push(root, self.working.len, self.head);
+            self.redo.clear();
+        }
+        self.head
+    }
+
+    /// Drop the staged edits.
+    pub fn discard(&mut self) {
+        self.working = self.head_snapshot();
+    }
+
+    /// Move the head to its parent, dropping the staged edits. Return the
+    /// new head, or `None` without doing anything at the initial version.
+    pub fn undo(&mut self) -> Option<Version> {
+        let parent = self.map.versions[self.head.0].parent?;
+        self.redo.push(self.head);
+        self.head = parent;
+        self.discard();
+        Some(parent)
+    }
+
+    /// Move the head back to the version last undone, dropping the staged
+    /// edits. Return the new head, or `None` without doing anything if
+    /// there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Version> {
+        self.head = self.redo.pop()?;
+        self.discard();
+        Some(self.head)
+    }
+
+    /// Return `true` if [`undo`](History::undo) would move the head.
+    pub fn can_undo(&self) -> bool {
+        self.map.versions[self.head.0].parent.is_some()
+    }
+
+    /// Return `true` if [`redo`](History::redo) would move the head.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Move the head to any version, dropping the staged edits and
+    /// clearing the redo stack. The next commit branches off `version`.
+    /// # Errors
+    /// Return `UnknownVersion` Error when `version` is not in the registry.
+    pub fn checkout(&mut self, version: Version) -> Result<()> {
+        self.map.record(version)?;
+        self.head = version;
+        self.redo.clear();
+        self.discard();
+        Ok(())
+    }
+
+    fn head_snapshot(&self) -> Snapshot<K, V> {
+        let record = &self.map.versions[self.head.0];
+        Snapshot {
+            root: record.root.clone(),
+            len: record.len,
+        }
+    }
+}
+
+impl<K: Ord, V> History<K, V> {
+    /// Get the ref of the value of `key` in the working copy.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.working.get(key)
+    }
+
+    /// Stage the insertion of an entry, returning `true` if the key is new
+    /// to the working copy.
+    pub fn insert(&mut self, key: K, value: V) -> bool {
+        let (root, added) = insert(&self.working.root, key, value);
+        self.working.root = Some(root);
+        self.working.len += usize::from(added);
+        added
+    }
+
+    /// Stage the removal of `key`, returning `true` if it was in the
+    /// working copy.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match remove(&self.working.root, key) {
+            Some(root) => {
+                self.working.root = root;
+                self.working.len -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for History<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("versions", &self.map.versions.len())
+            .field("head", &self.head)
+            .field("redo", &self.redo)
+            .finish()
+    }
+}
+
+/// Iterator over the entries of a [`Snapshot`].
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a PathNode<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = &node.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(&node.right);
+        self.len -= 1;
+        Some((&node.entry.0, &node.entry.1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'a, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Iter").field("len", &self.len).finish()
+    }
+}
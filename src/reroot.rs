@@ -0,0 +1,75 @@
+use crate::lca::TreeIndex;
+use crate::monoid::Monoid;
+
+/// Compute a tree DP for every choice of root, in `O(n)` calls of the
+/// closures, without recursion.
+///
+/// The DP value of a vertex in a rooted tree is `finalize(acc, vertex)`,
+/// `acc` combining `edge(value, child, vertex)` over the children, where
+/// `value` is the DP value of the child. The tree is taken as undirected,
+/// so `edge` also gets a parent in place of a child, and the value of
+/// every vertex is returned, by vertex, as if it were the root. Each
+/// vertex combines the edges from its neighbors in an unspecified order,
+/// so the monoid should be commutative.
+///
+/// For example, with `A = (Sum, Sum)` counting the nodes and summing
+/// their distances, `edge` adding one to every distance across the edge
+/// and `finalize` counting the vertex itself, the values give the sum of
+/// the distances from every vertex to all the others.
+pub fn reroot<A, D, E, F>(tree: &TreeIndex, edge: E, finalize: F) -> Vec<D>
+where
+    A: Monoid,
+    E: Fn(&D, usize, usize) -> A,
+    F: Fn(&A, usize) -> D,
+{
+    let len = tree.len();
+    let mut order: Vec<usize> = tree.root().into_iter().collect();
+    let mut idx = 0;
+    while idx < order.len() {
+        order.extend(tree.children(order[idx]));
+        idx += 1;
+    }
+
+    // Values of the subtrees, bottom-up.
+    let mut down: Vec<Option<D>> = (0..len).map(|_| None).collect();
+    for &vertex in order.iter().rev() {
+        let acc = tree.children(vertex).fold(A::identity(), |acc, child| {
+            let value = down[child].as_ref().expect("children come later");
+            acc.combine(&edge(value, child, vertex))
+        });
+        down[vertex] = Some(finalize(&acc, vertex));
+    }
+
+    // Values of the rest of the tree seen from every vertex, top-down,
+    // each child getting the edges of its siblings through prefix and
+    // suffix combinations.
+    let mut up: Vec<Option<D>> = (0..len).map(|_| None).collect();
+    let mut all: Vec<Option<D>> = (0..len).map(|_| None).collect();
+    for &vertex in &order {
+        let above = match (&up[vertex], tree.parent(vertex)) {
+            (Some(value), Some(parent)) => edge(value, parent, vertex),
+            _ => A::identity(),
+        };
+        let children: Vec<usize> = tree.children(vertex).collect();
+        let edges: Vec<A> = children
+            .iter()
+            .map(|&child| {
+                let value = down[child].as_ref().expect("every subtree is computed");
+                edge(value, child, vertex)
+            })
+            .collect();
+        let mut suffix = vec![A::identity(); edges.len() + 1];
+        for (idx, edge) in edges.iter().enumerate().rev() {
+            suffix[idx] = edge.combine(&suffix[idx + 1]);
+        }
+        let mut prefix = above;
+        for (idx, &child) in children.iter().enumerate() {
+            up[child] = Some(finalize(&prefix.combine(&suffix[idx + 1]), vertex));
+            prefix = prefix.combine(&edges[idx]);
+        }
+        all[vertex] = Some(finalize(&prefix, vertex));
+    }
+    all.into_iter()
+        .map(|value| value.expect("every vertex is reached"))
+        .collect()
+}
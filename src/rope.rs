@@ -0,0 +1,415 @@
+use crate::{Error, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Range;
+
+/// Maximum size in bytes of a leaf chunk.
+const CHUNK: usize = 512;
+
+#[derive(Debug, Clone)]
+enum RopeNode {
+    Leaf(String),
+    Branch(Branch),
+}
+
+#[derive(Debug, Clone)]
+struct Branch {
+    left: Box<RopeNode>,
+    right: Box<RopeNode>,
+    bytes: usize,
+    chars: usize,
+    height: usize,
+}
+
+type Link = Option<Box<RopeNode>>;
+
+impl RopeNode {
+    fn leaf(text: &str) -> Link {
+        if text.is_empty() {
+            None
+        } else {
+            Some(Box::new(RopeNode::Leaf(text.to_owned())))
+        }
+    }
+
+    fn branch(left: Box<RopeNode>, right: Box<RopeNode>) -> Box<RopeNode> {
+        Box::new(RopeNode::Branch(Branch {
+            bytes: left.bytes() + right.bytes(),
+            chars: left.chars() + right.chars(),
+            height: left.height().max(right.height()) + 1,
+            left,
+            right,
+        }))
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            RopeNode::Leaf(text) => text.len(),
+            RopeNode::Branch(branch) => branch.bytes,
+        }
+    }
+
+    fn chars(&self) -> usize {
+        match self {
+            RopeNode::Leaf(text) => text.chars().count(),
+            RopeNode::Branch(branch) => branch.chars,
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            RopeNode::Leaf(_) => 1,
+            RopeNode::Branch(branch) => branch.height,
+        }
+    }
+
+    fn into_children(self) -> (Box<RopeNode>, Box<RopeNode>) {
+        match self {
+            RopeNode::Branch(branch) => (branch.left, branch.right),
+            RopeNode::Leaf(_) => unreachable!("leaves have no children"),
+        }
+    }
+}
+
+/// Restore the height balance of a branch whose children differ by at most 2.
+fn balance(left: Box<RopeNode>, right: Box<RopeNode>) -> Box<RopeNode> {
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = (*left).into_children();
+        if ll.height() >= lr.height() {
+            RopeNode::branch(ll, RopeNode::branch(lr, right))
+        } else {
+            let (lrl, lrr) = (*lr).into_children();
+            RopeNode::branch(RopeNode::branch(ll, lrl), RopeNode::branch(lrr, right))
+        }
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = (*right).into_children();
+        if rr.height() >= rl.height() {
+            RopeNode::branch(RopeNode::branch(left, rl), rr)
+        } else {
+            let (rll, rlr) = (*rl).into_children();
+            RopeNode::branch(RopeNode::branch(left, rll), RopeNode::branch(rlr, rr))
+        }
+    } else {
+        RopeNode::branch(left, right)
+    }
+}
+
+/// Concatenate two trees in `O(|height difference|)`.
+fn join_nodes(left: Box<RopeNode>, right: Box<RopeNode>) -> Box<RopeNode> {
+    if let (RopeNode::Leaf(l), RopeNode::Leaf(r)) = (&*left, &*right) {
+        if l.len() + r.len() <= CHUNK {
+            return Box::new(RopeNode::Leaf(format!("{}{}", l, r)));
+        }
+    }
+    if left.height() > right.height() + 1 {
+        let (ll, lr) = (*left).into_children();
+        balance(ll, join_nodes(lr, right))
+    } else if right.height() > left.height() + 1 {
+        let (rl, rr) = (*right).into_children();
+        balance(join_nodes(left, rl), rr)
+    } else {
+        RopeNode::branch(left, right)
+    }
+}
+
+fn join(left: Link, right: Link) -> Link {
+    match (left, right) {
+        (Some(left), Some(right)) => Some(join_nodes(left, right)),
+        (left, None) => left,
+        (None, right) => right,
+    }
+}
+
+/// Split at a char index.
+fn split(node: RopeNode, at: usize) -> (Link, Link) {
+    match node {
+        RopeNode::Leaf(text) => {
+            let byte = char_to_byte(&text, at);
+            (RopeNode::leaf(&text[..byte]), RopeNode::leaf(&text[byte..]))
+        }
+        RopeNode::Branch(branch) => {
+            let chars = branch.left.chars();
+            if at < chars {
+                let (a, b) = split(*branch.left, at);
+                (a, join(b, Some(branch.right)))
+            } else if at > chars {
+                let (a, b) = split(*branch.right, at - chars);
+                (join(Some(branch.left), a), b)
+            } else {
+                (Some(branch.left), Some(branch.right))
+            }
+        }
+    }
+}
+
+fn char_to_byte(text: &str, at: usize) -> usize {
+    text.char_indices()
+        .nth(at)
+        .map(|(byte, _)| byte)
+        .unwrap_or(text.len())
+}
+
+/// Balanced rope over `String` chunks, indexed by chars.
+///
+/// Insertion, removal, concatenation, splitting and indexing
+/// all run in `O(log n)`.
+#[derive(Debug, Clone, Default)]
+pub struct Rope {
+    root: Link,
+}
+
+impl Rope {
+    /// Create an empty rope.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the length in bytes.
+    pub fn len_bytes(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.bytes())
+    }
+
+    /// Get the length in chars.
+    pub fn len_chars(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.chars())
+    }
+
+    /// Return `true` if the rope is empty.
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Get the height of the underlying tree.
+    pub fn height(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.height())
+    }
+
+    /// Append `other` to the end of the rope.
+    pub fn concat(&mut self, other: Rope) {
+        self.root = join(self.root.take(), other.root);
+    }
+
+    /// Split the rope at the char index `at`, returning the chars after it.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `at` is past the end.
+    pub fn split_off(&mut self, at: usize) -> Result<Rope> {
+        self.check_index(at)?;
+        let (left, right) = match self.root.take() {
+            Some(root) => split(*root, at),
+            None => (None, None),
+        };
+        self.root = left;
+        Ok(Rope { root: right })
+    }
+
+    /// Insert `text` at the char index `at`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `at` is past the end.
+    pub fn insert(&mut self, at: usize, text: &str) -> Result<()> {
+        let right = self.split_off(at)?;
+        self.concat(Rope::from(text));
+        self.concat(right);
+        Ok(())
+    }
+
+    /// Remove the chars in `range`, leaving the rope unchanged on error.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `range.end` is past the end or
+    /// `range.start` is past `range.end`.
+    pub fn remove(&mut self, range: Range<usize>) -> Result<()> {
+        self.check_index(range.end)?;
+        if range.start > range.end {
+            return Err(Error::IndexOutOfRange {
+                index: range.start,
+                len: range.end,
+            });
+        }
+        let right = self.split_off(range.end)?;
+        self.split_off(range.start)?;
+        self.concat(right);
+        Ok(())
+    }
+
+    /// Get the char at the char index `at`.
+    pub fn char(&self, at: usize) -> Option<char> {
+        let (text, at) = self.leaf_at(at, RopeNode::chars)?;
+        text.chars().nth(at)
+    }
+
+    /// Get the byte at the byte index `at`.
+    pub fn byte(&self, at: usize) -> Option<u8> {
+        let (text, at) = self.leaf_at(at, RopeNode::bytes)?;
+        text.as_bytes().get(at).copied()
+    }
+
+    /// Convert a char index into a byte index.
+    ///
+    /// Return `None` if `at` is past the end of the rope.
+    pub fn char_to_byte(&self, at: usize) -> Option<usize> {
+        match at.cmp(&self.len_chars()) {
+            Ordering::Greater => return None,
+            Ordering::Equal => return Some(self.len_bytes()),
+            Ordering::Less => {}
+        }
+        let mut node = self.root.as_deref()?;
+        let mut at = at;
+        let mut bytes = 0;
+        loop {
+            match node {
+                RopeNode::Leaf(text) => return Some(bytes + char_to_byte(text, at)),
+                RopeNode::Branch(branch) => {
+                    let chars = branch.left.chars();
+                    if at < chars {
+                        node = &branch.left;
+                    } else {
+                        at -= chars;
+                        bytes += branch.left.bytes();
+                        node = &branch.right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Convert a byte index into the index of the char starting at it.
+    ///
+    /// Return `None` if `at` is past the end of the rope or not on a char
+    /// boundary.
+    pub fn byte_to_char(&self, at: usize) -> Option<usize> {
+        match at.cmp(&self.len_bytes()) {
+            Ordering::Greater => return None,
+            Ordering::Equal => return Some(self.len_chars()),
+            Ordering::Less => {}
+        }
+        let mut node = self.root.as_deref()?;
+        let mut at = at;
+        let mut chars = 0;
+        loop {
+            match node {
+                RopeNode::Leaf(text) => {
+                    if !text.is_char_boundary(at) {
+                        return None;
+                    }
+                    return Some(chars + text[..at].chars().count());
+                }
+                RopeNode::Branch(branch) => {
+                    let bytes = branch.left.bytes();
+                    if at < bytes {
+                        node = &branch.left;
+                    } else {
+                        at -= bytes;
+                        chars += branch.left.chars();
+                        node = &branch.right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Create an iterator over the string chunks of the rope.
+    pub fn chunks(&self) -> Chunks<'_> {
+        Chunks {
+            stack: self.root.as_deref().into_iter().collect(),
+        }
+    }
+
+    /// Create an iterator over the chars of the rope.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.chunks().flat_map(str::chars)
+    }
+
+    fn leaf_at(&self, mut at: usize, len: fn(&RopeNode) -> usize) -> Option<(&str, usize)> {
+        let mut node = self.root.as_deref()?;
+        if at >= len(node) {
+            return None;
+        }
+        loop {
+            match node {
+                RopeNode::Leaf(text) => return Some((text, at)),
+                RopeNode::Branch(branch) => {
+                    let left = len(&branch.left);
+                    if at < left {
+                        node = &branch.left;
+                    } else {
+                        at -= left;
+                        node = &branch.right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        let len = self.len_chars();
+        if index > len {
+            Err(Error::IndexOutOfRange { index, len })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut leaves = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut end = rest.len().min(CHUNK);
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            leaves.extend(RopeNode::leaf(&rest[..end]));
+            rest = &rest[end..];
+        }
+        // Pair up adjacent subtrees level by level to get a balanced tree.
+        while leaves.len() > 1 {
+            let mut level = Vec::with_capacity(leaves.len().div_ceil(2));
+            let mut iter = leaves.into_iter();
+            while let Some(left) = iter.next() {
+                match iter.next() {
+                    Some(right) => level.push(join_nodes(left, right)),
+                    None => level.push(left),
+                }
+            }
+            leaves = level;
+        }
+        Self { root: leaves.pop() }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        Self::from(text.as_str())
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            write!(f, "{}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the string chunks of a [`Rope`].
+#[derive(Debug)]
+pub struct Chunks<'a> {
+    stack: Vec<&'a RopeNode>,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.pop()? {
+                RopeNode::Leaf(text) => return Some(text),
+                RopeNode::Branch(branch) => {
+                    self.stack.push(&branch.right);
+                    self.stack.push(&branch.left);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::monoid::Monoid;
+pub use crate::monoid::{Max, Min, Sum};
+use crate::{Error, Result};
+use std::ops::Range;
+
+/// A monoid of updates acting on values of type `M`.
+///
+/// Pending updates compose as `newer.combine(&older)`.
+pub trait Action<M>: Monoid {
+    /// Apply the update to the aggregate of `len` values.
+    fn act(&self, value: &M, len: usize) -> M;
+}
+
+impl<M: Clone> Action<M> for () {
+    fn act(&self, value: &M, _len: usize) -> M {
+        value.clone()
+    }
+}
+
+/// Add a constant to every value in a range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Add(pub i64);
+
+impl Monoid for Add {
+    fn identity() -> Self {
+        Add(0)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Add(self.0 + other.0)
+    }
+}
+
+impl Action<Sum> for Add {
+    fn act(&self, value: &Sum, len: usize) -> Sum {
+        Sum(value.0 + self.0 * len as i64)
+    }
+}
+
+impl Action<Max> for Add {
+    fn act(&self, value: &Max, _len: usize) -> Max {
+        if value.0 == i64::MIN {
+            *value
+        } else {
+            Max(value.0 + self.0)
+        }
+    }
+}
+
+impl Action<Min> for Add {
+    fn act(&self, value: &Min, _len: usize) -> Min {
+        if value.0 == i64::MAX {
+            *value
+        } else {
+            Min(value.0 + self.0)
+        }
+    }
+}
+
+/// Segment tree over a sequence of monoid values with lazy range updates.
+///
+/// Range queries and range updates run in `O(log n)`.
+#[derive(Debug, Clone)]
+pub struct SegmentTree<M, A = ()> {
+    len: usize,
+    sums: Vec<M>,
+    pending: Vec<Option<A>>,
+}
+
+impl<M: Monoid, A: Action<M>> SegmentTree<M, A> {
+    /// Build the tree over `values` in `O(n)`.
+    pub fn new(values: Vec<M>) -> Self {
+        let len = values.len();
+        let size = 4 * len.max(1);
+        let mut tree = Self {
+            len,
+            sums: vec![M::identity(); size],
+            pending: vec![None; size],
+        };
+        if len > 0 {
+            tree.build(1, 0, len, &values);
+        }
+        tree
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Aggregate the values in `range`, in order.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when the range is out of bounds.
+    pub fn query(&mut self, range: Range<usize>) -> Result<M> {
+        self.check(&range)?;
+        Ok(self.query_inner(1, 0, self.len, &range))
+    }
+
+    /// Apply `action` to every value in `range`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when the range is out of bounds.
+    pub fn apply(&mut self, range: Range<usize>, action: A) -> Result<()> {
+        self.check(&range)?;
+        self.apply_inner(1, 0, self.len, &range, &action);
+        Ok(())
+    }
+
+    /// Get the value at `index`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `index` is out of bounds.
+    pub fn get(&mut self, index: usize) -> Result<M> {
+        self.query(index..index + 1)
+    }
+
+    /// Replace the value at `index`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: M) -> Result<()> {
+        self.check(&(index..index + 1))?;
+        self.set_inner(1, 0, self.len, index, value);
+        Ok(())
+    }
+
+    fn check(&self, range: &Range<usize>) -> Result<()> {
+        if range.start > range.end || range.end > self.len {
+            Err(Error::IndexOutOfRange {
+                index: range.end.max(range.start),
+                len: self.len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, values: &[M]) {
+        if hi - lo == 1 {
+            self.sums[node] = values[lo].clone();
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self.build(2 * node, lo, mid, values);
+        self.build(2 * node + 1, mid, hi, values);
+        self.pull(node);
+    }
+
+    fn pull(&mut self, node: usize) {
+        self.sums[node] = self.sums[2 * node].combine(&self.sums[2 * node + 1]);
+    }
+
+    fn act(&mut self, node: usize, len: usize, action: &A) {
+        self.sums[node] = action.act(&self.sums[node], len);
+        let pending = match self.pending[node].take() {
+            Some(older) => action.combine(&older),
+            None => action.clone(),
+        };
+        self.pending[node] = Some(pending);
+    }
+
+    fn push(&mut self, node: usize, lo: usize, hi: usize) {
+        if let Some(action) = self.pending[node].take() {
+            let mid = (lo + hi) / 2;
+            self.act(2 * node, mid - lo, &action);
+            self.act(2 * node + 1, hi - mid, &action);
+        }
+    }
+
+    fn query_inner(&mut self, node: usize, lo: usize, hi: usize, range: &Range<usize>) -> M {
+        if range.end <= lo || hi <= range.start {
+            return M::identity();
+        }
+        if range.start <= lo && hi <= range.end {
+            return self.sums[node].clone();
+        }
+        self.push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        let left = self.query_inner(2 * node, lo, mid, range);
+        let right = self.query_inner(2 * node + 1, mid, hi, range);
+        left.combine(&right)
+    }
+
+    fn apply_inner(&mut self, node: usize, lo: usize, hi: usize, range: &Range<usize>, action: &A) {
+        if range.end <= lo || hi <= range.start {
+            return;
+        }
+        if range.start <= lo && hi <= range.end {
+            self.act(node, hi - lo, action);
+            return;
+        }
+        self.push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        self.apply_inner(2 * node, lo, mid, range, action);
+        self.apply_inner(2 * node + 1, mid, hi, range, action);
+        self.pull(node);
+    }
+
+    fn set_inner(&mut self, node: usize, lo: usize, hi: usize, index: usize, value: M) {
+        if hi - lo == 1 {
+            self.sums[node] = value;
+            return;
+        }
+        self.push(node, lo, hi);
+        let mid = (lo + hi) / 2;
+        if index < mid {
+            self.set_inner(2 * node, lo, mid, index, value);
+        } else {
+            self.set_inner(2 * node + 1, mid, hi, index, value);
+        }
+        self.pull(node);
+    }
+}
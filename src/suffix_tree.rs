@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+
+/// Symbols past the last unicode scalar value are used as terminators.
+const TERMINATOR: u32 = 0x11_0000;
+const ROOT: usize = 0;
+const LEAF: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct SuffixNode {
+    start: usize,
+    end: usize,
+    link: usize,
+    children: BTreeMap<u32, usize>,
+}
+
+impl SuffixNode {
+    fn new(start: usize, end: usize) -> Self {
+        Self {
+            start,
+            end,
+            link: ROOT,
+            children: BTreeMap::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.end == LEAF
+    }
+}
+
+/// Suffix tree over a raw symbol sequence, built by Ukkonen's algorithm.
+#[derive(Debug, Clone)]
+struct Ukkonen {
+    symbols: Vec<u32>,
+    nodes: Vec<SuffixNode>,
+}
+
+impl Ukkonen {
+    /// The last symbol of `symbols` must be unique.
+    fn build(symbols: Vec<u32>) -> Self {
+        let mut tree = Self {
+            symbols,
+            nodes: vec![SuffixNode::new(0, 0)],
+        };
+        let mut active_node = ROOT;
+        let mut active_edge = 0;
+        let mut active_length = 0;
+        let mut remainder = 0;
+
+        for i in 0..tree.symbols.len() {
+            let mut last_new: Option<usize> = None;
+            remainder += 1;
+            while remainder > 0 {
+                if active_length == 0 {
+                    active_edge = i;
+                }
+                let c = tree.symbols[active_edge];
+                match tree.nodes[active_node].children.get(&c).copied() {
+                    None => {
+                        let leaf = tree.push(SuffixNode::new(i, LEAF));
+                        tree.nodes[active_node].children.insert(c, leaf);
+                        if let Some(node) = last_new.take() {
+                            tree.nodes[node].link = active_node;
+                        }
+                    }
+                    Some(next) => {
+                        let len = tree.edge_len(next, i + 1);
+                        if active_length >= len {
+                            active_edge += len;
+                            active_length -= len;
+                            active_node = next;
+                            continue;
+                        }
+                        let start = tree.nodes[next].start;
+                        if tree.symbols[start + active_length] == tree.symbols[i] {
+                            if active_node != ROOT {
+                                if let Some(node) = last_new.take() {
+                                    tree.nodes[node].link = active_node;
+                                }
+                            }
+                            active_length += 1;
+                            break;
+                        }
+                        let split = tree.push(SuffixNode::new(start, start + active_length));
+                        tree.nodes[active_node].children.insert(c, split);
+                        let leaf = tree.push(SuffixNode::new(i, LEAF));
+                        tree.nodes[split].children.insert(tree.symbols[i], leaf);
+                        tree.nodes[next].start += active_length;
+                        let key = tree.symbols[tree.nodes[next].start];
+                        tree.nodes[split].children.insert(key, next);
+                        if let Some(node) = last_new.replace(split) {
+                            tree.nodes[node].link = split;
+                        }
+                    }
+                }
+                remainder -= 1;
+                if active_node == ROOT && active_length > 0 {
+                    active_length -= 1;
+                    active_edge = i + 1 - remainder;
+                } else if active_node != ROOT {
+                    active_node = tree.nodes[active_node].link;
+                }
+            }
+        }
+        tree
+    }
+
+    fn push(&mut self, node: SuffixNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    fn end(&self, node: usize, leaf_end: usize) -> usize {
+        if self.nodes[node].is_leaf() {
+            leaf_end
+        } else {
+            self.nodes[node].end
+        }
+    }
+
+    fn edge_len(&self, node: usize, leaf_end: usize) -> usize {
+        self.end(node, leaf_end) - self.nodes[node].start
+    }
+
+    /// Walk down from the root along `pattern`, returning the node
+    /// at or below the end of the match and its string depth.
+    fn locate(&self, pattern: &[u32]) -> Option<(usize, usize)> {
+        let mut node = ROOT;
+        let mut depth = 0;
+        let mut matched = 0;
+        while matched < pattern.len() {
+            let next = *self.nodes[node].children.get(&pattern[matched])?;
+            let start = self.nodes[next].start;
+            let end = self.end(next, self.symbols.len());
+            for &symbol in &self.symbols[start..end] {
+                if matched == pattern.len() {
+                    break;
+                }
+                if symbol != pattern[matched] {
+                    return None;
+                }
+                matched += 1;
+            }
+            depth += end - start;
+            node = next;
+        }
+        Some((node, depth))
+    }
+
+    /// Return `(string depth, suffix start)` for every node, where the
+    /// suffix start is the smallest suffix index among the leaves below it.
+    fn annotate(&self) -> Vec<(usize, usize)> {
+        let len = self.symbols.len();
+        let mut info = vec![(0, usize::MAX); self.nodes.len()];
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = vec![ROOT];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for &child in self.nodes[node].children.values() {
+                info[child].0 = info[node].0 + self.edge_len(child, len);
+                stack.push(child);
+            }
+        }
+        for &node in order.iter().rev() {
+            if self.nodes[node].is_leaf() {
+                info[node].1 = len - info[node].0;
+            }
+            for &child in self.nodes[node].children.values() {
+                info[node].1 = info[node].1.min(info[child].1);
+            }
+        }
+        info
+    }
+
+    fn leaves(&self, node: usize, depth: usize) -> Vec<usize> {
+        let len = self.symbols.len();
+        let mut result = Vec::new();
+        let mut stack = vec![(node, depth)];
+        while let Some((node, depth)) = stack.pop() {
+            if self.nodes[node].is_leaf() {
+                result.push(len - depth);
+            }
+            for &child in self.nodes[node].children.values() {
+                stack.push((child, depth + self.edge_len(child, len)));
+            }
+        }
+        result
+    }
+}
+
+/// Suffix tree of a string, built in linear time with Ukkonen's algorithm.
+#[derive(Debug, Clone)]
+pub struct SuffixTree {
+    text: String,
+    offsets: Vec<usize>,
+    tree: Ukkonen,
+}
+
+impl SuffixTree {
+    /// Build the suffix tree of `text`.
+    pub fn new(text: &str) -> Self {
+        let (offsets, mut symbols) = encode(text);
+        symbols.push(TERMINATOR);
+        Self {
+            text: text.to_owned(),
+            offsets,
+            tree: Ukkonen::build(symbols),
+        }
+    }
+
+    /// Get the indexed text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Return `true` if `pattern` is a substring of the text.
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.tree.locate(&encode(pattern).1).is_some()
+    }
+
+    /// Return the byte offsets of all occurrences of `pattern` in the text,
+    /// in ascending order.
+    pub fn find_all(&self, pattern: &str) -> Vec<usize> {
+        let (node, depth) = match self.tree.locate(&encode(pattern).1) {
+            Some(found) => found,
+            None => return Vec::new(),
+        };
+        let mut result: Vec<_> = self
+            .tree
+            .leaves(node, depth)
+            .into_iter()
+            .map(|start| self.offsets[start])
+            .collect();
+        result.sort_unstable();
+        result
+    }
+
+    /// Return the longest substring occurring at least twice in the text.
+    /// Return an empty string if there is none.
+    pub fn longest_repeated_substring(&self) -> &str {
+        let info = self.tree.annotate();
+        let (depth, start) = self
+            .tree
+            .nodes
+            .iter()
+            .zip(info)
+            .filter(|(node, _)| !node.is_leaf())
+            .map(|(_, info)| info)
+            .fold(
+                (0, 0),
+                |best, info| if info.0 > best.0 { info } else { best },
+            );
+        &self.text[self.offsets[start]..self.offsets[start + depth]]
+    }
+
+    /// Return the longest common substring of `a` and `b`, as a slice of `a`.
+    /// Return an empty string if there is none.
+    pub fn longest_common_substring<'a>(a: &'a str, b: &str) -> &'a str {
+        let (offsets, mut symbols) = encode(a);
+        let split = symbols.len();
+        symbols.push(TERMINATOR);
+        symbols.extend(encode(b).1);
+        symbols.push(TERMINATOR + 1);
+        let tree = Ukkonen::build(symbols);
+        let len = tree.symbols.len();
+
+        // Mark every node with the sides (bit 0 for `a`, bit 1 for `b`) of its leaves.
+        let mut marks = vec![0u8; tree.nodes.len()];
+        let mut depths = vec![0; tree.nodes.len()];
+        let mut order = Vec::with_capacity(tree.nodes.len());
+        let mut stack = vec![ROOT];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            for &child in tree.nodes[node].children.values() {
+                depths[child] = depths[node] + tree.edge_len(child, len);
+                stack.push(child);
+            }
+        }
+        let mut starts = vec![usize::MAX; tree.nodes.len()];
+        for &node in order.iter().rev() {
+            if tree.nodes[node].is_leaf() {
+                let start = len - depths[node];
+                if start < split {
+                    marks[node] = 0b01;
+                    starts[node] = start;
+                } else if start > split {
+                    marks[node] = 0b10;
+                }
+            }
+            for &child in tree.nodes[node].children.values() {
+                marks[node] |= marks[child];
+                starts[node] = starts[node].min(starts[child]);
+            }
+        }
+
+        let (depth, start) = order
+            .into_iter()
+            .filter(|&node| marks[node] == 0b11)
+            .map(|node| (depths[node], starts[node]))
+            .fold(
+                (0, 0),
+                |best, info| if info.0 > best.0 { info } else { best },
+            );
+        &a[offsets[start]..offsets[start + depth]]
+    }
+}
+
+/// Return the byte offset of every char (plus the end offset) and the symbols.
+fn encode(text: &str) -> (Vec<usize>, Vec<u32>) {
+    let mut offsets = Vec::with_capacity(text.len() + 1);
+    let mut symbols = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        offsets.push(offset);
+        symbols.push(c as u32);
+    }
+    offsets.push(text.len());
+    (offsets, symbols)
+}
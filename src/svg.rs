@@ -0,0 +1,312 @@
+use crate::binary_tree::Node as BinaryNode;
+use crate::diagram::Diagram;
+use crate::layout;
+use crate::tree::Node;
+use crate::width::str_width;
+use std::fmt::{Display, Write};
+
+/// Shape drawn around every node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeShape {
+    /// Circle fitting the label.
+    Circle,
+    /// Rectangle fitting the label.
+    Rect,
+    /// Rectangle with rounded corners.
+    RoundedRect,
+    /// Label only.
+    Plain,
+}
+
+/// Path drawn along every edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeShape {
+    /// Straight line.
+    Straight,
+    /// Cubic curve leaving and entering vertically.
+    Curved,
+    /// Vertical and horizontal segments.
+    Elbow,
+}
+
+/// Appearance of a tree rendered as SVG. Sizes are in pixels.
+#[derive(Debug, Clone)]
+pub struct SvgStyle {
+    node_shape: NodeShape,
+    node_fill: String,
+    node_stroke: String,
+    font_family: String,
+    font_size: u32,
+    text_fill: String,
+    edge_shape: EdgeShape,
+    edge_stroke: String,
+    edge_width: u32,
+    edge_dash: Option<String>,
+    padding: u32,
+    sibling_gap: u32,
+    level_gap: u32,
+    margin: u32,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            node_shape: NodeShape::Circle,
+            node_fill: "white".to_owned(),
+            node_stroke: "black".to_owned(),
+            font_family: "monospace".to_owned(),
+            font_size: 14,
+            text_fill: "black".to_owned(),
+            edge_shape: EdgeShape::Straight,
+            edge_stroke: "black".to_owned(),
+            edge_width: 1,
+            edge_dash: None,
+            padding: 6,
+            sibling_gap: 12,
+            level_gap: 32,
+            margin: 8,
+        }
+    }
+}
+
+impl SvgStyle {
+    /// Create the default style: black circles on white, straight edges.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the node shape.
+    pub fn node_shape(mut self, shape: NodeShape) -> Self {
+        self.node_shape = shape;
+        self
+    }
+
+    /// Set the fill and stroke colors of the nodes.
+    pub fn node_colors(mut self, fill: &str, stroke: &str) -> Self {
+        self.node_fill = fill.to_owned();
+        self.node_stroke = stroke.to_owned();
+        self
+    }
+
+    /// Set the font family and size of the labels.
+    pub fn font(mut self, family: &str, size: u32) -> Self {
+        self.font_family = family.to_owned();
+        self.font_size = size.max(1);
+        self
+    }
+
+    /// Set the color of the labels.
+    pub fn text_fill(mut self, color: &str) -> Self {
+        self.text_fill = color.to_owned();
+        self
+    }
+
+    /// Set the edge shape.
+    pub fn edge_shape(mut self, shape: EdgeShape) -> Self {
+        self.edge_shape = shape;
+        self
+    }
+
+    /// Set the color and width of the edges.
+    pub fn edge_stroke(mut self, color: &str, width: u32) -> Self {
+        self.edge_stroke = color.to_owned();
+        self.edge_width = width;
+        self
+    }
+
+    /// Set the dash pattern of the edges, such as `"4 2"`.
+    pub fn edge_dash(mut self, pattern: Option<&str>) -> Self {
+        self.edge_dash = pattern.map(str::to_owned);
+        self
+    }
+
+    /// Set the space between a label and its shape.
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Set the horizontal space between subtrees and the vertical space
+    /// between levels.
+    pub fn gaps(mut self, sibling: u32, level: u32) -> Self {
+        self.sibling_gap = sibling;
+        self.level_gap = level;
+        self
+    }
+
+    /// Set the space around the drawing.
+    pub fn margin(mut self, margin: u32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Get the size of the shape around a label `columns` wide in a
+    /// terminal, guessing every column to be 0.6 em wide.
+    fn node_size(&self, columns: usize) -> (i64, i64) {
+        let font = i64::from(self.font_size);
+        let padding = 2 * i64::from(self.padding);
+        let width = (columns as i64 * font * 3 + 4) / 5 + padding;
+        let height = font + padding;
+        match self.node_shape {
+            NodeShape::Circle => (width.max(height), width.max(height)),
+            _ => (width, height),
+        }
+    }
+
+    fn render(&self, diagram: &Diagram) -> String {
+        let sizes: Vec<_> = diagram
+            .labels
+            .iter()
+            .map(|label| self.node_size(str_width(label)))
+            .collect();
+        let widths: Vec<_> = sizes.iter().map(|&(width, _)| width).collect();
+        let row = sizes.iter().map(|&(_, height)| height).max().unwrap_or(0);
+        let layout = layout::tidy(&diagram.children, &widths, i64::from(self.sibling_gap));
+
+        let margin = i64::from(self.margin);
+        let level_gap = i64::from(self.level_gap);
+        let levels = layout.height as i64;
+        let width = layout.width + 2 * margin;
+        let height = levels * row + (levels - 1).max(0) * level_gap + 2 * margin;
+        let center = |idx: usize| {
+            let x = margin + layout.x[idx];
+            let y = margin + layout.depth[idx] as i64 * (row + level_gap) + row / 2;
+            (x, y)
+        };
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height,
+        )
+        .unwrap();
+
+        write!(
+            out,
+            r#"<g fill="none" stroke="{}" stroke-width="{}""#,
+            escape(&self.edge_stroke),
+            self.edge_width,
+        )
+        .unwrap();
+        if let Some(dash) = &self.edge_dash {
+            write!(out, r#" stroke-dasharray="{}""#, escape(dash)).unwrap();
+        }
+        out.push_str(">\n");
+        for (parent, child, _) in diagram.edges() {
+            let (x1, y1) = center(parent);
+            let (x2, y2) = center(child);
+            let (y1, y2) = (y1 + sizes[parent].1 / 2, y2 - sizes[child].1 / 2);
+            let middle = (y1 + y2) / 2;
+            match self.edge_shape {
+                EdgeShape::Straight => writeln!(
+                    out,
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                    x1, y1, x2, y2
+                ),
+                EdgeShape::Curved => writeln!(
+                    out,
+                    r#"<path d="M{} {} C{} {} {} {} {} {}"/>"#,
+                    x1, y1, x1, middle, x2, middle, x2, y2
+                ),
+                EdgeShape::Elbow => writeln!(
+                    out,
+                    r#"<path d="M{} {} V{} H{} V{}"/>"#,
+                    x1, y1, middle, x2, y2
+                ),
+            }
+            .unwrap();
+        }
+        out.push_str("</g>\n");
+
+        writeln!(
+            out,
+            r#"<g font-family="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+            escape(&self.font_family),
+            self.font_size,
+        )
+        .unwrap();
+        let paint = format!(
+            r#"fill="{}" stroke="{}""#,
+            escape(&self.node_fill),
+            escape(&self.node_stroke),
+        );
+        for (idx, label) in diagram.labels.iter().enumerate() {
+            let (x, y) = center(idx);
+            let (w, h) = sizes[idx];
+            match self.node_shape {
+                NodeShape::Circle => writeln!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+                    x,
+                    y,
+                    w / 2,
+                    paint
+                ),
+                NodeShape::Rect | NodeShape::RoundedRect => {
+                    let radius = if self.node_shape == NodeShape::RoundedRect {
+                        h / 4
+                    } else {
+                        0
+                    };
+                    writeln!(
+                        out,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {}/>"#,
+                        x - w / 2,
+                        y - h / 2,
+                        w,
+                        h,
+                        radius,
+                        paint
+                    )
+                }
+                NodeShape::Plain => Ok(()),
+            }
+            .unwrap();
+            writeln!(
+                out,
+                r#"<text x="{}" y="{}" fill="{}">{}</text>"#,
+                x,
+                y,
+                escape(&self.text_fill),
+                escape(label)
+            )
+            .unwrap();
+        }
+        out.push_str("</g>\n</svg>\n");
+        out
+    }
+}
+
+/// Escape text for XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl<T: Display> BinaryNode<T> {
+    /// Render the tree as an SVG document laid out with the
+    /// Reingold–Tilford algorithm, a lone child being kept on its side.
+    pub fn to_svg(&self, style: &SvgStyle) -> String {
+        style.render(&Diagram::binary(self))
+    }
+}
+
+impl<T: Display> Node<T> {
+    /// Render the tree as an SVG document laid out with the
+    /// Reingold–Tilford algorithm.
+    pub fn to_svg(&self, style: &SvgStyle) -> String {
+        style.render(&Diagram::n_ary(self))
+    }
+}
@@ -0,0 +1,130 @@
+use std::iter::FusedIterator;
+use std::mem;
+
+/// Merge sorted iterators into one sorted iterator through a loser tree.
+///
+/// Each item costs `O(log k)` comparisons for `k` iterators, against
+/// `O(k)` for a linear scan of the heads. Equal items come in the order of
+/// their iterators, so the merge is stable. The iterators are expected to
+/// be sorted; otherwise the output is not, but every item still comes out.
+pub fn kway_merge<I>(iters: I) -> KWayMerge<<I::Item as IntoIterator>::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: Ord,
+{
+    KWayMerge::new(iters.into_iter().map(IntoIterator::into_iter).collect())
+}
+
+/// Iterator returned by [`kway_merge`].
+///
+/// The sources are the leaves of a complete binary tree stored as an array,
+/// leaf `i` at `k + i`. Every inner node keeps the loser of the match
+/// played there, and the overall winner is kept apart, so after the winner
+/// is taken only the matches on the path from its leaf are replayed.
+#[derive(Debug, Clone)]
+pub struct KWayMerge<I: Iterator> {
+    sources: Vec<I>,
+    heads: Vec<Option<I::Item>>,
+    // `losers[0]` is the winner, `losers[n]` the loser at inner node `n`.
+    losers: Vec<usize>,
+}
+
+impl<I> KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+    fn new(mut sources: Vec<I>) -> Self {
+        let heads = sources.iter_mut().map(Iterator::next).collect();
+        let k = sources.len();
+        let mut merge = Self {
+            sources,
+            heads,
+            losers: vec![0; k.max(1)],
+        };
+        // Play every match bottom-up, keeping the winners aside.
+        let mut winners = vec![0; 2 * k];
+        for (leaf, winner) in winners[k..].iter_mut().enumerate() {
+            *winner = leaf;
+        }
+        for node in (1..k).rev() {
+            let (left, right) = (winners[2 * node], winners[2 * node + 1]);
+            let (winner, loser) = if merge.beats(right, left) {
+                (right, left)
+            } else {
+                (left, right)
+            };
+            winners[node] = winner;
+            merge.losers[node] = loser;
+        }
+        if k > 1 {
+            merge.losers[0] = winners[1];
+        }
+        merge
+    }
+
+    /// Return `true` if the head of source `a` comes before the head of
+    /// source `b`, an exhausted source losing every match.
+    fn beats(&self, a: usize, b: usize) -> bool {
+        match (&self.heads[a], &self.heads[b]) {
+            (Some(x), Some(y)) => x < y || (x == y && a < b),
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Get the number of sources.
+    pub fn sources(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Get the ref of the next item without taking it.
+    pub fn peek(&self) -> Option<&I::Item> {
+        self.heads.get(self.losers[0])?.as_ref()
+    }
+}
+
+impl<I> Iterator for KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut winner = self.losers[0];
+        let item = self.heads.get_mut(winner)?.take()?;
+        self.heads[winner] = self.sources[winner].next();
+        // Replay the matches from the leaf of the winner up to the root.
+        let mut node = (winner + self.sources.len()) / 2;
+        while node > 0 {
+            if self.beats(self.losers[node], winner) {
+                mem::swap(&mut self.losers[node], &mut winner);
+            }
+            node /= 2;
+        }
+        self.losers[0] = winner;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let heads = self.heads.iter().flatten().count();
+        self.sources.iter().map(Iterator::size_hint).fold(
+            (heads, Some(heads)),
+            |(low, high), (more_low, more_high)| {
+                (
+                    low.saturating_add(more_low),
+                    high.zip(more_high).and_then(|(a, b)| a.checked_add(b)),
+                )
+            },
+        )
+    }
+}
+
+impl<I> FusedIterator for KWayMerge<I>
+where
+    I: Iterator,
+    I::Item: Ord,
+{
+}
@@ -0,0 +1,368 @@
+use crate::{Error, Result};
+
+/// Index of a node in an [`Arena`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone)]
+struct ArenaNode<T> {
+    data: T,
+    parent: Option<NodeId>,
+    first_child: Option<NodeId>,
+    last_child: Option<NodeId>,
+    prev_sibling: Option<NodeId>,
+    next_sibling: Option<NodeId>,
+}
+
+/// Arena of rose tree nodes.
+///
+/// Children are kept in a doubly linked sibling list, so inserting next to
+/// a sibling, appending, prepending and detaching are `O(1)` apart from
+/// the `O(depth)` cycle check. Ids of removed nodes are never reused.
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    nodes: Vec<Option<ArenaNode<T>>>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Arena<T> {
+    /// Create an empty arena.
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Get the number of live nodes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no live nodes.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Create a detached node, returning its id.
+    pub fn new_node(&mut self, data: T) -> NodeId {
+        self.nodes.push(Some(ArenaNode {
+            data,
+            parent: None,
+            first_child: None,
+            last_child: None,
+            prev_sibling: None,
+            next_sibling: None,
+        }));
+        self.len += 1;
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Return `true` if `id` refers to a live node.
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_ok()
+    }
+
+    /// Get the ref of the data of a node.
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.node(id).ok().map(|node| &node.data)
+    }
+
+    /// Get the mutable ref of the data of a node.
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.node_mut(id).ok().map(|node| &mut node.data)
+    }
+
+    /// Get the parent of a node.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.parent
+    }
+
+    /// Get the first child of a node.
+    pub fn first_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.first_child
+    }
+
+    /// Get the last child of a node.
+    pub fn last_child(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.last_child
+    }
+
+    /// Get the previous sibling of a node.
+    pub fn prev_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.prev_sibling
+    }
+
+    /// Get the next sibling of a node.
+    pub fn next_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).ok()?.next_sibling
+    }
+
+    /// Create an iterator over the children of a node.
+    pub fn children(&self, id: NodeId) -> Children<'_, T> {
+        Children {
+            arena: self,
+            next: self.first_child(id),
+        }
+    }
+
+    /// Create an iterator over the ancestors of a node, parent first.
+    pub fn ancestors(&self, id: NodeId) -> Ancestors<'_, T> {
+        Ancestors {
+            arena: self,
+            next: self.parent(id),
+        }
+    }
+
+    /// Create a pre order iterator over the subtree of a node,
+    /// the node itself included.
+    pub fn descendants(&self, id: NodeId) -> Descendants<'_, T> {
+        Descendants {
+            arena: self,
+            root: id,
+            next: self.node(id).ok().map(|_| id),
+        }
+    }
+
+    /// Append `child` as the last child of `parent`,
+    /// detaching it from its current position first.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `child` is `parent` or one of its ancestors.
+    pub fn append(&mut self, parent: NodeId, child: NodeId) -> Result<()> {
+        self.prepare(parent, child)?;
+        let last = self.node(parent)?.last_child;
+        self.link(Some(parent), last, child, None);
+        Ok(())
+    }
+
+    /// Prepend `child` as the first child of `parent`,
+    /// detaching it from its current position first.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `child` is `parent` or one of its ancestors.
+    pub fn prepend(&mut self, parent: NodeId, child: NodeId) -> Result<()> {
+        self.prepare(parent, child)?;
+        let first = self.node(parent)?.first_child;
+        self.link(Some(parent), None, child, first);
+        Ok(())
+    }
+
+    /// Insert `node` right before `sibling`,
+    /// detaching it from its current position first.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `node` is `sibling` or one of its ancestors.
+    pub fn insert_before(&mut self, sibling: NodeId, node: NodeId) -> Result<()> {
+        self.prepare(sibling, node)?;
+        let target = self.node(sibling)?;
+        let (parent, prev) = (target.parent, target.prev_sibling);
+        self.link(parent, prev, node, Some(sibling));
+        Ok(())
+    }
+
+    /// Insert `node` right after `sibling`,
+    /// detaching it from its current position first.
+    /// # Errors
+    /// Return `RemovedNode` Error when either node has been removed, and
+    /// `WouldCycle` Error when `node` is `sibling` or one of its ancestors.
+    pub fn insert_after(&mut self, sibling: NodeId, node: NodeId) -> Result<()> {
+        self.prepare(sibling, node)?;
+        let target = self.node(sibling)?;
+        let (parent, next) = (target.parent, target.next_sibling);
+        self.link(parent, Some(sibling), node, next);
+        Ok(())
+    }
+
+    /// Unlink a node, with its subtree, from its parent and siblings.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn detach(&mut self, id: NodeId) -> Result<()> {
+        let node = self.node_mut(id)?;
+        let parent = node.parent.take();
+        let prev = node.prev_sibling.take();
+        let next = node.next_sibling.take();
+        match prev {
+            Some(prev) => self.node_mut(prev)?.next_sibling = next,
+            None => {
+                if let Some(parent) = parent {
+                    self.node_mut(parent)?.first_child = next;
+                }
+            }
+        }
+        match next {
+            Some(next) => self.node_mut(next)?.prev_sibling = prev,
+            None => {
+                if let Some(parent) = parent {
+                    self.node_mut(parent)?.last_child = prev;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Detach a node and remove it with its whole subtree,
+    /// returning its data.
+    /// # Errors
+    /// Return `RemovedNode` Error when the node has been removed.
+    pub fn remove(&mut self, id: NodeId) -> Result<T> {
+        self.detach(id)?;
+        let mut stack = vec![id];
+        let mut data = None;
+        while let Some(current) = stack.pop() {
+            let node = self.nodes[current.0]
+                .take()
+                .expect("subtrees only hold live nodes");
+            self.len -= 1;
+            let mut child = node.first_child;
+            while let Some(id) = child {
+                stack.push(id);
+                child = self.node(id)?.next_sibling;
+            }
+            if current == id {
+                data = Some(node.data);
+            }
+        }
+        Ok(data.expect("the removed node is visited first"))
+    }
+
+    fn node(&self, id: NodeId) -> Result<&ArenaNode<T>> {
+        self.nodes
+            .get(id.0)
+            .and_then(Option::as_ref)
+            .ok_or(Error::RemovedNode)
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Result<&mut ArenaNode<T>> {
+        self.nodes
+            .get_mut(id.0)
+            .and_then(Option::as_mut)
+            .ok_or(Error::RemovedNode)
+    }
+
+    /// Check that `node` may be moved next to or under `target`,
+    /// then detach it.
+    fn prepare(&mut self, target: NodeId, node: NodeId) -> Result<()> {
+        self.node(target)?;
+        self.node(node)?;
+        if target == node || self.ancestors(target).any(|id| id == node) {
+            return Err(Error::WouldCycle);
+        }
+        self.detach(node)
+    }
+
+    /// Link a detached node between two siblings under `parent`.
+    fn link(
+        &mut self,
+        parent: Option<NodeId>,
+        prev: Option<NodeId>,
+        id: NodeId,
+        next: Option<NodeId>,
+    ) {
+        let node = self.nodes[id.0].as_mut().expect("linked nodes are live");
+        node.parent = parent;
+        node.prev_sibling = prev;
+        node.next_sibling = next;
+        match prev {
+            Some(prev) => {
+                self.nodes[prev.0]
+                    .as_mut()
+                    .expect("siblings are live")
+                    .next_sibling = Some(id)
+            }
+            None => {
+                if let Some(parent) = parent {
+                    self.nodes[parent.0]
+                        .as_mut()
+                        .expect("parents are live")
+                        .first_child = Some(id);
+                }
+            }
+        }
+        match next {
+            Some(next) => {
+                self.nodes[next.0]
+                    .as_mut()
+                    .expect("siblings are live")
+                    .prev_sibling = Some(id)
+            }
+            None => {
+                if let Some(parent) = parent {
+                    self.nodes[parent.0]
+                        .as_mut()
+                        .expect("parents are live")
+                        .last_child = Some(id);
+                }
+            }
+        }
+    }
+}
+
+/// Iterator over the children of a node.
+#[derive(Debug)]
+pub struct Children<'a, T> {
+    arena: &'a Arena<T>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Children<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next?;
+        self.next = self.arena.next_sibling(id);
+        Some(id)
+    }
+}
+
+/// Iterator over the ancestors of a node.
+#[derive(Debug)]
+pub struct Ancestors<'a, T> {
+    arena: &'a Arena<T>,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Ancestors<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next?;
+        self.next = self.arena.parent(id);
+        Some(id)
+    }
+}
+
+/// Pre order iterator over a subtree.
+#[derive(Debug)]
+pub struct Descendants<'a, T> {
+    arena: &'a Arena<T>,
+    root: NodeId,
+    next: Option<NodeId>,
+}
+
+impl<'a, T> Iterator for Descendants<'a, T> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.next?;
+        self.next = self.arena.first_child(id).or_else(|| {
+            let mut current = id;
+            loop {
+                if current == self.root {
+                    return None;
+                }
+                if let Some(next) = self.arena.next_sibling(current) {
+                    return Some(next);
+                }
+                current = self.arena.parent(current)?;
+            }
+        });
+        Some(id)
+    }
+}
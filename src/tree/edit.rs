@@ -0,0 +1,240 @@
+use super::Node;
+use crate::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Costs of the operations turning one tree into another.
+pub trait EditCosts<T> {
+    /// Cost of inserting a node holding `data`.
+    fn insert(&self, data: &T) -> usize;
+
+    /// Cost of deleting a node holding `data`, its children taking its
+    /// place.
+    fn delete(&self, data: &T) -> usize;
+
+    /// Cost of replacing the data `from` of a node by `to`.
+    fn rename(&self, from: &T, to: &T) -> usize;
+}
+
+/// Costs of one for every insertion, deletion and change of data, and of
+/// zero for keeping equal data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UnitCosts;
+
+impl<T: PartialEq> EditCosts<T> for UnitCosts {
+    fn insert(&self, _data: &T) -> usize {
+        1
+    }
+
+    fn delete(&self, _data: &T) -> usize {
+        1
+    }
+
+    fn rename(&self, from: &T, to: &T) -> usize {
+        usize::from(from != to)
+    }
+}
+
+/// Nodes of a tree numbered in post order.
+struct PostOrder<'a, T> {
+    data: Vec<&'a T>,
+    /// Leftmost leaf of the subtree of every node.
+    leftmost: Vec<usize>,
+    /// Nodes with a left sibling, and the root, in increasing order.
+    keyroots: Vec<usize>,
+}
+
+impl<'a, T> PostOrder<'a, T> {
+    fn new(root: &'a Node<T>) -> Self {
+        let mut data = Vec::new();
+        let mut leftmost = Vec::new();
+        // Sizes of the subtrees whose parent is not numbered yet.
+        let mut sizes: Vec<usize> = Vec::new();
+        let mut stack = vec![(root, false)];
+        while let Some((node, expanded)) = stack.pop() {
+            if !expanded {
+                stack.push((node, true));
+                stack.extend(node.children().iter().rev().map(|child| (child, false)));
+                continue;
+            }
+            let children = sizes.len() - node.children().len();
+            let size = sizes.drain(children..).sum::<usize>() + 1;
+            sizes.push(size);
+            leftmost.push(data.len() + 1 - size);
+            data.push(node.data());
+        }
+        // The keyroot of a leftmost leaf is the highest node reaching it.
+        let mut highest = BTreeMap::new();
+        for (idx, &leaf) in leftmost.iter().enumerate() {
+            highest.insert(leaf, idx);
+        }
+        let mut keyroots: Vec<usize> = highest.into_values().collect();
+        keyroots.sort_unstable();
+        Self {
+            data,
+            leftmost,
+            keyroots,
+        }
+    }
+}
+
+/// Compute the edit distance between two ordered trees with the
+/// Zhang–Shasha algorithm, without recursion.
+///
+/// The distance is the least total cost of inserting, deleting and
+/// renaming nodes to turn `a` into `b`, where deleting a node puts its
+/// children in its place and inserting one adopts a run of consecutive
+/// siblings. It takes `O(n m)` space and at most `O(n² m²)` time for trees
+/// of `n` and `m` nodes, much less for balanced trees.
+pub fn edit_distance<T, C>(a: &Node<T>, b: &Node<T>, costs: &C) -> usize
+where
+    C: EditCosts<T>,
+{
+    let a = PostOrder::new(a);
+    let b = PostOrder::new(b);
+    let (n, m) = (a.data.len(), b.data.len());
+    let mut trees = vec![vec![0; m]; n];
+    let mut forests = vec![vec![0; m + 1]; n + 1];
+    for &i in &a.keyroots {
+        for &j in &b.keyroots {
+            let (li, lj) = (a.leftmost[i], b.leftmost[j]);
+            // `forests[x][y]` is the distance between the forests made of
+            // the first `x` nodes from `li` and the first `y` from `lj`.
+            forests[0][0] = 0;
+            for x in 1..=i - li + 1 {
+                forests[x][0] = forests[x - 1][0] + costs.delete(a.data[li + x - 1]);
+            }
+            for y in 1..=j - lj + 1 {
+                forests[0][y] = forests[0][y - 1] + costs.insert(b.data[lj + y - 1]);
+            }
+            for x in 1..=i - li + 1 {
+                let i1 = li + x - 1;
+                for y in 1..=j - lj + 1 {
+                    let j1 = lj + y - 1;
+                    let delete = forests[x - 1][y] + costs.delete(a.data[i1]);
+                    let insert = forests[x][y - 1] + costs.insert(b.data[j1]);
+                    if a.leftmost[i1] == li && b.leftmost[j1] == lj {
+                        let rename = forests[x - 1][y - 1] + costs.rename(a.data[i1], b.data[j1]);
+                        forests[x][y] = delete.min(insert).min(rename);
+                        trees[i1][j1] = forests[x][y];
+                    } else {
+                        let (p, q) = (a.leftmost[i1] - li, b.leftmost[j1] - lj);
+                        let replace = forests[p][q] + trees[i1][j1];
+                        forests[x][y] = delete.min(insert).min(replace);
+                    }
+                }
+            }
+        }
+    }
+    trees[n - 1][m - 1]
+}
+
+/// Operation of a patch, the node it applies to being given by the
+/// indices of the children to follow from the root.
+#[derive(Debug, Clone)]
+pub enum Edit<T> {
+    /// Replace the data of the node at `path`.
+    Replace {
+        /// Path of the node.
+        path: Vec<usize>,
+        /// The new data.
+        data: T,
+    },
+    /// Remove the subtree at `path`.
+    Delete {
+        /// Path of the root of the subtree.
+        path: Vec<usize>,
+    },
+    /// Insert a subtree so that its root ends up at `path`, shifting the
+    /// later siblings.
+    Insert {
+        /// Path of the root of the subtree once inserted.
+        path: Vec<usize>,
+        /// The subtree.
+        node: Node<T>,
+    },
+}
+
+/// Compute the edits turning `a` into `b`, without recursion.
+///
+/// Children are matched by position: the data of matched nodes is
+/// replaced where it differs, surplus children of `a` are deleted and
+/// surplus children of `b` are inserted. Applying the edits in order to
+/// `a` with [`apply_patch`] gives `b`.
+pub fn diff<T>(a: &Node<T>, b: &Node<T>) -> Vec<Edit<T>>
+where
+    T: PartialEq + Clone,
+{
+    let mut edits = Vec::new();
+    let mut stack = vec![(Vec::new(), a, b)];
+    while let Some((path, a, b)) = stack.pop() {
+        let child_path = |idx: usize| {
+            let mut child = path.clone();
+            child.push(idx);
+            child
+        };
+        if a.data() != b.data() {
+            edits.push(Edit::Replace {
+                path: path.clone(),
+                data: b.data().clone(),
+            });
+        }
+        // Edits only touch the children past the matched ones, so the
+        // paths into the matched subtrees stay valid.
+        let common = a.children().len().min(b.children().len());
+        for idx in (common..a.children().len()).rev() {
+            edits.push(Edit::Delete {
+                path: child_path(idx),
+            });
+        }
+        for (idx, node) in b.children().iter().enumerate().skip(common) {
+            edits.push(Edit::Insert {
+                path: child_path(idx),
+                node: node.clone(),
+            });
+        }
+        for idx in (0..common).rev() {
+            stack.push((child_path(idx), &a.children()[idx], &b.children()[idx]));
+        }
+    }
+    edits
+}
+
+/// Apply edits to a tree in order, such as the ones produced by [`diff`].
+/// # Errors
+/// Return `InvalidPath` Error when an edit refers to no node, or deletes or
+/// inserts the root. The edits before it are applied.
+pub fn apply_patch<T, I>(node: &mut Node<T>, edits: I) -> Result<()>
+where
+    I: IntoIterator<Item = Edit<T>>,
+{
+    for edit in edits {
+        match edit {
+            Edit::Replace { path, data } => {
+                *node.get_mut(&path).ok_or(Error::InvalidPath)?.data_mut() = data
+            }
+            Edit::Delete { path } => {
+                let (&idx, parent) = path.split_last().ok_or(Error::InvalidPath)?;
+                let children = node
+                    .get_mut(parent)
+                    .ok_or(Error::InvalidPath)?
+                    .children_mut();
+                if idx >= children.len() {
+                    return Err(Error::InvalidPath);
+                }
+                children.remove(idx);
+            }
+            Edit::Insert { path, node: child } => {
+                let (&idx, parent) = path.split_last().ok_or(Error::InvalidPath)?;
+                let children = node
+                    .get_mut(parent)
+                    .ok_or(Error::InvalidPath)?
+                    .children_mut();
+                if idx > children.len() {
+                    return Err(Error::InvalidPath);
+                }
+                children.insert(idx, child);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use super::iter::PreOrderIter;
+use super::{lcrs, Node};
+use crate::binary_tree::Node as BinaryNode;
+use crate::{Error, Result};
+use std::iter::FromIterator;
+use std::{slice, vec};
+
+/// Ordered collection of n-ary trees.
+#[derive(Debug, Clone)]
+pub struct Forest<T> {
+    trees: Vec<Node<T>>,
+}
+
+impl<T> Default for Forest<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Forest<T> {
+    /// Create an empty forest.
+    pub fn new() -> Self {
+        Self { trees: Vec::new() }
+    }
+
+    /// Get the number of trees.
+    pub fn len(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Return `true` if there are no trees.
+    pub fn is_empty(&self) -> bool {
+        self.trees.is_empty()
+    }
+
+    /// Get the number of nodes across all trees.
+    pub fn node_count(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Get the refs of the trees.
+    pub fn trees(&self) -> &[Node<T>] {
+        &self.trees
+    }
+
+    /// Get the mutable ref of the trees.
+    pub fn trees_mut(&mut self) -> &mut Vec<Node<T>> {
+        &mut self.trees
+    }
+
+    /// Add a tree at the end.
+    pub fn push(&mut self, tree: Node<T>) {
+        self.trees.push(tree);
+    }
+
+    /// Move all trees of `other` to the end, leaving it empty.
+    pub fn append(&mut self, other: &mut Forest<T>) {
+        self.trees.append(&mut other.trees);
+    }
+
+    /// Split off the trees from `at` onwards.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `at` is greater than the number
+    /// of trees.
+    pub fn split_off(&mut self, at: usize) -> Result<Forest<T>> {
+        self.check(at, self.trees.len() + 1)?;
+        Ok(Forest {
+            trees: self.trees.split_off(at),
+        })
+    }
+
+    /// Merge two trees by making the root of tree `child` the last child of
+    /// the root of tree `parent`, removing it from the forest.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when either index is out of range,
+    /// and `WouldCycle` Error when they are equal.
+    pub fn join(&mut self, parent: usize, child: usize) -> Result<()> {
+        self.check(parent, self.trees.len())?;
+        self.check(child, self.trees.len())?;
+        if parent == child {
+            return Err(Error::WouldCycle);
+        }
+        let tree = self.trees.remove(child);
+        let parent = if child < parent { parent - 1 } else { parent };
+        self.trees[parent].push_child(tree);
+        Ok(())
+    }
+
+    /// Split tree `index` by removing its root, putting the subtrees of
+    /// the root in its place. Return the data of the removed root.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `index` is out of range.
+    pub fn split_root(&mut self, index: usize) -> Result<T> {
+        self.check(index, self.trees.len())?;
+        let (data, children) = self.trees.remove(index).into_parts();
+        self.trees.splice(index..index, children);
+        Ok(data)
+    }
+
+    /// Create a pre order iterator over all trees in turn.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            trees: self.trees.iter(),
+            current: None,
+        }
+    }
+
+    /// Encode the forest as a binary tree in left-child right-sibling
+    /// encoding. Return `None` if the forest is empty.
+    pub fn into_binary(self) -> Option<BinaryNode<T>> {
+        lcrs::forest_into_binary(self.trees)
+    }
+
+    fn check(&self, index: usize, bound: usize) -> Result<()> {
+        if index < bound {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfRange {
+                index,
+                len: self.trees.len(),
+            })
+        }
+    }
+}
+
+impl<T> From<Vec<Node<T>>> for Forest<T> {
+    fn from(trees: Vec<Node<T>>) -> Self {
+        Self { trees }
+    }
+}
+
+impl<T> From<BinaryNode<T>> for Forest<T> {
+    /// Decode a binary tree in left-child right-sibling encoding.
+    fn from(node: BinaryNode<T>) -> Self {
+        Self {
+            trees: lcrs::binary_into_forest(node),
+        }
+    }
+}
+
+impl<T> FromIterator<Node<T>> for Forest<T> {
+    fn from_iter<I: IntoIterator<Item = Node<T>>>(iter: I) -> Self {
+        Self {
+            trees: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<Node<T>> for Forest<T> {
+    fn extend<I: IntoIterator<Item = Node<T>>>(&mut self, iter: I) {
+        self.trees.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for Forest<T> {
+    type Item = Node<T>;
+    type IntoIter = vec::IntoIter<Node<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.trees.into_iter()
+    }
+}
+
+/// Pre order iterator over all trees of a forest.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    trees: slice::Iter<'a, Node<T>>,
+    current: Option<PreOrderIter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(data) = self.current.as_mut().and_then(Iterator::next) {
+                return Some(data);
+            }
+            self.current = Some(self.trees.next()?.pre_order_iter());
+        }
+    }
+}
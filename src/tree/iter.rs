@@ -0,0 +1,122 @@
+use super::Node;
+use std::collections::VecDeque;
+use std::slice;
+
+/// Pre order traverse iterator.
+#[derive(Debug)]
+pub struct PreOrderIter<'a, T> {
+    stack: Vec<&'a Node<T>>,
+}
+
+impl<'a, T> PreOrderIter<'a, T> {
+    /// Create a pre order traverse iter.
+    pub fn new(node: &'a Node<T>) -> Self {
+        Self { stack: vec![node] }
+    }
+}
+
+impl<'a, T> Iterator for PreOrderIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children.iter().rev());
+        Some(&node.data)
+    }
+}
+
+/// Post order traverse iterator.
+#[derive(Debug)]
+pub struct PostOrderIter<'a, T> {
+    stack: Vec<(&'a Node<T>, slice::Iter<'a, Node<T>>)>,
+}
+
+impl<'a, T> PostOrderIter<'a, T> {
+    /// Create a post order traverse iter.
+    pub fn new(node: &'a Node<T>) -> Self {
+        Self {
+            stack: vec![(node, node.children.iter())],
+        }
+    }
+}
+
+impl<'a, T> Iterator for PostOrderIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, children) = self.stack.last_mut()?;
+            match children.next() {
+                Some(child) => self.stack.push((child, child.children.iter())),
+                None => {
+                    let data = &node.data;
+                    self.stack.pop();
+                    return Some(data);
+                }
+            }
+        }
+    }
+}
+
+/// Level order traverse iterator.
+#[derive(Debug)]
+pub struct LevelOrderIter<'a, T> {
+    queue: VecDeque<(usize, &'a Node<T>)>,
+}
+
+impl<'a, T> LevelOrderIter<'a, T> {
+    /// Create a level order traverse iter.
+    pub fn new(node: &'a Node<T>) -> Self {
+        let mut queue = VecDeque::new();
+        queue.push_back((0, node));
+        Self { queue }
+    }
+
+    /// Return the level in the tree of the next item
+    /// returned by `next`.
+    pub fn level(&self) -> usize {
+        self.queue.front().map_or(0, |(level, _)| *level)
+    }
+}
+
+impl<'a, T> Iterator for LevelOrderIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (level, node) = self.queue.pop_front()?;
+        self.queue
+            .extend(node.children.iter().map(|child| (level + 1, child)));
+        Some((level, &node.data))
+    }
+}
+
+/// Pre order iterator that does not descend below a depth.
+#[derive(Debug)]
+pub struct DepthLimitedIter<'a, T> {
+    stack: Vec<(usize, &'a Node<T>)>,
+    max_depth: usize,
+}
+
+impl<'a, T> DepthLimitedIter<'a, T> {
+    /// Create a pre order iterator over the nodes of the tree rooted at
+    /// `node` at most `max_depth` levels below it.
+    pub fn new(node: &'a Node<T>, max_depth: usize) -> Self {
+        Self {
+            stack: vec![(0, node)],
+            max_depth,
+        }
+    }
+}
+
+impl<'a, T> Iterator for DepthLimitedIter<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, node) = self.stack.pop()?;
+        if depth < self.max_depth {
+            self.stack
+                .extend(node.children.iter().rev().map(|child| (depth + 1, child)));
+        }
+        Some((depth, &node.data))
+    }
+}
@@ -0,0 +1,104 @@
+use super::Node;
+use crate::binary_tree::Node as BinaryNode;
+use crate::{Error, Result};
+use std::convert::TryFrom;
+
+/// Encode a forest as a binary tree, the first child of every node becoming
+/// its left child and its next sibling its right child.
+/// Return `None` if the forest is empty.
+pub fn forest_into_binary<T>(roots: Vec<Node<T>>) -> Option<BinaryNode<T>> {
+    // Flatten in pre order so children come after their parent.
+    let mut items = Vec::new();
+    let mut children: Vec<Vec<usize>> = Vec::new();
+    let mut stack: Vec<(Option<usize>, Node<T>)> =
+        roots.into_iter().rev().map(|root| (None, root)).collect();
+    let mut top = Vec::new();
+    while let Some((parent, node)) = stack.pop() {
+        let idx = items.len();
+        match parent {
+            Some(parent) => children[parent].push(idx),
+            None => top.push(idx),
+        }
+        let (data, kids) = node.into_parts();
+        items.push(Some(data));
+        children.push(Vec::new());
+        stack.extend(kids.into_iter().rev().map(|kid| (Some(idx), kid)));
+    }
+    let mut built: Vec<Option<BinaryNode<T>>> = items.iter().map(|_| None).collect();
+    let chain = |built: &mut Vec<Option<BinaryNode<T>>>, siblings: &[usize]| {
+        let mut next = None;
+        for &sibling in siblings.iter().rev() {
+            let mut node = built[sibling].take().expect("every node is built once");
+            node.set_right(next);
+            next = Some(node);
+        }
+        next
+    };
+    for idx in (0..items.len()).rev() {
+        let mut node = BinaryNode::new(items[idx].take().expect("every item is used once"));
+        node.set_left(chain(&mut built, &children[idx]));
+        built[idx] = Some(node);
+    }
+    chain(&mut built, &top)
+}
+
+/// Decode a binary tree in left-child right-sibling encoding into a forest.
+pub fn binary_into_forest<T>(root: BinaryNode<T>) -> Vec<Node<T>> {
+    // Flatten in pre order so children come after their parent.
+    let mut items = Vec::new();
+    let mut first = Vec::new();
+    let mut next = Vec::new();
+    let mut stack = vec![(None, root)];
+    while let Some((link, mut node)) = stack.pop() {
+        let idx = items.len();
+        if let Some((from, is_child)) = link {
+            let slot: &mut Vec<Option<usize>> = if is_child { &mut first } else { &mut next };
+            slot[from] = Some(idx);
+        }
+        let left = node.set_left(None);
+        let right = node.set_right(None);
+        items.push(Some(node.into_data()));
+        first.push(None);
+        next.push(None);
+        stack.extend(right.map(|right| (Some((idx, false)), right)));
+        stack.extend(left.map(|left| (Some((idx, true)), left)));
+    }
+    let mut built: Vec<Option<Node<T>>> = items.iter().map(|_| None).collect();
+    let siblings = |built: &mut Vec<Option<Node<T>>>, mut current: Option<usize>| {
+        let mut nodes = Vec::new();
+        while let Some(idx) = current {
+            nodes.push(built[idx].take().expect("every node is built once"));
+            current = next[idx];
+        }
+        nodes
+    };
+    for idx in (0..items.len()).rev() {
+        let data = items[idx].take().expect("every item is used once");
+        let children = siblings(&mut built, first[idx]);
+        built[idx] = Some(Node { data, children });
+    }
+    siblings(&mut built, Some(0))
+}
+
+impl<T> From<Node<T>> for BinaryNode<T> {
+    fn from(node: Node<T>) -> Self {
+        forest_into_binary(vec![node]).expect("a tree encodes to a non-empty binary tree")
+    }
+}
+
+impl<T> TryFrom<BinaryNode<T>> for Node<T> {
+    type Error = Error;
+
+    /// Decode a binary tree in left-child right-sibling encoding.
+    /// # Errors
+    /// Return `MalformedTree` Error when the root has a right child,
+    /// which encodes a forest rather than a single tree.
+    fn try_from(node: BinaryNode<T>) -> Result<Self> {
+        if node.right().is_some() {
+            return Err(Error::MalformedTree);
+        }
+        Ok(binary_into_forest(node)
+            .pop()
+            .expect("a binary tree decodes to a non-empty forest"))
+    }
+}
@@ -0,0 +1,162 @@
+use super::Node;
+use crate::bitvec::BitVec;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// N-ary tree stored as a level-order unary degree sequence (LOUDS).
+///
+/// Nodes are numbered in level order, the root being `0`. The shape takes
+/// about two bits per node: a leading `10` for a virtual super root, then
+/// for every node as many ones as it has children and a zero. The data
+/// lives in a separate array indexed by node number, so a read-mostly
+/// tree costs little more than its data.
+///
+/// Navigation runs on rank and select over the bits, taking `O(log n)`
+/// for the select, while `data` takes `O(1)`.
+#[derive(Debug, Clone)]
+pub struct Louds<T> {
+    bits: BitVec,
+    data: Vec<T>,
+}
+
+impl<T> Louds<T> {
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Return `true` if the tree has no node, which never happens for a
+    /// tree built from a [`Node`].
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the ref of the data of node `index`.
+    pub fn data(&self, index: usize) -> Option<&T> {
+        self.data.get(index)
+    }
+
+    /// Get the mutable ref of the data of node `index`.
+    pub fn data_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.data.get_mut(index)
+    }
+
+    /// Get the data of all nodes in level order.
+    pub fn data_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Get the parent of node `index`, `None` for the root and for nodes
+    /// out of range.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        let position = self.position(index)?;
+        // The block holding the one of a node follows the zero closing the
+        // block of its parent, the first zero closing the super root.
+        self.bits.rank0(position).checked_sub(1)
+    }
+
+    /// Get the first child of node `index`.
+    pub fn first_child(&self, index: usize) -> Option<usize> {
+        let start = self.children_start(index)?;
+        if self.bits.get(start) {
+            Some(self.bits.rank1(start))
+        } else {
+            None
+        }
+    }
+
+    /// Get the next sibling of node `index`.
+    pub fn next_sibling(&self, index: usize) -> Option<usize> {
+        let position = self.position(index)?;
+        if self.bits.get(position + 1) {
+            Some(index + 1)
+        } else {
+            None
+        }
+    }
+
+    /// Get the number of children of node `index`.
+    pub fn degree(&self, index: usize) -> Option<usize> {
+        Some(self.children(index)?.len())
+    }
+
+    /// Get the children of node `index`, which are numbered consecutively.
+    pub fn children(&self, index: usize) -> Option<Range<usize>> {
+        let start = self.children_start(index)?;
+        let end = self.bits.select(false, index + 1)?;
+        let first = self.bits.rank1(start);
+        Some(first..first + end - start)
+    }
+
+    /// Get the position of the one standing for node `index`.
+    fn position(&self, index: usize) -> Option<usize> {
+        if index < self.len() {
+            self.bits.select(true, index)
+        } else {
+            None
+        }
+    }
+
+    /// Get the position of the block of ones standing for the children of
+    /// node `index`.
+    fn children_start(&self, index: usize) -> Option<usize> {
+        if index < self.len() {
+            Some(self.bits.select(false, index)? + 1)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> From<Node<T>> for Louds<T> {
+    /// Encode a tree, moving the data out in level order.
+    fn from(node: Node<T>) -> Self {
+        let mut bits = vec![true, false];
+        let mut data = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(node);
+        while let Some(node) = queue.pop_front() {
+            let (value, children) = node.into_parts();
+            bits.extend(std::iter::repeat_n(true, children.len()));
+            bits.push(false);
+            queue.extend(children);
+            data.push(value);
+        }
+        Self {
+            bits: BitVec::new(bits.into_iter()),
+            data,
+        }
+    }
+}
+
+impl<T> From<Louds<T>> for Option<Node<T>> {
+    /// Decode the tree, `None` only for an empty encoding.
+    fn from(louds: Louds<T>) -> Self {
+        let len = louds.len();
+        let mut degrees = Vec::with_capacity(len);
+        let mut degree = 0;
+        // Past the super root, every zero closes the block of a node.
+        for position in 2..2 * len + 1 {
+            if louds.bits.get(position) {
+                degree += 1;
+            } else {
+                degrees.push(degree);
+                degree = 0;
+            }
+        }
+        // The children of a node directly precede the children of the next
+        // one, so going backwards their ranges end where the last began.
+        let mut built: Vec<Option<Node<T>>> = (0..len).map(|_| None).collect();
+        let mut end = len;
+        for (idx, data) in louds.data.into_iter().enumerate().rev() {
+            let start = end - degrees[idx];
+            let children = built[start..end]
+                .iter_mut()
+                .map(|child| child.take().expect("children are built first"))
+                .collect();
+            built[idx] = Some(Node { data, children });
+            end = start;
+        }
+        built.into_iter().next().flatten()
+    }
+}
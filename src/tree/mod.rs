@@ -0,0 +1,322 @@
+use crate::datasize::DataSize;
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::mem::{self, ManuallyDrop};
+use std::ptr;
+
+/// N-ary tree iter.
+pub mod iter;
+
+/// Arena-backed rose tree.
+pub mod arena;
+
+/// Left-child right-sibling encoding.
+pub mod lcrs;
+
+/// Forest of n-ary trees.
+pub mod forest;
+
+/// Succinct level-order unary degree sequence encoding.
+pub mod louds;
+
+/// Tree edit distance.
+pub mod edit;
+
+/// N-ary tree node.
+#[derive(Debug)]
+pub struct Node<T> {
+    data: T,
+    children: Vec<Node<T>>,
+}
+
+impl<T> Node<T> {
+    /// Create a node with no children.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            children: Vec::new(),
+        }
+    }
+
+    /// Create a builder.
+    pub fn builder() -> NodeBuilder<T> {
+        NodeBuilder::default()
+    }
+
+    /// Get the ref of the containing data.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Get the mutable ref of the containing data.
+    pub fn data_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Get the refs of the children.
+    pub fn children(&self) -> &[Node<T>] {
+        &self.children
+    }
+
+    /// Get the mutable ref of the children.
+    pub fn children_mut(&mut self) -> &mut Vec<Node<T>> {
+        &mut self.children
+    }
+
+    /// Get the ref of the child at `index`.
+    pub fn child(&self, index: usize) -> Option<&Node<T>> {
+        self.children.get(index)
+    }
+
+    /// Get the mutable ref of the child at `index`.
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut Node<T>> {
+        self.children.get_mut(index)
+    }
+
+    /// Append a child.
+    pub fn push_child(&mut self, node: Node<T>) {
+        self.children.push(node);
+    }
+
+    /// Return `true` if the node has no children.
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Convert into the containing data, dropping the children.
+    pub fn into_data(self) -> T {
+        self.into_parts().0
+    }
+
+    /// Convert into the containing data and the children.
+    pub fn into_parts(mut self) -> (T, Vec<Node<T>>) {
+        let children = mem::take(&mut self.children);
+        let node = ManuallyDrop::new(self);
+        // SAFETY: `node` is never dropped and its children are already
+        // taken, so the data is moved out exactly once and nothing is leaked.
+        (unsafe { ptr::read(&node.data) }, children)
+    }
+
+    /// Create a pre order traverse iterator
+    /// use this node as root.
+    pub fn pre_order_iter(&self) -> iter::PreOrderIter<'_, T> {
+        iter::PreOrderIter::new(self)
+    }
+
+    /// Create a post order traverse iterator
+    /// use this node as root.
+    pub fn post_order_iter(&self) -> iter::PostOrderIter<'_, T> {
+        iter::PostOrderIter::new(self)
+    }
+
+    /// Create a level order traverse iterator
+    /// use this node as root.
+    pub fn level_order_iter(&self) -> iter::LevelOrderIter<'_, T> {
+        iter::LevelOrderIter::new(self)
+    }
+
+    /// Create a pre order iterator over the data with its depth, not
+    /// descending more than `max_depth` levels below this node.
+    pub fn iter_to_depth(&self, max_depth: usize) -> iter::DepthLimitedIter<'_, T> {
+        iter::DepthLimitedIter::new(self, max_depth)
+    }
+
+    /// Copy the first `n` nodes in level order into a new tree, e.g. to
+    /// preview a huge tree, without visiting the others. Return `None` if
+    /// `n` is `0`.
+    pub fn take_nodes(&self, n: usize) -> Option<Node<T>>
+    where
+        T: Clone,
+    {
+        // Copy in level order with parent indices, then build bottom-up.
+        let mut taken: Vec<(T, Option<usize>)> = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back((self, None));
+        while let Some((node, parent)) = queue.pop_front() {
+            if taken.len() == n {
+                break;
+            }
+            let idx = taken.len();
+            taken.push((node.data.clone(), parent));
+            queue.extend(node.children.iter().map(|child| (child, Some(idx))));
+        }
+        // Children come after their parent, so going backwards every node
+        // gets all of its children, last first.
+        let mut children: Vec<Vec<Node<T>>> = taken.iter().map(|_| Vec::new()).collect();
+        let mut root = None;
+        for (idx, (data, parent)) in taken.into_iter().enumerate().rev() {
+            let mut own = std::mem::take(&mut children[idx]);
+            own.reverse();
+            let node = Node {
+                data,
+                children: own,
+            };
+            match parent {
+                Some(parent) => children[parent].push(node),
+                None => root = Some(node),
+            }
+        }
+        root
+    }
+}
+
+impl<T> Node<T> {
+    /// Pre order map.
+    pub fn pre_order_map<U, F>(self, mut f: F) -> Node<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let nodes = self
+            .into_pre_order()
+            .into_iter()
+            .map(|(data, degree)| (f(data), degree))
+            .collect();
+        Node::from_pre_order(nodes)
+    }
+
+    /// Post order map.
+    pub fn post_order_map<U, F>(self, mut f: F) -> Node<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        // Every node is emitted after its children, which are built by then.
+        let mut built: Vec<Node<U>> = Vec::new();
+        let mut stack = vec![Ok(self)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Ok(node) => {
+                    let (data, children) = node.into_parts();
+                    stack.push(Err((data, children.len())));
+                    stack.extend(children.into_iter().rev().map(Ok));
+                }
+                Err((data, degree)) => {
+                    let children = built.split_off(built.len() - degree);
+                    built.push(Node {
+                        data: f(data),
+                        children,
+                    });
+                }
+            }
+        }
+        built.pop().expect("the root is built last")
+    }
+
+    /// Move the data out in pre order, each with its number of children,
+    /// without recursion.
+    fn into_pre_order(self) -> Vec<(T, usize)> {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            let (data, children) = node.into_parts();
+            nodes.push((data, children.len()));
+            stack.extend(children.into_iter().rev());
+        }
+        nodes
+    }
+
+    /// Build a tree from the data in pre order, each with its number of
+    /// children, without recursion.
+    fn from_pre_order(nodes: Vec<(T, usize)>) -> Node<T> {
+        // Going backwards, the subtrees of a node are built before it, its
+        // first child on top of the stack.
+        let mut built: Vec<Node<T>> = Vec::new();
+        for (data, degree) in nodes.into_iter().rev() {
+            let children = built.drain(built.len() - degree..).rev().collect();
+            built.push(Node { data, children });
+        }
+        built.pop().expect("the root is built last")
+    }
+}
+
+impl<T: Clone> Clone for Node<T> {
+    /// Clone the tree with an explicit stack, so that cloning a deep tree
+    /// does not recurse once per level.
+    fn clone(&self) -> Self {
+        let mut nodes = Vec::new();
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            nodes.push((node.data.clone(), node.children.len()));
+            stack.extend(node.children.iter().rev());
+        }
+        Node::from_pre_order(nodes)
+    }
+}
+
+impl<T> Drop for Node<T> {
+    /// Unlink the descendants into a worklist, so that dropping a deep tree
+    /// does not recurse once per level.
+    fn drop(&mut self) {
+        let mut stack = mem::take(&mut self.children);
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T: DataSize> DataSize for Node<T> {
+    /// Count the children vectors at their capacity and the heap data of
+    /// every node, without recursion. The node itself is not counted.
+    fn heap_size(&self) -> usize {
+        let mut size = 0;
+        let mut stack = vec![self];
+        while let Some(node) = stack.pop() {
+            size +=
+                node.data.heap_size() + node.children.capacity() * std::mem::size_of::<Node<T>>();
+            stack.extend(&node.children);
+        }
+        size
+    }
+}
+
+/// N-ary tree node builder.
+#[derive(Debug, Clone)]
+pub struct NodeBuilder<T> {
+    data: Option<T>,
+    children: Vec<Node<T>>,
+}
+
+impl<T> Default for NodeBuilder<T> {
+    fn default() -> Self {
+        Self {
+            data: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl<T> NodeBuilder<T> {
+    /// Build the node.
+    /// # Errors
+    /// Return `MissingDataField` Error when the data field is not set.
+    pub fn build(self) -> Result<Node<T>> {
+        if let Some(data) = self.data {
+            Ok(Node {
+                data,
+                children: self.children,
+            })
+        } else {
+            Err(Error::MissingDataField)
+        }
+    }
+
+    /// Set `data` field.
+    pub fn data(mut self, data: T) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Append a child.
+    pub fn child(mut self, node: Node<T>) -> Self {
+        self.children.push(node);
+        self
+    }
+
+    /// Append several children.
+    pub fn children<I>(mut self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = Node<T>>,
+    {
+        self.children.extend(nodes);
+        self
+    }
+}
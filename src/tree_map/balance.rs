@@ -0,0 +1,220 @@
+use super::{rebalance, Link, MapNode};
+use crate::gen::{Rng, SplitMix64};
+use std::cmp::Ordering;
+
+mod private {
+    use super::{Link, MapNode};
+    use std::cmp::Ordering;
+
+    /// The hooks a balancing scheme gives the tree algorithms, kept out of
+    /// reach so that only the schemes of this crate implement them.
+    pub trait Scheme: Sized {
+        /// Get the rank of a new node.
+        fn fresh(&mut self) -> u64;
+
+        /// Recompute the rank of a node from its children.
+        fn update<K, V>(node: &mut MapNode<K, V>);
+
+        /// Get the side of the child to rotate up into the place of a node
+        /// breaking the invariant after one of its subtrees changed by a
+        /// single insertion or removal, and whether the inner grandchild
+        /// on that side must be rotated up first.
+        fn rotation<K, V>(node: &MapNode<K, V>) -> Option<(Ordering, bool)>;
+
+        /// Join two valid subtrees with a node whose key lies between
+        /// theirs.
+        fn join<K, V>(
+            left: Link<K, V>,
+            mid: Box<MapNode<K, V>>,
+            right: Link<K, V>,
+        ) -> Box<MapNode<K, V>>;
+
+        /// Check the invariant at a node whose subtrees are valid and have
+        /// the given heights, returning what is wrong.
+        fn check<K, V>(node: &MapNode<K, V>, left: usize, right: usize) -> Option<&'static str>;
+
+        /// Get the height of a subtree, `0` when empty.
+        fn height<K, V>(link: &Link<K, V>) -> usize;
+    }
+}
+
+use private::Scheme;
+
+/// Balancing scheme of a [`TreeMap`](super::TreeMap), chosen by its `B`
+/// type parameter: [`Avl`] or [`Treap`].
+pub trait Balance: Scheme {}
+
+/// Height-balanced AVL tree, the default scheme. Subtree heights differ
+/// by at most one, so the tree is at most about `1.44 log n` high.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Avl;
+
+impl Balance for Avl {}
+
+fn avl_height<K, V>(link: &Link<K, V>) -> u64 {
+    link.as_ref().map_or(0, |node| node.rank)
+}
+
+fn balance_factor<K, V>(node: &MapNode<K, V>) -> i64 {
+    avl_height(&node.left) as i64 - avl_height(&node.right) as i64
+}
+
+impl Scheme for Avl {
+    fn fresh(&mut self) -> u64 {
+        1
+    }
+
+    fn update<K, V>(node: &mut MapNode<K, V>) {
+        node.rank = 1 + avl_height(&node.left).max(avl_height(&node.right));
+    }
+
+    fn rotation<K, V>(node: &MapNode<K, V>) -> Option<(Ordering, bool)> {
+        let inner = |child: &Link<K, V>, inner_factor: i64| {
+            child
+                .as_ref()
+                .is_some_and(|child| balance_factor(child).signum() == inner_factor)
+        };
+        match balance_factor(node) {
+            2 => Some((Ordering::Less, inner(&node.left, -1))),
+            -2 => Some((Ordering::Greater, inner(&node.right, 1))),
+            _ => None,
+        }
+    }
+
+    /// Descend the taller subtree to a node of about the height of the
+    /// other, taking `O(1)` per level of height difference.
+    fn join<K, V>(
+        left: Link<K, V>,
+        mut mid: Box<MapNode<K, V>>,
+        right: Link<K, V>,
+    ) -> Box<MapNode<K, V>> {
+        let (left_height, right_height) = (avl_height(&left), avl_height(&right));
+        if left_height > right_height + 1 {
+            let mut left = left.expect("a taller subtree is not empty");
+            left.right = Some(Self::join(left.right.take(), mid, right));
+            rebalance::<K, V, Self>(left)
+        } else if right_height > left_height + 1 {
+            let mut right = right.expect("a taller subtree is not empty");
+            right.left = Some(Self::join(left, mid, right.left.take()));
+            rebalance::<K, V, Self>(right)
+        } else {
+            mid.left = left;
+            mid.right = right;
+            Self::update(&mut mid);
+            mid
+        }
+    }
+
+    fn check<K, V>(node: &MapNode<K, V>, left: usize, right: usize) -> Option<&'static str> {
+        if node.rank != 1 + left.max(right) as u64 {
+            Some("stored height is stale")
+        } else if left.abs_diff(right) > 1 {
+            Some("subtree heights differ by more than one")
+        } else {
+            None
+        }
+    }
+
+    fn height<K, V>(link: &Link<K, V>) -> usize {
+        avl_height(link) as usize
+    }
+}
+
+/// Treap, a search tree on the keys that is a heap on random priorities.
+/// It is `O(log n)` high in expectation whatever the order of insertion,
+/// and rotates at most once per level, which suits maps updated more than
+/// they are searched.
+///
+/// Priorities come from a [`SplitMix64`] seeded by [`Treap::with_seed`],
+/// or with a fixed seed by default, so the shape of a map is reproducible.
+#[derive(Debug, Clone)]
+pub struct Treap {
+    rng: SplitMix64,
+}
+
+impl Treap {
+    /// Create a scheme drawing priorities from a generator seeded by
+    /// `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SplitMix64::new(seed),
+        }
+    }
+}
+
+impl Default for Treap {
+    fn default() -> Self {
+        Self::with_seed(0x7265_6170)
+    }
+}
+
+impl Balance for Treap {}
+
+fn priority<K, V>(link: &Link<K, V>) -> Option<u64> {
+    link.as_ref().map(|node| node.rank)
+}
+
+impl Scheme for Treap {
+    fn fresh(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn update<K, V>(_: &mut MapNode<K, V>) {}
+
+    fn rotation<K, V>(node: &MapNode<K, V>) -> Option<(Ordering, bool)> {
+        match (priority(&node.left), priority(&node.right)) {
+            (Some(left), _) if left > node.rank => Some((Ordering::Less, false)),
+            (_, Some(right)) if right > node.rank => Some((Ordering::Greater, false)),
+            _ => None,
+        }
+    }
+
+    /// Sink `mid` below the root of higher priority until it tops both
+    /// subtrees, taking `O(1)` per level passed.
+    fn join<K, V>(
+        left: Link<K, V>,
+        mut mid: Box<MapNode<K, V>>,
+        right: Link<K, V>,
+    ) -> Box<MapNode<K, V>> {
+        let (left_priority, right_priority) = (priority(&left), priority(&right));
+        match (left, right) {
+            (Some(mut left), right)
+                if left.rank > mid.rank && Some(left.rank) >= right_priority =>
+            {
+                left.right = Some(Self::join(left.right.take(), mid, right));
+                left
+            }
+            (left, Some(mut right)) if right.rank > mid.rank => {
+                right.left = Some(Self::join(left, mid, right.left.take()));
+                right
+            }
+            (left, right) => {
+                debug_assert!(left_priority.max(right_priority) <= Some(mid.rank));
+                mid.left = left;
+                mid.right = right;
+                mid
+            }
+        }
+    }
+
+    fn check<K, V>(node: &MapNode<K, V>, _: usize, _: usize) -> Option<&'static str> {
+        if priority(&node.left).max(priority(&node.right)) > Some(node.rank) {
+            Some("priority is below the priority of a child")
+        } else {
+            None
+        }
+    }
+
+    /// Walk the subtree, as a treap does not keep heights.
+    fn height<K, V>(link: &Link<K, V>) -> usize {
+        let mut height = 0;
+        let mut stack: Vec<(&MapNode<K, V>, usize)> =
+            link.as_deref().map(|node| (node, 1)).into_iter().collect();
+        while let Some((node, depth)) = stack.pop() {
+            height = height.max(depth);
+            stack.extend(node.left.as_deref().map(|left| (left, depth + 1)));
+            stack.extend(node.right.as_deref().map(|right| (right, depth + 1)));
+        }
+        height
+    }
+}
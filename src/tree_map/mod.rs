@@ -0,0 +1,1640 @@
+use crate::binary_tree::pool::{Global, NodeAlloc};
+use crate::compare::{Comparator, Natural};
+use crate::datasize::DataSize;
+use crate::{Error, Result};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::iter::FromIterator;
+use std::mem;
+use std::ops::{Bound, Index, RangeBounds};
+use std::ptr;
+
+mod balance;
+
+pub use balance::{Avl, Balance, Treap};
+
+type Link<K, V> = Option<Box<MapNode<K, V>>>;
+
+/// Node of a [`TreeMap`], opaque outside of the map. Maps box their nodes
+/// through a [`NodeAlloc`] of this type.
+#[derive(Debug, Clone)]
+pub struct MapNode<K, V> {
+    key: K,
+    value: V,
+    /// What the balancing scheme keeps at the node, such as the height of
+    /// the subtree or a priority.
+    rank: u64,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> MapNode<K, V> {
+    fn new(key: K, value: V, rank: u64) -> Self {
+        Self {
+            key,
+            value,
+            rank,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+fn rotate_left<K, V, B: Balance>(mut node: Box<MapNode<K, V>>) -> Box<MapNode<K, V>> {
+    let mut pivot = node
+        .right
+        .take()
+        .expect("a right-heavy node has a right child");
+    node.right = pivot.left.take();
+    B::update(&mut node);
+    pivot.left = Some(node);
+    B::update(&mut pivot);
+    pivot
+}
+
+fn rotate_right<K, V, B: Balance>(mut node: Box<MapNode<K, V>>) -> Box<MapNode<K, V>> {
+    let mut pivot = node
+        .left
+        .take()
+        .expect("a left-heavy node has a left child");
+    node.left = pivot.right.take();
+    B::update(&mut node);
+    pivot.right = Some(node);
+    B::update(&mut pivot);
+    pivot
+}
+
+/// Rotate the child of a node on the `heavy` side up into its place,
+/// first rotating the inner grandchild up into the child's place if
+/// `inner`.
+fn rotate_up<K, V, B: Balance>(
+    mut node: Box<MapNode<K, V>>,
+    heavy: Ordering,
+    inner: bool,
+) -> Box<MapNode<K, V>> {
+    match heavy {
+        Ordering::Less => {
+            if inner {
+                node.left = node.left.take().map(rotate_left::<K, V, B>);
+            }
+            rotate_right::<K, V, B>(node)
+        }
+        _ => {
+            if inner {
+                node.right = node.right.take().map(rotate_right::<K, V, B>);
+            }
+            rotate_left::<K, V, B>(node)
+        }
+    }
+}
+
+/// Restore the invariant of the balancing scheme at a node after one of
+/// its subtrees changed by a single insertion or removal.
+fn rebalance<K, V, B: Balance>(mut node: Box<MapNode<K, V>>) -> Box<MapNode<K, V>> {
+    B::update(&mut node);
+    match B::rotation(&node) {
+        Some((heavy, inner)) => rotate_up::<K, V, B>(node, heavy, inner),
+        None => node,
+    }
+}
+
+/// Insert into a subtree, recursing once per level of a balanced tree.
+/// New nodes are created by `make`.
+fn insert<K, V, C: Comparator<K>, B: Balance>(
+    link: Link<K, V>,
+    key: K,
+    value: V,
+    cmp: &C,
+    old: &mut Option<V>,
+    make: impl FnOnce(K, V) -> Box<MapNode<K, V>>,
+) -> Box<MapNode<K, V>> {
+    let mut node = match link {
+        Some(node) => node,
+        None => return make(key, value),
+    };
+    match cmp.compare(&key, &node.key) {
+        Ordering::Less => {
+            node.left = Some(insert::<K, V, C, B>(
+                node.left.take(),
+                key,
+                value,
+                cmp,
+                old,
+                make,
+            ));
+        }
+        Ordering::Greater => {
+            node.right = Some(insert::<K, V, C, B>(
+                node.right.take(),
+                key,
+                value,
+                cmp,
+                old,
+                make,
+            ));
+        }
+        Ordering::Equal => {
+            *old = Some(mem::replace(&mut node.value, value));
+            return node;
+        }
+    }
+    rebalance::<K, V, B>(node)
+}
+
+/// Remove from a subtree the node where `cmp` answers `Equal`, going to the
+/// side it answers otherwise, recursing once per level of a balanced tree.
+fn remove<K, V, B: Balance>(
+    link: Link<K, V>,
+    cmp: &mut impl FnMut(&K) -> Ordering,
+    removed: &mut Option<(K, V)>,
+    alloc: &mut impl NodeAlloc<MapNode<K, V>>,
+) -> Link<K, V> {
+    let mut node = link?;
+    match cmp(&node.key) {
+        Ordering::Less => node.left = remove::<K, V, B>(node.left.take(), cmp, removed, alloc),
+        Ordering::Greater => node.right = remove::<K, V, B>(node.right.take(), cmp, removed, alloc),
+        Ordering::Equal => {
+            let MapNode {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = alloc.free(node);
+            *removed = Some((key, value));
+            return join_links::<K, V, B>(left, right);
+        }
+    }
+    Some(rebalance::<K, V, B>(node))
+}
+
+/// Detach the smallest node of a subtree.
+fn remove_min<K, V, B: Balance>(mut node: Box<MapNode<K, V>>) -> (Link<K, V>, Box<MapNode<K, V>>) {
+    match node.left.take() {
+        Some(left) => {
+            let (rest, min) = remove_min::<K, V, B>(left);
+            node.left = rest;
+            (Some(rebalance::<K, V, B>(node)), min)
+        }
+        None => (node.right.take(), node),
+    }
+}
+
+/// Detach the largest node of a subtree.
+fn remove_max<K, V, B: Balance>(mut node: Box<MapNode<K, V>>) -> (Link<K, V>, Box<MapNode<K, V>>) {
+    match node.right.take() {
+        Some(right) => {
+            let (rest, max) = remove_max::<K, V, B>(right);
+            node.right = rest;
+            (Some(rebalance::<K, V, B>(node)), max)
+        }
+        None => (node.left.take(), node),
+    }
+}
+
+/// Join two subtrees, the keys of `left` all coming first.
+fn join_links<K, V, B: Balance>(left: Link<K, V>, right: Link<K, V>) -> Link<K, V> {
+    match right {
+        Some(right) => {
+            let (rest, min) = remove_min::<K, V, B>(right);
+            Some(B::join(left, min, rest))
+        }
+        None => left,
+    }
+}
+
+/// Split a subtree into the nodes where `goes_left` holds, which must come
+/// first in key order, and the others, in `O(log n)`.
+fn split<K, V, B: Balance>(
+    link: Link<K, V>,
+    goes_left: &mut impl FnMut(&K) -> bool,
+) -> (Link<K, V>, Link<K, V>) {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (None, None),
+    };
+    let (left, right) = (node.left.take(), node.right.take());
+    if goes_left(&node.key) {
+        let (mid, rest) = split::<K, V, B>(right, goes_left);
+        (Some(B::join(left, node, mid)), rest)
+    } else {
+        let (rest, mid) = split::<K, V, B>(left, goes_left);
+        (rest, Some(B::join(mid, node, right)))
+    }
+}
+
+/// Split a subtree at `key` into the nodes before it, the node holding it
+/// if any, and the nodes after it, in `O(log n)`.
+fn split_at<K, V, C: Comparator<K>, B: Balance>(
+    link: Link<K, V>,
+    key: &K,
+    cmp: &C,
+) -> (Link<K, V>, Link<K, V>, Link<K, V>) {
+    let mut node = match link {
+        Some(node) => node,
+        None => return (None, None, None),
+    };
+    let (left, right) = (node.left.take(), node.right.take());
+    match cmp.compare(key, &node.key) {
+        Ordering::Less => {
+            let (before, found, after) = split_at::<K, V, C, B>(left, key, cmp);
+            (before, found, Some(B::join(after, node, right)))
+        }
+        Ordering::Greater => {
+            let (before, found, after) = split_at::<K, V, C, B>(right, key, cmp);
+            (Some(B::join(left, node, before)), found, after)
+        }
+        Ordering::Equal => (left, Some(node), right),
+    }
+}
+
+/// Set operation on the keys of two maps, see [`TreeMap::combine`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SetOp {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+impl SetOp {
+    /// Return `true` if a key of the first map is kept, given whether the
+    /// second map holds it too.
+    fn keeps(self, shared: bool) -> bool {
+        match self {
+            SetOp::Union => true,
+            SetOp::Intersection => shared,
+            SetOp::Difference | SetOp::SymmetricDifference => !shared,
+        }
+    }
+
+    /// Return `true` if the keys only the second map holds are kept.
+    fn keeps_other(self) -> bool {
+        matches!(self, SetOp::Union | SetOp::SymmetricDifference)
+    }
+}
+
+/// Combine two subtrees by `op`, splitting `other` at the root of `link`
+/// and joining the combined halves back, and count the shared keys.
+fn combine<K, V, C: Comparator<K>, B: Balance>(
+    link: Link<K, V>,
+    other: Link<K, V>,
+    op: SetOp,
+    cmp: &C,
+    shared: &mut usize,
+    alloc: &mut impl NodeAlloc<MapNode<K, V>>,
+) -> Link<K, V> {
+    let mut node = match link {
+        Some(node) => node,
+        None if op.keeps_other() => return other,
+        None => {
+            free_all(other, alloc);
+            return None;
+        }
+    };
+    if other.is_none() {
+        return if op == SetOp::Intersection {
+            free_all(Some(node), alloc);
+            None
+        } else {
+            Some(node)
+        };
+    }
+    let (before, found, after) = split_at::<K, V, C, B>(other, &node.key, cmp);
+    let found = found.map(|found| alloc.free(found)).is_some();
+    *shared += usize::from(found);
+    let left = combine::<K, V, C, B>(node.left.take(), before, op, cmp, shared, alloc);
+    let right = combine::<K, V, C, B>(node.right.take(), after, op, cmp, shared, alloc);
+    if op.keeps(found) {
+        Some(B::join(left, node, right))
+    } else {
+        alloc.free(node);
+        join_links::<K, V, B>(left, right)
+    }
+}
+
+/// Hand every node of a subtree back to `alloc`, dropping the entries.
+fn free_all<K, V>(link: Link<K, V>, alloc: &mut impl NodeAlloc<MapNode<K, V>>) {
+    let mut stack: Vec<_> = link.into_iter().collect();
+    while let Some(node) = stack.pop() {
+        let mut node = alloc.free(node);
+        stack.extend(node.left.take());
+        stack.extend(node.right.take());
+    }
+}
+
+/// Count the nodes of the first of two subtrees holding `len` nodes
+/// together, walking both in step so that only the smaller one is visited
+/// whole.
+fn count_first<K, V>(first: &Link<K, V>, second: &Link<K, V>, len: usize) -> usize {
+    let mut first_iter = Iter {
+        stack: Vec::new(),
+        len,
+    };
+    first_iter.push_left(first.as_deref());
+    let mut second_iter = Iter {
+        stack: Vec::new(),
+        len,
+    };
+    second_iter.push_left(second.as_deref());
+    let mut counted = 0;
+    loop {
+        match (first_iter.next(), second_iter.next()) {
+            (Some(_), Some(_)) => counted += 1,
+            (None, _) => return counted,
+            (Some(_), None) => return len - counted,
+        }
+    }
+}
+
+/// Keep the nodes of a subtree for which `keep` holds, visiting them in
+/// mid order and joining the kept parts back, and count the others.
+fn retain<K, V, B: Balance>(
+    link: Link<K, V>,
+    keep: &mut impl FnMut(&K, &mut V) -> bool,
+    removed: &mut usize,
+    alloc: &mut impl NodeAlloc<MapNode<K, V>>,
+) -> Link<K, V> {
+    let mut node = link?;
+    let left = retain::<K, V, B>(node.left.take(), keep, removed, alloc);
+    let kept = keep(&node.key, &mut node.value);
+    let right = retain::<K, V, B>(node.right.take(), keep, removed, alloc);
+    if kept {
+        Some(B::join(left, node, right))
+    } else {
+        *removed += 1;
+        alloc.free(node);
+        join_links::<K, V, B>(left, right)
+    }
+}
+
+/// Count the nodes of a subtree.
+fn count<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref()
+        .map_or(0, |node| 1 + count(&node.left) + count(&node.right))
+}
+
+/// Build a balanced subtree over the next `len` entries, which must be in
+/// increasing order of keys, creating the nodes with `make`. Sibling
+/// subtrees differ in size by at most one, so joining them around their
+/// parent takes `O(1)` under AVL, and the recursion is only about `log n`
+/// deep.
+fn build<K, V, I: Iterator<Item = (K, V)>, B: Balance>(
+    len: usize,
+    entries: &mut I,
+    make: &mut impl FnMut(K, V) -> Box<MapNode<K, V>>,
+) -> Link<K, V> {
+    if len == 0 {
+        return None;
+    }
+    let left = build::<K, V, I, B>(len / 2, entries, make);
+    let (key, value) = entries.next().expect("`len` entries remain");
+    let node = make(key, value);
+    let right = build::<K, V, I, B>(len - len / 2 - 1, entries, make);
+    Some(B::join(left, node, right))
+}
+
+/// Path from a node to one of its descendants, `Less` going left and
+/// `Greater` going right.
+type Path = Vec<Ordering>;
+
+/// Update `path[at..]`, leading from a node, for that node being rotated
+/// down to its `down` side.
+fn rotate_path(path: &mut Path, at: usize, down: Ordering) {
+    let up = down.reverse();
+    match (path.get(at), path.get(at + 1)) {
+        (None, _) => path.push(down),
+        (Some(&dir), _) if dir == down => path.insert(at, down),
+        (Some(_), None) => {
+            path.pop();
+        }
+        (Some(_), Some(&dir)) if dir == up => {
+            path.remove(at);
+        }
+        (Some(_), Some(_)) => path.swap(at, at + 1),
+    }
+}
+
+/// Rebalance a node like [`rebalance`], keeping `path` from it to a
+/// descendant up to date.
+fn rebalance_tracking<K, V, B: Balance>(
+    mut node: Box<MapNode<K, V>>,
+    path: &mut Path,
+) -> Box<MapNode<K, V>> {
+    B::update(&mut node);
+    let (heavy, inner) = match B::rotation(&node) {
+        Some(rotation) => rotation,
+        None => return node,
+    };
+    if inner && path.first() == Some(&heavy) {
+        rotate_path(path, 1, heavy);
+    }
+    rotate_path(path, 0, heavy.reverse());
+    rotate_up::<K, V, B>(node, heavy, inner)
+}
+
+/// Insert a new node at the end of `path`, which must lead to an empty
+/// link, created by `make`, returning the subtree and setting `at` to the
+/// path of the node.
+fn insert_at<K, V, B: Balance>(
+    link: Link<K, V>,
+    path: &[Ordering],
+    key: K,
+    value: V,
+    at: &mut Path,
+    make: impl FnOnce(K, V) -> Box<MapNode<K, V>>,
+) -> Box<MapNode<K, V>> {
+    let (&dir, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return make(key, value),
+    };
+    let mut node = link.expect("the path leads to an empty link");
+    match dir {
+        Ordering::Less => {
+            let left = insert_at::<K, V, B>(node.left.take(), rest, key, value, at, make);
+            node.left = Some(left);
+        }
+        _ => {
+            let right = insert_at::<K, V, B>(node.right.take(), rest, key, value, at, make);
+            node.right = Some(right);
+        }
+    }
+    at.insert(0, dir);
+    rebalance_tracking::<K, V, B>(node, at)
+}
+
+/// Get the ref of the node at the end of `path`.
+fn node_at<'a, K, V>(link: &'a Link<K, V>, path: &[Ordering]) -> &'a MapNode<K, V> {
+    let mut node = link.as_deref().expect("the path leads to a node");
+    for dir in path {
+        let next = match dir {
+            Ordering::Less => &node.left,
+            _ => &node.right,
+        };
+        node = next.as_deref().expect("the path leads to a node");
+    }
+    node
+}
+
+/// Get the mutable ref of the node at the end of `path`.
+fn node_at_mut<'a, K, V>(link: &'a mut Link<K, V>, path: &[Ordering]) -> &'a mut MapNode<K, V> {
+    let mut node = link.as_deref_mut().expect("the path leads to a node");
+    for dir in path {
+        let next = match dir {
+            Ordering::Less => &mut node.left,
+            _ => &mut node.right,
+        };
+        node = next.as_deref_mut().expect("the path leads to a node");
+    }
+    node
+}
+
+/// Ordered map over a balanced binary search tree, with the API of
+/// `std`'s `BTreeMap`.
+///
+/// Lookups, insertions and removals take `O(log n)`, and iteration runs in
+/// order of keys. The tree is kept balanced by the scheme `B`, an [`Avl`]
+/// tree by default or a [`Treap`], see [`Balance`]. Unlike `BTreeMap`, the
+/// tree can be checked with [`TreeMap::validate`], and keys may be ordered
+/// by any [`Comparator`] instead of `Ord`. Nodes are boxed through the
+/// allocator `A`, see [`NodeAlloc`].
+#[derive(Clone)]
+pub struct TreeMap<K, V, C = Natural, B = Avl, A = Global> {
+    root: Link<K, V>,
+    len: usize,
+    cmp: C,
+    balance: B,
+    alloc: A,
+}
+
+impl<K, V, C: Default, B: Default, A: Default> Default for TreeMap<K, V, C, B, A> {
+    fn default() -> Self {
+        Self::with_parts(C::default(), B::default(), A::default())
+    }
+}
+
+impl<K, V> TreeMap<K, V> {
+    /// Create an empty map ordered by `Ord`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a balanced map from entries in strictly increasing order of
+    /// keys in `O(n)`. See [`TreeMap::from_sorted_iter_with_comparator`].
+    /// # Errors
+    /// Return `Unsorted` Error when the keys are not strictly increasing.
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Result<Self>
+    where
+        K: Ord,
+    {
+        Self::from_sorted_iter_with_comparator(iter, Natural)
+    }
+}
+
+impl<K, V, B> TreeMap<K, V, Natural, B> {
+    /// Create an empty map ordered by `Ord`, balanced by `balance`.
+    pub fn with_balance(balance: B) -> Self {
+        Self::with_parts(Natural, balance, Global)
+    }
+}
+
+impl<K, V, A> TreeMap<K, V, Natural, Avl, A> {
+    /// Create an empty map ordered by `Ord`, boxing its nodes through
+    /// `alloc`.
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_comparator_in(Natural, alloc)
+    }
+}
+
+impl<K, V, C> TreeMap<K, V, C> {
+    /// Create an empty map ordered by `cmp`.
+    pub fn with_comparator(cmp: C) -> Self {
+        Self::with_comparator_in(cmp, Global)
+    }
+}
+
+impl<K, V, C, A> TreeMap<K, V, C, Avl, A> {
+    /// Create an empty map ordered by `cmp`, boxing its nodes through
+    /// `alloc`.
+    pub fn with_comparator_in(cmp: C, alloc: A) -> Self {
+        Self::with_parts(cmp, Avl, alloc)
+    }
+}
+
+impl<K, V, C, B, A> TreeMap<K, V, C, B, A> {
+    /// Create an empty map ordered by `cmp`, balanced by `balance` and
+    /// boxing its nodes through `alloc`.
+    pub fn with_parts(cmp: C, balance: B, alloc: A) -> Self {
+        Self {
+            root: None,
+            len: 0,
+            cmp,
+            balance,
+            alloc,
+        }
+    }
+
+    /// Get the ref of the comparator.
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    /// Get the ref of the balancing scheme.
+    pub fn balance(&self) -> &B {
+        &self.balance
+    }
+
+    /// Get the ref of the allocator.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Get the number of entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every entry, handing the nodes back to the allocator.
+    pub fn clear(&mut self)
+    where
+        A: NodeAlloc<MapNode<K, V>>,
+    {
+        free_all(self.root.take(), &mut self.alloc);
+        self.len = 0;
+    }
+
+    /// Get the height of the tree, `0` when empty. This takes `O(1)`
+    /// under [`Avl`], which keeps heights, and `O(n)` otherwise.
+    pub fn height(&self) -> usize
+    where
+        B: Balance,
+    {
+        B::height(&self.root)
+    }
+
+    /// Get the entry with the smallest key.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    /// Get the entry with the largest key.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    /// Create an iterator over the entries in increasing order of keys.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            len: self.len,
+        };
+        iter.push_left(self.root.as_deref());
+        iter
+    }
+
+    /// Create an iterator over the entries in increasing order of keys,
+    /// with mutable values.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            stack: Vec::new(),
+            next: self.root.as_deref_mut(),
+            len: self.len,
+        }
+    }
+
+    /// Create an iterator over the keys in increasing order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.iter())
+    }
+
+    /// Create an iterator over the values in increasing order of keys.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.iter())
+    }
+
+    /// Create an iterator over the mutable values in increasing order of
+    /// keys.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut(self.iter_mut())
+    }
+}
+
+impl<K, V, C, B: Balance, A: NodeAlloc<MapNode<K, V>>> TreeMap<K, V, C, B, A> {
+    /// Keep only the entries for which `f` holds, calling it in increasing
+    /// order of keys. This takes a single traversal, joining the kept
+    /// subtrees back so the tree stays balanced.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut f: F) {
+        let mut removed = 0;
+        self.root = retain::<K, V, B>(self.root.take(), &mut f, &mut removed, &mut self.alloc);
+        self.len -= removed;
+    }
+
+    /// Remove the entry with the smallest key.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let (rest, min) = remove_min::<K, V, B>(self.root.take()?);
+        self.root = rest;
+        self.len -= 1;
+        let min = self.alloc.free(min);
+        Some((min.key, min.value))
+    }
+
+    /// Remove the entry with the largest key.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let (rest, max) = remove_max::<K, V, B>(self.root.take()?);
+        self.root = rest;
+        self.len -= 1;
+        let max = self.alloc.free(max);
+        Some((max.key, max.value))
+    }
+}
+
+impl<K, V, C, B: Balance + Default> TreeMap<K, V, C, B> {
+    /// Build a balanced map over `len` entries in strictly increasing order
+    /// of keys.
+    fn from_sorted<I: Iterator<Item = (K, V)>>(len: usize, mut entries: I, cmp: C) -> Self {
+        let mut balance = B::default();
+        let root = build::<K, V, I, B>(len, &mut entries, &mut |key, value| {
+            Box::new(MapNode::new(key, value, balance.fresh()))
+        });
+        Self {
+            root,
+            len,
+            cmp,
+            balance,
+            alloc: Global,
+        }
+    }
+}
+
+impl<K, V, C: Comparator<K>> TreeMap<K, V, C> {
+    /// Build a balanced map ordered by `cmp` from entries in strictly
+    /// increasing order of keys, in `O(n)` instead of the `O(n log n)` of
+    /// inserting them one by one.
+    /// # Errors
+    /// Return `Unsorted` Error when the keys are not strictly increasing.
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = (K, V)>>(
+        iter: I,
+        cmp: C,
+    ) -> Result<Self> {
+        let entries: Vec<_> = iter.into_iter().collect();
+        let sorted = entries
+            .windows(2)
+            .all(|pair| cmp.compare(&pair[0].0, &pair[1].0) == Ordering::Less);
+        if !sorted {
+            return Err(Error::Unsorted);
+        }
+        Ok(Self::from_sorted(entries.len(), entries.into_iter(), cmp))
+    }
+}
+
+impl<K, V, C: Comparator<K>, B: Balance, A: NodeAlloc<MapNode<K, V>>> TreeMap<K, V, C, B, A> {
+    /// Combine the keys of two maps by `op`, keeping the entry of `self`
+    /// for a key both hold. Splitting `other` at every root of `self` and
+    /// joining the combined halves back takes `O(m log(n / m + 1))` for
+    /// sizes `m <= n`, either way round. The comparator and the allocator
+    /// of `self` are kept, and the nodes left out go back to the allocator.
+    pub(crate) fn combine(mut self, other: Self, op: SetOp) -> Self {
+        let mut shared = 0;
+        let root = combine::<K, V, C, B>(
+            self.root.take(),
+            other.root,
+            op,
+            &self.cmp,
+            &mut shared,
+            &mut self.alloc,
+        );
+        let (n, m) = (self.len, other.len);
+        let len = match op {
+            SetOp::Union => n + m - shared,
+            SetOp::Intersection => shared,
+            SetOp::Difference => n - shared,
+            SetOp::SymmetricDifference => n + m - 2 * shared,
+        };
+        Self {
+            root,
+            len,
+            cmp: self.cmp,
+            balance: self.balance,
+            alloc: self.alloc,
+        }
+    }
+}
+
+impl<K, V, C: Comparator<K>, B: Balance, A: NodeAlloc<MapNode<K, V>>> TreeMap<K, V, C, B, A> {
+    /// Get the node holding `key`.
+    fn node<Q>(&self, key: &Q) -> Option<&MapNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            link = match self.cmp.compare(key, node.key.borrow()) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    /// Get the ref of the value of `key`.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.node(key).map(|node| &node.value)
+    }
+
+    /// Get the refs of the key equal to `key` and of its value.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.node(key).map(|node| (&node.key, &node.value))
+    }
+
+    /// Get the mutable ref of the value of `key`.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut link = self.root.as_deref_mut();
+        while let Some(node) = link {
+            link = match self.cmp.compare(key, node.key.borrow()) {
+                Ordering::Less => node.left.as_deref_mut(),
+                Ordering::Greater => node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+            };
+        }
+        None
+    }
+
+    /// Return `true` if the map contains `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.node(key).is_some()
+    }
+
+    /// Insert an entry, returning the old value of `key` if any. The key
+    /// itself is not updated.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut old = None;
+        let (balance, alloc) = (&mut self.balance, &mut self.alloc);
+        self.root = Some(insert::<K, V, C, B>(
+            self.root.take(),
+            key,
+            value,
+            &self.cmp,
+            &mut old,
+            |key, value| alloc.alloc(MapNode::new(key, value, balance.fresh())),
+        ));
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// Get the entry of `key` for in-place manipulation. The entry
+    /// remembers where `key` belongs, so acting on it compares no more
+    /// keys.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, C, B, A> {
+        let mut path = Vec::new();
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            let dir = self.cmp.compare(&key, &node.key);
+            link = match dir {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return Entry::Occupied(OccupiedEntry { map: self, path }),
+            };
+            path.push(dir);
+        }
+        Entry::Vacant(VacantEntry {
+            map: self,
+            key,
+            path,
+        })
+    }
+
+    /// Remove `key`, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove `key`, returning the stored key and its value if it was
+    /// present.
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let cmp = &self.cmp;
+        let mut removed = None;
+        self.root = remove::<K, V, B>(
+            self.root.take(),
+            &mut |node_key| cmp.compare(key, node_key.borrow()),
+            &mut removed,
+            &mut self.alloc,
+        );
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    /// Create an iterator over the entries whose keys lie in `range`, in
+    /// increasing order. Finding the bounds takes `O(log n)`.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        let order = &self.cmp;
+        let cmp = |key: &K, bound: &Q| order.compare(key.borrow(), bound);
+        let after_start = |key: &K| match range.start_bound() {
+            Bound::Included(start) => cmp(key, start) != Ordering::Less,
+            Bound::Excluded(start) => cmp(key, start) == Ordering::Greater,
+            Bound::Unbounded => true,
+        };
+        let before_end = |key: &K| match range.end_bound() {
+            Bound::Included(end) => cmp(key, end) != Ordering::Greater,
+            Bound::Excluded(end) => cmp(key, end) == Ordering::Less,
+            Bound::Unbounded => true,
+        };
+        // The path to the first entry in range, keeping the nodes still to
+        // be visited.
+        let mut stack = Vec::new();
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            if after_start(&node.key) {
+                stack.push(node);
+                link = node.left.as_deref();
+            } else {
+                link = node.right.as_deref();
+            }
+        }
+        // The last entry in range.
+        let mut last = None;
+        let mut link = self.root.as_deref();
+        while let Some(node) = link {
+            if before_end(&node.key) {
+                last = Some(node);
+                link = node.right.as_deref();
+            } else {
+                link = node.left.as_deref();
+            }
+        }
+        match (stack.last(), last) {
+            (Some(first), Some(last))
+                if order.compare(&first.key, &last.key) != Ordering::Greater => {}
+            _ => stack.clear(),
+        }
+        Range { stack, last }
+    }
+
+    /// Create an iterator over the entries from the first key not less
+    /// than `key`, in increasing order, found in `O(log n)`. To resume
+    /// after a key already seen, use `range` with an excluded start bound.
+    pub fn iter_from<Q>(&self, key: &Q) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.range((Bound::Included(key), Bound::Unbounded))
+    }
+
+    /// Remove the entries whose keys lie in `range`, returning them in an
+    /// iterator in increasing order. Cutting them out takes `O(log n)`,
+    /// and they are removed even if the iterator is not consumed. The
+    /// nodes go back to the allocator as the entries are taken.
+    pub fn drain<Q, R>(&mut self, range: R) -> Drain<'_, K, V, A>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        let order = &self.cmp;
+        let cmp = |key: &K, bound: &Q| order.compare(key.borrow(), bound);
+        let (before, rest) =
+            split::<K, V, B>(self.root.take(), &mut |key| match range.start_bound() {
+                Bound::Included(start) => cmp(key, start) == Ordering::Less,
+                Bound::Excluded(start) => cmp(key, start) != Ordering::Greater,
+                Bound::Unbounded => false,
+            });
+        let (drained, after) = split::<K, V, B>(rest, &mut |key| match range.end_bound() {
+            Bound::Included(end) => cmp(key, end) != Ordering::Greater,
+            Bound::Excluded(end) => cmp(key, end) == Ordering::Less,
+            Bound::Unbounded => true,
+        });
+        self.root = join_links::<K, V, B>(before, after);
+        let mut iter = IntoIter {
+            stack: Vec::new(),
+            len: count(&drained),
+        };
+        self.len -= iter.len;
+        iter.push_left(drained);
+        Drain {
+            iter,
+            alloc: &mut self.alloc,
+        }
+    }
+
+    /// Split the map at `key`, keeping the entries with smaller keys and
+    /// returning the others, like `BTreeMap::split_off`. Cutting the tree
+    /// takes `O(log n)`, and both parts stay balanced; counting them takes
+    /// the size of the smaller one.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q> + Clone,
+        B: Clone,
+        A: Clone,
+    {
+        let order = &self.cmp;
+        let (before, after) = split::<K, V, B>(self.root.take(), &mut |node_key| {
+            order.compare(node_key.borrow(), key) == Ordering::Less
+        });
+        let len = count_first(&before, &after, self.len);
+        let rest = self.len - len;
+        self.root = before;
+        self.len = len;
+        Self {
+            root: after,
+            len: rest,
+            cmp: self.cmp.clone(),
+            balance: self.balance.clone(),
+            alloc: self.alloc.clone(),
+        }
+    }
+
+    /// Check the invariants of the tree: keys strictly increasing in mid
+    /// order, the invariant of the balancing scheme, such as heights up to
+    /// date and balance factors within one under [`Avl`], and the length.
+    /// They hold unless the comparator is inconsistent.
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first offending node.
+    pub fn validate(&self) -> Result<()> {
+        let mut path = Vec::new();
+        let mut prev = None;
+        let mut len = 0;
+        check::<K, V, C, B>(&self.root, &self.cmp, &mut path, &mut prev, &mut len)?;
+        if len != self.len {
+            return Err(Error::InvariantViolation {
+                path,
+                reason: format!("tree holds {} entries but the length is {}", len, self.len),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Check a subtree in mid order, returning its height.
+fn check<'a, K, V, C: Comparator<K>, B: Balance>(
+    link: &'a Link<K, V>,
+    cmp: &C,
+    path: &mut Vec<usize>,
+    prev: &mut Option<&'a K>,
+    len: &mut usize,
+) -> Result<usize> {
+    let node = match link {
+        Some(node) => node,
+        None => return Ok(0),
+    };
+    let violation = |path: &[usize], reason: &str| Error::InvariantViolation {
+        path: path.to_vec(),
+        reason: reason.to_string(),
+    };
+    path.push(0);
+    let left = check::<K, V, C, B>(&node.left, cmp, path, prev, len)?;
+    path.pop();
+    if prev.is_some_and(|prev| cmp.compare(prev, &node.key) != Ordering::Less) {
+        return Err(violation(
+            path,
+            "key is not greater than its mid order predecessor",
+        ));
+    }
+    *prev = Some(&node.key);
+    *len += 1;
+    path.push(1);
+    let right = check::<K, V, C, B>(&node.right, cmp, path, prev, len)?;
+    path.pop();
+    if let Some(reason) = B::check(node, left, right) {
+        return Err(violation(path, reason));
+    }
+    Ok(1 + left.max(right))
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, C, B, A> fmt::Debug for TreeMap<K, V, C, B, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, C, B, A> PartialEq for TreeMap<K, V, C, B, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq, C, B, A> Eq for TreeMap<K, V, C, B, A> {}
+
+impl<K, V, C, B, A, Q> Index<&Q> for TreeMap<K, V, C, B, A>
+where
+    B: Balance,
+    A: NodeAlloc<MapNode<K, V>>,
+    K: Borrow<Q>,
+    Q: ?Sized,
+    C: Comparator<K> + Comparator<Q>,
+{
+    type Output = V;
+
+    /// Get the ref of the value of `key`.
+    ///
+    /// # Panics
+    /// Panic if the key is not present.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<K, V, C, B, A> FromIterator<(K, V)> for TreeMap<K, V, C, B, A>
+where
+    C: Comparator<K> + Default,
+    B: Balance + Default,
+    A: NodeAlloc<MapNode<K, V>> + Default,
+{
+    /// Insert the entries one by one, the last value of a key winning.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, C: Comparator<K>, B: Balance, A: NodeAlloc<MapNode<K, V>>> Extend<(K, V)>
+    for TreeMap<K, V, C, B, A>
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(key, value)| {
+            self.insert(key, value);
+        });
+    }
+}
+
+impl<'a, K, V, C, B, A> Extend<(&'a K, &'a V)> for TreeMap<K, V, C, B, A>
+where
+    K: Copy,
+    V: Copy,
+    C: Comparator<K>,
+    B: Balance,
+    A: NodeAlloc<MapNode<K, V>>,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<K: DataSize, V: DataSize, C, B, A> DataSize for TreeMap<K, V, C, B, A> {
+    /// Count the boxed nodes and the heap data of the entries, without
+    /// recursion.
+    fn heap_size(&self) -> usize {
+        let mut size = 0;
+        let mut stack: Vec<&MapNode<K, V>> = self.root.as_deref().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            size += mem::size_of::<MapNode<K, V>>() + node.key.heap_size() + node.value.heap_size();
+            stack.extend(node.left.as_deref());
+            stack.extend(node.right.as_deref());
+        }
+        size
+    }
+}
+
+impl<K: Ord, V> From<BTreeMap<K, V>> for TreeMap<K, V> {
+    /// Bulk load the entries in `O(n)`, as they are already sorted.
+    fn from(map: BTreeMap<K, V>) -> Self {
+        Self::from_sorted(map.len(), map.into_iter(), Natural)
+    }
+}
+
+impl<K: Ord, V, C, B, A> From<TreeMap<K, V, C, B, A>> for BTreeMap<K, V> {
+    /// Collect the entries, re-sorting them by `Ord` if the map was
+    /// ordered by another comparator.
+    fn from(map: TreeMap<K, V, C, B, A>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<K, V, C, B, A> IntoIterator for TreeMap<K, V, C, B, A> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut iter = IntoIter {
+            stack: Vec::new(),
+            len: self.len,
+        };
+        iter.push_left(self.root);
+        iter
+    }
+}
+
+impl<'a, K, V, C, B, A> IntoIterator for &'a TreeMap<K, V, C, B, A> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, C, B, A> IntoIterator for &'a mut TreeMap<K, V, C, B, A> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over the entries of a [`TreeMap`].
+#[derive(Debug)]
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a MapNode<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: Option<&'a MapNode<K, V>>) {
+        while let Some(node) = link {
+            self.stack.push(node);
+            link = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        self.len -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+/// An entry waiting to be visited by [`IterMut`], with its right subtree.
+type PendingMut<'a, K, V> = (&'a K, &'a mut V, Option<&'a mut MapNode<K, V>>);
+
+/// Iterator over the entries of a [`TreeMap`] with mutable values.
+#[derive(Debug)]
+pub struct IterMut<'a, K, V> {
+    /// The entries on the path waiting to be visited.
+    stack: Vec<PendingMut<'a, K, V>>,
+    next: Option<&'a mut MapNode<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.next.take() {
+            let MapNode {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = node;
+            self.next = left.as_deref_mut();
+            self.stack.push((key, value, right.as_deref_mut()));
+        }
+        let (key, value, right) = self.stack.pop()?;
+        self.next = right;
+        self.len -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+
+/// Iterator moving the entries out of a [`TreeMap`].
+#[derive(Debug)]
+pub struct IntoIter<K, V> {
+    stack: Vec<Box<MapNode<K, V>>>,
+    len: usize,
+}
+
+impl<K, V> IntoIter<K, V> {
+    fn push_left(&mut self, mut link: Link<K, V>) {
+        while let Some(mut node) = link {
+            link = node.left.take();
+            self.stack.push(node);
+        }
+    }
+}
+
+impl<K, V> IntoIter<K, V> {
+    /// Take the box of the next entry, its subtrees detached.
+    fn next_node(&mut self) -> Option<Box<MapNode<K, V>>> {
+        let mut node = self.stack.pop()?;
+        self.push_left(node.right.take());
+        self.len -= 1;
+        Some(node)
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next_node()?;
+        Some((node.key, node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+/// Iterator moving a range of entries out of a [`TreeMap`], created by
+/// [`TreeMap::drain`]. The nodes go back to the allocator of the map as
+/// the entries are taken, and those left when it is dropped too.
+#[derive(Debug)]
+pub struct Drain<'a, K, V, A: NodeAlloc<MapNode<K, V>>> {
+    iter: IntoIter<K, V>,
+    alloc: &'a mut A,
+}
+
+impl<'a, K, V, A: NodeAlloc<MapNode<K, V>>> Iterator for Drain<'a, K, V, A> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.alloc.free(self.iter.next_node()?);
+        Some((node.key, node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, K, V, A: NodeAlloc<MapNode<K, V>>> ExactSizeIterator for Drain<'a, K, V, A> {}
+
+impl<'a, K, V, A: NodeAlloc<MapNode<K, V>>> Drop for Drain<'a, K, V, A> {
+    fn drop(&mut self) {
+        while let Some(node) = self.iter.next_node() {
+            self.alloc.free(node);
+        }
+    }
+}
+
+/// Iterator over the keys of a [`TreeMap`].
+#[derive(Debug)]
+pub struct Keys<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+
+/// Iterator over the values of a [`TreeMap`].
+#[derive(Debug)]
+pub struct Values<'a, K, V>(Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+/// Iterator over the mutable values of a [`TreeMap`].
+#[derive(Debug)]
+pub struct ValuesMut<'a, K, V>(IterMut<'a, K, V>);
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+/// Iterator over the entries of a [`TreeMap`] in a range of keys.
+#[derive(Debug)]
+pub struct Range<'a, K, V> {
+    stack: Vec<&'a MapNode<K, V>>,
+    /// The last entry in range.
+    last: Option<&'a MapNode<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if self.last.is_some_and(|last| ptr::eq(node, last)) {
+            self.stack.clear();
+        } else {
+            let mut link = node.right.as_deref();
+            while let Some(next) = link {
+                self.stack.push(next);
+                link = next.left.as_deref();
+            }
+        }
+        Some((&node.key, &node.value))
+    }
+}
+
+/// A view into a single entry of a [`TreeMap`], from [`TreeMap::entry`].
+pub enum Entry<'a, K, V, C = Natural, B = Avl, A = Global> {
+    /// The key is absent.
+    Vacant(VacantEntry<'a, K, V, C, B, A>),
+    /// The key is present.
+    Occupied(OccupiedEntry<'a, K, V, C, B, A>),
+}
+
+impl<'a, K: fmt::Debug, V: fmt::Debug, C, B, A> fmt::Debug for Entry<'a, K, V, C, B, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Vacant(entry) => f.debug_tuple("Entry").field(entry).finish(),
+            Entry::Occupied(entry) => f.debug_tuple("Entry").field(entry).finish(),
+        }
+    }
+}
+
+impl<'a, K, V, C, B, A> Entry<'a, K, V, C, B, A> {
+    /// Get the ref of the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Vacant(entry) => entry.key(),
+            Entry::Occupied(entry) => entry.key(),
+        }
+    }
+}
+
+impl<'a, K, V, C, B: Balance, A: NodeAlloc<MapNode<K, V>>> Entry<'a, K, V, C, B, A> {
+    /// Insert `default` if the entry is vacant, and return the mutable
+    /// ref of the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Insert the result of `default` if the entry is vacant, and return
+    /// the mutable ref of the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        self.or_insert_with_key(|_| default())
+    }
+
+    /// Insert the result of `default` called with the key if the entry is
+    /// vacant, and return the mutable ref of the value.
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Vacant(entry) => {
+                let value = default(entry.key());
+                entry.insert(value)
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+
+    /// Insert the default value if the entry is vacant, and return the
+    /// mutable ref of the value.
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Modify the value with `f` if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// A vacant entry of a [`TreeMap`].
+pub struct VacantEntry<'a, K, V, C = Natural, B = Avl, A = Global> {
+    map: &'a mut TreeMap<K, V, C, B, A>,
+    key: K,
+    /// Path from the root to the empty link where the key belongs.
+    path: Path,
+}
+
+impl<'a, K, V, C, B, A> VacantEntry<'a, K, V, C, B, A> {
+    /// Get the ref of the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take the key back.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+impl<'a, K, V, C, B: Balance, A: NodeAlloc<MapNode<K, V>>> VacantEntry<'a, K, V, C, B, A> {
+    /// Insert `value` under the key, and return its mutable ref.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        let mut at = Vec::new();
+        let (balance, alloc) = (&mut map.balance, &mut map.alloc);
+        map.root = Some(insert_at::<K, V, B>(
+            map.root.take(),
+            &self.path,
+            self.key,
+            value,
+            &mut at,
+            |key, value| alloc.alloc(MapNode::new(key, value, balance.fresh())),
+        ));
+        map.len += 1;
+        &mut node_at_mut(&mut map.root, &at).value
+    }
+}
+
+impl<'a, K: fmt::Debug, V, C, B, A> fmt::Debug for VacantEntry<'a, K, V, C, B, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(&self.key).finish()
+    }
+}
+
+/// An occupied entry of a [`TreeMap`].
+pub struct OccupiedEntry<'a, K, V, C = Natural, B = Avl, A = Global> {
+    map: &'a mut TreeMap<K, V, C, B, A>,
+    /// Path from the root to the node of the entry.
+    path: Path,
+}
+
+impl<'a, K, V, C, B, A> OccupiedEntry<'a, K, V, C, B, A> {
+    /// Get the ref of the key of the entry.
+    pub fn key(&self) -> &K {
+        &node_at(&self.map.root, &self.path).key
+    }
+
+    /// Get the ref of the value of the entry.
+    pub fn get(&self) -> &V {
+        &node_at(&self.map.root, &self.path).value
+    }
+
+    /// Get the mutable ref of the value of the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut node_at_mut(&mut self.map.root, &self.path).value
+    }
+
+    /// Convert the entry into the mutable ref of its value.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut node_at_mut(&mut self.map.root, &self.path).value
+    }
+
+    /// Replace the value of the entry, returning the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, K, V, C, B: Balance, A: NodeAlloc<MapNode<K, V>>> OccupiedEntry<'a, K, V, C, B, A> {
+    /// Remove the entry, returning its value.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Remove the entry, returning the stored key and its value.
+    pub fn remove_entry(self) -> (K, V) {
+        let mut dirs = self.path.iter().copied();
+        let mut removed = None;
+        self.map.root = remove::<K, V, B>(
+            self.map.root.take(),
+            &mut |_| dirs.next().unwrap_or(Ordering::Equal),
+            &mut removed,
+            &mut self.map.alloc,
+        );
+        self.map.len -= 1;
+        removed.expect("the path leads to a node")
+    }
+}
+
+impl<'a, K: fmt::Debug, V: fmt::Debug, C, B, A> fmt::Debug for OccupiedEntry<'a, K, V, C, B, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.key())
+            .field("value", self.get())
+            .finish()
+    }
+}
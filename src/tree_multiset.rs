@@ -0,0 +1,258 @@
+use crate::compare::{Comparator, Natural};
+use crate::tree_map::{self, TreeMap};
+use crate::{Error, Result};
+use std::borrow::Borrow;
+use std::fmt;
+use std::iter::FromIterator;
+
+/// Ordered multiset over an AVL tree.
+///
+/// Equal values are stored once with their multiplicity, the first one
+/// inserted being kept, so operations cost as in a [`TreeMap`] over the
+/// distinct values.
+#[derive(Clone)]
+pub struct TreeMultiSet<T, C = Natural> {
+    counts: TreeMap<T, usize, C>,
+    len: usize,
+}
+
+impl<T, C: Default> Default for TreeMultiSet<T, C> {
+    fn default() -> Self {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T> TreeMultiSet<T> {
+    /// Create an empty multiset ordered by `Ord`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T, C> TreeMultiSet<T, C> {
+    /// Create an empty multiset ordered by `cmp`.
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            counts: TreeMap::with_comparator(cmp),
+            len: 0,
+        }
+    }
+
+    /// Get the ref of the comparator.
+    pub fn comparator(&self) -> &C {
+        self.counts.comparator()
+    }
+
+    /// Get the number of values, counting duplicates.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Get the number of distinct values.
+    pub fn distinct_len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Return `true` if the multiset is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.len = 0;
+    }
+
+    /// Get the ref of the smallest value.
+    pub fn first(&self) -> Option<&T> {
+        self.counts.first_key_value().map(|(value, _)| value)
+    }
+
+    /// Get the ref of the largest value.
+    pub fn last(&self) -> Option<&T> {
+        self.counts.last_key_value().map(|(value, _)| value)
+    }
+
+    /// Create an iterator over the values in increasing order, repeating
+    /// each as many times as it was inserted.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            counts: self.counts.iter(),
+            current: None,
+            len: self.len,
+        }
+    }
+
+    /// Create an iterator over the distinct values and their counts, in
+    /// increasing order.
+    pub fn counts(&self) -> tree_map::Iter<'_, T, usize> {
+        self.counts.iter()
+    }
+}
+
+impl<T, C: Comparator<T>> TreeMultiSet<T, C> {
+    /// Add one occurrence of `value`.
+    pub fn insert(&mut self, value: T) {
+        self.insert_many(value, 1);
+    }
+
+    /// Add `count` occurrences of `value`.
+    pub fn insert_many(&mut self, value: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+        *self.counts.entry(value).or_insert(0) += count;
+        self.len += count;
+    }
+
+    /// Get the number of occurrences of `value`.
+    pub fn count<Q>(&self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.counts.get(value).copied().unwrap_or(0)
+    }
+
+    /// Return `true` if the multiset holds `value` at least once.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.counts.contains_key(value)
+    }
+
+    /// Remove one occurrence of `value`, returning `true` if there was
+    /// one.
+    pub fn remove_one<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        match self.counts.get_mut(value) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.counts.remove(value);
+            }
+            None => return false,
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Remove every occurrence of `value`, returning how many there were.
+    pub fn remove_all<Q>(&mut self, value: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let count = self.counts.remove(value).unwrap_or(0);
+        self.len -= count;
+        count
+    }
+
+    /// Check the invariants of the underlying [`TreeMap`], that no count
+    /// is zero and that the length is their sum.
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first broken invariant.
+    pub fn validate(&self) -> Result<()> {
+        self.counts.validate()?;
+        if self.counts.values().any(|&count| count == 0) {
+            return Err(Error::InvariantViolation {
+                path: Vec::new(),
+                reason: "a value is stored with a zero count".to_string(),
+            });
+        }
+        let len: usize = self.counts.values().sum();
+        if len != self.len {
+            return Err(Error::InvariantViolation {
+                path: Vec::new(),
+                reason: format!("counts sum to {} but the length is {}", len, self.len),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug, C> fmt::Debug for TreeMultiSet<T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, C> PartialEq for TreeMultiSet<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<T: Eq, C> Eq for TreeMultiSet<T, C> {}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for TreeMultiSet<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for TreeMultiSet<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|value| self.insert(value));
+    }
+}
+
+impl<'a, T: Copy, C: Comparator<T>> Extend<&'a T> for TreeMultiSet<T, C> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a, T, C> IntoIterator for &'a TreeMultiSet<T, C> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`TreeMultiSet`], with duplicates.
+#[derive(Debug)]
+pub struct Iter<'a, T> {
+    counts: tree_map::Iter<'a, T, usize>,
+    /// The value being repeated and how many times it is still due.
+    current: Option<(&'a T, usize)>,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (value, due) = match self.current.take() {
+            Some(current) => current,
+            None => {
+                let (value, &count) = self.counts.next()?;
+                (value, count)
+            }
+        };
+        if due > 1 {
+            self.current = Some((value, due - 1));
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
@@ -0,0 +1,535 @@
+use crate::binary_tree::pool::Global;
+use crate::compare::{Comparator, Natural};
+use crate::datasize::DataSize;
+use crate::tree_map::{self, Avl, Balance, SetOp, TreeMap};
+use crate::Result;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::{FromIterator, Peekable};
+use std::ops::{BitAnd, BitOr, BitXor, RangeBounds, Sub};
+
+/// Ordered set over a balanced binary search tree, with the API of
+/// `std`'s `BTreeSet`.
+///
+/// A [`TreeMap`] with unit values, balanced by the scheme `B`; see it for
+/// the costs. The operators
+/// `|`, `&`, `-` and `^` take both sets and combine them by splitting and
+/// joining their trees in `O(m log(n / m + 1))` for sizes `m <= n`, which
+/// beats merging when one set is much smaller. [`TreeSet::union`] and the
+/// like iterate over the result lazily instead.
+#[derive(Clone)]
+pub struct TreeSet<T, C = Natural, B = Avl> {
+    map: TreeMap<T, (), C, B>,
+}
+
+impl<T, C: Default, B: Default> Default for TreeSet<T, C, B> {
+    fn default() -> Self {
+        Self {
+            map: TreeMap::default(),
+        }
+    }
+}
+
+impl<T> TreeSet<T> {
+    /// Create an empty set ordered by `Ord`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a balanced set from values in strictly increasing order in
+    /// `O(n)`. See [`TreeMap::from_sorted_iter`].
+    /// # Errors
+    /// Return `Unsorted` Error when the values are not strictly increasing.
+    pub fn from_sorted_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self>
+    where
+        T: Ord,
+    {
+        Self::from_sorted_iter_with_comparator(iter, Natural)
+    }
+}
+
+impl<T, B> TreeSet<T, Natural, B> {
+    /// Create an empty set ordered by `Ord`, balanced by `balance`.
+    pub fn with_balance(balance: B) -> Self {
+        Self {
+            map: TreeMap::with_balance(balance),
+        }
+    }
+}
+
+impl<T, C> TreeSet<T, C> {
+    /// Create an empty set ordered by `cmp`.
+    pub fn with_comparator(cmp: C) -> Self {
+        Self {
+            map: TreeMap::with_comparator(cmp),
+        }
+    }
+}
+
+impl<T, C: Comparator<T>> TreeSet<T, C> {
+    /// Build a balanced set ordered by `cmp` from values in strictly
+    /// increasing order in `O(n)`.
+    /// # Errors
+    /// Return `Unsorted` Error when the values are not strictly increasing.
+    pub fn from_sorted_iter_with_comparator<I: IntoIterator<Item = T>>(
+        iter: I,
+        cmp: C,
+    ) -> Result<Self> {
+        let entries = iter.into_iter().map(|value| (value, ()));
+        Ok(Self {
+            map: TreeMap::from_sorted_iter_with_comparator(entries, cmp)?,
+        })
+    }
+}
+
+impl<T, C, B> TreeSet<T, C, B> {
+    /// Get the ref of the comparator.
+    pub fn comparator(&self) -> &C {
+        self.map.comparator()
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Return `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Remove every value.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Keep only the values for which `f` holds, calling it in increasing
+    /// order. See [`TreeMap::retain`].
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F)
+    where
+        B: Balance,
+    {
+        self.map.retain(|value, _| f(value));
+    }
+
+    /// Get the height of the tree, `0` when empty. See
+    /// [`TreeMap::height`].
+    pub fn height(&self) -> usize
+    where
+        B: Balance,
+    {
+        self.map.height()
+    }
+
+    /// Get the smallest value.
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(value, _)| value)
+    }
+
+    /// Get the largest value.
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(value, _)| value)
+    }
+
+    /// Remove the smallest value.
+    pub fn pop_first(&mut self) -> Option<T>
+    where
+        B: Balance,
+    {
+        self.map.pop_first().map(|(value, _)| value)
+    }
+
+    /// Remove the largest value.
+    pub fn pop_last(&mut self) -> Option<T>
+    where
+        B: Balance,
+    {
+        self.map.pop_last().map(|(value, _)| value)
+    }
+
+    /// Create an iterator over the values in increasing order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.map.keys())
+    }
+}
+
+impl<T, C: Comparator<T>, B: Balance> TreeSet<T, C, B> {
+    /// Return `true` if the set contains `value`.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Get the ref of the stored value equal to `value`.
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.map.get_key_value(value).map(|(value, _)| value)
+    }
+
+    /// Insert a value, returning `false` if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Remove a value, returning `false` if it was not present.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Remove a value, returning the stored one if it was present.
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+
+    /// Create an iterator over the values in `range`, in increasing order.
+    /// See [`TreeMap::range`].
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        Range(self.map.range(range))
+    }
+
+    /// Create an iterator over the values not less than `value`, in
+    /// increasing order, found in `O(log n)`.
+    pub fn iter_from<Q>(&self, value: &Q) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        Range(self.map.iter_from(value))
+    }
+
+    /// Remove the values in `range`, returning them in an iterator in
+    /// increasing order. Cutting them out takes `O(log n)`, and they are
+    /// removed even if the iterator is not consumed.
+    pub fn drain<Q, R>(&mut self, range: R) -> Drain<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+        R: RangeBounds<Q>,
+    {
+        Drain(self.map.drain(range))
+    }
+
+    /// Split the set at `value`, keeping the smaller values and returning
+    /// the others, in `O(log n)` plus the size of the smaller part. See
+    /// [`TreeMap::split_off`].
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q> + Clone,
+        B: Clone,
+    {
+        Self {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Check the invariants of the tree, see [`TreeMap::validate`].
+    /// # Errors
+    /// Return `InvariantViolation` Error at the first offending node.
+    pub fn validate(&self) -> Result<()> {
+        self.map.validate()
+    }
+
+    /// Iterate in increasing order over the values in `self` or `other`,
+    /// in `O(n + m)` without allocating. A value in both is taken from
+    /// `self`.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, C> {
+        Union {
+            merged: Merged::new(self, other),
+        }
+    }
+
+    /// Iterate in increasing order over the values in both `self` and
+    /// `other`, see [`TreeSet::union`].
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, C> {
+        Intersection {
+            merged: Merged::new(self, other),
+        }
+    }
+
+    /// Iterate in increasing order over the values in `self` but not in
+    /// `other`, see [`TreeSet::union`].
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, C> {
+        Difference {
+            merged: Merged::new(self, other),
+        }
+    }
+
+    /// Iterate in increasing order over the values in exactly one of
+    /// `self` and `other`, see [`TreeSet::union`].
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, C> {
+        SymmetricDifference {
+            merged: Merged::new(self, other),
+        }
+    }
+}
+
+macro_rules! set_op {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl<T, C: Comparator<T>, B: Balance> $trait for TreeSet<T, C, B> {
+            type Output = Self;
+
+            /// Combine the sets by splitting and joining their trees, see
+            /// [`TreeSet`]. A value in both is taken from `self`.
+            fn $method(self, other: Self) -> Self {
+                Self {
+                    map: self.map.combine(other.map, SetOp::$op),
+                }
+            }
+        }
+    };
+}
+
+set_op!(BitOr, bitor, Union);
+set_op!(BitAnd, bitand, Intersection);
+set_op!(Sub, sub, Difference);
+set_op!(BitXor, bitxor, SymmetricDifference);
+
+impl<T: DataSize, C, B> DataSize for TreeSet<T, C, B> {
+    fn heap_size(&self) -> usize {
+        self.map.heap_size()
+    }
+}
+
+impl<T: fmt::Debug, C, B> fmt::Debug for TreeSet<T, C, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq, C, B> PartialEq for TreeSet<T, C, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq, C, B> Eq for TreeSet<T, C, B> {}
+
+impl<T, C: Comparator<T> + Default, B: Balance + Default> FromIterator<T> for TreeSet<T, C, B> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, C: Comparator<T>, B: Balance> Extend<T> for TreeSet<T, C, B> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|value| (value, ())));
+    }
+}
+
+impl<'a, T: Copy, C: Comparator<T>, B: Balance> Extend<&'a T> for TreeSet<T, C, B> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T: Ord> From<BTreeSet<T>> for TreeSet<T> {
+    /// Bulk load the values in `O(n)`, as they are already sorted.
+    fn from(set: BTreeSet<T>) -> Self {
+        Self::from_sorted_iter(set).expect("a `BTreeSet` is sorted")
+    }
+}
+
+impl<T: Ord, C, B> From<TreeSet<T, C, B>> for BTreeSet<T> {
+    /// Collect the values, re-sorting them by `Ord` if the set was ordered
+    /// by another comparator.
+    fn from(set: TreeSet<T, C, B>) -> Self {
+        set.into_iter().collect()
+    }
+}
+
+impl<T, C, B> IntoIterator for TreeSet<T, C, B> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.map.into_iter())
+    }
+}
+
+impl<'a, T, C, B> IntoIterator for &'a TreeSet<T, C, B> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`TreeSet`].
+#[derive(Debug)]
+pub struct Iter<'a, T>(tree_map::Keys<'a, T, ()>);
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Iterator moving the values out of a [`TreeSet`].
+#[derive(Debug)]
+pub struct IntoIter<T>(tree_map::IntoIter<T, ()>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+/// Iterator moving a range of values out of a [`TreeSet`], created by
+/// [`TreeSet::drain`].
+#[derive(Debug)]
+pub struct Drain<'a, T>(tree_map::Drain<'a, T, (), Global>);
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+
+/// Iterator over the values of a [`TreeSet`] in a range.
+#[derive(Debug)]
+pub struct Range<'a, T>(tree_map::Range<'a, T, ()>);
+
+impl<'a, T> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(value, _)| value)
+    }
+}
+
+/// Walk two sets in step, pairing up equal values.
+#[derive(Debug)]
+struct Merged<'a, T, C> {
+    a: Peekable<Iter<'a, T>>,
+    b: Peekable<Iter<'a, T>>,
+    cmp: &'a C,
+}
+
+impl<'a, T, C> Merged<'a, T, C> {
+    fn new<B>(a: &'a TreeSet<T, C, B>, b: &'a TreeSet<T, C, B>) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+            cmp: a.comparator(),
+        }
+    }
+}
+
+impl<'a, T, C: Comparator<T>> Iterator for Merged<'a, T, C> {
+    /// The value from either set or both.
+    type Item = (Option<&'a T>, Option<&'a T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ord = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => self.cmp.compare(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+        Some(match ord {
+            Ordering::Less => (self.a.next(), None),
+            Ordering::Greater => (None, self.b.next()),
+            Ordering::Equal => (self.a.next(), self.b.next()),
+        })
+    }
+}
+
+macro_rules! set_iter {
+    ($(#[$doc:meta])* $name:ident, |$a:ident, $b:ident| $pick:expr) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<'a, T, C = Natural> {
+            merged: Merged<'a, T, C>,
+        }
+
+        impl<'a, T, C: Comparator<T>> Iterator for $name<'a, T, C> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.merged.find_map(|($a, $b)| $pick)
+            }
+        }
+    };
+}
+
+set_iter!(
+    /// Lazy union of two sets, see [`TreeSet::union`].
+    Union,
+    |a, b| a.or(b)
+);
+
+set_iter!(
+    /// Lazy intersection of two sets, see [`TreeSet::intersection`].
+    Intersection,
+    |a, b| b.and(a)
+);
+
+set_iter!(
+    /// Lazy difference of two sets, see [`TreeSet::difference`].
+    Difference,
+    |a, b| if b.is_none() { a } else { None }
+);
+
+set_iter!(
+    /// Lazy symmetric difference of two sets, see
+    /// [`TreeSet::symmetric_difference`].
+    SymmetricDifference,
+    |a, b| if a.is_some() != b.is_some() { a.or(b) } else { None }
+);
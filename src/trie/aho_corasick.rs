@@ -0,0 +1,135 @@
+use super::{Trie, ROOT};
+use std::borrow::Borrow;
+use std::collections::VecDeque;
+
+/// A match reported by the automaton.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    /// Pattern index given by the trie.
+    pub pattern: usize,
+    /// Start position of the match in the haystack.
+    pub start: usize,
+    /// End position (exclusive) of the match in the haystack.
+    pub end: usize,
+}
+
+/// Multi-pattern matcher built from a trie by adding failure links.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<K> {
+    trie: Trie<K>,
+    fail: Vec<usize>,
+    output: Vec<Option<usize>>,
+}
+
+impl<K: Ord> AhoCorasick<K> {
+    /// Build the failure and output links of `trie`.
+    pub fn new(trie: Trie<K>) -> Self {
+        let len = trie.nodes.len();
+        let mut fail = vec![ROOT; len];
+        let mut output = vec![None; len];
+        let mut queue: VecDeque<_> = trie.nodes[ROOT].children.values().copied().collect();
+
+        while let Some(node) = queue.pop_front() {
+            for (symbol, &child) in trie.nodes[node].children.iter() {
+                let mut state = fail[node];
+                fail[child] = loop {
+                    if let Some(&next) = trie.nodes[state].children.get(symbol) {
+                        break next;
+                    }
+                    if state == ROOT {
+                        break ROOT;
+                    }
+                    state = fail[state];
+                };
+                let suffix = fail[child];
+                output[child] = if trie.nodes[suffix].pattern.is_some() {
+                    Some(suffix)
+                } else {
+                    output[suffix]
+                };
+                queue.push_back(child);
+            }
+        }
+
+        Self { trie, fail, output }
+    }
+
+    /// Get the underlying trie.
+    pub fn trie(&self) -> &Trie<K> {
+        &self.trie
+    }
+
+    /// Create an iterator over all (possibly overlapping) matches
+    /// in `haystack`, ordered by end position.
+    pub fn find_iter<I>(&self, haystack: I) -> FindIter<'_, K, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<K>,
+    {
+        FindIter {
+            automaton: self,
+            haystack: haystack.into_iter(),
+            state: ROOT,
+            position: 0,
+            pending: None,
+        }
+    }
+
+    fn step(&self, mut state: usize, symbol: &K) -> usize {
+        loop {
+            if let Some(&next) = self.trie.nodes[state].children.get(symbol) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.fail[state];
+        }
+    }
+}
+
+impl<K: Ord> From<Trie<K>> for AhoCorasick<K> {
+    fn from(trie: Trie<K>) -> Self {
+        Self::new(trie)
+    }
+}
+
+/// Streaming match iterator of an [`AhoCorasick`] automaton.
+#[derive(Debug)]
+pub struct FindIter<'a, K, I> {
+    automaton: &'a AhoCorasick<K>,
+    haystack: I,
+    state: usize,
+    position: usize,
+    pending: Option<usize>,
+}
+
+impl<'a, K, I> Iterator for FindIter<'a, K, I>
+where
+    K: Ord,
+    I: Iterator,
+    I::Item: Borrow<K>,
+{
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.pending {
+                let automaton = self.automaton;
+                self.pending = automaton.output[node];
+                if let Some(pattern) = automaton.trie.nodes[node].pattern {
+                    return Some(Match {
+                        pattern,
+                        start: self.position - automaton.trie.nodes[node].depth,
+                        end: self.position,
+                    });
+                }
+                continue;
+            }
+            let symbol = self.haystack.next()?;
+            self.position += 1;
+            self.state = self.automaton.step(self.state, symbol.borrow());
+            self.pending = Some(self.state);
+        }
+    }
+}
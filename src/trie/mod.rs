@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+/// Aho-Corasick automaton.
+pub mod aho_corasick;
+
+pub use aho_corasick::AhoCorasick;
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone)]
+struct TrieNode<K> {
+    children: BTreeMap<K, usize>,
+    pattern: Option<usize>,
+    depth: usize,
+}
+
+impl<K> TrieNode<K> {
+    fn new(depth: usize) -> Self {
+        Self {
+            children: BTreeMap::new(),
+            pattern: None,
+            depth,
+        }
+    }
+}
+
+/// Prefix tree over sequences of `K`.
+///
+/// Every inserted word is given a pattern index in insertion order.
+#[derive(Debug, Clone)]
+pub struct Trie<K> {
+    nodes: Vec<TrieNode<K>>,
+    len: usize,
+}
+
+impl<K> Default for Trie<K> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![TrieNode::new(0)],
+            len: 0,
+        }
+    }
+}
+
+impl<K: Ord> Trie<K> {
+    /// Create an empty trie.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of words in the trie.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the trie contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a word, returning its pattern index.
+    /// Inserting an existing word returns the index it was given before.
+    pub fn insert<I>(&mut self, word: I) -> usize
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut node = ROOT;
+        for symbol in word {
+            node = match self.nodes[node].children.get(&symbol) {
+                Some(&next) => next,
+                None => {
+                    let next = self.nodes.len();
+                    let depth = self.nodes[node].depth + 1;
+                    self.nodes.push(TrieNode::new(depth));
+                    self.nodes[node].children.insert(symbol, next);
+                    next
+                }
+            };
+        }
+        let len = &mut self.len;
+        *self.nodes[node].pattern.get_or_insert_with(|| {
+            *len += 1;
+            *len - 1
+        })
+    }
+
+    /// Return the pattern index of `word` if it is in the trie.
+    pub fn get<I>(&self, word: I) -> Option<usize>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.walk(word).and_then(|node| self.nodes[node].pattern)
+    }
+
+    /// Return `true` if `word` is in the trie.
+    pub fn contains<I>(&self, word: I) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.get(word).is_some()
+    }
+
+    /// Return `true` if some word in the trie starts with `prefix`.
+    pub fn starts_with<I>(&self, prefix: I) -> bool
+    where
+        I: IntoIterator<Item = K>,
+    {
+        self.walk(prefix).is_some()
+    }
+
+    /// Build an Aho-Corasick automaton over the words of the trie.
+    pub fn into_automaton(self) -> AhoCorasick<K> {
+        AhoCorasick::new(self)
+    }
+
+    fn walk<I>(&self, word: I) -> Option<usize>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        word.into_iter().try_fold(ROOT, |node, symbol| {
+            self.nodes[node].children.get(&symbol).copied()
+        })
+    }
+}
+
+impl<K: Ord, W: IntoIterator<Item = K>> Extend<W> for Trie<K> {
+    /// Insert the words in order, giving new ones the next pattern indices.
+    fn extend<I: IntoIterator<Item = W>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|word| {
+            self.insert(word);
+        });
+    }
+}
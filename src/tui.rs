@@ -0,0 +1,193 @@
+use crate::explorer::Explorer;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+
+/// State of a [`TreeView`]: the [`Explorer`] holding the expanded nodes
+/// and the cursor, the first line shown and the search query.
+///
+/// Key handling is left to the application, which calls the explorer
+/// moves, e.g. [`Explorer::go_down`] on `j` and [`Explorer::toggle`] on
+/// enter, between frames.
+#[derive(Debug, Clone)]
+pub struct TreeViewState {
+    explorer: Explorer,
+    offset: usize,
+    query: String,
+}
+
+impl TreeViewState {
+    /// Create the state of a view of `explorer`, scrolled to the top.
+    pub fn new(explorer: Explorer) -> Self {
+        Self {
+            explorer,
+            offset: 0,
+            query: String::new(),
+        }
+    }
+
+    /// Get the ref of the explorer.
+    pub fn explorer(&self) -> &Explorer {
+        &self.explorer
+    }
+
+    /// Get the mutable ref of the explorer, to move the cursor or expand
+    /// and collapse nodes.
+    pub fn explorer_mut(&mut self) -> &mut Explorer {
+        &mut self.explorer
+    }
+
+    /// Get the index of the first visible line shown.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Get the search query, empty when not searching.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Highlight the labels containing `query` and move the cursor to the
+    /// next one, see [`Explorer::search`]. Return `false` if no other node
+    /// matches.
+    pub fn search(&mut self, query: &str) -> bool {
+        self.query.clear();
+        self.query.push_str(query);
+        self.explorer.search(query)
+    }
+
+    /// Move the cursor to the next match of the current query.
+    pub fn search_next(&mut self) -> bool {
+        !self.query.is_empty() && self.explorer.search(&self.query)
+    }
+
+    /// Stop highlighting the matches.
+    pub fn clear_search(&mut self) {
+        self.query.clear();
+    }
+}
+
+/// Widget drawing the visible lines of a [`TreeViewState`], scrolling to
+/// keep the cursor in view.
+///
+/// Collapsed nodes are marked `▸` and expanded ones `▾`, the selected line
+/// is drawn in the highlight style and the matches of the search query in
+/// the match style.
+#[derive(Debug, Clone)]
+pub struct TreeView<'a> {
+    block: Option<Block<'a>>,
+    style: Style,
+    highlight_style: Style,
+    match_style: Style,
+}
+
+impl Default for TreeView<'_> {
+    fn default() -> Self {
+        Self {
+            block: None,
+            style: Style::default(),
+            highlight_style: Style::default().add_modifier(Modifier::REVERSED),
+            match_style: Style::default().add_modifier(Modifier::BOLD),
+        }
+    }
+}
+
+impl<'a> TreeView<'a> {
+    /// Create a view with reversed highlighting and bold matches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap the view in `block`.
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Set the base style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Set the style of the selected line.
+    pub fn highlight_style(mut self, style: Style) -> Self {
+        self.highlight_style = style;
+        self
+    }
+
+    /// Set the style of the matches of the search query.
+    pub fn match_style(mut self, style: Style) -> Self {
+        self.match_style = style;
+        self
+    }
+
+    /// Split `label` into spans, styling the matches of `query`.
+    fn label<'l>(&self, label: &'l str, query: &str) -> Vec<Span<'l>> {
+        if query.is_empty() {
+            return vec![Span::raw(label)];
+        }
+        let mut spans = Vec::new();
+        let mut rest = label;
+        while let Some(at) = rest.find(query) {
+            let (before, found) = rest.split_at(at);
+            let (found, after) = found.split_at(query.len());
+            if !before.is_empty() {
+                spans.push(Span::raw(before));
+            }
+            spans.push(Span::styled(found, self.match_style));
+            rest = after;
+        }
+        if !rest.is_empty() {
+            spans.push(Span::raw(rest));
+        }
+        spans
+    }
+}
+
+impl StatefulWidget for TreeView<'_> {
+    type State = TreeViewState;
+
+    fn render(mut self, area: Rect, buf: &mut Buffer, state: &mut TreeViewState) {
+        buf.set_style(area, self.style);
+        let area = match self.block.take() {
+            Some(block) => {
+                let inner = block.inner(area);
+                block.render(area, buf);
+                inner
+            }
+            None => area,
+        };
+        if area.is_empty() {
+            return;
+        }
+        let lines = state.explorer.lines();
+        let height = usize::from(area.height);
+        // Scroll as little as possible to keep the cursor in view, without
+        // leaving blank lines at the bottom.
+        let cursor = lines.iter().position(|line| line.selected).unwrap_or(0);
+        let mut offset = state.offset.min(lines.len().saturating_sub(height));
+        if cursor < offset {
+            offset = cursor;
+        } else if cursor >= offset + height {
+            offset = cursor + 1 - height;
+        }
+        state.offset = offset;
+        for (y, line) in (area.y..).zip(lines.iter().skip(offset).take(height)) {
+            let marker = match line.expanded {
+                Some(true) => "▾ ",
+                Some(false) => "▸ ",
+                None => "",
+            };
+            let mut spans = vec![Span::raw(line.prefix.as_str()), Span::raw(marker)];
+            spans.extend(self.label(line.label, &state.query));
+            buf.set_line(area.x, y, &Line::from(spans), area.width);
+            if line.selected {
+                let row = Rect::new(area.x, y, area.width, 1);
+                buf.set_style(row, self.highlight_style);
+            }
+        }
+    }
+}
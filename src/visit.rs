@@ -0,0 +1,217 @@
+use crate::binary_tree::Node as BinaryNode;
+use crate::tree::Node;
+
+/// Indices of the children to follow from a root to a node, `0` going
+/// left and `1` going right in binary trees.
+pub type Path = Vec<usize>;
+
+/// What a visit does after the callback returns, as in `walkdir`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraversalControl {
+    /// Go on, descending into the children of the node.
+    Continue,
+    /// Go on, but skip the descendants of the node.
+    SkipSubtree,
+    /// End the visit at this node.
+    Stop,
+}
+
+impl<T> BinaryNode<T> {
+    /// Visit the nodes in pre order with their depth, without recursion,
+    /// letting `f` prune subtrees or end the visit.
+    ///
+    /// Return the node at which `f` returned `Stop`, if any.
+    pub fn visit<F>(&self, mut f: F) -> Option<&BinaryNode<T>>
+    where
+        F: FnMut(&BinaryNode<T>, usize) -> TraversalControl,
+    {
+        let mut stack = vec![(self, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            match f(node, depth) {
+                TraversalControl::Continue => {
+                    stack.extend(node.right().map(|right| (right, depth + 1)));
+                    stack.extend(node.left().map(|left| (left, depth + 1)));
+                }
+                TraversalControl::SkipSubtree => {}
+                TraversalControl::Stop => return Some(node),
+            }
+        }
+        None
+    }
+}
+
+impl<T> Node<T> {
+    /// Visit the nodes in pre order with their depth, without recursion,
+    /// letting `f` prune subtrees or end the visit.
+    ///
+    /// Return the node at which `f` returned `Stop`, if any.
+    pub fn visit<F>(&self, mut f: F) -> Option<&Node<T>>
+    where
+        F: FnMut(&Node<T>, usize) -> TraversalControl,
+    {
+        let mut stack = vec![(self, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            match f(node, depth) {
+                TraversalControl::Continue => {
+                    let children = node.children().iter().rev();
+                    stack.extend(children.map(|child| (child, depth + 1)));
+                }
+                TraversalControl::SkipSubtree => {}
+                TraversalControl::Stop => return Some(node),
+            }
+        }
+        None
+    }
+}
+
+impl<T> BinaryNode<T> {
+    /// Get the ref of the node at `path`.
+    pub fn get(&self, path: &[usize]) -> Option<&BinaryNode<T>> {
+        let mut node = self;
+        for &idx in path {
+            node = match idx {
+                0 => node.left()?,
+                1 => node.right()?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Get the mutable ref of the node at `path`.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut BinaryNode<T>> {
+        let mut node = self;
+        for &idx in path {
+            node = match idx {
+                0 => node.left_mut()?,
+                1 => node.right_mut()?,
+                _ => return None,
+            };
+        }
+        Some(node)
+    }
+
+    /// Get the first node in pre order whose data matches `pred`, with its
+    /// path for [`BinaryNode::get_mut`].
+    pub fn find<P>(&self, mut pred: P) -> Option<(Path, &BinaryNode<T>)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.find_with_node(|node| if pred(node.data()) { Some(()) } else { None })
+            .map(|(path, node, ())| (path, node))
+    }
+
+    /// Get the first result of `f` on the data in pre order, with the path
+    /// of its node.
+    pub fn find_map<U, F>(&self, mut f: F) -> Option<(Path, U)>
+    where
+        F: FnMut(&T) -> Option<U>,
+    {
+        self.find_with_node(|node| f(node.data()))
+            .map(|(path, _, found)| (path, found))
+    }
+
+    /// Get the path of the first node in pre order whose data matches
+    /// `pred`.
+    pub fn position<P>(&self, pred: P) -> Option<Path>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.find(pred).map(|(path, _)| path)
+    }
+
+    /// Search in pre order without recursion, keeping the path of the
+    /// current node.
+    fn find_with_node<U, F>(&self, mut f: F) -> Option<(Path, &BinaryNode<T>, U)>
+    where
+        F: FnMut(&BinaryNode<T>) -> Option<U>,
+    {
+        let mut path = Vec::new();
+        // Nodes with their depth and index among their siblings.
+        let mut stack = vec![(self, 0, 0)];
+        while let Some((node, depth, idx)) = stack.pop() {
+            if depth > 0 {
+                path.truncate(depth - 1);
+                path.push(idx);
+            }
+            if let Some(found) = f(node) {
+                return Some((path, node, found));
+            }
+            stack.extend(node.right().map(|right| (right, depth + 1, 1)));
+            stack.extend(node.left().map(|left| (left, depth + 1, 0)));
+        }
+        None
+    }
+}
+
+impl<T> Node<T> {
+    /// Get the ref of the node at `path`.
+    pub fn get(&self, path: &[usize]) -> Option<&Node<T>> {
+        let mut node = self;
+        for &idx in path {
+            node = node.child(idx)?;
+        }
+        Some(node)
+    }
+
+    /// Get the mutable ref of the node at `path`.
+    pub fn get_mut(&mut self, path: &[usize]) -> Option<&mut Node<T>> {
+        let mut node = self;
+        for &idx in path {
+            node = node.child_mut(idx)?;
+        }
+        Some(node)
+    }
+
+    /// Get the first node in pre order whose data matches `pred`, with its
+    /// path for [`Node::get_mut`].
+    pub fn find<P>(&self, mut pred: P) -> Option<(Path, &Node<T>)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.find_with_node(|node| if pred(node.data()) { Some(()) } else { None })
+            .map(|(path, node, ())| (path, node))
+    }
+
+    /// Get the first result of `f` on the data in pre order, with the path
+    /// of its node.
+    pub fn find_map<U, F>(&self, mut f: F) -> Option<(Path, U)>
+    where
+        F: FnMut(&T) -> Option<U>,
+    {
+        self.find_with_node(|node| f(node.data()))
+            .map(|(path, _, found)| (path, found))
+    }
+
+    /// Get the path of the first node in pre order whose data matches
+    /// `pred`.
+    pub fn position<P>(&self, pred: P) -> Option<Path>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.find(pred).map(|(path, _)| path)
+    }
+
+    /// Search in pre order without recursion, keeping the path of the
+    /// current node.
+    fn find_with_node<U, F>(&self, mut f: F) -> Option<(Path, &Node<T>, U)>
+    where
+        F: FnMut(&Node<T>) -> Option<U>,
+    {
+        let mut path = Vec::new();
+        // Nodes with their depth and index among their siblings.
+        let mut stack = vec![(self, 0, 0)];
+        while let Some((node, depth, idx)) = stack.pop() {
+            if depth > 0 {
+                path.truncate(depth - 1);
+                path.push(idx);
+            }
+            if let Some(found) = f(node) {
+                return Some((path, node, found));
+            }
+            let children = node.children().iter().enumerate().rev();
+            stack.extend(children.map(|(idx, child)| (child, depth + 1, idx)));
+        }
+        None
+    }
+}
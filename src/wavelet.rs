@@ -0,0 +1,189 @@
+use crate::bitvec::BitVec;
+use crate::{Error, Result};
+use std::ops::Range;
+
+/// Wavelet tree over a sequence of integers, stored as a wavelet matrix.
+///
+/// Uses one bit vector per bit of the largest value, so every query
+/// below runs in `O(log σ)` (`select` in `O(log σ log n)`).
+#[derive(Debug, Clone, Default)]
+pub struct WaveletTree {
+    /// Levels from the most significant bit down, with their zero counts.
+    levels: Vec<(BitVec, usize)>,
+    len: usize,
+}
+
+impl WaveletTree {
+    /// Build the tree over `values` in `O(n log σ)`.
+    pub fn new(values: &[u64]) -> Self {
+        let max = values.iter().copied().max().unwrap_or(0);
+        let bits = (64 - max.leading_zeros()).max(1);
+        let mut current = values.to_vec();
+        let mut levels = Vec::with_capacity(bits as usize);
+        for level in (0..bits).rev() {
+            let bit = |value: &u64| value >> level & 1 == 1;
+            let bv = BitVec::new(current.iter().map(bit));
+            let (ones, zeros): (Vec<u64>, Vec<u64>) = current.into_iter().partition(bit);
+            levels.push((bv, zeros.len()));
+            current = zeros;
+            current.extend(ones);
+        }
+        Self {
+            levels,
+            len: values.len(),
+        }
+    }
+
+    /// Get the number of values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the value at `index`.
+    pub fn get(&self, mut index: usize) -> Option<u64> {
+        if index >= self.len {
+            return None;
+        }
+        let mut value = 0;
+        for (bv, zeros) in &self.levels {
+            value <<= 1;
+            if bv.get(index) {
+                value |= 1;
+                index = zeros + bv.rank1(index);
+            } else {
+                index = bv.rank0(index);
+            }
+        }
+        Some(value)
+    }
+
+    /// Count the occurrences of `value` in `0..end`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `end` is out of bounds.
+    pub fn rank(&self, value: u64, end: usize) -> Result<usize> {
+        self.check(&(0..end))?;
+        Ok(self
+            .descend(value, 0..end)
+            .map_or(0, |range| range.end - range.start))
+    }
+
+    /// Find the position of the `k`-th (0-based) occurrence of `value`.
+    pub fn select(&self, value: u64, k: usize) -> Option<usize> {
+        let range = self.descend(value, 0..self.len)?;
+        if range.start + k >= range.end {
+            return None;
+        }
+        let mut position = range.start + k;
+        for (level, (bv, zeros)) in self.levels.iter().enumerate().rev() {
+            position = if self.bit(value, level) {
+                bv.select(true, position - zeros)?
+            } else {
+                bv.select(false, position)?
+            };
+        }
+        Some(position)
+    }
+
+    /// Find the `k`-th (0-based) smallest value in `range`.
+    /// Return `Ok(None)` if `range` holds at most `k` values.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `range` is out of bounds.
+    pub fn quantile(&self, range: Range<usize>, mut k: usize) -> Result<Option<u64>> {
+        self.check(&range)?;
+        if range.end - range.start <= k {
+            return Ok(None);
+        }
+        let (mut start, mut end) = (range.start, range.end);
+        let mut value = 0;
+        for (bv, zeros) in &self.levels {
+            value <<= 1;
+            let in_zeros = bv.rank0(end) - bv.rank0(start);
+            if k < in_zeros {
+                start = bv.rank0(start);
+                end = bv.rank0(end);
+            } else {
+                k -= in_zeros;
+                value |= 1;
+                start = zeros + bv.rank1(start);
+                end = zeros + bv.rank1(end);
+            }
+        }
+        Ok(Some(value))
+    }
+
+    /// Count the values in `range` lying within `values`.
+    /// # Errors
+    /// Return `IndexOutOfRange` Error when `range` is out of bounds.
+    pub fn range_count(&self, range: Range<usize>, values: Range<u64>) -> Result<usize> {
+        self.check(&range)?;
+        if values.start >= values.end {
+            return Ok(0);
+        }
+        Ok(self.count_less(&range, values.end) - self.count_less(&range, values.start))
+    }
+
+    fn check(&self, range: &Range<usize>) -> Result<()> {
+        if range.start > range.end || range.end > self.len {
+            Err(Error::IndexOutOfRange {
+                index: range.end.max(range.start),
+                len: self.len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the bit of `value` examined at `level`.
+    fn bit(&self, value: u64, level: usize) -> bool {
+        value >> (self.levels.len() - 1 - level) & 1 == 1
+    }
+
+    /// Return `true` if `value` needs more bits than the levels hold.
+    fn too_wide(&self, value: u64) -> bool {
+        self.levels.len() < 64 && value >> self.levels.len() != 0
+    }
+
+    /// Follow `value` down to the bottom level, narrowing `range` to the
+    /// positions of its occurrences there.
+    fn descend(&self, value: u64, range: Range<usize>) -> Option<Range<usize>> {
+        if self.too_wide(value) {
+            return None;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+        for (level, (bv, zeros)) in self.levels.iter().enumerate() {
+            if self.bit(value, level) {
+                start = zeros + bv.rank1(start);
+                end = zeros + bv.rank1(end);
+            } else {
+                start = bv.rank0(start);
+                end = bv.rank0(end);
+            }
+        }
+        Some(start..end)
+    }
+
+    /// Count the values in `range` smaller than `bound`.
+    fn count_less(&self, range: &Range<usize>, bound: u64) -> usize {
+        if self.too_wide(bound) {
+            return range.end - range.start;
+        }
+        let (mut start, mut end) = (range.start, range.end);
+        let mut count = 0;
+        for (level, (bv, zeros)) in self.levels.iter().enumerate() {
+            if self.bit(bound, level) {
+                count += bv.rank0(end) - bv.rank0(start);
+                start = zeros + bv.rank1(start);
+                end = zeros + bv.rank1(end);
+            } else {
+                start = bv.rank0(start);
+                end = bv.rank0(end);
+            }
+        }
+        count
+    }
+}
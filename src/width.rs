@@ -0,0 +1,133 @@
+//! Terminal column widths of text.
+
+/// Ranges of characters taking two columns: East Asian wide and fullwidth
+/// characters and emoji presented as pictographs.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115f),
+    (0x231a, 0x231b),
+    (0x2329, 0x232a),
+    (0x23e9, 0x23ec),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x26aa, 0x26ab),
+    (0x26bd, 0x26be),
+    (0x26f5, 0x26f5),
+    (0x26fd, 0x26fd),
+    (0x2705, 0x2705),
+    (0x270a, 0x270b),
+    (0x274c, 0x274c),
+    (0x2753, 0x2755),
+    (0x2795, 0x2797),
+    (0x2b1b, 0x2b1c),
+    (0x2e80, 0x303e),
+    (0x3041, 0x33ff),
+    (0x3400, 0x4dbf),
+    (0x4e00, 0x9fff),
+    (0xa000, 0xa4cf),
+    (0xa960, 0xa97f),
+    (0xac00, 0xd7a3),
+    (0xf900, 0xfaff),
+    (0xfe10, 0xfe19),
+    (0xfe30, 0xfe6f),
+    (0xff00, 0xff60),
+    (0xffe0, 0xffe6),
+    (0x16fe0, 0x18cff),
+    (0x1b000, 0x1b2ff),
+    (0x1f004, 0x1f004),
+    (0x1f0cf, 0x1f0cf),
+    (0x1f18e, 0x1f18e),
+    (0x1f191, 0x1f19a),
+    (0x1f200, 0x1f251),
+    (0x1f300, 0x1f64f),
+    (0x1f680, 0x1f6ff),
+    (0x1f7e0, 0x1f7eb),
+    (0x1f90c, 0x1f9ff),
+    (0x1fa70, 0x1faff),
+    (0x20000, 0x2fffd),
+    (0x30000, 0x3fffd),
+];
+
+/// Ranges of characters taking no column: combining marks, zero width
+/// spaces and joiners, and variation selectors.
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036f),
+    (0x0483, 0x0489),
+    (0x0591, 0x05bd),
+    (0x0610, 0x061a),
+    (0x064b, 0x065f),
+    (0x0e31, 0x0e31),
+    (0x0e34, 0x0e3a),
+    (0x0e47, 0x0e4e),
+    (0x1ab0, 0x1aff),
+    (0x1dc0, 0x1dff),
+    (0x200b, 0x200f),
+    (0x2028, 0x202e),
+    (0x2060, 0x2064),
+    (0x20d0, 0x20ff),
+    (0xfe00, 0xfe0f),
+    (0xfe20, 0xfe2f),
+    (0xfeff, 0xfeff),
+    (0xe0100, 0xe01ef),
+];
+
+fn contains(ranges: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    ranges
+        .binary_search_by(|&(start, end)| {
+            if end < c {
+                std::cmp::Ordering::Less
+            } else if start > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Get the number of columns `c` takes in a terminal.
+pub(crate) fn char_width(c: char) -> usize {
+    if c.is_control() || contains(ZERO, c) {
+        0
+    } else if contains(WIDE, c) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Get the number of columns `text` takes in a terminal.
+pub(crate) fn str_width(text: &str) -> usize {
+    text.chars().map(char_width).sum()
+}
+
+/// Cut every line of `text` to at most `max` columns, ending the cut
+/// lines with `…`.
+pub(crate) fn truncate(text: &str, max: Option<usize>) -> String {
+    let max = match max {
+        Some(max) => max,
+        None => return text.to_owned(),
+    };
+    let mut out = String::with_capacity(text.len());
+    for (idx, line) in text.split('\n').enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        if str_width(line) <= max {
+            out.push_str(line);
+            continue;
+        }
+        let mut width = 0;
+        for c in line.chars() {
+            width += char_width(c);
+            if width + 1 > max {
+                break;
+            }
+            out.push(c);
+        }
+        if max > 0 {
+            out.push('…');
+        }
+    }
+    out
+}
@@ -0,0 +1,92 @@
+use gray_tree::trie::aho_corasick::Match;
+use gray_tree::trie::Trie;
+
+/// Every occurrence of every pattern, by scanning the haystack.
+fn naive(patterns: &[&str], haystack: &str) -> Vec<Match> {
+    let haystack = haystack.as_bytes();
+    let mut matches = Vec::new();
+    for end in 1..=haystack.len() {
+        for (pattern, word) in patterns.iter().enumerate() {
+            if haystack[..end].ends_with(word.as_bytes()) {
+                matches.push(Match {
+                    pattern,
+                    start: end - word.len(),
+                    end,
+                });
+            }
+        }
+    }
+    matches
+}
+
+fn automaton_matches(patterns: &[&str], haystack: &str) -> Vec<Match> {
+    let mut trie = Trie::new();
+    for (idx, word) in patterns.iter().enumerate() {
+        assert_eq!(trie.insert(word.bytes()), idx);
+    }
+    let matches: Vec<Match> = trie.into_automaton().find_iter(haystack.bytes()).collect();
+    assert!(matches.windows(2).all(|pair| pair[0].end <= pair[1].end));
+    matches
+}
+
+fn sorted(mut matches: Vec<Match>) -> Vec<Match> {
+    matches.sort_by_key(|found| (found.end, found.start, found.pattern));
+    matches
+}
+
+#[test]
+fn overlapping_and_suffix_patterns() {
+    // Patterns that overlap, and patterns that are suffixes of others.
+    let patterns = ["he", "she", "his", "hers", "e", "s", "ers"];
+    let haystack = "ushershishehers";
+    let found = automaton_matches(&patterns, haystack);
+    assert_eq!(sorted(found.clone()), sorted(naive(&patterns, haystack)));
+    // "she", "he" and "e" all end at the same position.
+    let at_4: Vec<usize> = found
+        .iter()
+        .filter(|found| found.end == 4)
+        .map(|found| found.pattern)
+        .collect();
+    assert_eq!(at_4.len(), 3);
+
+    let patterns = ["a", "aa", "aaa"];
+    let found = automaton_matches(&patterns, "aaaa");
+    assert_eq!(found.len(), 4 + 3 + 2);
+    assert_eq!(sorted(found), sorted(naive(&patterns, "aaaa")));
+    assert!(automaton_matches(&patterns, "bbb").is_empty());
+}
+
+#[test]
+fn matches_naive_scan() {
+    let mut seed: u64 = 3;
+    let mut next = |modulo: u64| {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (seed >> 33) % modulo
+    };
+    for _ in 0..50 {
+        let words: Vec<String> = (0..1 + next(8))
+            .map(|_| {
+                (0..1 + next(4))
+                    .map(|_| (b'a' + next(3) as u8) as char)
+                    .collect()
+            })
+            .collect();
+        // Distinct patterns, so that pattern indices follow the words.
+        let mut patterns: Vec<&str> = Vec::new();
+        for word in &words {
+            if !patterns.contains(&word.as_str()) {
+                patterns.push(word);
+            }
+        }
+        let haystack: String = (0..next(60))
+            .map(|_| (b'a' + next(3) as u8) as char)
+            .collect();
+        assert_eq!(
+            sorted(automaton_matches(&patterns, &haystack)),
+            sorted(naive(&patterns, &haystack)),
+            "{:?} in {:?}",
+            patterns,
+            haystack
+        );
+    }
+}
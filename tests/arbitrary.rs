@@ -0,0 +1,120 @@
+#![cfg(any(feature = "proptest", feature = "quickcheck"))]
+
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::binary_tree::Node;
+use gray_tree::tree_map::TreeMap;
+use gray_tree::tree_set::TreeSet;
+
+#[cfg(feature = "proptest")]
+#[test]
+fn proptest_shrinks_by_removing_subtrees() {
+    use proptest::arbitrary::any_with;
+    use proptest::collection::size_range;
+    use proptest::prop_assert;
+    use proptest::strategy::{Strategy, ValueTree};
+    use proptest::test_runner::{TestError, TestRunner};
+
+    let mut runner = TestRunner::deterministic();
+    let strategy = any_with::<Node<u8>>((size_range(1..=40), Default::default()));
+    for _ in 0..100 {
+        let len = strategy
+            .new_tree(&mut runner)
+            .unwrap()
+            .current()
+            .iter()
+            .count();
+        assert!((1..=40).contains(&len));
+    }
+    let result = runner.run(&strategy, |node| {
+        prop_assert!(node.iter().count() < 5);
+        Ok(())
+    });
+    match result {
+        Err(TestError::Fail(_, node)) => assert_eq!(node.iter().count(), 5),
+        result => panic!("unexpected {:?}", result),
+    }
+
+    let strategy = gray_tree::arbitrary::node(0..10u8, 1..=40);
+    let result = runner.run(&strategy, |node| {
+        prop_assert!(node.iter().all(|&value| value != 0));
+        Ok(())
+    });
+    match result {
+        Err(TestError::Fail(_, node)) => assert_eq!(node.iter().collect::<Vec<_>>(), [&0]),
+        result => panic!("unexpected {:?}", result),
+    }
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn proptest_containers() {
+    use proptest::arbitrary::any;
+    use proptest::strategy::{Strategy, ValueTree};
+    use proptest::test_runner::TestRunner;
+
+    let mut runner = TestRunner::deterministic();
+    for _ in 0..50 {
+        let mut tree = any::<BinarySearchTree<u16>>()
+            .new_tree(&mut runner)
+            .unwrap();
+        let len = tree.current().len();
+        while tree.simplify() {
+            let smaller = tree.current();
+            assert!(smaller.len() < len);
+            assert!(smaller
+                .iter()
+                .zip(smaller.iter().skip(1))
+                .all(|(a, b)| a < b));
+        }
+        let set = any::<TreeSet<u16>>()
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        assert!(set.len() < 100);
+        let map = any::<TreeMap<u8, bool>>()
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        assert!(map.len() <= 256);
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn quickcheck_shrinks_by_removing_subtrees() {
+    use quickcheck::{Arbitrary, Gen};
+
+    let mut g = Gen::new(30);
+    let mut node = loop {
+        let node = Node::<u8>::arbitrary(&mut g);
+        let len = node.iter().count();
+        assert!((1..=30).contains(&len));
+        if len >= 5 {
+            break node;
+        }
+    };
+    // Shrink greedily as quickcheck does.
+    while let Some(smaller) = node.shrink().find(|node| node.iter().count() >= 5) {
+        node = smaller;
+    }
+    assert_eq!(node.iter().count(), 5);
+    assert!(node.shrink().all(|node| node.iter().count() < 5));
+
+    let tree = BinarySearchTree::from_sorted(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+    let candidates: Vec<Vec<i32>> = tree
+        .shrink()
+        .map(|tree| tree.iter().copied().collect())
+        .collect();
+    assert_eq!(candidates[0], []);
+    assert_eq!(candidates[1], [1, 2, 3]);
+    assert_eq!(candidates[2], [5, 6, 7]);
+    assert!(candidates.contains(&vec![4, 5, 6, 7]));
+    assert!(candidates.contains(&vec![1, 2, 3, 4, 6, 7]));
+
+    let set: TreeSet<i32> = (0..10).collect();
+    assert!(set.shrink().all(|smaller| smaller.len() < 10));
+    let map: TreeMap<i32, char> = (0..10).zip('a'..).collect();
+    assert!(map.shrink().all(|smaller| smaller
+        .iter()
+        .all(|(key, value)| map.get(key) == Some(value))));
+}
@@ -0,0 +1,183 @@
+use gray_tree::binary_tree::arc::ArcNode;
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, Rng, SplitMix64};
+
+/// Pre order data with the presence of both children.
+type Shape = Vec<(usize, bool, bool)>;
+
+fn shape(root: &Node<usize>) -> Shape {
+    let mut shape = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        shape.push((*node.data(), node.left().is_some(), node.right().is_some()));
+        stack.extend(node.right());
+        stack.extend(node.left());
+    }
+    shape
+}
+
+fn arc_shape(root: &ArcNode<usize>) -> Shape {
+    let mut shape = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        shape.push((*node.data(), node.left().is_some(), node.right().is_some()));
+        stack.extend(node.right());
+        stack.extend(node.left());
+    }
+    shape
+}
+
+/// Get the nodes along `path`, `true` going right, as far as it goes.
+fn follow<'a>(root: &'a ArcNode<usize>, path: &[bool]) -> Vec<&'a ArcNode<usize>> {
+    let mut nodes = vec![root];
+    for &is_right in path {
+        let node = nodes[nodes.len() - 1];
+        match if is_right { node.right() } else { node.left() } {
+            Some(child) => nodes.push(child),
+            None => break,
+        }
+    }
+    nodes
+}
+
+fn follow_mut<'a>(mut node: &'a mut Node<usize>, path: &[bool]) -> &'a mut Node<usize> {
+    for &is_right in path {
+        node = if is_right {
+            node.right_mut().unwrap()
+        } else {
+            node.left_mut().unwrap()
+        };
+    }
+    node
+}
+
+#[test]
+fn path_copies_keep_every_version() {
+    let mut rng = SplitMix64::new(364);
+    let base = gen::uniform(40, &mut rng).unwrap();
+    let mut models = vec![base.clone()];
+    let mut versions = vec![ArcNode::from(base)];
+    for step in 0..300 {
+        let from = (rng.next_u64() % versions.len() as u64) as usize;
+        let path: Vec<bool> = (0..rng.next_u64() % 8)
+            .map(|_| rng.next_u64() % 2 == 1)
+            .collect();
+        let nodes = follow(&versions[from], &path);
+        let path = &path[..nodes.len() - 1];
+        let prune = rng.next_u64().is_multiple_of(4);
+
+        // Copy the target node, then every node above it.
+        let mut model = models[from].clone();
+        let target = follow_mut(&mut model, path);
+        let mut node = if prune {
+            target.set_right(None);
+            nodes[nodes.len() - 1].with_right(None)
+        } else {
+            *target.data_mut() = 1000 + step;
+            nodes[nodes.len() - 1].with_data(1000 + step)
+        };
+        for (parent, &is_right) in nodes.iter().rev().skip(1).zip(path.iter().rev()) {
+            node = if is_right {
+                parent.with_right(Some(node))
+            } else {
+                parent.with_left(Some(node))
+            };
+        }
+
+        // Subtrees off the path stay shared with the old version.
+        let copied = follow(&node, path);
+        for (old, new) in nodes.iter().zip(&copied) {
+            assert!(!old.ptr_eq(new));
+        }
+        for (depth, &is_right) in path.iter().enumerate() {
+            let (old, new) = (nodes[depth], copied[depth]);
+            let (old, new) = if is_right {
+                (old.left(), new.left())
+            } else {
+                (old.right(), new.right())
+            };
+            assert_eq!(old.is_some(), new.is_some());
+            if let (Some(old), Some(new)) = (old, new) {
+                assert!(old.ptr_eq(new));
+            }
+        }
+        models.push(model);
+        versions.push(node);
+    }
+    for (version, model) in versions.iter().zip(&models) {
+        let expected = shape(model);
+        assert_eq!(arc_shape(version), expected);
+        assert_eq!(version.len(), expected.len());
+        assert_eq!(shape(&version.to_node()), expected);
+        let doubled = version.map(|data| data * 2);
+        let expected_doubled: Shape = expected.iter().map(|&(d, l, r)| (d * 2, l, r)).collect();
+        assert_eq!(arc_shape(&doubled), expected_doubled);
+    }
+    // Shared versions are cloned out, unshared ones moved.
+    let expected: Vec<Shape> = models.iter().map(shape).collect();
+    let converted: Vec<Shape> = versions
+        .into_iter()
+        .map(|v| shape(&v.into_node()))
+        .collect();
+    assert_eq!(converted, expected);
+}
+
+fn post_order(node: &Node<usize>, out: &mut Vec<usize>) {
+    if let Some(left) = node.left() {
+        post_order(left, out);
+    }
+    if let Some(right) = node.right() {
+        post_order(right, out);
+    }
+    out.push(*node.data());
+}
+
+/// Check that `new` shares exactly the subtrees of `old` holding no
+/// changed value, returning `true` if `old` holds one.
+fn check_sharing(old: &ArcNode<usize>, new: &ArcNode<usize>, changed: &[bool]) -> bool {
+    let mut any = changed[*old.data()];
+    for (old, new) in [(old.left(), new.left()), (old.right(), new.right())] {
+        if let (Some(old), Some(new)) = (old, new) {
+            any |= check_sharing(old, new, changed);
+        }
+    }
+    assert_eq!(old.ptr_eq(new), !any);
+    any
+}
+
+#[test]
+fn cow_maps_copy_changed_paths_only() {
+    let mut rng = SplitMix64::new(365);
+    for len in 1..40 {
+        // Values are `0..len` in mid order.
+        let model = gen::uniform(len, &mut rng).unwrap();
+        let tree = ArcNode::from(model.clone());
+        let changed: Vec<bool> = (0..len).map(|_| rng.next_u64().is_multiple_of(5)).collect();
+        let mut orders = vec![Vec::new(); 3];
+        orders[0] = shape(&model).into_iter().map(|(data, _, _)| data).collect();
+        orders[1] = (0..len).collect();
+        post_order(&model, &mut orders[2]);
+
+        for (order, expected) in orders.iter().enumerate() {
+            let mut seen = Vec::new();
+            let f = |data: &usize| {
+                seen.push(*data);
+                Some(data + 100).filter(|_| changed[*data])
+            };
+            let mapped = match order {
+                0 => tree.pre_order_map_cow(f),
+                1 => tree.mid_order_map_cow(f),
+                _ => tree.post_order_map_cow(f),
+            };
+            assert_eq!(&seen, expected);
+            let expected_shape: Shape = shape(&model)
+                .into_iter()
+                .map(|(data, l, r)| (if changed[data] { data + 100 } else { data }, l, r))
+                .collect();
+            assert_eq!(arc_shape(&mapped), expected_shape);
+            check_sharing(&tree, &mapped, &changed);
+        }
+        let unchanged = tree.mid_order_map_cow(|_| None);
+        assert!(unchanged.ptr_eq(&tree));
+    }
+}
@@ -0,0 +1,197 @@
+use gray_tree::binary_tree::arena::{Arena, NodeId};
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::Error;
+
+/// Links of every label ever inserted, `None` once removed.
+#[derive(Default)]
+struct Model {
+    nodes: Vec<Option<Links>>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Links {
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+impl Model {
+    fn links(&mut self, label: usize) -> &mut Links {
+        self.nodes[label].as_mut().unwrap()
+    }
+
+    fn is_ancestor(&self, ancestor: usize, mut label: usize) -> bool {
+        loop {
+            if label == ancestor {
+                return true;
+            }
+            match self.nodes[label].unwrap().parent {
+                Some(parent) => label = parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn detach(&mut self, label: usize) {
+        if let Some(parent) = self.links(label).parent.take() {
+            let parent = self.links(parent);
+            if parent.left == Some(label) {
+                parent.left = None;
+            } else {
+                parent.right = None;
+            }
+        }
+    }
+
+    fn set_child(&mut self, parent: usize, child: Option<usize>, is_right: bool) -> Option<usize> {
+        if let Some(child) = child {
+            self.detach(child);
+            self.links(child).parent = Some(parent);
+        }
+        let links = self.links(parent);
+        let slot = if is_right {
+            &mut links.right
+        } else {
+            &mut links.left
+        };
+        let old = std::mem::replace(slot, child);
+        if let Some(old) = old {
+            self.links(old).parent = None;
+        }
+        old
+    }
+
+    fn pre_order(&self, label: usize) -> Vec<usize> {
+        let mut order = Vec::new();
+        let mut stack = vec![label];
+        while let Some(label) = stack.pop() {
+            let links = self.nodes[label].unwrap();
+            order.push(label);
+            stack.extend(links.right);
+            stack.extend(links.left);
+        }
+        order
+    }
+}
+
+#[test]
+fn matches_parent_and_child_links() {
+    let mut rng = SplitMix64::new(361);
+    let mut arena = Arena::with_capacity(8);
+    let mut model = Model::default();
+    // Current handle of every label, and handles gone stale.
+    let mut ids: Vec<NodeId> = Vec::new();
+    let mut stale: Vec<NodeId> = Vec::new();
+    for _ in 0..3000 {
+        if ids.is_empty() || rng.next_u64().is_multiple_of(6) {
+            ids.push(arena.insert(ids.len()));
+            model.nodes.push(Some(Links::default()));
+            continue;
+        }
+        let a = (rng.next_u64() % ids.len() as u64) as usize;
+        let b = (rng.next_u64() % ids.len() as u64) as usize;
+        let live = |label: usize| model.nodes[label].is_some();
+        match rng.next_u64() % 8 {
+            op @ 0..=2 => {
+                let child = if op == 2 { None } else { Some(b) };
+                let is_right = rng.next_u64() % 2 == 1;
+                let handle = child.map(|child| ids[child]);
+                let result = if is_right {
+                    arena.set_right(ids[a], handle)
+                } else {
+                    arena.set_left(ids[a], handle)
+                };
+                if !live(a) || child.is_some_and(|child| !live(child)) {
+                    assert!(matches!(result, Err(Error::RemovedNode)));
+                } else if child.is_some_and(|child| model.is_ancestor(child, a)) {
+                    assert!(matches!(result, Err(Error::WouldCycle)));
+                } else {
+                    let old = model.set_child(a, child, is_right);
+                    assert_eq!(result.unwrap(), old.map(|old| ids[old]));
+                }
+            }
+            3 => {
+                let result = arena.detach(ids[a]);
+                if live(a) {
+                    result.unwrap();
+                    model.detach(a);
+                } else {
+                    assert!(matches!(result, Err(Error::RemovedNode)));
+                }
+            }
+            4 => {
+                let result = arena.remove(ids[a]);
+                if live(a) {
+                    assert_eq!(result.unwrap(), a);
+                    model.detach(a);
+                    let links = model.nodes[a].take().unwrap();
+                    for child in links.left.into_iter().chain(links.right) {
+                        model.links(child).parent = None;
+                    }
+                    stale.push(ids[a]);
+                } else {
+                    assert!(matches!(result, Err(Error::RemovedNode)));
+                }
+            }
+            5 => {
+                let result = arena.remove_subtree(ids[a]);
+                if live(a) {
+                    model.detach(a);
+                    let order = model.pre_order(a);
+                    assert_eq!(result.unwrap(), order);
+                    for label in order {
+                        model.nodes[label] = None;
+                        stale.push(ids[label]);
+                    }
+                } else {
+                    assert!(matches!(result, Err(Error::RemovedNode)));
+                }
+            }
+            6 if live(a) => {
+                // Move the subtree out and back in, under new handles.
+                model.detach(a);
+                let order = model.pre_order(a);
+                let tree: Node<usize> = arena.take_tree(ids[a]).unwrap();
+                let data: Vec<usize> = tree.iter_to_depth(usize::MAX).map(|(_, &d)| d).collect();
+                assert_eq!(data, order);
+                let root = arena.insert_tree(tree);
+                let handles: Vec<NodeId> = arena.pre_order(root).collect();
+                for (&label, handle) in order.iter().zip(handles) {
+                    assert_eq!(arena.get(handle), Some(&label));
+                    stale.push(std::mem::replace(&mut ids[label], handle));
+                }
+            }
+            _ => {
+                if let Some(data) = arena.get_mut(ids[a]) {
+                    assert_eq!(*data, a);
+                } else {
+                    assert!(!live(a));
+                }
+            }
+        }
+
+        let live_count = model.nodes.iter().flatten().count();
+        assert_eq!(arena.len(), live_count);
+        assert_eq!(arena.is_empty(), live_count == 0);
+        for &id in &stale {
+            assert!(!arena.contains(id));
+            assert_eq!(arena.get(id), None);
+            assert_eq!(arena.pre_order(id).count(), 0);
+        }
+        for (label, links) in model.nodes.iter().enumerate() {
+            let id = ids[label];
+            let Some(links) = links else { continue };
+            let at = |label: Option<usize>| label.map(|label| ids[label]);
+            assert_eq!(arena.get(id), Some(&label));
+            assert_eq!(arena.parent(id), at(links.parent));
+            assert_eq!(arena.left(id), at(links.left));
+            assert_eq!(arena.right(id), at(links.right));
+            let mut root = label;
+            while let Some(parent) = model.nodes[root].unwrap().parent {
+                root = parent;
+            }
+            assert_eq!(arena.root(id), ids[root]);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use gray_tree::binary_tree::augment::{AugmentedNode, Height, MaxEnd, MinMax, Size, Sum};
+use gray_tree::binary_tree::Node;
+use gray_tree::{gen, Error};
+use std::ops::Range;
+
+#[test]
+fn aggregates_follow_mutations() {
+    let tree: AugmentedNode<usize, Sum<usize>> = gen::complete(10).unwrap().into();
+    assert_eq!(tree.aggregate(), &Sum(45));
+    tree.validate().unwrap();
+
+    let mut cursor = tree.into_cursor();
+    assert!(cursor.go_left() && cursor.go_right());
+    assert_eq!(cursor.depth(), 2);
+    cursor.modify(|data| *data += 100);
+    assert!(cursor.set_right(Some(AugmentedNode::new(1000))).is_none());
+    assert!(cursor.go_left());
+    assert!(!cursor.go_left() && !cursor.go_right());
+    assert!(cursor.go_up() && cursor.go_up());
+    assert_eq!(cursor.depth(), 1);
+    let tree = cursor.into_root();
+    assert_eq!(tree.aggregate(), &Sum(45 + 100 + 1000));
+    tree.validate().unwrap();
+
+    let mut tree: AugmentedNode<usize, MinMax<usize>> = gen::complete(7).unwrap().into();
+    assert_eq!(tree.aggregate(), &MinMax { min: 0, max: 6 });
+    tree.rotate_left().unwrap();
+    tree.rotate_left().unwrap();
+    assert!(matches!(tree.rotate_left(), Err(Error::MissingChild)));
+    assert_eq!(*tree.data(), 6);
+    assert_eq!(tree.left().unwrap().aggregate(), &MinMax { min: 0, max: 5 });
+    tree.validate().unwrap();
+    let node = tree.into_node();
+    assert!(node.iter().copied().eq(0..7));
+}
+
+#[test]
+fn interval_stabbing() {
+    // Intervals ordered by start, with the largest end of each subtree.
+    type Interval = AugmentedNode<Range<u32>, MaxEnd<u32>>;
+    let leaf = |range: Range<u32>| Some(Interval::new(range));
+    let tree = Interval::with_children(
+        5..20,
+        Some(Interval::with_children(1..4, leaf(0..2), leaf(3..9))),
+        Some(Interval::with_children(15..17, leaf(10..30), None)),
+    );
+    assert_eq!(tree.aggregate(), &MaxEnd(30));
+
+    let stab = |point: u32| {
+        let mut hits = Vec::new();
+        let mut stack = vec![&tree];
+        while let Some(node) = stack.pop() {
+            if node.aggregate().0 <= point {
+                continue;
+            }
+            if node.data().contains(&point) {
+                hits.push(node.data().clone());
+            }
+            stack.extend(node.left());
+            if node.data().start <= point {
+                stack.extend(node.right());
+            }
+        }
+        hits.sort_by_key(|range| range.start);
+        hits
+    };
+    assert_eq!(stab(3), [1..4, 3..9]);
+    assert_eq!(stab(16), [5..20, 10..30, 15..17]);
+    assert_eq!(stab(25).len(), 1);
+    assert_eq!(stab(25)[0], 10..30);
+    assert!(stab(40).is_empty());
+}
+
+#[test]
+fn deep_chains_without_recursion() {
+    let chain = gen::right_chain(100_000).unwrap();
+    let tree: AugmentedNode<usize, Size> = chain.into();
+    assert_eq!(tree.aggregate(), &Size(100_000));
+    let heights: AugmentedNode<usize, Height> = tree.into_node().into();
+    assert_eq!(heights.aggregate(), &Height(100_000));
+    let node: Node<usize> = heights.into_node();
+    assert_eq!(node.iter().count(), 100_000);
+}
+
+#[test]
+fn kth_by_subtree_sizes() {
+    let node = Node::from_sorted_vec((0..100).map(|value| value * 3).collect()).unwrap();
+    let tree: AugmentedNode<u32, Size> = node.into();
+    for k in 0..100 {
+        assert_eq!(tree.kth_smallest(k as usize), Some(&(k * 3)));
+        assert_eq!(tree.kth_largest(k as usize), Some(&(297 - k * 3)));
+    }
+    assert_eq!(tree.kth_smallest(100), None);
+    assert_eq!(tree.kth_largest(100), None);
+}
@@ -0,0 +1,37 @@
+use gray_tree::gen;
+use gray_tree::tree::Node;
+
+#[test]
+fn binary_bounded_traversals() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6, 7}}};
+    let shallow: Vec<_> = tree.iter_to_depth(1).collect();
+    assert_eq!(shallow, [(0, &1), (1, &2), (1, &3)]);
+    assert_eq!(tree.iter_to_depth(0).count(), 1);
+    assert_eq!(tree.iter_to_depth(10).count(), 8);
+
+    assert_eq!(tree.take_nodes(5).unwrap().to_leetcode(), "[1,2,3,4,5]");
+    assert_eq!(
+        tree.take_nodes(100).unwrap().to_leetcode(),
+        tree.to_leetcode()
+    );
+    assert!(tree.take_nodes(0).is_none());
+
+    let chain = gen::left_chain(200_000).unwrap();
+    assert_eq!(chain.iter_to_depth(9).count(), 10);
+    assert_eq!(
+        chain.take_nodes(3).unwrap().to_leetcode(),
+        "[199999,199998,null,199997]"
+    );
+}
+
+#[test]
+fn nary_bounded_traversals() {
+    let tree: Node<String> = Node::from_newick("((d,e)b,(f)c)a;").unwrap();
+    let shallow: Vec<_> = tree
+        .iter_to_depth(1)
+        .map(|(depth, name)| (depth, name.as_str()))
+        .collect();
+    assert_eq!(shallow, [(0, "a"), (1, "b"), (1, "c")]);
+    assert_eq!(tree.take_nodes(4).unwrap().to_newick(), "((d)b,c)a");
+    assert!(tree.take_nodes(0).is_none());
+}
@@ -0,0 +1,71 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::Error;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+fn height<T>(root: Option<&Node<T>>) -> usize {
+    let mut stack: Vec<(usize, &Node<T>)> = root.into_iter().map(|node| (1, node)).collect();
+    let mut height = 0;
+    while let Some((depth, node)) = stack.pop() {
+        height = height.max(depth);
+        stack.extend(node.left().map(|child| (depth + 1, child)));
+        stack.extend(node.right().map(|child| (depth + 1, child)));
+    }
+    height
+}
+
+#[test]
+fn matches_a_btree_set() {
+    let mut rng = SplitMix64::new(360);
+    let mut tree = BinarySearchTree::new();
+    let mut model = BTreeSet::new();
+    for _ in 0..5000 {
+        let value = rng.next_u64() % 300;
+        match rng.next_u64() % 3 {
+            0 | 1 => assert_eq!(tree.insert(value), model.insert(value)),
+            _ => assert_eq!(tree.remove(&value), model.take(&value)),
+        }
+        assert_eq!(tree.contains(&value), model.contains(&value));
+        assert_eq!(tree.len(), model.len());
+        assert_eq!(tree.is_empty(), model.is_empty());
+        assert_eq!(tree.first(), model.iter().next());
+        assert_eq!(tree.last(), model.iter().next_back());
+    }
+    assert!(tree.iter().eq(model.iter()));
+    assert!((&tree).into_iter().eq(model.iter()));
+    let node = tree.into_node().unwrap();
+    let tree = BinarySearchTree::<_>::try_from(node).unwrap();
+    assert_eq!(tree.len(), model.len());
+    assert_eq!(Vec::from(tree), model.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn conversions_check_the_order() {
+    for len in 0..100u32 {
+        let values: Vec<u32> = (0..len).map(|v| v * 2).collect();
+        let tree = BinarySearchTree::<_>::try_from(values.clone()).unwrap();
+        assert_eq!(tree.len(), values.len());
+        // Height-balanced: at most `ceil(log2(len + 1))` levels.
+        let bound = (usize::BITS - (len as usize).leading_zeros()) as usize;
+        assert!(height(tree.root()) <= bound);
+        assert!(tree.iter().eq(values.iter()));
+        let node: Option<Node<u32>> = tree.into();
+        assert_eq!(node.is_none(), len == 0);
+    }
+    assert!(matches!(
+        BinarySearchTree::<_>::try_from(vec![1, 3, 3]),
+        Err(Error::Unsorted)
+    ));
+    assert!(matches!(
+        BinarySearchTree::<_>::try_from(vec![2, 1]),
+        Err(Error::Unsorted)
+    ));
+    let mut node = Node::new(2);
+    node.set_left(Some(Node::new(3)));
+    assert!(matches!(
+        BinarySearchTree::<_>::try_from(node),
+        Err(Error::Unsorted)
+    ));
+}
@@ -0,0 +1,126 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::gen;
+use std::convert::TryFrom;
+
+#[test]
+fn rebalanced_keeps_mid_order() {
+    let chain = gen::right_chain(100_000).unwrap();
+    let tree = chain.rebalanced();
+    assert!(tree.iter().copied().eq(0..100_000));
+    let height = tree.level_order_iter().last().unwrap().0 + 1;
+    assert_eq!(height, 17);
+
+    let tree = gray_tree::tree! {3 => {2 => {1,},}}.rebalanced();
+    assert_eq!(tree.to_leetcode(), "[2,1,3]");
+}
+
+#[test]
+fn bst_rebalance() {
+    let mut bst = BinarySearchTree::new();
+    bst.extend(0..7);
+    bst.rebalance();
+    assert_eq!(bst.root().unwrap().to_leetcode(), "[3,1,5,0,2,4,6]");
+    assert_eq!(bst.len(), 7);
+    assert!(bst.validate().is_ok());
+}
+
+#[test]
+fn bst_merge() {
+    let a: BinarySearchTree<_> = [1, 3, 5, 7, 9].iter().copied().collect();
+    let mut b = BinarySearchTree::new();
+    b.extend(&[2, 3, 4, 10, 11, 12]);
+    let merged = a.merge(b);
+    assert_eq!(merged.len(), 10);
+    assert_eq!(Vec::from(merged.clone()), [1, 2, 3, 4, 5, 7, 9, 10, 11, 12]);
+    assert!(merged.validate().is_ok());
+    let height = merged.root().unwrap().level_order_iter().last().unwrap().0 + 1;
+    assert_eq!(height, 4);
+    assert!(BinarySearchTree::<i32>::new()
+        .merge(BinarySearchTree::new())
+        .is_empty());
+}
+
+#[test]
+fn bst_split() {
+    let bst: BinarySearchTree<_> = (0..20).collect();
+    let (less, rest) = bst.clone().split(&7);
+    assert_eq!(Vec::from(less.clone()), (0..7).collect::<Vec<_>>());
+    assert_eq!(Vec::from(rest.clone()), (7..20).collect::<Vec<_>>());
+    assert_eq!((less.len(), rest.len()), (7, 13));
+    assert!(less.validate().is_ok() && rest.validate().is_ok());
+
+    let (less, rest) = bst.clone().split(&100);
+    assert_eq!((less.len(), rest.len()), (20, 0));
+    let (less, rest) = bst.split(&-1);
+    assert_eq!((less.len(), rest.len()), (0, 20));
+    assert!(rest.validate().is_ok());
+}
+
+#[test]
+fn bst_retain() {
+    let mut bst: BinarySearchTree<_> = (0..100).collect();
+    let mut visited = Vec::new();
+    bst.retain(|&value| {
+        visited.push(value);
+        value % 10 < 3
+    });
+    assert!(visited.into_iter().eq(0..100));
+    assert_eq!(bst.len(), 30);
+    assert_eq!(
+        bst.iter().take(4).copied().collect::<Vec<_>>(),
+        [0, 1, 2, 10]
+    );
+    assert!(bst.validate().is_ok());
+    // Pruning in place never makes the tree higher.
+    let height = bst.root().unwrap().level_order_iter().last().unwrap().0 + 1;
+    assert!(height <= 7);
+    bst.retain(|_| false);
+    assert!(bst.is_empty() && bst.root().is_none());
+
+    // Deep trees are pruned without recursion.
+    let mut chain = BinarySearchTree::<_>::try_from(gen::right_chain(100_000).unwrap()).unwrap();
+    chain.retain(|value| value % 2 == 1);
+    assert_eq!(chain.len(), 50_000);
+    assert!(chain.iter().copied().eq((1..100_000).step_by(2)));
+    assert!(chain.validate().is_ok());
+}
+
+#[test]
+fn bst_iter_from() {
+    let bst: BinarySearchTree<_> = (0..50).map(|value| value * 2).collect();
+    assert!(bst.iter_from(&31).copied().eq((32..100).step_by(2)));
+    assert!(bst.iter_from(&-5).copied().eq(bst.iter().copied()));
+    assert_eq!(bst.iter_from(&98).count(), 1);
+    assert_eq!(bst.iter_from(&99).next(), None);
+}
+
+#[test]
+fn bst_kth() {
+    let bst: BinarySearchTree<_> = (0..50).map(|value| value * 2).collect();
+    for k in 0..50 {
+        assert_eq!(bst.kth_smallest(k), Some(&(k * 2)));
+        assert_eq!(bst.kth_largest(k), Some(&(98 - k * 2)));
+    }
+    assert_eq!(bst.kth_smallest(50), None);
+    assert_eq!(bst.kth_largest(usize::MAX), None);
+    assert_eq!(BinarySearchTree::<i32>::new().kth_smallest(0), None);
+}
+
+#[test]
+fn bst_set_operations() {
+    let a: BinarySearchTree<_> = [1, 2, 3, 5, 8].iter().copied().collect();
+    let b: BinarySearchTree<_> = [2, 4, 5, 6].iter().copied().collect();
+    let collect = |iter: &mut dyn Iterator<Item = &i32>| iter.copied().collect::<Vec<_>>();
+    assert_eq!(collect(&mut a.union(&b)), [1, 2, 3, 4, 5, 6, 8]);
+    assert_eq!(collect(&mut a.intersection(&b)), [2, 5]);
+    assert_eq!(collect(&mut a.difference(&b)), [1, 3, 8]);
+    assert_eq!(collect(&mut a.symmetric_difference(&b)), [1, 3, 4, 6, 8]);
+
+    let union = &a | &b;
+    assert_eq!(union.len(), 7);
+    assert!(union.validate().is_ok());
+    assert_eq!(Vec::from(&a & &b), [2, 5]);
+    assert_eq!(Vec::from(&a - &b), [1, 3, 8]);
+    assert_eq!(Vec::from(&b ^ &a), [1, 3, 4, 6, 8]);
+    assert!((&a - &a).is_empty());
+}
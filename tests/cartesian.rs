@@ -0,0 +1,78 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{Rng, SplitMix64};
+
+/// Pre order shape of the cartesian tree of `values[range]`, by index,
+/// splitting at the leftmost minimum.
+fn naive_cartesian(values: &[u32], start: usize, end: usize, out: &mut Vec<(usize, bool, bool)>) {
+    if start == end {
+        return;
+    }
+    let min = (start..end).min_by_key(|&idx| (values[idx], idx)).unwrap();
+    out.push((min, min > start, min + 1 < end));
+    naive_cartesian(values, start, min, out);
+    naive_cartesian(values, min + 1, end, out);
+}
+
+/// Pre order of `(data, has left, has right)`.
+fn pre_order_shape<T>(root: &Node<T>) -> Vec<(&T, bool, bool)> {
+    let mut shape = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        shape.push((node.data(), node.left().is_some(), node.right().is_some()));
+        stack.extend(node.right());
+        stack.extend(node.left());
+    }
+    shape
+}
+
+/// Mid order of the data.
+fn mid_order<T>(root: &Node<T>) -> Vec<&T> {
+    let mut order = Vec::new();
+    let mut stack = Vec::new();
+    let mut cursor = Some(root);
+    while cursor.is_some() || !stack.is_empty() {
+        while let Some(node) = cursor {
+            stack.push(node);
+            cursor = node.left();
+        }
+        let node = stack.pop().unwrap();
+        order.push(node.data());
+        cursor = node.right();
+    }
+    order
+}
+
+#[test]
+fn cartesian_tree_matches_naive_split() {
+    let mut rng = SplitMix64::new(6);
+    for len in 0..60 {
+        // Few distinct values, to have ties.
+        let values: Vec<u32> = (0..len).map(|_| (rng.next_u64() % 5) as u32).collect();
+        let mut expected = Vec::new();
+        naive_cartesian(&values, 0, len, &mut expected);
+        let tree = match Node::cartesian_tree(&values) {
+            Some(tree) => tree,
+            None => {
+                assert!(values.is_empty());
+                continue;
+            }
+        };
+        let base = values.as_ptr() as usize;
+        let shape: Vec<(usize, bool, bool)> = pre_order_shape(&tree)
+            .into_iter()
+            .map(|(value, left, right)| {
+                let idx = (*value as *const u32 as usize - base) / std::mem::size_of::<u32>();
+                (idx, left, right)
+            })
+            .collect();
+        assert_eq!(shape, expected);
+        assert!(mid_order(&tree)
+            .into_iter()
+            .map(|value| **value)
+            .eq(values.iter().copied()));
+
+        let owned = Node::cartesian_tree_from_vec(values.clone()).unwrap();
+        assert!(mid_order(&owned).into_iter().eq(values.iter()));
+        assert_eq!(*owned.data(), *values.iter().min().unwrap());
+    }
+}
@@ -0,0 +1,22 @@
+#![cfg(feature = "color")]
+
+use gray_tree::binary_tree::display::{Color, TreeFormatter};
+use gray_tree::binary_tree::Node;
+
+#[test]
+fn labels_are_colored_by_node() {
+    let mut root = Node::new(2);
+    root.set_left(Some(Node::new(1)));
+    root.set_right(Some(Node::new(3)));
+    let paint = |node: &Node<i32>| match node.data() % 2 {
+        0 => Some(Color::Red),
+        _ => None,
+    };
+    let output = root.display_colored(paint).to_string();
+    assert_eq!(output, "\x1b[31m2\x1b[0m\n├── 1\n└── 3\n");
+
+    let format = TreeFormatter::new().max_depth(Some(0));
+    let paint = |_: &Node<i32>| Some(Color::Rgb(1, 2, 3));
+    let output = root.display_with(format).colored(paint).to_string();
+    assert_eq!(output, "\x1b[38;2;1;2;3m2\x1b[0m\n└── …\n");
+}
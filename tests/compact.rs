@@ -0,0 +1,58 @@
+use gray_tree::binary_tree::Node;
+
+fn sample() -> Node<String> {
+    Node::builder()
+        .data("root".to_owned())
+        .left(
+            Node::builder()
+                .data("left".to_owned())
+                .right(Node::new("ünïcode".to_owned()))
+                .build()
+                .unwrap(),
+        )
+        .right(Node::new(String::new()))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let tree = sample();
+    let mut bytes = Vec::new();
+    tree.write_to(&mut bytes).unwrap();
+    let back: Node<String> = Node::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(back.to_json(), tree.to_json());
+}
+
+#[test]
+fn reject_truncated_and_malformed_input() {
+    let mut bytes = Vec::new();
+    sample().write_to(&mut bytes).unwrap();
+    for len in 0..bytes.len() {
+        assert!(Node::<String>::read_from(&bytes[..len]).is_err());
+    }
+
+    // One node claiming a left child.
+    let mut bytes = 1u64.to_le_bytes().to_vec();
+    bytes.push(0b01);
+    bytes.extend_from_slice(&7i32.to_le_bytes());
+    assert!(Node::<i32>::read_from(bytes.as_slice()).is_err());
+}
+
+#[test]
+fn round_trip_deep_right_spine() {
+    let mut root = Node::new(0u32);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    let mut bytes = Vec::new();
+    root.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 8 + 250_000 + 4_000_000);
+    let back: Node<u32> = Node::read_from(bytes.as_slice()).unwrap();
+    assert!(back
+        .level_order_iter()
+        .map(|(_, &data)| data)
+        .eq((0..1_000_000).rev()));
+}
@@ -0,0 +1,71 @@
+use gray_tree::compare::{Comparator, Natural, Reversed};
+use gray_tree::tree_map::TreeMap;
+use gray_tree::tree_multiset::TreeMultiSet;
+use gray_tree::tree_set::TreeSet;
+use std::cmp::Ordering;
+use std::ops::Bound;
+
+fn case_insensitive(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+#[test]
+fn closure_comparator() {
+    let mut map = TreeMap::with_comparator(|a: &String, b: &String| case_insensitive(a, b));
+    map.insert("Banana".to_string(), 1);
+    map.insert("apple".to_string(), 2);
+    assert_eq!(map.insert("BANANA".to_string(), 3), Some(1));
+    *map.entry("APPLE".to_string()).or_insert(0) += 10;
+    assert_eq!(format!("{:?}", map), r#"{"apple": 12, "Banana": 3}"#);
+    assert_eq!(map.get(&"aPPle".to_string()), Some(&12));
+    assert_eq!(map[&"banana".to_string()], 3);
+    let keys: Vec<_> = map
+        .range("B".to_string()..)
+        .map(|(key, _)| key.as_str())
+        .collect();
+    assert_eq!(keys, ["Banana"]);
+    map.validate().unwrap();
+}
+
+#[test]
+fn reversed_and_keyed_orders() {
+    let mut set: TreeSet<i32, Reversed> = (0..10).collect();
+    assert_eq!(set.first(), Some(&9));
+    assert_eq!(
+        set.range((Bound::Included(&7), Bound::Included(&2)))
+            .copied()
+            .collect::<Vec<_>>(),
+        [7, 6, 5, 4, 3, 2]
+    );
+    assert_eq!(set.drain(..5).collect::<Vec<_>>(), [9, 8, 7, 6]);
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), [5, 4, 3, 2, 1, 0]);
+    set.validate().unwrap();
+
+    #[derive(Debug, PartialEq)]
+    struct Person {
+        name: &'static str,
+        age: u32,
+    }
+    let by_age = |a: &Person, b: &Person| a.age.cmp(&b.age);
+    let mut people = TreeMultiSet::with_comparator(by_age);
+    people.insert(Person {
+        name: "ann",
+        age: 31,
+    });
+    people.insert(Person {
+        name: "bob",
+        age: 25,
+    });
+    people.insert(Person {
+        name: "cid",
+        age: 31,
+    });
+    assert_eq!(people.len(), 3);
+    assert_eq!(people.distinct_len(), 2);
+    assert_eq!(people.first().unwrap().name, "bob");
+    assert_eq!(people.count(&Person { name: "", age: 31 }), 2);
+    people.validate().unwrap();
+
+    assert_eq!(Natural.compare("a", "b"), Ordering::Less);
+    assert_eq!(Reversed(Natural).compare("a", "b"), Ordering::Greater);
+}
@@ -0,0 +1,186 @@
+use gray_tree::concurrent::ConcurrentSearchTree;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+
+#[test]
+fn set_operations() {
+    let tree = ConcurrentSearchTree::new();
+    assert!(tree.is_empty());
+    for value in [5, 3, 8, 1, 4, 7, 9] {
+        assert!(tree.insert(value));
+    }
+    assert!(!tree.insert(4));
+    assert!(tree.contains(&4));
+    assert!(tree.remove(&3));
+    assert!(!tree.remove(&3));
+    assert!(!tree.contains(&3));
+    assert!(tree.remove(&5));
+    assert!(tree.remove(&1));
+    assert!(tree.insert(5));
+    assert_eq!(tree.to_vec(), [4, 5, 7, 8, 9]);
+    assert_eq!(tree.len(), 5);
+    assert_eq!(format!("{:?}", tree), "{4, 5, 7, 8, 9}");
+}
+
+#[test]
+fn disjoint_writers() {
+    let tree = ConcurrentSearchTree::new();
+    thread::scope(|scope| {
+        for t in 0..4 {
+            let tree = &tree;
+            scope.spawn(move || {
+                for i in 0..2000 {
+                    assert!(tree.insert(i * 4 + t));
+                }
+                for i in (0..2000).filter(|i| i % 2 == 0) {
+                    assert!(tree.remove(&(i * 4 + t)));
+                }
+            });
+        }
+    });
+    let expected: Vec<_> = (0..8000).filter(|v| (v / 4) % 2 == 1).collect();
+    assert_eq!(tree.to_vec(), expected);
+    assert_eq!(tree.len(), expected.len());
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Call {
+    Insert,
+    Remove,
+    Contains,
+}
+
+/// A call on one key, with its result and the clock ticks taken just
+/// before the call and just after it returned.
+#[derive(Debug, Clone, Copy)]
+struct Event {
+    call: Call,
+    result: bool,
+    invoked: u64,
+    returned: u64,
+}
+
+/// Search for an order of the calls on one key, each thread's calls in
+/// program order and every call placed between its invocation and return,
+/// under which the results match a sequential set starting without the key.
+fn linearizable(histories: &[Vec<Event>]) -> bool {
+    let mut seen = HashSet::new();
+    let mut stack = vec![(vec![0; histories.len()], false)];
+    while let Some((cursors, present)) = stack.pop() {
+        if !seen.insert((cursors.clone(), present)) {
+            continue;
+        }
+        let pending = || {
+            histories
+                .iter()
+                .zip(&cursors)
+                .filter_map(|(h, &c)| h.get(c))
+        };
+        // A call can come first only if it began before any pending call
+        // returned.
+        let deadline = match pending().map(|event| event.returned).min() {
+            Some(deadline) => deadline,
+            None => return true,
+        };
+        for (thread, history) in histories.iter().enumerate() {
+            let event = match history.get(cursors[thread]) {
+                Some(event) if event.invoked < deadline => event,
+                _ => continue,
+            };
+            let (expected, after) = match event.call {
+                Call::Insert => (!present, true),
+                Call::Remove => (present, false),
+                Call::Contains => (present, present),
+            };
+            if event.result == expected {
+                let mut cursors = cursors.clone();
+                cursors[thread] += 1;
+                stack.push((cursors, after));
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn history_check_rejects_impossible_results() {
+    let event = |call, result, invoked, returned| Event {
+        call,
+        result,
+        invoked,
+        returned,
+    };
+    let sequential = vec![vec![
+        event(Call::Insert, true, 0, 1),
+        event(Call::Insert, true, 2, 3),
+    ]];
+    assert!(!linearizable(&sequential));
+    let overlapping = vec![
+        vec![event(Call::Insert, true, 0, 3)],
+        vec![event(Call::Contains, false, 1, 2)],
+    ];
+    assert!(linearizable(&overlapping));
+    let stale = vec![
+        vec![event(Call::Insert, true, 0, 1)],
+        vec![event(Call::Contains, false, 2, 3)],
+    ];
+    assert!(!linearizable(&stale));
+}
+
+/// Every thread inserts, removes and looks up the same two keys at random,
+/// recording the results, and the history of every key must be
+/// linearizable. Operations on distinct keys are independent, so checking
+/// every key on its own is enough.
+#[test]
+fn contended_keys_are_linearizable() {
+    const KEYS: usize = 2;
+    const THREADS: u64 = 4;
+    let tree = ConcurrentSearchTree::new();
+    let clock = AtomicU64::new(0);
+    let histories: Vec<Vec<Vec<Event>>> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let (tree, clock) = (&tree, &clock);
+                scope.spawn(move || {
+                    let mut history = vec![Vec::new(); KEYS];
+                    let mut state = t.wrapping_mul(0x9e37_79b9_7f4a_7c15) + 1;
+                    for _ in 0..2_000 {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        let key = (state % KEYS as u64) as usize;
+                        let call = [Call::Insert, Call::Remove, Call::Contains]
+                            [(state >> 32) as usize % 3];
+                        let invoked = clock.fetch_add(1, Ordering::SeqCst);
+                        let result = match call {
+                            Call::Insert => tree.insert(key),
+                            Call::Remove => tree.remove(&key),
+                            Call::Contains => tree.contains(&key),
+                        };
+                        let returned = clock.fetch_add(1, Ordering::SeqCst);
+                        history[key].push(Event {
+                            call,
+                            result,
+                            invoked,
+                            returned,
+                        });
+                    }
+                    history
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    for key in 0..KEYS {
+        let per_thread: Vec<Vec<Event>> = histories
+            .iter()
+            .map(|history| history[key].clone())
+            .collect();
+        assert!(linearizable(&per_thread), "key {}", key);
+    }
+    assert_eq!(tree.len(), tree.to_vec().len());
+}
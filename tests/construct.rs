@@ -0,0 +1,139 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::binary_tree::Node;
+use gray_tree::Error;
+
+#[test]
+fn from_sorted_is_balanced() {
+    let tree = Node::from_sorted(&[1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(tree.to_leetcode(), "[4,2,6,1,3,5]");
+    assert!(Node::<i32>::from_sorted(&[]).is_none());
+
+    let bst = BinarySearchTree::from_sorted(&[1, 2, 3]).unwrap();
+    assert_eq!(bst.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    assert!(matches!(
+        BinarySearchTree::from_sorted(&[2, 1]),
+        Err(Error::Unsorted)
+    ));
+}
+
+#[test]
+fn from_pre_and_mid_order() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let pre = [1, 2, 4, 5, 8, 3, 6, 9, 7];
+    let inorder = [4, 2, 8, 5, 1, 6, 9, 3, 7];
+    let rebuilt = Node::from_pre_in(&pre, &inorder).unwrap();
+    assert_eq!(rebuilt.to_leetcode(), tree.to_leetcode());
+
+    let skewed = Node::from_pre_in(&[1, 2, 3], &[3, 2, 1]).unwrap();
+    assert_eq!(skewed.to_leetcode(), "[1,2,null,3]");
+
+    for (pre, inorder) in [
+        (&[1, 2][..], &[1, 3][..]),
+        (&[1, 2, 3], &[3, 1, 2]),
+        (&[1, 1], &[1, 1]),
+        (&[1], &[1, 2]),
+        (&[], &[]),
+    ] {
+        assert!(matches!(
+            Node::from_pre_in(pre, inorder),
+            Err(Error::InconsistentTraversals)
+        ));
+    }
+}
+
+#[test]
+fn from_post_or_level_and_mid_order() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let inorder = [4, 2, 8, 5, 1, 6, 9, 3, 7];
+    let post = [4, 8, 5, 2, 9, 6, 7, 3, 1];
+    let level = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let rebuilt = Node::from_post_in(&post, &inorder).unwrap();
+    assert_eq!(rebuilt.to_leetcode(), tree.to_leetcode());
+    let rebuilt = Node::from_level_in(&level, &inorder).unwrap();
+    assert_eq!(rebuilt.to_leetcode(), tree.to_leetcode());
+
+    let skewed = Node::from_post_in(&[3, 2, 1], &[1, 2, 3]).unwrap();
+    assert_eq!(skewed.to_leetcode(), "[1,null,2,null,3]");
+    let skewed = Node::from_level_in(&[1, 2, 3], &[3, 2, 1]).unwrap();
+    assert_eq!(skewed.to_leetcode(), "[1,2,null,3]");
+
+    assert!(Node::from_post_in(&[1, 2, 3], &[2, 3, 1]).is_err());
+    assert!(Node::from_level_in(&[1, 3, 2], &[2, 1, 3]).is_err());
+    assert!(Node::from_level_in(&[1, 2], &[2, 2]).is_err());
+}
+
+#[test]
+fn collect_into_bst() {
+    let bst: BinarySearchTree<_> = vec![5, 3, 8, 3, 1].into_iter().collect();
+    assert_eq!(bst.len(), 4);
+    assert_eq!(bst.iter().copied().collect::<Vec<_>>(), [1, 3, 5, 8]);
+    let bst: BinarySearchTree<_> = (0..7).collect();
+    let root = bst.into_node().unwrap();
+    assert_eq!(root.to_leetcode(), "[3,1,5,0,2,4,6]");
+}
+
+#[test]
+fn edge_list_round_trip() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let (nodes, mut edges) = tree.to_edge_list();
+    assert_eq!(nodes, [&1, &2, &4, &5, &8, &3, &6, &9, &7]);
+    assert_eq!(edges[..3], [(0, 1, false), (1, 2, false), (1, 3, true)]);
+    edges.reverse();
+    let nodes: Vec<_> = nodes.into_iter().copied().collect();
+    let rebuilt = Node::from_edge_list(nodes, &edges).unwrap();
+    assert_eq!(rebuilt.to_leetcode(), tree.to_leetcode());
+
+    let single = Node::from_edge_list(vec!['a'], &[]).unwrap();
+    assert_eq!(*single.data(), 'a');
+    for edges in [
+        &[(0, 1, false), (0, 2, false)][..],
+        &[(0, 2, false), (1, 2, true)],
+        &[(0, 1, false)],
+        &[(1, 2, false), (2, 1, false)],
+    ] {
+        assert!(matches!(
+            Node::from_edge_list(vec![0, 1, 2], edges),
+            Err(Error::MalformedTree)
+        ));
+    }
+    assert!(matches!(
+        Node::from_edge_list(vec![0, 1], &[(0, 2, true)]),
+        Err(Error::IndexOutOfRange { index: 2, len: 2 })
+    ));
+    assert!(matches!(
+        Node::<i32>::from_edge_list(Vec::new(), &[]),
+        Err(Error::MalformedTree)
+    ));
+}
+
+#[test]
+fn unfold_from_seeds() {
+    // The Calkin-Wilf tree of positive rationals.
+    let tree = Node::unfold_to_depth((1u32, 1u32), 2, |(num, den)| {
+        (
+            format!("{}/{}", num, den),
+            Some((num, num + den)),
+            Some((num + den, den)),
+        )
+    });
+    assert_eq!(
+        tree.to_leetcode(),
+        r#"["1/1","1/2","2/1","1/3","3/2","2/3","3/1"]"#
+    );
+
+    // A binary search tree over a range, stopping by itself.
+    let tree = Node::unfold(0..7, |range| {
+        let mid = (range.start + range.end) / 2;
+        let left = (range.start < mid).then_some(range.start..mid);
+        let right = (mid + 1 < range.end).then_some(mid + 1..range.end);
+        (mid, left, right)
+    });
+    assert_eq!(tree.to_leetcode(), "[3,1,5,0,2,4,6]");
+
+    let chain = Node::unfold(0, |depth| {
+        (depth, None, (depth < 100_000).then(|| depth + 1))
+    });
+    assert_eq!(chain.level_order_iter().count(), 100_001);
+    let capped = Node::unfold_to_depth(0, 0, |depth: u32| (depth, Some(depth + 1), None));
+    assert!(capped.left().is_none());
+}
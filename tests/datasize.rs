@@ -0,0 +1,56 @@
+use gray_tree::binary_tree::arena::Arena;
+use gray_tree::binary_tree::flat::FlatTree;
+use gray_tree::binary_tree::rc::RcNode;
+use gray_tree::binary_tree::Node;
+use gray_tree::datasize::DataSize;
+use gray_tree::tree::Node as TreeNode;
+use gray_tree::tree_map::TreeMap;
+use std::convert::TryFrom;
+use std::mem::size_of;
+
+#[test]
+fn std_types() {
+    assert_eq!(5u64.heap_size(), 0);
+    let text = String::with_capacity(10);
+    assert_eq!(text.heap_size(), 10);
+    let mut words = Vec::with_capacity(4);
+    words.push(String::with_capacity(3));
+    assert_eq!(words.heap_size(), 4 * size_of::<String>() + 3);
+    assert_eq!(Some(Box::new(7u32)).heap_size(), 4);
+    assert_eq!((String::with_capacity(2), 1u8).heap_size(), 2);
+}
+
+#[test]
+fn backends_on_the_same_tree() {
+    let tree = Node::from_sorted_vec((0..100u64).collect()).unwrap();
+    assert_eq!(tree.heap_size(), 99 * size_of::<Node<u64>>());
+
+    let labels = Node::from_sorted_vec((0..100).map(|n| n.to_string()).collect()).unwrap();
+    let data: usize = labels.iter().map(String::capacity).sum();
+    assert_eq!(labels.heap_size(), 99 * size_of::<Node<String>>() + data);
+
+    // Every shared node carries two reference counts and a borrow flag.
+    let shared = RcNode::from(tree.clone());
+    assert!(shared.heap_size() > 100 * size_of::<Node<u64>>());
+    assert!(shared.left().unwrap().heap_size() < shared.heap_size());
+
+    let mut arena = Arena::with_capacity(128);
+    arena.insert_tree(tree.clone());
+    let arena_size = arena.heap_size();
+    let mut grown = Arena::with_capacity(256);
+    grown.insert_tree(tree.clone());
+    assert!(grown.heap_size() > arena_size);
+
+    let flat = FlatTree::try_from(tree).unwrap();
+    assert!(flat.heap_size() >= 100 * size_of::<Option<u64>>());
+
+    let mut root = TreeNode::new(String::from("root"));
+    *root.children_mut() = Vec::with_capacity(2);
+    root.push_child(TreeNode::new(String::new()));
+    assert_eq!(root.heap_size(), 2 * size_of::<TreeNode<String>>() + 4);
+
+    let map: TreeMap<u32, String> = (0..10).map(|key| (key, "x".repeat(8))).collect();
+    assert!(map.heap_size() >= 10 * 8);
+    let empty: TreeMap<u32, String> = TreeMap::new();
+    assert_eq!(empty.heap_size(), 0);
+}
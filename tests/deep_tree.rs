@@ -0,0 +1,81 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::tree::Node as RoseNode;
+
+#[test]
+fn drop_deep_right_spine() {
+    let mut root = Node::new(0);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    drop(root);
+}
+
+#[test]
+fn clone_deep_right_spine() {
+    let mut root = Node::new(0);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    let cloned = root.clone();
+    assert_eq!(cloned.level_order_iter().count(), 1_000_000);
+}
+
+#[test]
+fn map_deep_right_spine() {
+    let mut root = Node::new(0);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    let root = root.pre_order_map(|idx| idx + 1);
+    let root = root.mid_order_map(|idx| idx * 2);
+    let root = root.post_order_map(|idx| idx - 1);
+    assert_eq!(*root.data(), 1_999_999);
+}
+
+#[test]
+fn fold_deep_right_spine() {
+    let mut root = Node::new(0u64);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    let sum = root.fold_tree(|data, left, right| data + left.unwrap_or(0) + right.unwrap_or(0));
+    assert_eq!(sum, 999_999 * 1_000_000 / 2);
+}
+
+fn deep_path(len: usize) -> RoseNode<usize> {
+    let mut root = RoseNode::new(0);
+    for idx in 1..len {
+        let mut node = RoseNode::new(idx);
+        node.push_child(root);
+        root = node;
+    }
+    root
+}
+
+#[test]
+fn drop_deep_rose_path() {
+    let depth = 1_000_000;
+    let newick = format!("{}a{};", "(".repeat(depth), ")".repeat(depth));
+    let root = RoseNode::<String>::from_newick(&newick).unwrap();
+    drop(root);
+    drop(deep_path(1_000_000));
+}
+
+#[test]
+fn clone_and_map_deep_rose_path() {
+    let root = deep_path(1_000_000);
+    let cloned = root.clone();
+    assert_eq!(cloned.post_order_iter().count(), 1_000_000);
+    let root = root.pre_order_map(|idx| idx + 1);
+    let root = root.post_order_map(|idx| idx * 2);
+    assert_eq!(*root.data(), 2_000_000);
+    assert_eq!(root.post_order_iter().next(), Some(&2));
+}
@@ -0,0 +1,199 @@
+use gray_tree::binary_tree::display::{Glyphs, TreeFormatter};
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, SplitMix64};
+
+fn tree(root: &str, left: &str, right: &str) -> Node<String> {
+    let mut node = Node::new(root.to_owned());
+    node.set_left(Some(Node::new(left.to_owned())));
+    node.set_right(Some(Node::new(right.to_owned())));
+    node
+}
+
+#[test]
+fn wide_characters_take_two_columns() {
+    let output = tree("根", "漢字", "b").to_string();
+    assert_eq!(output, "  根\n  / \\\n漢字 b\n");
+}
+
+#[test]
+fn labels_are_stacked_and_truncated() {
+    let node = tree("root", "one\ntwo", "three");
+    let output = format!("{:.3}", node);
+    assert_eq!(output, "  ro…\n /   \\\none\ntwo th…\n");
+    let output = format!("{:#.3}", node);
+    assert_eq!(output, "ro…\n├── one\n│   two\n└── th…\n");
+}
+
+#[test]
+fn compact_lists_nodes_in_pre_order() {
+    let mut node = Node::new("0".to_owned());
+    let mut child = tree("1", "2", "3");
+    child.set_right(None);
+    node.set_right(Some(child));
+    let output = node.display_compact().to_string();
+    assert_eq!(output, "0\n├── ∅\n└── 1\n    ├── 2\n    └── ∅\n");
+    assert_eq!(format!("{:#}", node), output);
+
+    let mut rng = SplitMix64::new(4);
+    for n in 1..40 {
+        let node = gen::uniform(n, &mut rng).unwrap();
+        let output = node.display_compact().to_string();
+        let labels: Vec<&str> = output
+            .lines()
+            .map(|line| line.trim_start_matches(|c| "├└│─ ".contains(c)))
+            .filter(|label| *label != "∅")
+            .collect();
+        let pre_order: Vec<String> = node
+            .to_pre_order_shape()
+            .into_iter()
+            .map(|(data, _, _)| data.to_string())
+            .collect();
+        assert_eq!(labels, pre_order);
+        // The missing side of a lone child takes a line of its own.
+        let lone = node
+            .to_pre_order_shape()
+            .into_iter()
+            .filter(|&(_, has_left, has_right)| has_left != has_right)
+            .count();
+        assert_eq!(output.lines().count(), n + lone);
+    }
+}
+
+#[test]
+fn formatter_options() {
+    let node = Node::<i32>::from_leetcode("[0,1,2,3,4,null,5,6]")
+        .unwrap()
+        .unwrap();
+    let show = |format: TreeFormatter| node.display_with(format).to_string();
+    assert_eq!(
+        show(TreeFormatter::new()),
+        node.display_compact().to_string()
+    );
+    assert_eq!(
+        show(TreeFormatter::new().glyphs(Glyphs::ASCII)),
+        "0\n|-- 1\n|   |-- 3\n|   |   |-- 6\n|   |   `-- ()\n|   `-- 4\n`-- 2\n    |-- ()\n    `-- 5\n"
+    );
+    assert_eq!(
+        show(
+            TreeFormatter::new()
+                .glyphs(Glyphs::ROUNDED)
+                .indent(1)
+                .padding(0)
+        ),
+        "0\n├─1\n│ ├─3\n│ │ ├─6\n│ │ ╰─∅\n│ ╰─4\n╰─2\n  ├─∅\n  ╰─5\n"
+    );
+    assert_eq!(
+        show(TreeFormatter::new().max_depth(Some(1)).elision("...")),
+        "0\n├── 1\n│   └── ...\n└── 2\n    └── ...\n"
+    );
+    // Only the first node of every level in pre order is kept.
+    assert_eq!(
+        show(TreeFormatter::new().max_per_level(Some(1))),
+        "0\n├── 1\n│   ├── 3\n│   │   ├── 6\n│   │   └── ∅\n│   └── …\n└── …\n"
+    );
+
+    // The label width set on the formatter wins over the precision.
+    let node = Node::<i32>::from_leetcode("[12345,678]").unwrap().unwrap();
+    let format = TreeFormatter::new().max_label_width(Some(3));
+    assert_eq!(
+        node.display_with(format).to_string(),
+        "12…\n├── 678\n└── ∅\n"
+    );
+    let format = TreeFormatter::new();
+    assert_eq!(
+        format!("{:.3}", node.display_with(format)),
+        "12…\n├── 678\n└── ∅\n"
+    );
+    let format = TreeFormatter::new().max_label_width(Some(4));
+    assert_eq!(
+        format!("{:.2}", node.display_with(format)),
+        "123…\n├── 678\n└── ∅\n"
+    );
+}
+
+#[test]
+fn horizontal_reads_mid_order_bottom_up() {
+    let node = Node::<i32>::from_leetcode("[0,1,2,3,4,null,5,6]")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        node.display_horizontal().to_string(),
+        "    ┌── 5\n┌── 2\n0\n│   ┌── 4\n└── 1\n    └── 3\n        └── 6\n"
+    );
+
+    // Later lines of a label leave room for the line to the left subtree.
+    let mut node = tree("ab\ncd", "l", "r1\nr2");
+    assert_eq!(
+        node.display_horizontal().to_string(),
+        "┌── r1\n│       r2\nab\n│   cd\n└── l\n"
+    );
+    assert_eq!(
+        format!("{:.1}", node.display_horizontal()),
+        "┌── …\n│       …\n…\n│   …\n└── l\n"
+    );
+    node.set_left(None);
+    assert_eq!(
+        node.display_horizontal().to_string(),
+        "┌── r1\n│       r2\nab\n    cd\n"
+    );
+
+    let mut rng = SplitMix64::new(6);
+    for n in 1..40 {
+        let node = gen::uniform(n, &mut rng).unwrap();
+        let output = node.display_horizontal().to_string();
+        let labels: Vec<usize> = output
+            .lines()
+            .rev()
+            .map(|line| {
+                line.trim_start_matches(|c| "┌└│─ ".contains(c))
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(labels, (0..n).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn top_down_rows_follow_levels() {
+    let mut rng = SplitMix64::new(7);
+    for n in 1..60 {
+        let node = gen::uniform(n, &mut rng).unwrap();
+        let output = node.to_string();
+        let lines: Vec<&str> = output.lines().collect();
+        let mut levels: Vec<Vec<String>> = Vec::new();
+        for (level, data) in node.level_order_iter() {
+            if levels.len() == level {
+                levels.push(Vec::new());
+            }
+            levels[level].push(data.to_string());
+        }
+        // Every level on a line, below the connectors to its parents.
+        assert_eq!(lines.len(), 2 * levels.len() - 1);
+        for (line, level) in lines.iter().step_by(2).zip(&levels) {
+            let labels: Vec<&str> = line
+                .split([' ', '_'])
+                .filter(|label| !label.is_empty())
+                .collect();
+            assert_eq!(&labels, level);
+        }
+        // Subtrees are packed, so the width is bounded by the labels
+        // rather than growing with the depth.
+        let labels: usize = levels.iter().flatten().map(|label| label.len() + 1).sum();
+        assert!(lines.iter().all(|line| line.chars().count() <= labels));
+    }
+
+    // A chain moves one column per level.
+    let chain = gen::right_chain(30).unwrap();
+    let width = chain.to_string().lines().map(str::len).max().unwrap();
+    assert_eq!(width, 28 + "29".len());
+}
+
+#[test]
+fn empty_labels_take_a_column() {
+    let mut node = Node::new("a".to_owned());
+    node.set_right(Some(Node::new(String::new())));
+    assert_eq!(node.to_string(), "a\n \\\n\n");
+    let output = tree("", "", "\u{301}").to_string();
+    assert_eq!(output.lines().count(), 3);
+}
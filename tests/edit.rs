@@ -0,0 +1,71 @@
+use gray_tree::tree::edit::{edit_distance, EditCosts, UnitCosts};
+use gray_tree::tree::Node;
+
+fn parse(input: &str) -> Node<String> {
+    Node::from_newick(input).unwrap()
+}
+
+#[test]
+fn zhang_shasha_distances() {
+    // The example of the original paper.
+    let a = parse("((d,e)c,b)f;");
+    let b = parse("((d)c,e,b)f;");
+    assert_eq!(edit_distance(&a, &b, &UnitCosts), 2);
+    assert_eq!(edit_distance(&a, &a, &UnitCosts), 0);
+    assert_eq!(
+        edit_distance(&parse("a;"), &parse("(b,c)a;"), &UnitCosts),
+        2
+    );
+    assert_eq!(
+        edit_distance(&parse("(b,c)a;"), &parse("x;"), &UnitCosts),
+        3
+    );
+}
+
+struct Weighted;
+
+impl EditCosts<String> for Weighted {
+    fn insert(&self, _data: &String) -> usize {
+        5
+    }
+
+    fn delete(&self, _data: &String) -> usize {
+        5
+    }
+
+    fn rename(&self, from: &String, to: &String) -> usize {
+        if from == to {
+            0
+        } else {
+            2
+        }
+    }
+}
+
+#[test]
+fn custom_costs() {
+    let a = parse("(b,c)a;");
+    let b = parse("(b,x)a;");
+    assert_eq!(edit_distance(&a, &b, &Weighted), 2);
+    assert_eq!(edit_distance(&a, &parse("(b)a;"), &Weighted), 5);
+}
+
+#[test]
+fn diff_and_patch() {
+    use gray_tree::tree::edit::{apply_patch, diff, Edit};
+
+    let a = parse("((d,e)c,b,g)f;");
+    let b = parse("((x)c,y)f;");
+    let edits = diff(&a, &b);
+    assert_eq!(edits.len(), 4);
+    let mut patched = a.clone();
+    apply_patch(&mut patched, edits).unwrap();
+    assert_eq!(patched.to_newick(), "((x)c,y)f");
+    assert!(diff(&b, &b).is_empty());
+
+    let mut patched = a;
+    let bad = vec![Edit::Delete { path: vec![5] }];
+    assert!(apply_patch(&mut patched, bad).is_err());
+    let bad = vec![Edit::Delete { path: Vec::new() }];
+    assert!(apply_patch(&mut patched, bad).is_err());
+}
@@ -0,0 +1,107 @@
+use gray_tree::euler_tour::EulerTourTree;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::monoid::Sum;
+use gray_tree::Error;
+use std::collections::BTreeSet;
+
+/// Vertices reachable from `start`, not crossing the edge `start - skip`.
+fn component(edges: &BTreeSet<(usize, usize)>, start: usize, skip: Option<usize>) -> Vec<usize> {
+    let mut seen = vec![start];
+    let mut stack = vec![start];
+    while let Some(vertex) = stack.pop() {
+        for &(a, b) in edges.range((vertex, 0)..(vertex + 1, 0)) {
+            let blocked = a == start && Some(b) == skip;
+            if !blocked && !seen.contains(&b) {
+                seen.push(b);
+                stack.push(b);
+            }
+        }
+    }
+    seen
+}
+
+#[test]
+fn matches_an_edge_set_forest() {
+    const LEN: usize = 30;
+    let mut rng = SplitMix64::new(31);
+    let mut forest = EulerTourTree::new();
+    let mut values: Vec<i64> = Vec::new();
+    for vertex in 0..LEN {
+        values.push(vertex as i64 * 3 - 20);
+        assert_eq!(forest.add(Sum(values[vertex])), vertex);
+    }
+    // Both directions of every edge.
+    let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for _ in 0..4000 {
+        let u = (rng.next_u64() % LEN as u64) as usize;
+        let v = (rng.next_u64() % LEN as u64) as usize;
+        let connected = component(&edges, u, None).contains(&v);
+        match rng.next_u64() % 5 {
+            0 | 1 => {
+                let linked = forest.link(u, v);
+                if connected {
+                    assert!(matches!(linked, Err(Error::AlreadyConnected)));
+                } else {
+                    linked.unwrap();
+                    edges.insert((u, v));
+                    edges.insert((v, u));
+                }
+            }
+            2 => {
+                let cut = forest.cut(u, v);
+                if edges.remove(&(u, v)) {
+                    edges.remove(&(v, u));
+                    cut.unwrap();
+                } else {
+                    assert!(matches!(cut, Err(Error::NoSuchEdge)));
+                }
+            }
+            3 => {
+                values[u] = (rng.next_u64() % 100) as i64 - 50;
+                forest.set_value(u, Sum(values[u]));
+            }
+            _ => {
+                let subtree = forest.subtree_aggregate(u, v);
+                if edges.contains(&(u, v)) {
+                    let below = component(&edges, u, Some(v));
+                    let sum = below.iter().map(|&vertex| values[vertex]).sum();
+                    assert_eq!(subtree.unwrap(), Sum(sum));
+                } else {
+                    assert!(matches!(subtree, Err(Error::NoSuchEdge)));
+                }
+            }
+        }
+        let members = component(&edges, u, None);
+        assert_eq!(forest.connected(u, v), members.contains(&v));
+        assert_eq!(forest.tree_size(u), members.len());
+        let sum = members.iter().map(|&vertex| values[vertex]).sum();
+        assert_eq!(forest.tree_aggregate(u), Sum(sum));
+        assert_eq!(forest.value(u), &Sum(values[u]));
+    }
+    assert_eq!(forest.len(), LEN);
+}
+
+#[test]
+fn out_of_range_vertices_are_rejected() {
+    let mut forest = EulerTourTree::new();
+    forest.add(Sum(1));
+    forest.add(Sum(2));
+    assert!(matches!(
+        forest.link(0, 2),
+        Err(Error::IndexOutOfRange { index: 2, len: 2 })
+    ));
+    assert!(matches!(
+        forest.cut(3, 0),
+        Err(Error::IndexOutOfRange { index: 3, len: 2 })
+    ));
+    assert!(matches!(
+        forest.subtree_aggregate(0, 7),
+        Err(Error::IndexOutOfRange { index: 7, len: 2 })
+    ));
+    assert!(matches!(
+        forest.subtree_aggregate(0, 1),
+        Err(Error::NoSuchEdge)
+    ));
+    forest.link(0, 1).unwrap();
+    assert_eq!(forest.subtree_aggregate(0, 1).unwrap(), Sum(1));
+}
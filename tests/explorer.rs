@@ -0,0 +1,71 @@
+use gray_tree::explorer::Explorer;
+use gray_tree::tree::Node;
+
+fn tree() -> Node<&'static str> {
+    let mut src = Node::new("src");
+    let mut bin = Node::new("bin");
+    bin.push_child(Node::new("main.rs"));
+    src.push_child(bin);
+    src.push_child(Node::new("lib.rs"));
+    let mut root = Node::new(".");
+    root.push_child(src);
+    root.push_child(Node::new("Cargo.toml"));
+    root
+}
+
+#[test]
+fn expand_collapse_and_move() {
+    let mut explorer = Explorer::n_ary(&tree());
+    assert_eq!(explorer.len(), 6);
+    assert_eq!(
+        explorer.to_string(),
+        "> ▾ .\n  ├── ▸ src\n  └── Cargo.toml\n"
+    );
+    assert!(!explorer.go_up());
+    assert!(explorer.go_down());
+    explorer.expand();
+    assert_eq!(
+        explorer.to_string(),
+        "  ▾ .\n> ├── ▾ src\n  │   ├── ▸ bin\n  │   └── lib.rs\n  └── Cargo.toml\n"
+    );
+    assert!(explorer.go_down() && explorer.go_down() && explorer.go_down());
+    assert_eq!(explorer.label(explorer.cursor()), Some("Cargo.toml"));
+    assert!(!explorer.go_down());
+
+    let lines = explorer.lines();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[2].prefix, "│   ├── ");
+    assert_eq!(lines[2].depth, 2);
+    assert_eq!(lines[2].expanded, Some(false));
+    assert!(lines[4].selected);
+
+    explorer.expand_all();
+    assert_eq!(explorer.lines().len(), 6);
+    assert!(explorer.go_up());
+    assert_eq!(explorer.label(explorer.cursor()), Some("lib.rs"));
+    assert!(explorer.go_parent());
+    explorer.toggle();
+    assert!(!explorer.is_expanded(explorer.cursor()));
+    assert_eq!(explorer.lines().len(), 3);
+}
+
+#[test]
+fn search_reveals_matches() {
+    let mut explorer = Explorer::n_ary(&tree());
+    assert!(explorer.search(".rs"));
+    assert_eq!(explorer.label(explorer.cursor()), Some("main.rs"));
+    assert!(explorer.to_string().contains("> │   │   └── main.rs"));
+    assert!(explorer.search(".rs"));
+    assert_eq!(explorer.label(explorer.cursor()), Some("lib.rs"));
+    assert!(explorer.search(".rs"));
+    assert_eq!(explorer.label(explorer.cursor()), Some("main.rs"));
+    assert!(!explorer.search("missing"));
+
+    explorer.collapse_all();
+    assert_eq!(explorer.cursor(), 0);
+    assert_eq!(explorer.lines().len(), 3);
+
+    let binary = gray_tree::tree! {1 => {, 3}};
+    let explorer = Explorer::binary(&binary);
+    assert_eq!(explorer.to_string(), "> ▾ 1\n  └── 3\n");
+}
@@ -0,0 +1,159 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::expr::{Expr, ExprNode, Op};
+use gray_tree::Error;
+use std::collections::HashMap;
+
+/// Random expression over `x`, built with the constructors, together with
+/// its value computed directly.
+fn random_expr(seed: &mut u64, depth: usize, x: f64) -> (Expr, f64) {
+    let mut next = |modulo: u64| {
+        *seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (*seed >> 33) % modulo
+    };
+    let pick = if depth == 0 { next(2) } else { next(7) };
+    let leaf = next(9) as f64 + 1.0;
+    match pick {
+        0 => (Expr::num(leaf), leaf),
+        1 => (Expr::var("x"), x),
+        2 => {
+            let (operand, value) = random_expr(seed, depth - 1, x);
+            (Expr::negate(operand), -value)
+        }
+        _ => {
+            let op = [Op::Add, Op::Sub, Op::Mul, Op::Div][pick as usize - 3];
+            let (lhs, a) = random_expr(seed, depth - 1, x);
+            let (rhs, b) = random_expr(seed, depth - 1, x);
+            let value = match op {
+                Op::Add => a + b,
+                Op::Sub => a - b,
+                Op::Mul => a * b,
+                _ => a / b,
+            };
+            (Expr::binary(op, lhs, rhs), value)
+        }
+    }
+}
+
+fn same(a: f64, b: f64) -> bool {
+    (a.is_nan() && b.is_nan()) || a == b || (a - b).abs() <= 1e-9 * a.abs().max(1.0)
+}
+
+#[test]
+fn eval_and_substitute_match_direct_computation() {
+    let mut seed = 11;
+    for _ in 0..200 {
+        let (expr, value) = random_expr(&mut seed, 5, 3.0);
+        let vars: HashMap<String, f64> = vec![("x".to_owned(), 3.0)].into_iter().collect();
+        assert!(same(expr.eval_with(&vars).unwrap(), value));
+        let substituted = expr.clone().substitute("x", &Expr::num(3.0));
+        assert!(same(substituted.eval().unwrap(), value));
+        let nested = expr.substitute("x", &Expr::binary(Op::Add, Expr::num(1.0), Expr::num(2.0)));
+        assert!(same(nested.eval().unwrap(), value));
+    }
+
+    let expr = Expr::binary(Op::Pow, Expr::num(2.0), Expr::var("y"));
+    assert!(matches!(expr.eval(), Err(Error::UnboundVariable(name)) if name == "y"));
+}
+
+#[test]
+fn display_and_from_node() {
+    let sum = Expr::binary(Op::Add, Expr::num(1.0), Expr::var("x"));
+    let product = Expr::binary(Op::Mul, sum.clone(), Expr::num(2.0));
+    assert_eq!(product.to_string(), "(1+x)*2");
+    let difference = Expr::binary(Op::Sub, Expr::num(1.0), sum.clone());
+    assert_eq!(difference.to_string(), "1-(1+x)");
+    let power = Expr::binary(
+        Op::Pow,
+        Expr::num(2.0),
+        Expr::binary(Op::Pow, Expr::num(3.0), Expr::num(2.0)),
+    );
+    assert_eq!(power.to_string(), "2^3^2");
+    assert_eq!(power.eval().unwrap(), 512.0);
+    assert_eq!(Expr::negate(sum).to_string(), "-(1+x)");
+
+    let node = product.into_node();
+    assert!(Expr::from_node(node.clone()).is_ok());
+    let mut missing = Node::new(ExprNode::Op(Op::Add));
+    missing.set_left(Some(Node::new(ExprNode::Num(1.0))));
+    assert!(matches!(
+        Expr::from_node(missing),
+        Err(Error::MalformedExpression)
+    ));
+    let mut leaf = Node::new(ExprNode::Var("x".to_owned()));
+    leaf.set_left(Some(Node::new(ExprNode::Num(1.0))));
+    assert!(matches!(
+        Expr::from_node(leaf),
+        Err(Error::MalformedExpression)
+    ));
+    let mut negation = Node::new(ExprNode::Op(Op::Neg));
+    negation.set_left(Some(Node::new(ExprNode::Num(1.0))));
+    negation.set_right(Some(Node::new(ExprNode::Num(1.0))));
+    assert!(matches!(
+        Expr::from_node(negation),
+        Err(Error::MalformedExpression)
+    ));
+}
+
+#[test]
+fn parse_matches_display_and_precedence() {
+    let mut seed = 23;
+    let vars: HashMap<String, f64> = vec![("x".to_owned(), 3.0)].into_iter().collect();
+    for _ in 0..200 {
+        let (expr, value) = random_expr(&mut seed, 5, 3.0);
+        let text = expr.to_string();
+        let parsed: Expr = text.parse().unwrap();
+        assert_eq!(parsed.to_string(), text);
+        assert!(same(parsed.eval_with(&vars).unwrap(), value), "{}", text);
+    }
+
+    let eval = |text: &str| Expr::parse(text).unwrap().eval().unwrap();
+    assert_eq!(eval("1 + 2 * 3"), 7.0);
+    assert_eq!(eval("(1 + 2) * 3"), 9.0);
+    assert_eq!(eval("1 - 2 - 3"), -4.0);
+    assert_eq!(eval("8 / 4 / 2"), 1.0);
+    assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+    assert_eq!(eval("-2 ^ 2"), -4.0);
+    assert_eq!(eval("2 * -3"), -6.0);
+    assert_eq!(eval("--2.5"), 2.5);
+
+    let error = |text: &str| match Expr::parse(text) {
+        Err(Error::Parse { position, .. }) => position,
+        other => panic!("{:?} parsed as {:?}", text, other),
+    };
+    assert_eq!(error(""), 0);
+    assert_eq!(error("1 +"), 3);
+    assert_eq!(error("(1 + 2"), 6);
+    assert_eq!(error("1 2"), 2);
+    assert_eq!(error("1 + 2)"), 5);
+    assert_eq!(error("1 $ 2"), 2);
+    assert_eq!(error("1..2"), 0);
+}
+
+#[test]
+fn deep_nesting_does_not_overflow() {
+    let depth = 200_000;
+    let parens = format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+    let expr = Expr::parse(&parens).unwrap();
+    assert_eq!(expr.to_string(), "x");
+    let unclosed = format!("{}x", "(".repeat(depth));
+    assert!(matches!(
+        Expr::parse(&unclosed),
+        Err(Error::Parse { position, .. }) if position == depth + 1
+    ));
+
+    let negations = format!("{}x", "-".repeat(depth));
+    let expr = Expr::parse(&negations).unwrap();
+    assert_eq!(expr.to_string(), negations);
+    let expr = expr.substitute("x", &Expr::num(2.0));
+    assert_eq!(expr.eval().unwrap(), 2.0);
+
+    let sum = vec!["1"; depth].join("+");
+    let expr = Expr::parse(&sum).unwrap();
+    assert_eq!(expr.to_string(), sum);
+    assert_eq!(expr.eval().unwrap(), depth as f64);
+
+    let power = vec!["1"; depth].join("^");
+    let expr = Expr::parse(&power).unwrap();
+    assert_eq!(expr.to_string(), power);
+    assert_eq!(expr.eval().unwrap(), 1.0);
+}
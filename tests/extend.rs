@@ -0,0 +1,22 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::heap::TreeHeap;
+use gray_tree::trie::Trie;
+
+#[test]
+fn extend_containers() {
+    let mut bst = BinarySearchTree::new();
+    bst.extend(vec![3, 1, 2]);
+    bst.extend(&[2, 4]);
+    assert_eq!(bst.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+    let mut heap = TreeHeap::max();
+    heap.extend(vec![3, 1, 4]);
+    heap.extend(&[1, 5]);
+    assert_eq!(heap.len(), 5);
+    assert_eq!(heap.pop(), Some(5));
+
+    let mut trie = Trie::new();
+    trie.extend(vec!["she".chars(), "he".chars(), "she".chars()]);
+    assert_eq!(trie.len(), 2);
+    assert_eq!(trie.get("he".chars()), Some(1));
+}
@@ -0,0 +1,73 @@
+use gray_tree::finger_tree::{FingerTree, Size};
+use gray_tree::gen::{Rng, SplitMix64};
+use std::collections::VecDeque;
+
+fn check(tree: &FingerTree<u32, Size>, model: &VecDeque<u32>) {
+    assert_eq!(tree.len(), model.len());
+    assert_eq!(tree.is_empty(), model.is_empty());
+    assert_eq!(tree.front(), model.front());
+    assert_eq!(tree.back(), model.back());
+    assert!(tree.iter().eq(model.iter()));
+}
+
+#[test]
+fn deque_operations_match_vec_deque() {
+    let mut rng = SplitMix64::new(8);
+    let mut tree: FingerTree<u32, Size> = FingerTree::new();
+    let mut model = VecDeque::new();
+    for step in 0..5000 {
+        let value = rng.next_u64() as u32 % 1000;
+        match rng.next_u64() % 6 {
+            0 | 1 => {
+                tree.push_back(value);
+                model.push_back(value);
+            }
+            2 | 3 => {
+                tree.push_front(value);
+                model.push_front(value);
+            }
+            4 => assert_eq!(tree.pop_front(), model.pop_front()),
+            _ => assert_eq!(tree.pop_back(), model.pop_back()),
+        }
+        if step % 50 == 0 {
+            check(&tree, &model);
+            for (idx, value) in model.iter().enumerate() {
+                assert_eq!(tree.get(idx), Some(value));
+            }
+            assert_eq!(tree.get(model.len()), None);
+        }
+    }
+    check(&tree, &model);
+    while let Some(value) = model.pop_front() {
+        assert_eq!(tree.pop_front(), Some(value));
+    }
+    assert!(tree.is_empty() && tree.pop_back().is_none());
+}
+
+#[test]
+fn append_and_split_match_vectors() {
+    let mut rng = SplitMix64::new(21);
+    for _ in 0..100 {
+        let left_len = (rng.next_u64() % 60) as u32;
+        let right_len = (rng.next_u64() % 60) as u32;
+        let mut tree: FingerTree<u32, Size> = (0..left_len).collect();
+        let right: FingerTree<u32, Size> = (left_len..left_len + right_len).collect();
+        tree.append(right);
+        let all: Vec<u32> = (0..left_len + right_len).collect();
+        assert!(tree.iter().eq(all.iter()));
+
+        let at = (rng.next_u64() % (all.len() as u64 + 2)) as usize;
+        let (before, after) = tree.split_at(at);
+        let at = at.min(all.len());
+        assert!(before.iter().eq(all[..at].iter()));
+        assert!(after.iter().eq(all[at..].iter()));
+        assert_eq!(before.len() + after.len(), all.len());
+
+        // Splitting where the predicate never holds keeps everything left.
+        let mut joined = before;
+        joined.append(after);
+        let (whole, rest) = joined.split(|size| size.0 > all.len());
+        assert_eq!(whole.len(), all.len());
+        assert!(rest.is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+use gray_tree::tree;
+use std::collections::HashMap;
+
+#[test]
+fn equal_subtrees_share_fingerprints() {
+    let tree = tree! {1 => {2 => {4, 5}, 3 => {2 => {4, 5}, 2 => {4,}}}};
+    assert_eq!(tree.fingerprint(), tree.clone().fingerprint());
+    assert_ne!(
+        tree! {1 => {2,}}.fingerprint(),
+        tree! {1 => {, 2}}.fingerprint()
+    );
+
+    let mut groups: HashMap<u64, Vec<_>> = HashMap::new();
+    for (hash, node) in tree.subtree_hashes() {
+        groups.entry(hash).or_default().push(node);
+    }
+    let repeated: Vec<_> = groups
+        .values()
+        .filter(|nodes| nodes.len() > 1 && nodes[0].left().is_some())
+        .collect();
+    assert_eq!(repeated.len(), 1);
+    assert_eq!(repeated[0].len(), 2);
+    assert_eq!(repeated[0][0].to_leetcode(), "[2,4,5]");
+}
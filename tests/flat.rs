@@ -0,0 +1,141 @@
+use gray_tree::binary_tree::flat::FlatTree;
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, Rng, SplitMix64};
+use gray_tree::Error;
+use std::convert::TryFrom;
+
+/// Random heap-array slots, every node holding its own index.
+fn random_slots(rng: &mut SplitMix64, levels: u32) -> Vec<Option<usize>> {
+    let size = (1 << levels) - 1;
+    let mut slots = vec![None; size];
+    slots[0] = Some(0);
+    for idx in 1..size {
+        if slots[(idx - 1) / 2].is_some() && !rng.next_u64().is_multiple_of(3) {
+            slots[idx] = Some(idx);
+        }
+    }
+    while let Some(None) = slots.last() {
+        slots.pop();
+    }
+    slots
+}
+
+fn occupied(slots: &[Option<usize>], idx: usize) -> Option<usize> {
+    slots.get(idx).copied().flatten()
+}
+
+fn pre_order(slots: &[Option<usize>], idx: usize, out: &mut Vec<usize>) {
+    if occupied(slots, idx).is_some() {
+        out.push(idx);
+        pre_order(slots, 2 * idx + 1, out);
+        pre_order(slots, 2 * idx + 2, out);
+    }
+}
+
+fn mid_order(slots: &[Option<usize>], idx: usize, out: &mut Vec<usize>) {
+    if occupied(slots, idx).is_some() {
+        mid_order(slots, 2 * idx + 1, out);
+        out.push(idx);
+        mid_order(slots, 2 * idx + 2, out);
+    }
+}
+
+fn post_order(slots: &[Option<usize>], idx: usize, out: &mut Vec<usize>) {
+    if occupied(slots, idx).is_some() {
+        post_order(slots, 2 * idx + 1, out);
+        post_order(slots, 2 * idx + 2, out);
+        out.push(idx);
+    }
+}
+
+#[test]
+fn matches_index_arithmetic() {
+    let mut rng = SplitMix64::new(362);
+    for levels in (1..=9).cycle().take(60) {
+        let slots = random_slots(&mut rng, levels);
+        let len = slots.iter().flatten().count();
+        let tree = FlatTree::from_slots(slots.clone()).unwrap();
+        assert_eq!(tree.len(), len);
+        assert_eq!(tree.slots(), &slots[..]);
+        assert_eq!(tree.root(), Some(&0));
+        for idx in 0..slots.len() + 3 {
+            assert_eq!(tree.get(idx).copied(), occupied(&slots, idx));
+            let child = |child: usize| occupied(&slots, child).map(|_| child);
+            assert_eq!(tree.left(idx), child(2 * idx + 1));
+            assert_eq!(tree.right(idx), child(2 * idx + 2));
+            let parent = idx.checked_sub(1).and_then(|idx| child(idx / 2));
+            assert_eq!(tree.parent(idx), parent);
+        }
+        let levels: Vec<(usize, usize)> = tree.level_order_iter().map(|(l, &d)| (l, d)).collect();
+        let expected: Vec<(usize, usize)> = slots
+            .iter()
+            .flatten()
+            .map(|&idx| (((idx + 1) as f64).log2() as usize, idx))
+            .collect();
+        assert_eq!(levels, expected);
+
+        // Every node holds its index, so mapping records the order.
+        let mut orders = [Vec::new(), Vec::new(), Vec::new()];
+        pre_order(&slots, 0, &mut orders[0]);
+        mid_order(&slots, 0, &mut orders[1]);
+        post_order(&slots, 0, &mut orders[2]);
+        let mut seen = [Vec::new(), Vec::new(), Vec::new()];
+        let mapped = [
+            tree.clone().pre_order_map(|idx| seen[0].push(idx)),
+            tree.clone().mid_order_map(|idx| seen[1].push(idx)),
+            tree.clone().post_order_map(|idx| seen[2].push(idx)),
+        ];
+        assert_eq!(seen, orders);
+        assert!(mapped.iter().all(|mapped| mapped.len() == len));
+        assert_eq!(tree.clone().mid_order_map(|idx| idx + 1).get(0), Some(&1));
+
+        let node = Option::<Node<usize>>::from(tree.clone()).unwrap();
+        let mut stack = vec![(0, &node)];
+        let mut count = 0;
+        while let Some((idx, node)) = stack.pop() {
+            assert_eq!(*node.data(), idx);
+            count += 1;
+            stack.extend(node.left().map(|left| (2 * idx + 1, left)));
+            stack.extend(node.right().map(|right| (2 * idx + 2, right)));
+        }
+        assert_eq!(count, len);
+        assert_eq!(node.to_level_order_vec().unwrap(), slots);
+        assert_eq!(FlatTree::try_from(node).unwrap(), tree);
+        assert_eq!(tree.into_slots(), slots);
+    }
+}
+
+#[test]
+fn deep_trees_are_rejected() {
+    let chain = gen::left_chain(10).unwrap();
+    let tree = FlatTree::try_from(chain).unwrap();
+    assert_eq!(tree.len(), 10);
+    assert_eq!(tree.slots().len(), 1 << 9);
+    for len in [usize::BITS as usize, usize::BITS as usize + 1, 100_000] {
+        assert!(matches!(
+            FlatTree::try_from(gen::left_chain(len).unwrap()),
+            Err(Error::CapacityOverflow)
+        ));
+    }
+}
+
+#[test]
+fn orphans_are_rejected_or_dropped() {
+    let slots = vec![Some(0), None, Some(2), Some(3)];
+    assert!(matches!(
+        FlatTree::from_slots(slots.clone()),
+        Err(Error::MalformedTree)
+    ));
+    let node = Node::from_level_order(slots).unwrap();
+    assert_eq!(
+        node.to_level_order_vec().unwrap(),
+        vec![Some(0), None, Some(2)]
+    );
+    assert!(Node::from_level_order(vec![None, Some(1)]).is_none());
+    let tree = FlatTree::from_slots(vec![Some(1), None, None]).unwrap();
+    assert_eq!(tree.slots(), &[Some(1)]);
+    assert!(FlatTree::<u8>::from_slots(vec![None]).unwrap().is_empty());
+    let complete = FlatTree::from_complete(vec![5, 6, 7, 8]);
+    assert_eq!(complete.len(), 4);
+    assert_eq!(complete.parent(3), Some(1));
+}
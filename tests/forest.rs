@@ -0,0 +1,128 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::tree::forest::Forest;
+use gray_tree::tree::Node;
+use gray_tree::Error;
+
+/// Roots in order and the children of every vertex.
+#[derive(Clone)]
+struct Model {
+    roots: Vec<usize>,
+    children: Vec<Vec<usize>>,
+}
+
+impl Model {
+    fn pre_order(&self, vertex: usize, depth: usize, out: &mut Vec<(usize, usize)>) {
+        out.push((depth, vertex));
+        for &child in &self.children[vertex] {
+            self.pre_order(child, depth + 1, out);
+        }
+    }
+
+    fn shapes(&self) -> Vec<Vec<(usize, usize)>> {
+        self.roots
+            .iter()
+            .map(|&root| {
+                let mut shape = Vec::new();
+                self.pre_order(root, 0, &mut shape);
+                shape
+            })
+            .collect()
+    }
+}
+
+fn shape(node: &Node<usize>) -> Vec<(usize, usize)> {
+    node.iter_to_depth(usize::MAX)
+        .map(|(depth, &vertex)| (depth, vertex))
+        .collect()
+}
+
+fn check(forest: &Forest<usize>, model: &Model) {
+    let shapes = model.shapes();
+    assert_eq!(forest.len(), shapes.len());
+    assert_eq!(forest.is_empty(), shapes.is_empty());
+    assert_eq!(
+        forest.node_count(),
+        shapes.iter().map(Vec::len).sum::<usize>()
+    );
+    assert_eq!(forest.trees().iter().map(shape).collect::<Vec<_>>(), shapes);
+    let pre: Vec<usize> = shapes.iter().flatten().map(|&(_, v)| v).collect();
+    assert_eq!(forest.iter().copied().collect::<Vec<_>>(), pre);
+}
+
+#[test]
+fn operations_match_a_model() {
+    let mut rng = SplitMix64::new(359);
+    let len = 40;
+    let mut forest: Forest<usize> = (0..len).map(Node::new).collect();
+    let mut model = Model {
+        roots: (0..len).collect(),
+        children: vec![Vec::new(); len],
+    };
+    for _ in 0..400 {
+        let trees = model.roots.len();
+        let a = (rng.next_u64() % (trees as u64 + 1)) as usize;
+        let b = (rng.next_u64() % (trees as u64 + 1)) as usize;
+        match rng.next_u64() % 4 {
+            0 | 1 => {
+                let joined = forest.join(a, b);
+                if a >= trees || b >= trees {
+                    let index = if a >= trees { a } else { b };
+                    assert!(matches!(
+                        joined,
+                        Err(Error::IndexOutOfRange { index: i, len: l }) if i == index && l == trees
+                    ));
+                } else if a == b {
+                    assert!(matches!(joined, Err(Error::WouldCycle)));
+                } else {
+                    joined.unwrap();
+                    let (parent, child) = (model.roots[a], model.roots.remove(b));
+                    model.children[parent].push(child);
+                }
+            }
+            2 => {
+                let split = forest.split_root(a);
+                if a >= trees {
+                    assert!(split.is_err());
+                } else {
+                    let root = model.roots[a];
+                    assert_eq!(split.unwrap(), root);
+                    let children = std::mem::take(&mut model.children[root]);
+                    model.roots.splice(a..=a, children);
+                }
+            }
+            _ => {
+                let mut tail = forest.split_off(a).unwrap();
+                assert!(forest.split_off(forest.len() + 1).is_err());
+                let mut tail_model = model.clone();
+                tail_model.roots = model.roots.split_off(a);
+                check(&tail, &tail_model);
+                check(&forest, &model);
+                forest.append(&mut tail);
+                assert!(tail.is_empty());
+                model.roots.append(&mut tail_model.roots);
+            }
+        }
+        check(&forest, &model);
+    }
+}
+
+#[test]
+fn binary_encoding_round_trips() {
+    let forest: Forest<u8> = vec![
+        Node::builder()
+            .data(0)
+            .children(vec![Node::new(1), Node::new(2)])
+            .build()
+            .unwrap(),
+        Node::new(3),
+    ]
+    .into();
+    let binary = forest.clone().into_binary().unwrap();
+    assert_eq!(binary.right().map(|node| *node.data()), Some(3));
+    let decoded = Forest::from(binary);
+    assert_eq!(
+        decoded.iter().collect::<Vec<_>>(),
+        forest.iter().collect::<Vec<_>>()
+    );
+    assert!(Forest::<u8>::new().into_binary().is_none());
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "fs")]
+
+use gray_tree::fs::{FsTree, WalkOptions};
+use std::fs;
+use std::path::PathBuf;
+
+fn scratch(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("gray-tree-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("src/bin")).unwrap();
+    fs::create_dir_all(root.join("target/debug")).unwrap();
+    fs::write(root.join("Cargo.toml"), "").unwrap();
+    fs::write(root.join("src/lib.rs"), "").unwrap();
+    fs::write(root.join("src/bin/main.rs"), "fn main() {}").unwrap();
+    fs::write(root.join("target/debug/out"), "").unwrap();
+    root
+}
+
+#[test]
+fn reads_like_the_tree_command() {
+    let root = scratch("read");
+    let tree = FsTree::read(&root).unwrap();
+    assert_eq!(tree.counts(), (4, 4));
+    let src = tree.root().child(1).unwrap();
+    let main = src.child(0).unwrap().child(0).unwrap().data();
+    assert_eq!(main.name, "main.rs");
+    assert_eq!(main.metadata.len(), 12);
+    assert_eq!(main.path, root.join("src/bin/main.rs"));
+
+    let shown = tree.to_string();
+    let body = shown.split_once('\n').unwrap().1;
+    assert_eq!(
+        body,
+        "\
+├── Cargo.toml
+├── src
+│   ├── bin
+│   │   └── main.rs
+│   └── lib.rs
+└── target
+    └── debug
+        └── out
+
+4 directories, 4 files"
+    );
+
+    let options = WalkOptions::new()
+        .max_depth(Some(1))
+        .filter(|entry| entry.name != "target");
+    let tree = FsTree::read_with(&root, &options).unwrap();
+    let names: Vec<_> = tree
+        .root()
+        .pre_order_iter()
+        .skip(1)
+        .map(|entry| entry.name.as_str())
+        .collect();
+    assert_eq!(names, ["Cargo.toml", "src"]);
+    assert!(tree.to_string().ends_with("1 directory, 1 file"));
+
+    fs::remove_dir_all(&root).unwrap();
+    assert!(matches!(FsTree::read(&root), Err(gray_tree::Error::Io(_))));
+}
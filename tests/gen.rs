@@ -0,0 +1,61 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, SplitMix64};
+
+fn is_mid_numbered(node: &Node<usize>, len: usize) -> bool {
+    node.iter().copied().eq(0..len)
+}
+
+#[test]
+fn random_shapes_hold_mid_order_numbers() {
+    let mut rng = SplitMix64::new(42);
+    for n in 1..40 {
+        let tree = gen::uniform(n, &mut rng).unwrap();
+        assert!(is_mid_numbered(&tree, n));
+        let tree = gen::random_bst(n, &mut rng).unwrap();
+        assert!(is_mid_numbered(&tree, n));
+        let tree = gen::degenerate(n, &mut rng).unwrap();
+        assert!(is_mid_numbered(&tree, n));
+        assert_eq!(tree.level_order_iter().last().unwrap().0, n - 1);
+    }
+    assert!(gen::uniform(0, &mut rng).is_none());
+    assert!(gen::random_bst(0, &mut rng).is_none());
+    assert!(gen::degenerate(0, &mut rng).is_none());
+}
+
+#[test]
+fn uniform_reaches_every_shape() {
+    // There are five shapes of three nodes.
+    let mut rng = SplitMix64::new(7);
+    let mut shapes = std::collections::HashSet::new();
+    for _ in 0..200 {
+        shapes.insert(gen::uniform(3, &mut rng).unwrap().to_leetcode());
+    }
+    assert_eq!(shapes.len(), 5);
+
+    let mut counter = 0u64;
+    let mut user_rng = || {
+        counter = counter.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        counter
+    };
+    assert!(gen::uniform(10, &mut user_rng).is_some());
+}
+
+#[test]
+fn fixed_shapes() {
+    assert_eq!(gen::complete(6).unwrap().to_leetcode(), "[3,1,5,0,2,4]");
+    assert_eq!(gen::perfect(2).unwrap().to_leetcode(), "[1,0,2]");
+    assert!(gen::perfect(0).is_none());
+    assert_eq!(gen::left_chain(3).unwrap().to_leetcode(), "[2,1,null,0]");
+    assert_eq!(
+        gen::right_chain(3).unwrap().to_leetcode(),
+        "[0,null,1,null,2]"
+    );
+}
+
+#[test]
+fn shrink_removes_subtrees() {
+    let tree = gen::complete(3).unwrap();
+    let candidates: Vec<_> = gen::shrink(&tree).map(|t| t.to_leetcode()).collect();
+    assert_eq!(candidates, ["[0]", "[2]", "[1,null,2]", "[1,0]"]);
+    assert_eq!(gen::shrink(&Node::new(1)).count(), 0);
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "petgraph")]
+
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{random_bst, SplitMix64};
+use gray_tree::Error;
+use petgraph::algo::{is_cyclic_directed, toposort};
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::Direction;
+
+#[test]
+fn graph_round_trip() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let graph = tree.to_graph();
+    assert_eq!(graph.node_count(), 9);
+    assert_eq!(graph.edge_count(), 8);
+    assert!(!is_cyclic_directed(&graph));
+    let root = NodeIndex::new(0);
+    assert_eq!(*graph[root], 1);
+    assert_eq!(
+        graph.neighbors_directed(root, Direction::Incoming).count(),
+        0
+    );
+    let order: Vec<_> = toposort(&graph, None)
+        .unwrap()
+        .into_iter()
+        .map(|idx| *graph[idx])
+        .collect();
+    assert_eq!(order[0], 1);
+
+    let owned = graph.map(|_, &&data| data, |_, &is_right| is_right);
+    let rebuilt = Node::try_from_graph(owned).unwrap();
+    assert_eq!(rebuilt.to_leetcode(), tree.to_leetcode());
+
+    let mut rng = SplitMix64::new(431);
+    for len in 1..50 {
+        let tree = random_bst(len, &mut rng).unwrap();
+        let graph = tree
+            .to_graph()
+            .map(|_, &&data| data, |_, &is_right| is_right);
+        let rebuilt = Node::try_from_graph(graph).unwrap();
+        assert_eq!(rebuilt.to_pre_order_shape(), tree.to_pre_order_shape());
+    }
+}
+
+#[test]
+fn graphs_that_are_not_trees_are_rejected() {
+    let graph = |edges: &[(u32, u32, bool)]| {
+        let mut graph = Graph::new();
+        for data in 0..3 {
+            graph.add_node(data);
+        }
+        graph.extend_with_edges(edges);
+        graph
+    };
+    for edges in [
+        &[(0, 1, false), (0, 2, false)][..],
+        &[(0, 2, false), (1, 2, true)],
+        &[(0, 1, false)],
+        &[(0, 1, false), (1, 2, false), (2, 1, true)],
+        &[(0, 1, false), (0, 2, true), (0, 1, true)],
+    ] {
+        assert!(matches!(
+            Node::try_from_graph(graph(edges)),
+            Err(Error::MalformedTree)
+        ));
+    }
+    assert!(matches!(
+        Node::<i32>::try_from_graph(Graph::new()),
+        Err(Error::MalformedTree)
+    ));
+}
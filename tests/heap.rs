@@ -0,0 +1,57 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::heap::{HeapKind, TreeHeap};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[test]
+fn matches_binary_heap() {
+    let mut rng = SplitMix64::new(3);
+    let mut max = TreeHeap::max();
+    let mut min = TreeHeap::min();
+    let mut max_model = BinaryHeap::new();
+    let mut min_model = BinaryHeap::new();
+    for step in 0..3000 {
+        if rng.next_u64().is_multiple_of(3) {
+            assert_eq!(max.pop(), max_model.pop());
+            assert_eq!(min.pop(), min_model.pop().map(|Reverse(value)| value));
+        } else {
+            let value = rng.next_u64() % 100;
+            max.push(value);
+            min.push(value);
+            max_model.push(value);
+            min_model.push(Reverse(value));
+        }
+        assert_eq!(max.len(), max_model.len());
+        assert_eq!(max.peek(), max_model.peek());
+        assert_eq!(min.peek(), min_model.peek().map(|Reverse(value)| value));
+        if step % 100 == 0 {
+            max.validate().unwrap();
+            min.validate().unwrap();
+        }
+    }
+    assert_eq!(max.kind(), HeapKind::Max);
+    assert_eq!(min.kind(), HeapKind::Min);
+
+    // The tree stays complete, so its height is logarithmic.
+    let len = min.len();
+    let height = min
+        .root()
+        .map_or(0, |root| root.level_order_iter().last().unwrap().0 + 1);
+    assert_eq!(height, (usize::BITS - len.leading_zeros()) as usize);
+
+    let mut drained = Vec::new();
+    while let Some(value) = min.pop() {
+        drained.push(value);
+    }
+    let mut expected = min_model.into_sorted_vec();
+    expected.reverse();
+    let expected: Vec<u64> = expected.into_iter().map(|Reverse(value)| value).collect();
+    assert_eq!(drained, expected);
+    assert!(min.is_empty() && min.peek().is_none() && min.root().is_none());
+
+    // The level order array is an ordinary binary heap.
+    let mut items = max.into_vec();
+    assert!((1..items.len()).all(|idx| items[(idx - 1) / 2] >= items[idx]));
+    items.sort_unstable();
+    assert_eq!(items, max_model.into_sorted_vec());
+}
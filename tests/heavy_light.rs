@@ -0,0 +1,143 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::heavy_light::{HeavyLight, PathTree};
+use gray_tree::monoid::Sum;
+use gray_tree::segment_tree::Add;
+use gray_tree::Error;
+
+/// Random tree with shuffled labels, as the parent of every vertex.
+fn random_parents(rng: &mut SplitMix64, len: usize) -> Vec<Option<usize>> {
+    let mut labels: Vec<usize> = (0..len).collect();
+    for idx in (1..len).rev() {
+        labels.swap(idx, (rng.next_u64() % (idx as u64 + 1)) as usize);
+    }
+    let mut parents = vec![None; len];
+    for idx in 1..len {
+        let parent = (rng.next_u64() % idx as u64) as usize;
+        parents[labels[idx]] = Some(labels[parent]);
+    }
+    parents
+}
+
+fn ancestors(parents: &[Option<usize>], mut vertex: usize) -> Vec<usize> {
+    let mut path = vec![vertex];
+    while let Some(parent) = parents[vertex] {
+        path.push(parent);
+        vertex = parent;
+    }
+    path
+}
+
+/// Vertices on the path between `u` and `v`.
+fn path(parents: &[Option<usize>], u: usize, v: usize) -> Vec<usize> {
+    let up = ancestors(parents, u);
+    let down = ancestors(parents, v);
+    let lca = *up.iter().find(|vertex| down.contains(vertex)).unwrap();
+    let mut path: Vec<usize> = up.into_iter().take_while(|&x| x != lca).collect();
+    path.push(lca);
+    path.extend(down.into_iter().take_while(|&x| x != lca));
+    path
+}
+
+fn subtree(parents: &[Option<usize>], root: usize) -> Vec<usize> {
+    (0..parents.len())
+        .filter(|&vertex| ancestors(parents, vertex).contains(&root))
+        .collect()
+}
+
+#[test]
+fn decomposition_matches_parent_walks() {
+    let mut rng = SplitMix64::new(54);
+    for len in 1..40 {
+        let parents = random_parents(&mut rng, len);
+        let hld = HeavyLight::new(&parents).unwrap();
+        assert_eq!(hld.len(), len);
+        let mut positions: Vec<usize> = (0..len).map(|v| hld.position(v)).collect();
+        positions.sort_unstable();
+        assert_eq!(positions, (0..len).collect::<Vec<_>>());
+        for vertex in 0..len {
+            assert_eq!(hld.depth(vertex), ancestors(&parents, vertex).len() - 1);
+            let mut expected: Vec<usize> = subtree(&parents, vertex)
+                .into_iter()
+                .map(|v| hld.position(v))
+                .collect();
+            expected.sort_unstable();
+            assert_eq!(hld.subtree(vertex).collect::<Vec<_>>(), expected);
+        }
+        for _ in 0..20 {
+            let u = (rng.next_u64() % len as u64) as usize;
+            let v = (rng.next_u64() % len as u64) as usize;
+            let path = path(&parents, u, v);
+            let lca = *path
+                .iter()
+                .min_by_key(|&&vertex| hld.depth(vertex))
+                .unwrap();
+            assert_eq!(hld.lca(u, v), lca);
+            let mut covered: Vec<usize> = hld.path(u, v).into_iter().flatten().collect();
+            covered.sort_unstable();
+            let mut expected: Vec<usize> = path.iter().map(|&v| hld.position(v)).collect();
+            expected.sort_unstable();
+            assert_eq!(covered, expected);
+        }
+    }
+}
+
+#[test]
+fn path_tree_matches_naive_updates() {
+    let mut rng = SplitMix64::new(55);
+    let len = 60;
+    let parents = random_parents(&mut rng, len);
+    let mut values: Vec<i64> = (0..len as i64).map(|v| v * 7 % 13 - 6).collect();
+    let mut tree: PathTree<Sum, Add> =
+        PathTree::new(&parents, values.iter().map(|&v| Sum(v)).collect()).unwrap();
+    for _ in 0..2000 {
+        let u = (rng.next_u64() % len as u64) as usize;
+        let v = (rng.next_u64() % len as u64) as usize;
+        let delta = (rng.next_u64() % 21) as i64 - 10;
+        match rng.next_u64() % 6 {
+            0 => {
+                values[u] = delta;
+                tree.set(u, Sum(delta));
+            }
+            1 => {
+                path(&parents, u, v)
+                    .iter()
+                    .for_each(|&x| values[x] += delta);
+                tree.path_apply(u, v, Add(delta));
+            }
+            2 => {
+                subtree(&parents, u)
+                    .iter()
+                    .for_each(|&x| values[x] += delta);
+                tree.subtree_apply(u, Add(delta));
+            }
+            3 => {
+                let sum = path(&parents, u, v).iter().map(|&x| values[x]).sum();
+                assert_eq!(tree.path_query(u, v), Sum(sum));
+            }
+            4 => {
+                let sum = subtree(&parents, u).iter().map(|&x| values[x]).sum();
+                assert_eq!(tree.subtree_query(u), Sum(sum));
+            }
+            _ => assert_eq!(tree.get(u), Sum(values[u])),
+        }
+    }
+}
+
+#[test]
+fn rejects_malformed_parents() {
+    for parents in [
+        vec![Some(1), Some(0)],
+        vec![None, None],
+        vec![None, Some(5)],
+        vec![None, Some(2), Some(1)],
+    ] {
+        assert!(matches!(
+            HeavyLight::new(&parents),
+            Err(Error::MalformedTree)
+        ));
+    }
+    let values = vec![Sum(1); 3];
+    let result: Result<PathTree<Sum>, _> = PathTree::new(&[None, Some(0)], values);
+    assert!(matches!(result, Err(Error::MalformedTree)));
+    assert!(HeavyLight::new(&[]).unwrap().is_empty());
+}
@@ -0,0 +1,43 @@
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::tree::Node;
+
+#[test]
+fn nested_lists_with_escaping() {
+    let mut root = Node::new("<root>");
+    let mut child = Node::new("a & b");
+    child.push_child(Node::new("\"q\""));
+    root.push_child(child);
+    root.push_child(Node::new("it's"));
+    assert_eq!(
+        root.to_html(),
+        "<ul><li>&lt;root&gt;<ul><li>a &amp; b<ul><li>&quot;q&quot;</li></ul></li>\
+         <li>it&#39;s</li></ul></li></ul>"
+    );
+    assert_eq!(Node::new(1).to_html_collapsible(), "<ul><li>1</li></ul>");
+    assert_eq!(
+        root.to_html_collapsible(),
+        "<ul><li><details open><summary>&lt;root&gt;</summary><ul>\
+         <li><details open><summary>a &amp; b</summary><ul><li>&quot;q&quot;</li></ul></details></li>\
+         <li>it&#39;s</li></ul></details></li></ul>"
+    );
+}
+
+#[test]
+fn binary_sides_are_kept() {
+    let tree = gray_tree::tree! {1 => {2 => {, 4}, 3}};
+    assert_eq!(
+        tree.to_html(),
+        "<ul><li>1<ul><li>2<ul><li></li><li>4</li></ul></li><li>3</li></ul></li></ul>"
+    );
+
+    // Deep trees are written without recursion.
+    let mut chain = BinaryNode::new(0);
+    for value in 1..100_000 {
+        let mut parent = BinaryNode::new(value);
+        parent.set_left(Some(chain));
+        chain = parent;
+    }
+    let html = chain.to_html_collapsible();
+    assert_eq!(html.matches("<details open>").count(), 99_999);
+    assert!(html.ends_with("</details></li></ul>"));
+}
@@ -0,0 +1,83 @@
+use gray_tree::huffman::HuffmanTree;
+use gray_tree::Error;
+
+/// Optimal total code length, merging the two lightest weights each time.
+fn model_cost(mut weights: Vec<u64>) -> u64 {
+    let mut cost = 0;
+    while weights.len() > 1 {
+        weights.sort_unstable_by(|a, b| b.cmp(a));
+        let merged = weights.pop().unwrap() + weights.pop().unwrap();
+        cost += merged;
+        weights.push(merged);
+    }
+    cost
+}
+
+#[test]
+fn codes_are_optimal_and_prefix_free() {
+    let mut seed: u64 = 5;
+    for len in 2..30 {
+        let frequencies: Vec<(u32, u64)> = (0..len)
+            .map(|symbol| {
+                seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                (symbol, 1 + (seed >> 33) % 100)
+            })
+            .collect();
+        let tree = HuffmanTree::new(frequencies.clone()).unwrap().unwrap();
+        let cost: u64 = frequencies
+            .iter()
+            .map(|(symbol, weight)| weight * tree.code(symbol).unwrap().len() as u64)
+            .sum();
+        let weights = frequencies.iter().map(|&(_, weight)| weight).collect();
+        assert_eq!(cost, model_cost(weights));
+        assert_eq!(
+            tree.root().data().weight(),
+            frequencies.iter().map(|f| f.1).sum::<u64>()
+        );
+
+        let codes: Vec<&Vec<bool>> = tree.codes().values().collect();
+        for (idx, a) in codes.iter().enumerate() {
+            for b in &codes[idx + 1..] {
+                assert!(!a.starts_with(b) && !b.starts_with(a));
+            }
+        }
+    }
+}
+
+#[test]
+fn encode_decode_round_trip() {
+    let text = "abracadabra alakazam";
+    let tree = HuffmanTree::from_data(text.chars()).unwrap();
+    let symbols: Vec<char> = text.chars().collect();
+    let bits = tree.encode(&symbols).unwrap();
+    assert!(bits.len() < 8 * text.len());
+    assert_eq!(tree.decode(bits.iter().copied()).unwrap(), symbols);
+    assert_eq!(tree.code(&'a').unwrap().len(), 1);
+
+    assert!(matches!(tree.encode(&['q']), Err(Error::UnknownSymbol)));
+    let truncated = &bits[..bits.len() - 1];
+    assert!(matches!(
+        tree.decode(truncated.iter().copied()),
+        Err(Error::TruncatedCode)
+    ));
+    assert_eq!(tree.decode(Vec::new()).unwrap(), Vec::<char>::new());
+
+    // A single symbol still gets a one-bit code.
+    let tree = HuffmanTree::from_data("zzz".chars()).unwrap();
+    assert_eq!(tree.code(&'z'), Some(&[false][..]));
+    assert_eq!(tree.decode(vec![false; 3]).unwrap(), ['z'; 3]);
+    assert!(matches!(
+        tree.decode(vec![false, true]),
+        Err(Error::InvalidCode)
+    ));
+    assert!(HuffmanTree::<char>::new(Vec::new()).unwrap().is_none());
+}
+
+#[test]
+fn duplicate_symbols_are_rejected() {
+    let frequencies = vec![('a', 3), ('b', 1), ('a', 2)];
+    assert!(matches!(
+        HuffmanTree::new(frequencies),
+        Err(Error::DuplicateSymbol)
+    ));
+}
@@ -0,0 +1,93 @@
+use gray_tree::tree;
+
+#[test]
+fn trees_iterate_in_mid_order() {
+    let mut tree = tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let mut values = Vec::new();
+    for value in &tree {
+        values.push(*value);
+    }
+    assert_eq!(values, [4, 2, 8, 5, 1, 6, 9, 3, 7]);
+
+    for value in &mut tree {
+        *value *= 10;
+    }
+    let values: Vec<_> = tree.into_iter().collect();
+    assert_eq!(values, [40, 20, 80, 50, 10, 60, 90, 30, 70]);
+}
+
+#[test]
+fn flatten_in_every_order() {
+    use gray_tree::binary_tree::Order;
+
+    let tree = || tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    assert_eq!(tree().flatten(Order::Pre), [1, 2, 4, 5, 8, 3, 6, 9, 7]);
+    assert_eq!(tree().flatten(Order::Mid), [4, 2, 8, 5, 1, 6, 9, 3, 7]);
+    assert_eq!(tree().flatten(Order::Post), [4, 8, 5, 2, 9, 6, 7, 3, 1]);
+    assert_eq!(tree().flatten(Order::Level), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    let mut spine = tree();
+    spine.flatten_to_right_spine();
+    assert_eq!(
+        spine.to_leetcode(),
+        "[1,null,2,null,4,null,5,null,8,null,3,null,6,null,9,null,7]"
+    );
+}
+
+#[test]
+fn fold_tree_sees_child_results() {
+    let tree = tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    let size = tree.fold_tree(|_, left, right| 1 + left.unwrap_or(0) + right.unwrap_or(0));
+    assert_eq!(size, 9);
+    let height = tree.fold_tree(|_, left, right| 1 + left.unwrap_or(0).max(right.unwrap_or(0)));
+    assert_eq!(height, 4);
+    let newick =
+        tree.fold_tree(
+            |data, left: Option<String>, right: Option<String>| match (left, right) {
+                (None, None) => data.to_string(),
+                (left, right) => format!(
+                    "({},{}){}",
+                    left.unwrap_or_default(),
+                    right.unwrap_or_default(),
+                    data
+                ),
+            },
+        );
+    assert_eq!(newick, tree.to_newick());
+}
+
+#[test]
+fn subtrees_in_every_order() {
+    use gray_tree::binary_tree::Order;
+    use std::collections::HashMap;
+
+    let tree = tree! {1 => {2 => {4, 2 => {4,}}, 3 => {2 => {4,}, 4}}};
+    let roots = |order| {
+        tree.subtrees(order)
+            .map(|node| *node.data())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(roots(Order::Pre), tree.clone().flatten(Order::Pre));
+    assert_eq!(roots(Order::Mid), tree.clone().flatten(Order::Mid));
+    assert_eq!(roots(Order::Post), tree.clone().flatten(Order::Post));
+    assert_eq!(roots(Order::Level), tree.clone().flatten(Order::Level));
+
+    // Find the duplicate subtrees by their serialization.
+    let mut seen = HashMap::new();
+    for subtree in tree.subtrees(Order::Post) {
+        *seen.entry(subtree.to_newick()).or_insert(0) += 1;
+    }
+    let mut duplicates: Vec<_> = seen
+        .into_iter()
+        .filter(|&(_, count)| count > 1)
+        .map(|(newick, _)| newick)
+        .collect();
+    duplicates.sort();
+    assert_eq!(duplicates, ["(4,)2", "4"]);
+
+    let leaves = tree
+        .subtrees(Order::Level)
+        .filter(|node| node.left().is_none() && node.right().is_none())
+        .count();
+    assert_eq!(leaves, 4);
+}
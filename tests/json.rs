@@ -0,0 +1,60 @@
+use gray_tree::binary_tree::Node;
+
+fn sample() -> Node<i32> {
+    Node::builder()
+        .data(1)
+        .left(Node::builder().data(2).right(Node::new(4)).build().unwrap())
+        .right(Node::new(3))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn compact_shape() {
+    assert_eq!(
+        sample().to_json(),
+        r#"{"data":1,"left":{"data":2,"left":null,"right":{"data":4,"left":null,"right":null}},"right":{"data":3,"left":null,"right":null}}"#
+    );
+}
+
+#[test]
+fn round_trip_compact_and_pretty() {
+    let tree = sample();
+    let compact: Node<i32> = Node::from_json(&tree.to_json()).unwrap();
+    let pretty: Node<i32> = Node::from_json(&tree.to_json_pretty()).unwrap();
+    assert_eq!(compact.to_json(), tree.to_json());
+    assert_eq!(pretty.to_json(), tree.to_json());
+}
+
+#[test]
+fn round_trip_escaped_strings() {
+    let tree = Node::new("quote \" slash \\ line \n ctrl \u{1} 😀".to_owned());
+    let back: Node<String> = Node::from_json(&tree.to_json()).unwrap();
+    assert_eq!(back.data(), tree.data());
+}
+
+#[test]
+fn reject_malformed_input() {
+    for input in &[
+        "null",
+        "{}",
+        r#"{"data":1,"data":2}"#,
+        r#"{"data":1,"left":3}"#,
+        r#"{"data":1} trailing"#,
+    ] {
+        assert!(Node::<i32>::from_json(input).is_err(), "{}", input);
+    }
+}
+
+#[test]
+fn round_trip_deep_right_spine() {
+    let mut root = Node::new(0);
+    for idx in 1..1_000_000 {
+        let mut node = Node::new(idx);
+        node.set_right(Some(root));
+        root = node;
+    }
+    let json = root.to_json();
+    let back: Node<i32> = Node::from_json(&json).unwrap();
+    assert_eq!(back.to_json(), json);
+}
@@ -0,0 +1,133 @@
+use gray_tree::binary_tree::arena::Arena;
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::lca::{EulerLca, Lca, LcaIndex, TreeIndex};
+use gray_tree::tree::Node;
+use gray_tree::{gen, Error};
+
+/// Walk up to the LCA, for comparison.
+fn naive_lca(tree: &TreeIndex, mut u: usize, mut v: usize) -> usize {
+    while tree.depth(u) > tree.depth(v) {
+        u = tree.parent(u).unwrap();
+    }
+    while tree.depth(v) > tree.depth(u) {
+        v = tree.parent(v).unwrap();
+    }
+    while u != v {
+        u = tree.parent(u).unwrap();
+        v = tree.parent(v).unwrap();
+    }
+    u
+}
+
+#[test]
+fn binary_lifting_matches_naive() {
+    // Every vertex hangs below a pseudo-random earlier one.
+    let mut seed: usize = 12345;
+    let mut parents = vec![None];
+    for v in 1..300 {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        parents.push(Some((seed >> 33) % v));
+    }
+    let index = LcaIndex::new(TreeIndex::new(&parents).unwrap());
+    let tree = index.tree();
+    for u in (0..300).step_by(7) {
+        for v in (0..300).step_by(11) {
+            let lca = naive_lca(tree, u, v);
+            assert_eq!(index.lca(u, v), lca);
+            assert_eq!(
+                index.distance(u, v),
+                tree.depth(u) + tree.depth(v) - 2 * tree.depth(lca)
+            );
+        }
+        assert_eq!(index.ancestor(u, tree.depth(u)), Some(0));
+        assert_eq!(index.ancestor(u, tree.depth(u) + 1), None);
+    }
+
+    let chain = gen::right_chain(10_000).unwrap();
+    let index = LcaIndex::new(TreeIndex::binary(&chain));
+    assert_eq!(index.lca(9_999, 4_321), 4_321);
+    assert_eq!(index.distance(10, 9_999), 9_989);
+
+    assert!(matches!(
+        TreeIndex::new(&[None, None]),
+        Err(Error::MalformedTree)
+    ));
+    assert!(matches!(
+        TreeIndex::new(&[Some(1), Some(0)]),
+        Err(Error::MalformedTree)
+    ));
+}
+
+#[test]
+fn queries_by_path_and_id() {
+    //       1
+    //     /   \
+    //    2     3
+    //   / \     \
+    //  4   5     6
+    let tree: BinaryNode<i32> = BinaryNode::from_leetcode("[1,2,3,4,5,null,6]")
+        .unwrap()
+        .unwrap();
+    let index = LcaIndex::new(TreeIndex::binary(&tree));
+    assert_eq!(index.tree().len(), 6);
+    assert_eq!(index.lca_by_path(&[0, 0], &[0, 1]), Some(vec![0]));
+    assert_eq!(index.lca_by_path(&[0, 1], &[1, 1]), Some(vec![]));
+    assert_eq!(index.distance_by_path(&[0, 0], &[1, 1]), Some(4));
+    assert_eq!(index.lca_by_path(&[1, 0], &[0]), None);
+
+    let mut arena = Arena::new();
+    let root = arena.insert_tree(tree);
+    let index = LcaIndex::new(TreeIndex::arena(&arena, root).unwrap());
+    let left = arena.left(root).unwrap();
+    let (four, five) = (arena.left(left).unwrap(), arena.right(left).unwrap());
+    let six = arena.right(arena.right(root).unwrap()).unwrap();
+    assert_eq!(index.lca_by_id(four, five), Some(left));
+    assert_eq!(index.lca_by_id(five, six), Some(root));
+    assert_eq!(index.distance_by_id(four, six), Some(4));
+    arena.remove(six).unwrap();
+    assert!(matches!(
+        TreeIndex::arena(&arena, six),
+        Err(Error::RemovedNode)
+    ));
+
+    let mut root = Node::new("root");
+    root.children_mut()
+        .extend(vec![Node::new("a"), Node::new("b"), Node::new("c")]);
+    root.children_mut()[2].children_mut().push(Node::new("d"));
+    let index = LcaIndex::new(TreeIndex::n_ary(&root));
+    assert_eq!(index.lca_by_path(&[2, 0], &[2]), Some(vec![2]));
+    assert_eq!(index.distance_by_path(&[2, 0], &[0]), Some(3));
+}
+
+#[test]
+fn euler_tour_agrees_with_binary_lifting() {
+    let mut seed: usize = 99;
+    let mut parents = vec![None];
+    for v in 1..500 {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        parents.push(Some((seed >> 33) % v));
+    }
+    let tree = TreeIndex::new(&parents).unwrap();
+    let indexes: Vec<Box<dyn Lca>> = vec![
+        Box::new(LcaIndex::new(tree.clone())),
+        Box::new(EulerLca::new(tree.clone())),
+    ];
+    for u in (0..500).step_by(13) {
+        for v in (0..500).step_by(3) {
+            let lca = naive_lca(&tree, u, v);
+            for index in &indexes {
+                assert_eq!(index.lca(u, v), lca);
+            }
+        }
+    }
+
+    let euler = EulerLca::new(TreeIndex::binary(&gen::complete(7).unwrap()));
+    assert_eq!(euler.tour(), [0, 1, 2, 1, 3, 1, 0, 4, 5, 4, 6, 4, 0]);
+    assert_eq!(euler.lca_by_path(&[1, 0], &[1, 1]), Some(vec![1]));
+    assert_eq!(euler.distance_by_path(&[0, 0], &[1, 1]), Some(4));
+
+    let chain = gen::right_chain(10_000).unwrap();
+    let euler = EulerLca::new(TreeIndex::binary(&chain));
+    assert_eq!(euler.lca(9_999, 4_321), 4_321);
+    assert_eq!(euler.tour().len(), 19_999);
+}
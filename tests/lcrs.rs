@@ -0,0 +1,96 @@
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::tree::lcrs::{binary_into_forest, forest_into_binary};
+use gray_tree::tree::Node;
+use gray_tree::Error;
+use std::convert::TryFrom;
+
+/// Random forest over `0..len` as the roots and the children lists.
+fn random_forest(rng: &mut SplitMix64, len: usize) -> (Vec<usize>, Vec<Vec<usize>>) {
+    let mut roots = Vec::new();
+    let mut children = vec![Vec::new(); len];
+    for vertex in 0..len {
+        match rng.next_u64() % (vertex as u64 + 1) {
+            0 => roots.push(vertex),
+            parent => children[parent as usize - 1].push(vertex),
+        }
+    }
+    (roots, children)
+}
+
+fn build(children: &[Vec<usize>], vertex: usize) -> Node<usize> {
+    let mut node = Node::new(vertex);
+    for &child in &children[vertex] {
+        node.push_child(build(children, child));
+    }
+    node
+}
+
+/// Check every binary node against the first child and next sibling.
+fn check_encoding(root: Option<&BinaryNode<usize>>, roots: &[usize], children: &[Vec<usize>]) {
+    let next_sibling = |vertex: usize| {
+        let list = children
+            .iter()
+            .find(|list| list.contains(&vertex))
+            .map_or(roots, Vec::as_slice);
+        let idx = list.iter().position(|&x| x == vertex).unwrap();
+        list.get(idx + 1).copied()
+    };
+    assert_eq!(root.map(|node| *node.data()), roots.first().copied());
+    let mut stack: Vec<&BinaryNode<usize>> = root.into_iter().collect();
+    let mut seen = 0;
+    while let Some(node) = stack.pop() {
+        let vertex = *node.data();
+        seen += 1;
+        assert_eq!(
+            node.left().map(|n| *n.data()),
+            children[vertex].first().copied()
+        );
+        assert_eq!(node.right().map(|n| *n.data()), next_sibling(vertex));
+        stack.extend(node.left());
+        stack.extend(node.right());
+    }
+    assert_eq!(seen, children.len());
+}
+
+fn shape(node: &Node<usize>) -> Vec<(usize, usize)> {
+    node.pre_order_iter()
+        .zip(node.iter_to_depth(usize::MAX).map(|(depth, _)| depth))
+        .map(|(&vertex, depth)| (depth, vertex))
+        .collect()
+}
+
+#[test]
+fn encoding_matches_children_lists() {
+    let mut rng = SplitMix64::new(358);
+    for len in 0..50 {
+        let (roots, children) = random_forest(&mut rng, len);
+        let forest: Vec<Node<usize>> = roots.iter().map(|&r| build(&children, r)).collect();
+        let shapes: Vec<_> = forest.iter().map(shape).collect();
+        let binary = forest_into_binary(forest);
+        check_encoding(binary.as_ref(), &roots, &children);
+        let decoded = binary.map_or(Vec::new(), binary_into_forest);
+        assert_eq!(decoded.iter().map(shape).collect::<Vec<_>>(), shapes);
+    }
+}
+
+#[test]
+fn single_trees_round_trip() {
+    let mut rng = SplitMix64::new(359);
+    for len in 1..50 {
+        let (_, mut children) = random_forest(&mut rng, len);
+        // Hang the other roots under vertex 0 to get one tree.
+        for vertex in 1..len {
+            if !children.iter().any(|list| list.contains(&vertex)) {
+                children[0].push(vertex);
+            }
+        }
+        let tree = build(&children, 0);
+        let expected = shape(&tree);
+        let binary = BinaryNode::from(tree);
+        check_encoding(Some(&binary), &[0], &children);
+        assert_eq!(shape(&Node::try_from(binary).unwrap()), expected);
+    }
+    let forest = forest_into_binary(vec![Node::new(0), Node::new(1)]).unwrap();
+    assert!(matches!(Node::try_from(forest), Err(Error::MalformedTree)));
+}
@@ -0,0 +1,28 @@
+use gray_tree::binary_tree::Node;
+
+#[test]
+fn round_trip() {
+    for input in &[
+        "[1]",
+        "[1,2,3,null,5]",
+        "[1,null,2,3]",
+        "[5,4,8,11,null,13,4,7,2,null,null,null,1]",
+    ] {
+        let tree = Node::<i64>::from_leetcode(input).unwrap().unwrap();
+        assert_eq!(tree.to_leetcode(), *input);
+    }
+}
+
+#[test]
+fn empty_and_padded_input() {
+    assert!(Node::<i64>::from_leetcode(" [ ] ").unwrap().is_none());
+    let tree = Node::<i64>::from_leetcode("[ 1, null, 2, null, null, null ]").unwrap();
+    assert_eq!(tree.unwrap().to_leetcode(), "[1,null,2]");
+}
+
+#[test]
+fn reject_malformed_input() {
+    for input in &["", "[null]", "[1,x]", "[1,null,null,2]", "[1,2", "[1,]"] {
+        assert!(Node::<i64>::from_leetcode(input).is_err(), "{}", input);
+    }
+}
@@ -0,0 +1,71 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, SplitMix64};
+use gray_tree::Error;
+
+#[test]
+fn from_heap_array() {
+    let slots = vec![Some(1), Some(2), Some(3), None, Some(5), None, Some(7)];
+    let tree = Node::from_level_order(slots).unwrap();
+    assert_eq!(tree.to_leetcode(), "[1,2,3,null,5,null,7]");
+
+    // The children of a hole are dropped along with it.
+    let tree = Node::from_level_order(vec![Some(1), None, Some(3), Some(4)]).unwrap();
+    assert_eq!(tree.to_leetcode(), "[1,null,3]");
+    assert!(Node::<i32>::from_level_order(vec![None, Some(2)]).is_none());
+    assert!(Node::<i32>::from_level_order(Vec::new()).is_none());
+}
+
+#[test]
+fn to_heap_array() {
+    let slots = vec![Some(1), Some(2), Some(3), None, Some(5), None, Some(7)];
+    let tree = Node::from_level_order(slots.clone()).unwrap();
+    assert_eq!(tree.to_level_order_vec().unwrap(), slots);
+
+    let tree = Node::from_leetcode("[1,2,null,3]").unwrap().unwrap();
+    assert_eq!(
+        tree.to_level_order_vec().unwrap(),
+        vec![Some(1), Some(2), None, Some(3)]
+    );
+}
+
+#[test]
+fn deep_trees_overflow_the_heap_array() {
+    // A right chain puts the node at depth `d` at index `2^(d + 1) - 2`.
+    let chain = gen::right_chain(10).unwrap();
+    let slots = chain.to_level_order_vec().unwrap();
+    assert_eq!(slots.len(), (1 << 10) - 1);
+    assert_eq!(slots.iter().flatten().count(), 10);
+    // The slots of the first chain cannot be allocated, and the indices of
+    // the others do not even fit in `usize`.
+    for len in [usize::BITS as usize, usize::BITS as usize + 1, 100_000] {
+        let chain = gen::right_chain(len).unwrap();
+        assert!(matches!(
+            chain.to_level_order_vec(),
+            Err(Error::CapacityOverflow)
+        ));
+    }
+}
+
+#[test]
+fn levels_match_depths() {
+    let mut rng = SplitMix64::new(8);
+    for n in 1..60 {
+        let tree = gen::uniform(n, &mut rng).unwrap();
+        // Breadth first over whole levels, every node with its depth.
+        let mut model = Vec::new();
+        let mut level = vec![&tree];
+        let mut depth = 0;
+        while !level.is_empty() {
+            model.extend(level.iter().map(|node| (depth, node.data())));
+            level = level
+                .iter()
+                .flat_map(|node| node.left().into_iter().chain(node.right()))
+                .collect();
+            depth += 1;
+        }
+        assert!(tree.level_order_iter().eq(model));
+    }
+    let chain = gen::left_chain(5).unwrap();
+    let levels: Vec<_> = chain.level_order_iter().map(|(level, _)| level).collect();
+    assert_eq!(levels, [0, 1, 2, 3, 4]);
+}
@@ -0,0 +1,151 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::link_cut::LinkCutTree;
+use gray_tree::monoid::Monoid;
+use gray_tree::Error;
+
+/// Concatenation of node labels, which does not commute, so path
+/// aggregates show the order of the path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Labels(Vec<usize>);
+
+impl Monoid for Labels {
+    fn identity() -> Self {
+        Labels(Vec::new())
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        Labels(self.0.iter().chain(&other.0).copied().collect())
+    }
+}
+
+/// Forest kept as a parent array, rerooted by reversing parent links.
+struct Forest {
+    parent: Vec<Option<usize>>,
+}
+
+impl Forest {
+    fn root(&self, mut node: usize) -> usize {
+        while let Some(parent) = self.parent[node] {
+            node = parent;
+        }
+        node
+    }
+
+    fn reroot(&mut self, node: usize) {
+        let mut previous = None;
+        let mut current = Some(node);
+        while let Some(at) = current {
+            current = std::mem::replace(&mut self.parent[at], previous);
+            previous = Some(at);
+        }
+    }
+
+    /// Nodes from `u` to `v` once `u` is the root.
+    fn path(&self, u: usize, mut v: usize) -> Vec<usize> {
+        let mut path = vec![v];
+        while v != u {
+            v = self.parent[v].unwrap();
+            path.push(v);
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[test]
+fn matches_a_parent_array_forest() {
+    const LEN: usize = 40;
+    let mut rng = SplitMix64::new(12);
+    let mut tree = LinkCutTree::new();
+    for node in 0..LEN {
+        assert_eq!(tree.add(Labels(vec![node])), node);
+    }
+    let mut model = Forest {
+        parent: vec![None; LEN],
+    };
+    for _ in 0..5000 {
+        let u = (rng.next_u64() % LEN as u64) as usize;
+        let v = (rng.next_u64() % LEN as u64) as usize;
+        let connected = model.root(u) == model.root(v);
+        match rng.next_u64() % 6 {
+            0 | 1 => {
+                let linked = tree.link(u, v);
+                if connected {
+                    assert!(matches!(linked, Err(Error::AlreadyConnected)));
+                } else {
+                    linked.unwrap();
+                    model.reroot(u);
+                    model.parent[u] = Some(v);
+                }
+            }
+            2 => {
+                let cut = tree.cut(u, v);
+                if u != v && connected {
+                    model.reroot(u);
+                }
+                if u != v && connected && model.parent[v] == Some(u) {
+                    cut.unwrap();
+                    model.parent[v] = None;
+                } else {
+                    assert!(matches!(cut, Err(Error::NoSuchEdge)));
+                }
+            }
+            3 => assert_eq!(tree.cut_parent(u), model.parent[u].take()),
+            4 => {
+                tree.make_root(u);
+                model.reroot(u);
+            }
+            _ => {
+                let path = tree.path_aggregate(u, v);
+                if connected {
+                    model.reroot(u);
+                    assert_eq!(path, Some(Labels(model.path(u, v))));
+                } else {
+                    assert_eq!(path, None);
+                }
+            }
+        }
+        assert_eq!(tree.connected(u, v), model.root(u) == model.root(v));
+        assert_eq!(tree.find_root(u), model.root(u));
+    }
+    for node in 0..LEN {
+        assert_eq!(tree.find_root(node), model.root(node));
+    }
+}
+
+#[test]
+fn values_follow_updates() {
+    let mut tree: LinkCutTree<Labels> = LinkCutTree::new();
+    for node in 0..4 {
+        tree.add(Labels(vec![node]));
+    }
+    tree.link(0, 1).unwrap();
+    tree.link(1, 2).unwrap();
+    tree.link(3, 2).unwrap();
+    assert_eq!(tree.path_aggregate(0, 3), Some(Labels(vec![0, 1, 2, 3])));
+    tree.set_value(2, Labels(vec![20, 21]));
+    assert_eq!(tree.value(2), &Labels(vec![20, 21]));
+    assert_eq!(
+        tree.path_aggregate(3, 0),
+        Some(Labels(vec![3, 20, 21, 1, 0]))
+    );
+    assert_eq!(tree.len(), 4);
+    assert!(!tree.is_empty());
+}
+
+#[test]
+fn out_of_range_nodes_are_rejected() {
+    let mut tree: LinkCutTree<Labels> = LinkCutTree::new();
+    tree.add(Labels(vec![0]));
+    tree.add(Labels(vec![1]));
+    assert!(matches!(
+        tree.link(0, 2),
+        Err(Error::IndexOutOfRange { index: 2, len: 2 })
+    ));
+    assert!(matches!(
+        tree.cut(5, 0),
+        Err(Error::IndexOutOfRange { index: 5, len: 2 })
+    ));
+    tree.link(0, 1).unwrap();
+    tree.cut(0, 1).unwrap();
+}
@@ -0,0 +1,26 @@
+use gray_tree::tree::louds::Louds;
+use gray_tree::tree::Node;
+
+#[test]
+fn navigate_and_round_trip() {
+    let input = "((d,e)b,c,(f)g)a;";
+    let louds = Louds::from(Node::<String>::from_newick(input).unwrap());
+    assert_eq!(louds.len(), 7);
+    assert_eq!(louds.data_slice(), ["a", "b", "c", "g", "d", "e", "f"]);
+
+    assert_eq!(louds.parent(0), None);
+    assert_eq!(louds.first_child(0), Some(1));
+    assert_eq!(louds.next_sibling(1), Some(2));
+    assert_eq!(louds.next_sibling(3), None);
+    assert_eq!(louds.children(3), Some(6..7));
+    assert_eq!(louds.degree(2), Some(0));
+    assert_eq!(louds.first_child(2), None);
+    assert_eq!(louds.parent(5), Some(1));
+    assert_eq!(louds.parent(6), Some(3));
+    assert_eq!(louds.next_sibling(4), Some(5));
+    assert_eq!(louds.next_sibling(5), None);
+    assert_eq!(louds.parent(7), None);
+
+    let node: Option<Node<String>> = louds.into();
+    assert_eq!(node.unwrap().to_newick(), input.trim_end_matches(";"));
+}
@@ -0,0 +1,15 @@
+use gray_tree::tree;
+
+#[test]
+fn literal_trees() {
+    let tree = tree! {1 => {2 => {4, 5 => {8,}}, 3 => {6 => {, 9}, 7}}};
+    assert_eq!(
+        tree.to_leetcode(),
+        "[1,2,3,4,5,6,7,null,null,8,null,null,9]"
+    );
+    let tree = tree! {"a".to_owned() => {"b".to_owned() => {}, "c".to_owned(),}};
+    assert_eq!(tree.to_leetcode(), r#"["a","b","c"]"#);
+    assert_eq!(tree! {1 => {2}}.to_leetcode(), "[1,2]");
+    assert_eq!(tree! {1 => {, 2 + 1}}.to_leetcode(), "[1,null,3]");
+    assert_eq!(tree!(1).to_leetcode(), "[1]");
+}
@@ -0,0 +1,120 @@
+use gray_tree::merkle::{MerkleHasher, MerkleTree, Side, StdHasher};
+
+/// Hasher writing the shape of the computation, to check it by reading.
+#[derive(Debug, Clone, Copy, Default)]
+struct Spell;
+
+impl MerkleHasher for Spell {
+    type Hash = String;
+
+    fn hash_leaf(&self, data: &[u8]) -> String {
+        String::from_utf8_lossy(data).into_owned()
+    }
+
+    fn hash_node(&self, left: &String, right: &String) -> String {
+        format!("({}{})", left, right)
+    }
+}
+
+/// Hash a level at a time from scratch, promoting a lone last node.
+fn model_root(leaves: &[&str]) -> Option<String> {
+    let mut level: Vec<String> = leaves.iter().map(|leaf| leaf.to_string()).collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => format!("({}{})", left, right),
+                [single] => single.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level.pop()
+}
+
+#[test]
+fn roots_and_proofs() {
+    let tree = MerkleTree::new(Spell, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(tree.len(), 5);
+    assert_eq!(tree.root().map(String::as_str), Some("(((ab)(cd))e)"));
+    assert_eq!(tree.leaf(2).map(String::as_str), Some("c"));
+    assert!(tree.leaf(5).is_none());
+
+    let proof = tree.proof(2).unwrap();
+    assert_eq!(proof.index(), 2);
+    assert_eq!(
+        proof.path(),
+        [
+            (Side::Right, "d".to_owned()),
+            (Side::Left, "(ab)".to_owned()),
+            (Side::Right, "e".to_owned()),
+        ]
+    );
+    // The last leaf is promoted, so its path skips the levels without a
+    // sibling.
+    assert_eq!(tree.proof(4).unwrap().path().len(), 1);
+    assert!(tree.proof(5).is_none());
+
+    let letters = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k"];
+    for len in 0..=letters.len() {
+        let leaves = &letters[..len];
+        let tree = MerkleTree::new(Spell, leaves.to_vec());
+        let root = model_root(leaves);
+        assert_eq!(tree.root(), root.as_ref());
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(idx).unwrap();
+            let root = root.as_ref().unwrap();
+            assert!(proof.verify(&Spell, root, leaf.as_bytes()));
+            assert!(!proof.verify(&Spell, root, b"x"));
+        }
+    }
+
+    let tree: MerkleTree<StdHasher> = MerkleTree::from_leaves(vec!["left", "right"]);
+    let root = *tree.root().unwrap();
+    let proof = tree.proof(1).unwrap();
+    assert!(proof.verify(&StdHasher, &root, b"right"));
+    assert!(!proof.verify(&StdHasher, &root, b"left"));
+    assert!(MerkleTree::<StdHasher>::from_leaves(Vec::<&[u8]>::new())
+        .root()
+        .is_none());
+}
+
+#[test]
+fn incremental_updates_match_rebuilding() {
+    let mut leaves: Vec<String> = Vec::new();
+    let mut tree = MerkleTree::new(Spell, Vec::<&str>::new());
+    for step in 0..40 {
+        let leaf = format!("{}", step % 10);
+        tree.push(leaf.as_bytes());
+        leaves.push(leaf);
+        if step % 3 == 0 {
+            let idx = step * 7 % leaves.len();
+            leaves[idx] = "x".to_owned();
+            tree.update_leaf(idx, b"x").unwrap();
+        }
+        let rebuilt = MerkleTree::new(Spell, &leaves);
+        assert_eq!(tree.root(), rebuilt.root());
+        assert_eq!(tree.len(), leaves.len());
+        // Proofs of the updated tree verify on their own.
+        let root = tree.root().unwrap().clone();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(idx).unwrap();
+            assert_eq!(proof, rebuilt.proof(idx).unwrap());
+            assert!(proof.verify(&Spell, &root, leaf.as_bytes()));
+        }
+    }
+    assert!(matches!(
+        tree.update_leaf(40, b"y"),
+        Err(gray_tree::Error::IndexOutOfRange { index: 40, len: 40 })
+    ));
+
+    // A proof taken before an update no longer verifies against the new root.
+    let mut tree: MerkleTree<StdHasher> = MerkleTree::from_leaves(vec!["a", "b", "c"]);
+    let proof = tree.proof(0).unwrap();
+    tree.update_leaf(2, b"z").unwrap();
+    assert!(!proof.verify(&StdHasher, tree.root().unwrap(), b"a"));
+    assert!(tree
+        .proof(0)
+        .unwrap()
+        .verify(&StdHasher, tree.root().unwrap(), b"a"));
+}
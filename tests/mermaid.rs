@@ -0,0 +1,58 @@
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::gen::{self, SplitMix64};
+use gray_tree::tree::Node;
+
+#[test]
+fn binary_edges_keep_their_side() {
+    let mut child = BinaryNode::new("b<c>");
+    child.set_left(Some(BinaryNode::new("x\ny")));
+    let mut root = BinaryNode::new("a\"#");
+    root.set_right(Some(child));
+    assert_eq!(
+        root.to_mermaid(),
+        "graph TD\n    n0[\"a#quot;#35;\"]\n    n1[\"b#lt;c#gt;\"]\n    n2[\"x<br>y\"]\n    n0 -->|R| n1\n    n1 -->|L| n2\n"
+    );
+}
+
+#[test]
+fn n_ary_nodes_in_pre_order() {
+    let mut middle = Node::new(2);
+    middle.push_child(Node::new(3));
+    let mut root = Node::new(0);
+    root.push_child(Node::new(1));
+    root.push_child(middle);
+    root.push_child(Node::new(4));
+    assert_eq!(
+        root.to_mermaid(),
+        "graph TD\n    n0[\"0\"]\n    n1[\"1\"]\n    n2[\"2\"]\n    n3[\"3\"]\n    n4[\"4\"]\n    n0 --> n1\n    n0 --> n2\n    n2 --> n3\n    n0 --> n4\n"
+    );
+}
+
+#[test]
+fn every_binary_node_and_edge_is_exported() {
+    let mut rng = SplitMix64::new(5);
+    for n in 1..40 {
+        let tree = gen::uniform(n, &mut rng).unwrap();
+        let mermaid = tree.to_mermaid();
+        let mut lines = mermaid.lines();
+        assert_eq!(lines.next(), Some("graph TD"));
+        // Nodes are numbered in pre order.
+        let labels: Vec<String> = tree
+            .to_pre_order_shape()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (data, _, _))| format!("    n{}[\"{}\"]", idx, data))
+            .collect();
+        let lines: Vec<&str> = lines.collect();
+        assert_eq!(lines[..n], labels[..]);
+        assert_eq!(lines.len(), 2 * n - 1);
+        let sides = |side: &str| lines[n..].iter().filter(|line| line.contains(side)).count();
+        let (lefts, rights) = tree.to_pre_order_shape().into_iter().fold(
+            (0, 0),
+            |(l, r), (_, has_left, has_right)| {
+                (l + usize::from(has_left), r + usize::from(has_right))
+            },
+        );
+        assert_eq!((sides("-->|L|"), sides("-->|R|")), (lefts, rights));
+    }
+}
@@ -0,0 +1,125 @@
+use gray_tree::binary_tree::augment::{AugmentedNode, Folded};
+use gray_tree::fenwick::FenwickTree;
+use gray_tree::finger_tree::FingerTree;
+use gray_tree::gen::{self, Rng, SplitMix64};
+use gray_tree::monoid::{Measure, Monoid, Sum};
+use gray_tree::segment_tree::SegmentTree;
+
+/// 2x2 integer matrices under multiplication, which does not commute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Matrix([[i64; 2]; 2]);
+
+impl Monoid for Matrix {
+    fn identity() -> Self {
+        Matrix([[1, 0], [0, 1]])
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        let (a, b) = (self.0, other.0);
+        let cell = |i: usize, j: usize| a[i][0] * b[0][j] + a[i][1] * b[1][j];
+        Matrix([[cell(0, 0), cell(0, 1)], [cell(1, 0), cell(1, 1)]])
+    }
+}
+
+impl Measure<Matrix> for Matrix {
+    fn measure(item: &Matrix) -> Self {
+        *item
+    }
+}
+
+/// Smallest value with its index, ties going to the smaller index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MinIndex(i64, usize);
+
+impl Monoid for MinIndex {
+    fn identity() -> Self {
+        MinIndex(i64::MAX, usize::MAX)
+    }
+
+    fn combine(&self, other: &Self) -> Self {
+        if (other.0, other.1) < (self.0, self.1) {
+            *other
+        } else {
+            *self
+        }
+    }
+}
+
+fn matrices(n: usize) -> Vec<Matrix> {
+    let mut rng = SplitMix64::new(9);
+    (0..n)
+        .map(|_| {
+            let mut cell = || (rng.next_u64() % 5) as i64 - 2;
+            Matrix([[cell(), cell()], [cell(), cell()]])
+        })
+        .collect()
+}
+
+fn product(items: &[Matrix]) -> Matrix {
+    items
+        .iter()
+        .fold(Matrix::identity(), |acc, item| acc.combine(item))
+}
+
+#[test]
+fn matrix_product_everywhere() {
+    let items = matrices(12);
+
+    let mut segments: SegmentTree<Matrix> = SegmentTree::new(items.clone());
+    assert_eq!(segments.query(3..9).unwrap(), product(&items[3..9]));
+
+    let fingers: FingerTree<Matrix, Matrix> = items.iter().copied().collect();
+    assert_eq!(fingers.measure(), product(&items));
+
+    let shape = gen::complete(items.len()).unwrap();
+    let tree: AugmentedNode<Matrix, Folded<Matrix>> = shape.mid_order_map(|i| items[i]).into();
+    assert_eq!(tree.aggregate().0, product(&items));
+    tree.validate().unwrap();
+
+    let pair: (Matrix, Sum) = Monoid::combine(&(items[0], Sum(2)), &(items[1], Sum(3)));
+    assert_eq!(pair, (items[0].combine(&items[1]), Sum(5)));
+}
+
+#[test]
+fn min_with_index() {
+    let values = [5, 3, 8, 3, 9, 1, 1, 7];
+    let items: Vec<_> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| MinIndex(value, i))
+        .collect();
+
+    let mut segments: SegmentTree<MinIndex> = SegmentTree::new(items.clone());
+    assert_eq!(segments.query(0..5).unwrap(), MinIndex(3, 1));
+    assert_eq!(segments.query(4..8).unwrap(), MinIndex(1, 5));
+
+    let mut fenwick = FenwickTree::from(items);
+    assert_eq!(fenwick.prefix(4).unwrap(), MinIndex(3, 1));
+    assert_eq!(fenwick.prefix(0).unwrap(), MinIndex::identity());
+    fenwick.add(2, &MinIndex(0, 2)).unwrap();
+    assert_eq!(fenwick.prefix(4).unwrap(), MinIndex(0, 2));
+    assert!(fenwick.add(8, &MinIndex(0, 8)).is_err());
+    assert!(fenwick.prefix(9).is_err());
+}
+
+#[test]
+fn fenwick_prefix_sums() {
+    let mut rng = SplitMix64::new(4);
+    let mut values = vec![0i64; 100];
+    let mut fenwick: FenwickTree<Sum> = FenwickTree::new(100);
+    for _ in 0..1000 {
+        let index = (rng.next_u64() % 100) as usize;
+        let delta = (rng.next_u64() % 21) as i64 - 10;
+        values[index] += delta;
+        fenwick.add(index, &Sum(delta)).unwrap();
+    }
+    for end in 0..=100 {
+        assert_eq!(
+            fenwick.prefix(end).unwrap().0,
+            values[..end].iter().sum::<i64>()
+        );
+    }
+    let built = FenwickTree::from(values.iter().map(|&v| Sum(v)).collect::<Vec<_>>());
+    assert_eq!(built.prefix(100).unwrap(), fenwick.prefix(100).unwrap());
+    assert_eq!(built.len(), 100);
+}
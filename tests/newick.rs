@@ -0,0 +1,49 @@
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::tree::Node;
+use gray_tree::Error;
+
+#[test]
+fn binary_round_trip() {
+    let input = "((4,(8,)5)2,((,9)6,7)3)1";
+    let tree = BinaryNode::<i32>::from_newick(input).unwrap();
+    assert_eq!(
+        tree.to_leetcode(),
+        "[1,2,3,4,5,6,7,null,null,8,null,null,9]"
+    );
+    assert_eq!(tree.to_newick(), input);
+}
+
+#[test]
+fn n_ary_round_trip_with_quoted_labels() {
+    let tree = Node::<String>::from_newick("(('b c','it''s',d)a, e, 'x(y)')root;").unwrap();
+    assert_eq!(tree.children().len(), 3);
+    assert_eq!(tree.children()[0].children()[1].data(), "it's");
+    let output = tree.to_newick();
+    assert_eq!(output, "(('b c','it''s',d)a,e,'x(y)')root");
+    assert_eq!(
+        Node::<String>::from_newick(&output).unwrap().to_newick(),
+        output
+    );
+}
+
+#[test]
+fn reject_malformed_input() {
+    for input in &[
+        "",
+        "(1,2",
+        "(1,2,3)4",
+        "(1:0.5,2)3",
+        "(1,2)3 x",
+        "'abc",
+        "(1,2)x",
+    ] {
+        assert!(
+            matches!(
+                BinaryNode::<i32>::from_newick(input),
+                Err(Error::Parse { position, .. }) if position <= input.len()
+            ),
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "rayon")]
+
+use gray_tree::gen::{self, SplitMix64};
+
+#[test]
+fn par_map_matches_sequential_map() {
+    let mut rng = SplitMix64::new(3);
+    let tree = gen::uniform(5000, &mut rng).unwrap();
+    let expected = tree.clone().mid_order_map(|x| x * 2);
+    let mapped = tree.par_map(|x| x * 2);
+    assert_eq!(mapped.to_leetcode(), expected.to_leetcode());
+
+    let chain = gen::left_chain(100_000).unwrap().par_map(|x| x + 1);
+    assert_eq!(chain.iter().next(), Some(&1));
+}
+
+#[test]
+fn par_fold_joins_subtrees() {
+    let tree = gen::perfect(12).unwrap();
+    let n = (1 << 12) - 1;
+    let sum = tree.par_fold(|x, l, r| x + l.unwrap_or(0) + r.unwrap_or(0));
+    assert_eq!(sum, n * (n - 1) / 2);
+    let height = tree.par_fold(|_, l: Option<usize>, r| 1 + l.unwrap_or(0).max(r.unwrap_or(0)));
+    assert_eq!(height, 12);
+
+    let chain = gen::right_chain(100_000).unwrap();
+    assert_eq!(chain.par_fold(|_, l, r| 1 + l.or(r).unwrap_or(0)), 100_000);
+}
+
+#[test]
+fn runs_on_the_current_pool() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(3)
+        .build()
+        .unwrap();
+    let tree = gen::perfect(10).unwrap();
+    let mapped = pool.install(|| {
+        tree.clone()
+            .par_map(|x| (x, rayon::current_thread_index().is_some()))
+    });
+    assert!(mapped.iter().all(|&(_, on_pool)| on_pool));
+    let one = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let sum = one.install(|| tree.par_fold(|x, l, r| x + l.unwrap_or(0) + r.unwrap_or(0)));
+    assert_eq!(sum, 1023 * 1022 / 2);
+}
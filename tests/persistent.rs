@@ -0,0 +1,136 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::persistent::{History, PersistentMap};
+use gray_tree::Error;
+use std::collections::BTreeMap;
+use std::thread;
+
+#[test]
+fn old_versions_stay_queryable() {
+    let mut rng = SplitMix64::new(8);
+    let mut map = PersistentMap::new();
+    let mut expected = vec![BTreeMap::new()];
+    for step in 0..2000u64 {
+        // Mostly extend the latest version, sometimes branch off an old one.
+        let base = if step % 10 == 0 {
+            rng.next_u64() as usize % map.version_count()
+        } else {
+            map.latest().index()
+        };
+        let base_version = map.versions().nth(base).unwrap();
+        let mut next = expected[base].clone();
+        let key = rng.next_u64() % 300;
+        let version = if rng.next_u64().is_multiple_of(4) {
+            next.remove(&key);
+            map.remove(base_version, &key).unwrap()
+        } else {
+            next.insert(key, step);
+            map.insert(base_version, key, step).unwrap()
+        };
+        assert_eq!(map.parent(version).unwrap(), Some(base_version));
+        expected.push(next);
+    }
+    assert_eq!(map.version_count(), expected.len());
+    for (version, expected) in map.versions().zip(&expected) {
+        let snapshot = map.snapshot(version).unwrap();
+        assert_eq!(snapshot.len(), expected.len());
+        assert!(snapshot.iter().eq(expected.iter()));
+        assert!(snapshot.height() <= 12);
+    }
+    let latest = map.latest();
+    let key = *expected.last().unwrap().keys().next().unwrap();
+    assert_eq!(
+        map.get(latest, &key).unwrap(),
+        expected.last().unwrap().get(&key)
+    );
+
+    let stats = map.stats();
+    assert_eq!(stats.versions, 2001);
+    assert_eq!(
+        stats.logical_nodes,
+        expected.iter().map(BTreeMap::len).sum::<usize>()
+    );
+    // Path copying allocates a few nodes per version instead of a copy.
+    assert!(stats.allocated_nodes < 2001 * 10);
+    assert!(stats.sharing_ratio() > 4.0);
+}
+
+#[test]
+fn versions_and_snapshots() {
+    let mut map = PersistentMap::new();
+    let empty = map.initial();
+    let one = map.insert(empty, "a", 1).unwrap();
+    let two = map.insert(one, "b", 2).unwrap();
+    let changed = map.insert(two, "a", 10).unwrap();
+    let same = map.remove(changed, "zzz").unwrap();
+
+    assert_eq!(map.get(one, "a").unwrap(), Some(&1));
+    assert_eq!(map.get(changed, "a").unwrap(), Some(&10));
+    assert_eq!(map.get(empty, "a").unwrap(), None);
+    assert_eq!(map.len(same).unwrap(), 2);
+    assert_eq!(map.parent(empty).unwrap(), None);
+    assert_eq!(
+        map.versions().collect::<Vec<_>>(),
+        [empty, one, two, changed, same]
+    );
+    assert_eq!(map.stats().allocated_nodes, 1 + 2 + 1);
+
+    let snapshot = map.snapshot(changed).unwrap();
+    let handle = thread::spawn(move || format!("{:?}", snapshot));
+    assert_eq!(handle.join().unwrap(), r#"{"a": 10, "b": 2}"#);
+
+    let other: PersistentMap<&str, i32> = PersistentMap::new();
+    assert!(matches!(other.snapshot(two), Err(Error::UnknownVersion)));
+}
+
+#[test]
+fn undo_redo_and_branching() {
+    let mut history = History::new();
+    let initial = history.head();
+    assert!(!history.can_undo());
+    assert_eq!(history.commit(), initial);
+
+    assert!(history.insert("a", 1));
+    assert!(history.insert("b", 2));
+    assert!(history.is_dirty());
+    let first = history.commit();
+    assert!(!history.is_dirty());
+    assert!(!history.insert("a", 10));
+    assert!(history.remove("b"));
+    assert!(!history.remove("b"));
+    let second = history.commit();
+    assert_eq!(history.get("a"), Some(&10));
+
+    assert_eq!(history.undo(), Some(first));
+    assert_eq!(history.get("a"), Some(&1));
+    assert_eq!(history.undo(), Some(initial));
+    assert_eq!(history.undo(), None);
+    assert!(history.working().is_empty());
+    assert_eq!(history.redo(), Some(first));
+    history.insert("c", 3);
+    // Redoing drops the staged edits.
+    assert_eq!(history.redo(), Some(second));
+    assert_eq!(history.get("c"), None);
+    assert_eq!(history.redo(), None);
+
+    history.checkout(first).unwrap();
+    assert!(!history.can_redo());
+    history.insert("d", 4);
+    history.discard();
+    assert_eq!(history.get("d"), None);
+    history.insert("d", 4);
+    let branch = history.commit();
+    assert_eq!(history.versions().parent(branch).unwrap(), Some(first));
+    let entries: Vec<_> = history.working().iter().collect();
+    assert_eq!(entries, [(&"a", &1), (&"b", &2), (&"d", &4)]);
+    assert_eq!(history.versions().get(second, "d").unwrap(), None);
+
+    let mut other = PersistentMap::new();
+    for key in 0..10 {
+        other.insert(other.latest(), key, ()).unwrap();
+    }
+    assert!(matches!(
+        history.checkout(other.latest()),
+        Err(Error::UnknownVersion)
+    ));
+    assert_eq!(history.head(), branch);
+}
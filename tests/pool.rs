@@ -0,0 +1,220 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::binary_tree::pool::{NodeAlloc, NodePool};
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::heap::{HeapKind, TreeHeap};
+use gray_tree::tree_map::{Entry, MapNode, TreeMap};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+/// Allocator counting the boxes going through it.
+#[derive(Debug, Default, Clone)]
+struct Counting {
+    allocs: usize,
+    frees: usize,
+}
+
+impl Counting {
+    fn live(&self) -> usize {
+        self.allocs - self.frees
+    }
+}
+
+impl<N> NodeAlloc<N> for Counting {
+    fn alloc(&mut self, node: N) -> Box<N> {
+        self.allocs += 1;
+        Box::new(node)
+    }
+
+    fn free(&mut self, node: Box<N>) -> N {
+        self.frees += 1;
+        *node
+    }
+}
+
+#[test]
+fn pool_reuses_boxes() {
+    let mut pool = NodePool::new();
+    let node = pool.alloc_node(1);
+    let addr: *const Node<i32> = &*node;
+    assert_eq!(pool.free(node).into_data(), 1);
+    assert_eq!(pool.len(), 1);
+    let node = pool.alloc_node(2);
+    assert!(std::ptr::eq(addr, &*node));
+    assert!(pool.is_empty());
+
+    let mut tree = Node::new(0);
+    tree.set_left(Some(Node::new(1)));
+    tree.set_right(Some(Node::new(2)));
+    pool.reclaim(node);
+    pool.reclaim(tree.boxed());
+    assert_eq!(pool.len(), 4);
+    pool.clear();
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn bst_allocates_through_allocator() {
+    let mut rng = SplitMix64::new(11);
+    let mut counting = Counting::default();
+    let mut pool = NodePool::new();
+    let mut counted = BinarySearchTree::new_in(&mut counting);
+    let mut pooled = BinarySearchTree::new_in(&mut pool);
+    let mut model = BTreeSet::new();
+    for _ in 0..2000 {
+        let value = rng.next_u64() % 200;
+        if rng.next_u64().is_multiple_of(2) {
+            assert_eq!(counted.insert(value), model.insert(value));
+            pooled.insert(value);
+        } else {
+            assert_eq!(counted.remove(&value), model.take(&value));
+            pooled.remove(&value);
+        }
+        assert_eq!(counted.allocator().live(), model.len());
+    }
+    assert!(counted.iter().eq(model.iter()));
+    assert!(pooled.iter().eq(model.iter()));
+    drop(counted);
+    assert!(counting.allocs > model.len());
+
+    // Removals leave their boxes in the pool, and insertions take them
+    // back before allocating.
+    let before = pool.len();
+    let mut pooled = BinarySearchTree::new_in(&mut pool);
+    let count = before as u64;
+    for value in 0..count {
+        pooled.insert(value);
+    }
+    assert_eq!(pooled.allocator().len(), 0);
+    pooled.insert(count);
+    for value in 0..=count {
+        pooled.remove(&value);
+    }
+    assert_eq!(pool.len(), before + 1);
+
+    // Rejected nodes go back to the allocator and kept ones stay in place.
+    let mut counting = Counting::default();
+    let mut counted = BinarySearchTree::new_in(&mut counting);
+    counted.extend(vec![50, 25, 75, 10, 30, 60, 90, 5, 27, 80]);
+    let kept: Vec<*const i32> = counted
+        .iter()
+        .filter(|value| *value % 25 != 0)
+        .map(|value| value as *const i32)
+        .collect();
+    counted.retain(|value| value % 25 != 0);
+    assert_eq!(counted.len(), 7);
+    assert!(counted.iter().copied().eq(vec![5, 10, 27, 30, 60, 80, 90]));
+    assert!(counted.validate().is_ok());
+    assert!(counted.iter().map(|value| value as *const i32).eq(kept));
+    assert_eq!(counted.allocator().frees, 3);
+    assert_eq!(counted.allocator().live(), 7);
+}
+
+#[test]
+fn bst_bulk_operations_allocate_through_allocator() {
+    let tree = BinarySearchTree::from_sorted_in(&[1, 3, 5, 7], Counting::default()).unwrap();
+    assert_eq!(tree.allocator().allocs, 4);
+    let other = BinarySearchTree::<_, Counting>::try_from(vec![2, 3, 4, 6]).unwrap();
+    assert_eq!(other.allocator().allocs, 4);
+    let merged = tree.merge(other);
+    assert!(merged.iter().copied().eq(1..=7));
+    assert_eq!(merged.allocator().frees, 4);
+    assert_eq!(merged.allocator().live(), 7);
+
+    let (less, greater) = merged.split(&4);
+    assert!(less.iter().copied().eq(1..4));
+    assert!(greater.iter().copied().eq(4..=7));
+    assert_eq!(less.allocator().allocs, greater.allocator().allocs);
+
+    let collected: BinarySearchTree<i32, Counting> = (0..10).rev().collect();
+    assert_eq!(collected.allocator().allocs, 10);
+    let union = &collected | &BinarySearchTree::from_iter(5..15);
+    assert!(union.iter().copied().eq(0..15));
+    assert_eq!(union.allocator().live(), 15);
+    let wrapped = BinarySearchTree::<_, Counting>::try_from(Node::new(1)).unwrap();
+    assert_eq!(wrapped.allocator().allocs, 1);
+}
+
+#[test]
+fn heap_allocates_through_allocator() {
+    let mut rng = SplitMix64::new(12);
+    let mut counting = Counting::default();
+    let mut heap = TreeHeap::new_in(HeapKind::Max, &mut counting);
+    let mut model = BinaryHeap::new();
+    for _ in 0..2000 {
+        if rng.next_u64().is_multiple_of(3) {
+            assert_eq!(heap.pop(), model.pop());
+        } else {
+            let value = rng.next_u64() % 100;
+            heap.push(value);
+            model.push(value);
+        }
+        // The root is held inline, every other node is boxed.
+        assert_eq!(heap.allocator().live(), model.len().saturating_sub(1));
+    }
+    heap.validate().unwrap();
+
+    let mut pool = NodePool::new();
+    let mut heap = TreeHeap::new_in(HeapKind::Min, &mut pool);
+    heap.extend(0..10);
+    while heap.pop().is_some() {}
+    assert_eq!(pool.len(), 9);
+}
+
+#[test]
+fn tree_map_allocates_through_allocator() {
+    let mut rng = SplitMix64::new(13);
+    let mut counting = Counting::default();
+    let mut map = TreeMap::new_in(&mut counting);
+    let mut model = BTreeMap::new();
+    for step in 0..3000 {
+        let key = rng.next_u64() % 300;
+        match rng.next_u64() % 7 {
+            0 | 1 => assert_eq!(map.insert(key, step), model.insert(key, step)),
+            2 => assert_eq!(map.remove(&key), model.remove(&key)),
+            3 => match map.entry(key) {
+                Entry::Occupied(entry) => {
+                    assert_eq!(Some(entry.remove()), model.remove(&key));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(step);
+                    model.insert(key, step);
+                }
+            },
+            4 => assert_eq!(map.pop_first(), model.pop_first()),
+            5 => {
+                // Only part of the range is taken, the rest goes back to
+                // the allocator when the iterator is dropped.
+                let range = key..key + 10;
+                let drained: Vec<_> = map.drain(range.clone()).take(3).collect();
+                let expected: Vec<_> = model.range(range.clone()).take(3).collect();
+                assert!(drained.iter().map(|(k, v)| (k, v)).eq(expected));
+                model.retain(|key, _| !range.contains(key));
+            }
+            _ => {
+                map.retain(|key, _| key % 7 != 0);
+                model.retain(|key, _| key % 7 != 0);
+            }
+        }
+        assert_eq!(map.allocator().live(), model.len());
+    }
+    map.validate().unwrap();
+    assert!(map.iter().eq(model.iter()));
+    map.clear();
+    assert_eq!(map.allocator().live(), 0);
+
+    let mut pool: NodePool<MapNode<u64, ()>> = NodePool::new();
+    let mut map = TreeMap::new_in(&mut pool);
+    map.extend((0..10).map(|key| (key, ())));
+    assert_eq!(map.pop_last(), Some((9, ())));
+    map.retain(|key, _| key % 3 == 0);
+    assert_eq!(map.allocator().len(), 7);
+    map.insert(20, ());
+    assert_eq!(map.allocator().len(), 6);
+    assert!(map.keys().copied().eq([0, 3, 6, 20]));
+    assert_eq!(map.drain(..5).count(), 2);
+    assert_eq!(map.allocator().len(), 8);
+    map.clear();
+    assert_eq!(map.allocator().len(), 10);
+}
@@ -0,0 +1,157 @@
+use gray_tree::binary_tree::rc::RcNode;
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::Error;
+
+#[test]
+fn parent_links_follow_attach_and_detach() {
+    let root = RcNode::new(0);
+    let left = RcNode::new(1);
+    let leaf = RcNode::new(2);
+    root.set_left(Some(left.clone())).unwrap();
+    left.set_right(Some(leaf.clone())).unwrap();
+
+    assert_eq!(leaf.depth(), 2);
+    let ancestors: Vec<_> = leaf.ancestors().map(|node| *node.data()).collect();
+    assert_eq!(ancestors, [1, 0]);
+    assert!(leaf.root().ptr_eq(&root));
+    assert_eq!(root.depth(), 0);
+    assert!(root.root().ptr_eq(&root));
+    assert!(matches!(
+        leaf.set_left(Some(root.clone())),
+        Err(Error::WouldCycle)
+    ));
+
+    // Moving a subtree updates its parent link.
+    root.set_right(Some(leaf.clone())).unwrap();
+    assert!(left.right().is_none());
+    assert!(leaf.parent().unwrap().ptr_eq(&root));
+    assert_eq!(leaf.depth(), 1);
+
+    left.detach();
+    assert!(left.parent().is_none());
+    assert!(root.left().is_none());
+
+    // Parents are not kept alive by their children.
+    let orphan = {
+        let parent = RcNode::new(3);
+        let child = RcNode::new(4);
+        parent.set_left(Some(child.clone())).unwrap();
+        child
+    };
+    assert!(orphan.parent().is_none());
+    assert_eq!(orphan.depth(), 0);
+}
+
+/// Parent and child links of every node, by label.
+#[derive(Clone, Copy, Default)]
+struct Links {
+    parent: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+fn is_ancestor(model: &[Links], ancestor: usize, mut label: usize) -> bool {
+    loop {
+        if label == ancestor {
+            return true;
+        }
+        match model[label].parent {
+            Some(parent) => label = parent,
+            None => return false,
+        }
+    }
+}
+
+fn detach(model: &mut [Links], label: usize) {
+    if let Some(parent) = model[label].parent.take() {
+        if model[parent].left == Some(label) {
+            model[parent].left = None;
+        } else {
+            model[parent].right = None;
+        }
+    }
+}
+
+fn pre_order(model: &[Links], label: usize) -> Vec<usize> {
+    let mut order = Vec::new();
+    let mut stack = vec![label];
+    while let Some(label) = stack.pop() {
+        order.push(label);
+        stack.extend(model[label].right);
+        stack.extend(model[label].left);
+    }
+    order
+}
+
+#[test]
+fn links_match_a_model() {
+    const LEN: usize = 24;
+    let mut rng = SplitMix64::new(363);
+    let nodes: Vec<RcNode<usize>> = (0..LEN).map(RcNode::new).collect();
+    let mut model = vec![Links::default(); LEN];
+    for _ in 0..3000 {
+        let a = (rng.next_u64() % LEN as u64) as usize;
+        let b = (rng.next_u64() % LEN as u64) as usize;
+        match rng.next_u64() % 5 {
+            op @ 0..=3 => {
+                let child = if op == 3 { None } else { Some(b) };
+                let is_right = op == 1;
+                let handle = child.map(|child| nodes[child].clone());
+                let result = if is_right {
+                    nodes[a].set_right(handle)
+                } else {
+                    nodes[a].set_left(handle)
+                };
+                if child.is_some_and(|child| is_ancestor(&model, child, a)) {
+                    assert!(matches!(result, Err(Error::WouldCycle)));
+                    continue;
+                }
+                if let Some(child) = child {
+                    detach(&mut model, child);
+                    model[child].parent = Some(a);
+                }
+                let slot = if is_right {
+                    &mut model[a].right
+                } else {
+                    &mut model[a].left
+                };
+                let old = std::mem::replace(slot, child);
+                if let Some(old) = old {
+                    model[old].parent = None;
+                }
+                let got = result.unwrap().map(|node| *node.data());
+                assert_eq!(got, old);
+            }
+            _ => {
+                nodes[a].detach();
+                detach(&mut model, a);
+            }
+        }
+        for (label, node) in nodes.iter().enumerate() {
+            let links = model[label];
+            let label_of = |node: Option<RcNode<usize>>| node.map(|node| *node.data());
+            assert_eq!(label_of(node.parent()), links.parent);
+            assert_eq!(label_of(node.left()), links.left);
+            assert_eq!(label_of(node.right()), links.right);
+            let mut root = label;
+            while let Some(parent) = model[root].parent {
+                root = parent;
+            }
+            assert!(node.root().ptr_eq(&nodes[root]));
+        }
+    }
+    // Copying out and back in keeps the shape of every subtree.
+    for (label, node) in nodes.iter().enumerate() {
+        let tree: Node<usize> = node.to_node();
+        let data: Vec<usize> = tree.iter_to_depth(usize::MAX).map(|(_, &d)| d).collect();
+        assert_eq!(data, pre_order(&model, label));
+        let copy = RcNode::from(tree);
+        assert!(copy.parent().is_none());
+        assert_eq!(copy.to_node().iter_to_depth(usize::MAX).count(), data.len());
+        let weak = copy.downgrade();
+        assert!(weak.upgrade().unwrap().ptr_eq(&copy));
+        drop(copy);
+        assert!(weak.upgrade().is_none());
+    }
+}
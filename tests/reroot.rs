@@ -0,0 +1,63 @@
+use gray_tree::gen;
+use gray_tree::lca::{EulerLca, Lca, TreeIndex};
+use gray_tree::monoid::{Max, Sum};
+use gray_tree::reroot::reroot;
+
+fn random_tree(len: usize) -> TreeIndex {
+    let mut seed: usize = 7;
+    let mut parents = vec![None];
+    for v in 1..len {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        parents.push(Some((seed >> 33) % v));
+    }
+    TreeIndex::new(&parents).unwrap()
+}
+
+#[test]
+fn sum_of_distances_from_every_vertex() {
+    let tree = random_tree(200);
+    // Nodes counted and distances summed on one side of every edge.
+    let sums: Vec<(i64, i64)> = reroot(
+        &tree,
+        |&(nodes, dist): &(i64, i64), _, _| (Sum(nodes), Sum(dist + nodes)),
+        |&(Sum(nodes), Sum(dist)), _| (nodes + 1, dist),
+    );
+    let lca = EulerLca::new(tree.clone());
+    for (vertex, &(nodes, dist)) in sums.iter().enumerate() {
+        let expected: usize = (0..200).map(|other| lca.distance(vertex, other)).sum();
+        assert_eq!(nodes, 200);
+        assert_eq!(dist, expected as i64);
+    }
+
+    let single = reroot(
+        &TreeIndex::new(&[None]).unwrap(),
+        |&count: &i64, _, _| Sum(count),
+        |&Sum(sum), _| sum + 1,
+    );
+    assert_eq!(single, [1]);
+}
+
+#[test]
+fn eccentricities_of_a_deep_chain() {
+    let chain = gen::right_chain(50_000).unwrap();
+    let tree = TreeIndex::binary(&chain);
+    let far: Vec<i64> = reroot(
+        &tree,
+        |&far: &i64, _, _| Max(far + 1),
+        |&Max(far), _| far.max(0),
+    );
+    assert_eq!(far[0], 49_999);
+    assert_eq!(far[25_000], 25_000);
+    assert_eq!(far[49_999], 49_999);
+
+    // The edge closure sees which way it is crossed: rooted anywhere, the
+    // edges kept from the original orientation are those off the path to
+    // the original root.
+    let tree = TreeIndex::new(&[None, Some(0), Some(0), Some(1)]).unwrap();
+    let kept: Vec<i64> = reroot(
+        &tree,
+        |&count: &i64, from, to| Sum(count + i64::from(tree.parent(from) == Some(to))),
+        |&Sum(count), _| count,
+    );
+    assert_eq!(kept, [3, 2, 2, 1]);
+}
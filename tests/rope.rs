@@ -0,0 +1,128 @@
+use gray_tree::rope::Rope;
+use gray_tree::Error;
+
+fn byte_at(text: &str, at: usize) -> usize {
+    text.char_indices()
+        .nth(at)
+        .map_or(text.len(), |(idx, _)| idx)
+}
+
+fn check(rope: &Rope, model: &str) {
+    assert_eq!(rope.to_string(), model);
+    assert_eq!(rope.len_chars(), model.chars().count());
+    assert_eq!(rope.len_bytes(), model.len());
+    assert_eq!(rope.is_empty(), model.is_empty());
+    assert!(rope.chars().eq(model.chars()));
+}
+
+#[test]
+fn edits_match_a_string_model() {
+    let pieces = ["a", "bc", "déf", "ghij", "✓", "", "klmno"];
+    let mut seed: u64 = 17;
+    let mut next = |modulo: usize| {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (seed >> 33) as usize % modulo.max(1)
+    };
+    let mut rope = Rope::new();
+    let mut model = String::new();
+    for step in 0..2000 {
+        let len = model.chars().count();
+        match step % 5 {
+            0 | 1 => {
+                let at = next(len + 1);
+                let piece = pieces[next(pieces.len())];
+                rope.insert(at, piece).unwrap();
+                model.insert_str(byte_at(&model, at), piece);
+            }
+            2 => {
+                let start = next(len + 1);
+                let end = start + next(len - start + 1).min(3);
+                rope.remove(start..end).unwrap();
+                model.replace_range(byte_at(&model, start)..byte_at(&model, end), "");
+            }
+            3 => {
+                let at = next(len + 1);
+                let tail = rope.split_off(at).unwrap();
+                let model_tail = model.split_off(byte_at(&model, at));
+                check(&tail, &model_tail);
+                check(&rope, &model);
+                rope.concat(tail);
+                model.push_str(&model_tail);
+            }
+            _ => {
+                let other = pieces[next(pieces.len())];
+                rope.concat(Rope::from(other));
+                model.push_str(other);
+            }
+        }
+        check(&rope, &model);
+        if step % 97 == 0 {
+            for (at, c) in model.chars().enumerate() {
+                assert_eq!(rope.char(at), Some(c));
+                assert_eq!(rope.char_to_byte(at), Some(byte_at(&model, at)));
+            }
+            for (at, _) in model.char_indices() {
+                assert_eq!(rope.byte_to_char(at), Some(model[..at].chars().count()));
+            }
+        }
+    }
+    let len = rope.len_chars();
+    assert!(rope.height() <= 4 * (usize::BITS - len.leading_zeros()) as usize);
+    assert_eq!(rope.char(len), None);
+}
+
+#[test]
+fn bad_ranges_leave_the_rope_unchanged() {
+    let mut rope = Rope::from("abcdefgh");
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 5..2;
+    assert!(matches!(
+        rope.remove(reversed),
+        Err(Error::IndexOutOfRange { index: 5, len: 2 })
+    ));
+    check(&rope, "abcdefgh");
+    assert!(matches!(
+        rope.remove(2..9),
+        Err(Error::IndexOutOfRange { index: 9, len: 8 })
+    ));
+    check(&rope, "abcdefgh");
+    assert!(rope.remove(9..10).is_err());
+    assert!(rope.insert(9, "x").is_err());
+    assert!(rope.split_off(9).is_err());
+    check(&rope, "abcdefgh");
+
+    rope.remove(8..8).unwrap();
+    rope.remove(0..0).unwrap();
+    check(&rope, "abcdefgh");
+    rope.remove(0..8).unwrap();
+    check(&rope, "");
+    assert!(rope.remove(0..1).is_err());
+}
+
+#[test]
+fn indices_past_the_end_are_rejected() {
+    let rope = Rope::from("abc");
+    assert_eq!(rope.char_to_byte(3), Some(3));
+    assert_eq!(rope.byte_to_char(3), Some(3));
+    assert_eq!(rope.char_to_byte(4), None);
+    assert_eq!(rope.char_to_byte(10), None);
+    assert_eq!(rope.byte_to_char(4), None);
+    assert_eq!(rope.byte_to_char(10), None);
+
+    // `é` takes two bytes and `😀` four.
+    let rope = Rope::from("aé😀b");
+    assert_eq!(rope.char_to_byte(2), Some(3));
+    assert_eq!(rope.char_to_byte(4), Some(8));
+    assert_eq!(rope.char_to_byte(5), None);
+    assert_eq!(rope.byte_to_char(3), Some(2));
+    assert_eq!(rope.byte_to_char(2), None);
+    assert_eq!(rope.byte_to_char(5), None);
+    assert_eq!(rope.byte_to_char(8), Some(4));
+    assert_eq!(rope.byte_to_char(9), None);
+
+    let empty = Rope::new();
+    assert_eq!(empty.char_to_byte(0), Some(0));
+    assert_eq!(empty.byte_to_char(0), Some(0));
+    assert_eq!(empty.char_to_byte(1), None);
+    assert_eq!(empty.byte_to_char(1), None);
+}
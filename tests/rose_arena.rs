@@ -0,0 +1,202 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::tree::arena::{Arena, NodeId};
+use gray_tree::Error;
+
+/// Sibling lists, the parent of each list being `None` for the chains
+/// of detached nodes.
+#[derive(Default)]
+struct Model {
+    lists: Vec<(Option<usize>, Vec<usize>)>,
+    live: Vec<bool>,
+}
+
+impl Model {
+    fn add(&mut self) -> usize {
+        self.live.push(true);
+        self.lists.push((None, vec![self.live.len() - 1]));
+        self.live.len() - 1
+    }
+
+    fn list_of(&self, node: usize) -> usize {
+        self.lists
+            .iter()
+            .position(|(_, list)| list.contains(&node))
+            .unwrap()
+    }
+
+    fn parent(&self, node: usize) -> Option<usize> {
+        self.lists[self.list_of(node)].0
+    }
+
+    fn children(&self, node: usize) -> Vec<usize> {
+        self.lists
+            .iter()
+            .find(|(parent, _)| *parent == Some(node))
+            .map_or(Vec::new(), |(_, list)| list.clone())
+    }
+
+    fn siblings(&self, node: usize) -> (Option<usize>, Option<usize>) {
+        let list = &self.lists[self.list_of(node)].1;
+        let idx = list.iter().position(|&x| x == node).unwrap();
+        (
+            idx.checked_sub(1).map(|idx| list[idx]),
+            list.get(idx + 1).copied(),
+        )
+    }
+
+    fn is_ancestor(&self, ancestor: usize, mut node: usize) -> bool {
+        loop {
+            if node == ancestor {
+                return true;
+            }
+            match self.parent(node) {
+                Some(parent) => node = parent,
+                None => return false,
+            }
+        }
+    }
+
+    fn detach(&mut self, node: usize) {
+        let list = self.list_of(node);
+        self.lists[list].1.retain(|&x| x != node);
+        self.lists
+            .retain(|(parent, list)| parent.is_some() || !list.is_empty());
+        self.lists.push((None, vec![node]));
+    }
+
+    /// Insert a detached node at `idx` in the list holding `target`, or in
+    /// the children of `target` if `under` is set.
+    fn insert(&mut self, target: usize, node: usize, under: bool, first: bool) {
+        self.lists.retain(|(_, list)| list != &[node]);
+        let list = if under {
+            match self.lists.iter().position(|(p, _)| *p == Some(target)) {
+                Some(list) => list,
+                None => {
+                    self.lists.push((Some(target), Vec::new()));
+                    self.lists.len() - 1
+                }
+            }
+        } else {
+            self.list_of(target)
+        };
+        let items = &mut self.lists[list].1;
+        let idx = match (under, first) {
+            (true, true) => 0,
+            (true, false) => items.len(),
+            (false, before) => {
+                let idx = items.iter().position(|&x| x == target).unwrap();
+                if before {
+                    idx
+                } else {
+                    idx + 1
+                }
+            }
+        };
+        items.insert(idx, node);
+    }
+
+    fn pre_order(&self, node: usize, out: &mut Vec<usize>) {
+        out.push(node);
+        for child in self.children(node) {
+            self.pre_order(child, out);
+        }
+    }
+
+    fn remove(&mut self, node: usize) {
+        self.detach(node);
+        let mut subtree = Vec::new();
+        self.pre_order(node, &mut subtree);
+        for &x in &subtree {
+            self.live[x] = false;
+        }
+        self.lists.retain(|(parent, list)| match parent {
+            Some(parent) => !subtree.contains(parent),
+            None => !subtree.contains(&list[0]),
+        });
+    }
+}
+
+#[test]
+fn matches_sibling_lists() {
+    let mut rng = SplitMix64::new(357);
+    let mut arena = Arena::new();
+    let mut ids: Vec<NodeId> = Vec::new();
+    let mut model = Model::default();
+    for _ in 0..8 {
+        ids.push(arena.new_node(model.add()));
+    }
+    for _ in 0..1500 {
+        let a = (rng.next_u64() % ids.len() as u64) as usize;
+        let b = (rng.next_u64() % ids.len() as u64) as usize;
+        let op = rng.next_u64() % 10;
+        let result = match op {
+            0 => {
+                ids.push(arena.new_node(model.add()));
+                Ok(())
+            }
+            1..=4 => match op {
+                1 => arena.append(ids[a], ids[b]),
+                2 => arena.prepend(ids[a], ids[b]),
+                3 => arena.insert_before(ids[a], ids[b]),
+                _ => arena.insert_after(ids[a], ids[b]),
+            },
+            5 | 6 => arena.detach(ids[a]),
+            7 => arena.remove(ids[a]).map(|data| assert_eq!(data, a)),
+            _ => arena
+                .get_mut(ids[a])
+                .map_or(Err(Error::RemovedNode), |data| {
+                    assert_eq!(*data, a);
+                    Ok(())
+                }),
+        };
+        if op != 0 && (!model.live[a] || (matches!(op, 1..=4) && !model.live[b])) {
+            assert!(matches!(result, Err(Error::RemovedNode)));
+        } else if matches!(op, 1..=4) && model.is_ancestor(b, a) {
+            assert!(matches!(result, Err(Error::WouldCycle)));
+        } else {
+            result.unwrap();
+            match op {
+                1..=4 => {
+                    model.detach(b);
+                    model.insert(a, b, op <= 2, op == 2 || op == 3);
+                }
+                5 | 6 => model.detach(a),
+                7 => model.remove(a),
+                _ => {}
+            }
+        }
+
+        let live: Vec<usize> = (0..ids.len()).filter(|&x| model.live[x]).collect();
+        assert_eq!(arena.len(), live.len());
+        for x in 0..ids.len() {
+            let id = ids[x];
+            assert_eq!(arena.contains(id), model.live[x]);
+            if !model.live[x] {
+                assert_eq!(arena.get(id), None);
+                continue;
+            }
+            assert_eq!(arena.get(id), Some(&x));
+            let at = |node: Option<usize>| node.map(|node| ids[node]);
+            assert_eq!(arena.parent(id), at(model.parent(x)));
+            let (prev, next) = model.siblings(x);
+            assert_eq!(arena.prev_sibling(id), at(prev));
+            assert_eq!(arena.next_sibling(id), at(next));
+            let children = model.children(x);
+            assert_eq!(arena.first_child(id), at(children.first().copied()));
+            assert_eq!(arena.last_child(id), at(children.last().copied()));
+            let got: Vec<NodeId> = arena.children(id).collect();
+            assert_eq!(got, children.iter().map(|&c| ids[c]).collect::<Vec<_>>());
+            let mut ancestors = Vec::new();
+            let mut node = x;
+            while let Some(parent) = model.parent(node) {
+                ancestors.push(ids[parent]);
+                node = parent;
+            }
+            assert_eq!(arena.ancestors(id).collect::<Vec<_>>(), ancestors);
+            let mut pre = Vec::new();
+            model.pre_order(x, &mut pre);
+            let pre: Vec<NodeId> = pre.into_iter().map(|node| ids[node]).collect();
+            assert_eq!(arena.descendants(id).collect::<Vec<_>>(), pre);
+        }
+    }
+}
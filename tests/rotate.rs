@@ -0,0 +1,16 @@
+use gray_tree::Error;
+
+#[test]
+fn rotations_keep_mid_order() {
+    let mut tree = gray_tree::tree! {4 => {2 => {1, 3}, 6 => {5, 7}}};
+    tree.rotate_left().unwrap();
+    assert_eq!(tree.to_leetcode(), "[6,4,7,2,5,null,null,1,3]");
+    assert!(tree.iter().copied().eq(1..=7));
+    tree.rotate_right().unwrap();
+    assert_eq!(tree.to_leetcode(), "[4,2,6,1,3,5,7]");
+
+    let mut leaf = gray_tree::binary_tree::Node::new(1);
+    assert!(matches!(leaf.rotate_left(), Err(Error::MissingChild)));
+    assert!(matches!(leaf.rotate_right(), Err(Error::MissingChild)));
+    assert_eq!(leaf.to_leetcode(), "[1]");
+}
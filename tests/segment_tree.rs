@@ -0,0 +1,66 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::segment_tree::{Add, Max, Min, SegmentTree, Sum};
+use gray_tree::Error;
+
+#[test]
+fn lazy_updates_match_a_vector() {
+    let mut rng = SplitMix64::new(354);
+    for len in [1, 2, 7, 64, 100] {
+        let mut values: Vec<i64> = (0..len)
+            .map(|_| (rng.next_u64() % 100) as i64 - 50)
+            .collect();
+        let mut sums: SegmentTree<Sum, Add> =
+            SegmentTree::new(values.iter().map(|&v| Sum(v)).collect());
+        let mut maxs: SegmentTree<Max, Add> =
+            SegmentTree::new(values.iter().map(|&v| Max(v)).collect());
+        let mut mins: SegmentTree<Min, Add> =
+            SegmentTree::new(values.iter().map(|&v| Min(v)).collect());
+        for _ in 0..500 {
+            let a = (rng.next_u64() % (len as u64 + 1)) as usize;
+            let b = (rng.next_u64() % (len as u64 + 1)) as usize;
+            let range = a.min(b)..a.max(b);
+            let delta = (rng.next_u64() % 21) as i64 - 10;
+            match rng.next_u64() % 3 {
+                0 => {
+                    values[range.clone()].iter_mut().for_each(|v| *v += delta);
+                    sums.apply(range.clone(), Add(delta)).unwrap();
+                    maxs.apply(range.clone(), Add(delta)).unwrap();
+                    mins.apply(range, Add(delta)).unwrap();
+                }
+                1 if !range.is_empty() => {
+                    values[range.start] = delta;
+                    sums.set(range.start, Sum(delta)).unwrap();
+                    maxs.set(range.start, Max(delta)).unwrap();
+                    mins.set(range.start, Min(delta)).unwrap();
+                }
+                _ => {
+                    let slice = &values[range.clone()];
+                    assert_eq!(sums.query(range.clone()).unwrap(), Sum(slice.iter().sum()));
+                    let max = slice.iter().copied().max().unwrap_or(i64::MIN);
+                    assert_eq!(maxs.query(range.clone()).unwrap(), Max(max));
+                    let min = slice.iter().copied().min().unwrap_or(i64::MAX);
+                    assert_eq!(mins.query(range).unwrap(), Min(min));
+                }
+            }
+        }
+        for (index, &value) in values.iter().enumerate() {
+            assert_eq!(sums.get(index).unwrap(), Sum(value));
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::reversed_empty_ranges)]
+fn rejects_out_of_range() {
+    let mut tree: SegmentTree<Sum, Add> = SegmentTree::new(vec![Sum(1); 4]);
+    assert!(matches!(
+        tree.query(2..5),
+        Err(Error::IndexOutOfRange { index: 5, len: 4 })
+    ));
+    assert!(tree.query(3..2).is_err());
+    assert!(tree.apply(0..5, Add(1)).is_err());
+    assert!(tree.set(4, Sum(0)).is_err());
+    assert!(tree.get(4).is_err());
+    assert_eq!(tree.query(0..4).unwrap(), Sum(4));
+    assert_eq!(tree.query(2..2).unwrap(), Sum(0));
+}
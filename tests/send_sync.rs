@@ -0,0 +1,38 @@
+use gray_tree::binary_tree::{self, bst, iter};
+use gray_tree::tree;
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn iterators_are_send_and_sync() {
+    assert_send_sync::<iter::LevelOrderIter<'_, i32>>();
+    assert_send_sync::<iter::Iter<'_, i32>>();
+    assert_send_sync::<iter::IterMut<'_, i32>>();
+    assert_send_sync::<iter::IntoIter<i32>>();
+    assert_send_sync::<bst::Iter<'_, i32>>();
+    assert_send_sync::<binary_tree::arena::PreOrder<'_, i32>>();
+    assert_send_sync::<binary_tree::flat::LevelOrderIter<'_, i32>>();
+    assert_send_sync::<tree::iter::PreOrderIter<'_, i32>>();
+    assert_send_sync::<tree::iter::PostOrderIter<'_, i32>>();
+    assert_send_sync::<tree::iter::LevelOrderIter<'_, i32>>();
+    assert_send_sync::<tree::arena::Children<'_, i32>>();
+    assert_send_sync::<tree::arena::Ancestors<'_, i32>>();
+    assert_send_sync::<tree::arena::Descendants<'_, i32>>();
+    assert_send_sync::<tree::forest::Iter<'_, i32>>();
+    assert_send_sync::<gray_tree::rope::Chunks<'_>>();
+}
+
+#[test]
+fn iterators_cross_scoped_threads() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5}, 3}};
+    let levels = tree.level_order_iter();
+    let mid = tree.iter();
+    let (levels, mid) = thread::scope(|scope| {
+        let levels = scope.spawn(move || levels.map(|(level, &x)| (level, x)).collect::<Vec<_>>());
+        let mid = scope.spawn(move || mid.copied().collect::<Vec<_>>());
+        (levels.join().unwrap(), mid.join().unwrap())
+    });
+    assert_eq!(levels, [(0, 1), (1, 2), (1, 3), (2, 4), (2, 5)]);
+    assert_eq!(mid, [4, 2, 5, 1, 3]);
+}
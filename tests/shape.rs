@@ -0,0 +1,84 @@
+use gray_tree::binary_tree::Node;
+use gray_tree::gen::{self, SplitMix64};
+use gray_tree::Error;
+
+#[test]
+fn pre_order_shape_round_trip() {
+    let mut rng = SplitMix64::new(21);
+    for n in 1..50 {
+        let tree = gen::uniform(n, &mut rng).unwrap();
+        let shape: Vec<_> = tree
+            .to_pre_order_shape()
+            .into_iter()
+            .map(|(&data, has_left, has_right)| (data, has_left, has_right))
+            .collect();
+        assert_eq!(tree.clone().into_pre_order_shape(), shape);
+        let rebuilt = Node::from_pre_order_shape(shape.clone()).unwrap();
+        assert_eq!(rebuilt.into_pre_order_shape(), shape);
+    }
+
+    // Deep trees round-trip without recursion.
+    let chain: Vec<_> = (0..100_000)
+        .map(|idx| (idx, idx + 1 < 100_000, false))
+        .collect();
+    let tree = Node::from_pre_order_shape(chain.clone()).unwrap();
+    assert_eq!(tree.into_pre_order_shape(), chain);
+}
+
+#[test]
+fn pre_order_shape_rejects_missing_or_extra_nodes() {
+    let shape = vec![(1, true, true), (2, false, false), (3, false, false)];
+    assert!(Node::from_pre_order_shape(shape).is_ok());
+
+    // A child is announced but never given.
+    let missing = vec![(1, true, true), (2, false, false)];
+    assert!(matches!(
+        Node::from_pre_order_shape(missing),
+        Err(Error::MalformedTree)
+    ));
+    let missing = vec![(1, false, true)];
+    assert!(matches!(
+        Node::from_pre_order_shape(missing),
+        Err(Error::MalformedTree)
+    ));
+
+    // Nodes left over after the root is complete.
+    let extra = vec![(1, false, true), (2, false, false), (3, false, false)];
+    assert!(matches!(
+        Node::from_pre_order_shape(extra),
+        Err(Error::MalformedTree)
+    ));
+    assert!(matches!(
+        Node::<i32>::from_pre_order_shape(Vec::new()),
+        Err(Error::MalformedTree)
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let mut rng = SplitMix64::new(373);
+    for n in 1..50 {
+        let tree = gen::uniform(n, &mut rng).unwrap();
+        let json = serde_json::to_string(&tree).unwrap();
+        let back: Node<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.into_pre_order_shape(), tree.into_pre_order_shape());
+    }
+    let tree =
+        Node::from_pre_order_shape(vec![(1, true, true), (2, false, false), (3, false, false)])
+            .unwrap();
+    assert_eq!(
+        serde_json::to_string(&tree).unwrap(),
+        "[[1,true,true],[2,false,false],[3,false,false]]"
+    );
+
+    // Deep trees round-trip without recursion.
+    let tree = gen::right_chain(100_000).unwrap();
+    let json = serde_json::to_vec(&tree).unwrap();
+    let back: Node<usize> = serde_json::from_slice(&json).unwrap();
+    assert!(back.iter().copied().eq(0..100_000));
+
+    let malformed = serde_json::from_str::<Node<i32>>("[[1,true,true],[2,false,false]]");
+    assert!(malformed.is_err());
+    assert!(serde_json::from_str::<Node<i32>>("[]").is_err());
+}
@@ -0,0 +1,42 @@
+use gray_tree::binary_tree::arena::Arena;
+use gray_tree::binary_tree::stats::{self, TreeStats};
+use gray_tree::binary_tree::Node;
+use gray_tree::gen;
+
+#[test]
+fn shape_statistics() {
+    let tree: Node<i32> = Node::from_leetcode("[1,2,3,4,null,null,7,8]")
+        .unwrap()
+        .unwrap();
+    let stats = tree.stats();
+    assert_eq!(stats.size, 6);
+    assert_eq!(stats.leaves, 2);
+    assert_eq!(stats.levels, [1, 2, 2, 1]);
+    assert_eq!(stats.height(), 4);
+    assert_eq!(stats.path_length, 1 + 1 + 2 + 2 + 3);
+    assert!((stats.average_depth() - 9.0 / 6.0).abs() < 1e-12);
+
+    // Balanced trees have shorter paths than chains of the same size.
+    let balanced = Node::from_sorted_vec((0..1023).collect()).unwrap().stats();
+    let chain = gen::right_chain(1023).unwrap().stats();
+    assert_eq!(balanced.height(), 10);
+    assert_eq!(balanced.levels[9], 512);
+    assert_eq!(chain.levels, vec![1; 1023]);
+    assert!(balanced.average_depth() < 9.0);
+    assert_eq!(chain.path_length, 1022 * 1023 / 2);
+
+    // The same statistics for any storage.
+    let mut arena = Arena::new();
+    let root = arena.insert_tree(tree);
+    assert_eq!(TreeStats::of((&arena, root)), stats);
+}
+
+#[test]
+fn weighted_path_length() {
+    let tree = Node::from_leetcode("[5,3,8,1]").unwrap().unwrap();
+    assert_eq!(tree.weighted_path_length(|_| 1), tree.stats().path_length);
+    assert_eq!(tree.weighted_path_length(|&data| data), 3 + 8 + 2);
+    assert_eq!(stats::weighted_path_length(&tree, |&data| data * 10), 130);
+    let single = Node::new(7);
+    assert_eq!(single.weighted_path_length(|&data| data), 0);
+}
@@ -0,0 +1,127 @@
+use gray_tree::suffix_tree::SuffixTree;
+
+/// Pseudo-random strings over a small alphabet, to get many repeats.
+fn strings(alphabet: &[char], count: usize) -> Vec<String> {
+    let mut seed: u64 = 42;
+    (0..count)
+        .map(|idx| {
+            (0..idx % 23)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+                    alphabet[(seed >> 33) as usize % alphabet.len()]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn naive_find_all(text: &str, pattern: &str) -> Vec<usize> {
+    (0..=text.len())
+        .filter(|&at| text.is_char_boundary(at) && text[at..].starts_with(pattern))
+        .collect()
+}
+
+fn substrings(text: &str) -> Vec<&str> {
+    let bounds: Vec<usize> = text
+        .char_indices()
+        .map(|(at, _)| at)
+        .chain(Some(text.len()))
+        .collect();
+    let mut all = Vec::new();
+    for (idx, &start) in bounds.iter().enumerate() {
+        for &end in &bounds[idx..] {
+            all.push(&text[start..end]);
+        }
+    }
+    all
+}
+
+fn occurrences(text: &str, pattern: &str) -> usize {
+    naive_find_all(text, pattern).len()
+}
+
+#[test]
+fn search_matches_brute_force() {
+    let mut texts = strings(&['a', 'b', 'c'], 40);
+    texts.extend(vec![
+        "aaaa".to_owned(),
+        "abcabxabcd".to_owned(),
+        "ééaé".to_owned(),
+    ]);
+    for text in &texts {
+        let tree = SuffixTree::new(text);
+        assert_eq!(tree.text(), text);
+        let mut patterns = substrings(text);
+        patterns.extend(vec!["abcd", "aaaaa", "d", "xa", "é"]);
+        for pattern in patterns {
+            assert_eq!(
+                tree.contains(pattern),
+                text.contains(pattern),
+                "{:?} in {:?}",
+                pattern,
+                text
+            );
+            if !pattern.is_empty() {
+                assert_eq!(tree.find_all(pattern), naive_find_all(text, pattern));
+            }
+        }
+    }
+
+    let tree = SuffixTree::new("abcabxabcd");
+    assert_eq!(tree.find_all("abc"), [0, 6]);
+    assert_eq!(tree.find_all("ab"), [0, 3, 6]);
+    let tree = SuffixTree::new("aaaa");
+    assert_eq!(tree.find_all("aa"), [0, 1, 2]);
+}
+
+#[test]
+fn longest_repeated_matches_brute_force() {
+    let mut texts = strings(&['a', 'b'], 40);
+    texts.extend(vec![
+        "aaaa".to_owned(),
+        "abcabxabcd".to_owned(),
+        "abc".to_owned(),
+    ]);
+    for text in &texts {
+        let tree = SuffixTree::new(text);
+        let found = tree.longest_repeated_substring();
+        let best = substrings(text)
+            .into_iter()
+            .filter(|sub| occurrences(text, sub) >= 2)
+            .map(str::len)
+            .max()
+            .unwrap_or(0);
+        assert_eq!(found.len(), best, "in {:?}", text);
+        assert!(found.is_empty() || occurrences(text, found) >= 2);
+    }
+    assert_eq!(SuffixTree::new("aaaa").longest_repeated_substring(), "aaa");
+    assert_eq!(
+        SuffixTree::new("abcabxabcd").longest_repeated_substring(),
+        "abc"
+    );
+    assert_eq!(SuffixTree::new("abc").longest_repeated_substring(), "");
+}
+
+#[test]
+fn longest_common_matches_brute_force() {
+    let texts = strings(&['a', 'b', 'c'], 30);
+    for a in &texts {
+        for b in texts.iter().step_by(3) {
+            let found = SuffixTree::longest_common_substring(a, b);
+            let best = substrings(a)
+                .into_iter()
+                .filter(|sub| b.contains(sub))
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            assert_eq!(found.len(), best, "{:?} and {:?}", a, b);
+            assert!(a.contains(found) && b.contains(found));
+        }
+    }
+    assert_eq!(
+        SuffixTree::longest_common_substring("abcabxabcd", "xabcdy"),
+        "xabcd"
+    );
+    assert_eq!(SuffixTree::longest_common_substring("aaaa", "baab"), "aa");
+    assert_eq!(SuffixTree::longest_common_substring("abc", "xyz"), "");
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "svg")]
+
+use gray_tree::binary_tree::Node as BinaryNode;
+use gray_tree::svg::{NodeShape, SvgStyle};
+use gray_tree::tree::Node;
+
+fn attr(line: &str, name: &str) -> i64 {
+    let start = line.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3;
+    line[start..].split('"').next().unwrap().parse().unwrap()
+}
+
+/// Check that the node rectangles of every level are disjoint and inside
+/// the drawing.
+fn assert_disjoint(svg: &str) {
+    let width = attr(svg.lines().next().unwrap(), "width");
+    let mut boxes: Vec<_> = svg
+        .lines()
+        .filter(|line| line.starts_with("<rect"))
+        .map(|line| {
+            let x = attr(line, "x");
+            (attr(line, "y"), x, x + attr(line, "width"))
+        })
+        .collect();
+    boxes.sort_unstable();
+    for pair in boxes.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            assert!(pair[0].2 <= pair[1].1, "{:?} overlap", pair);
+        }
+    }
+    assert!(boxes
+        .iter()
+        .all(|&(_, left, right)| left >= 0 && right <= width));
+}
+
+#[test]
+fn binary_layout_is_disjoint() {
+    let style = SvgStyle::new().node_shape(NodeShape::Rect);
+    for input in &[
+        "((4,(8,)5)2,((,9)6,7)3)1",
+        "(((((,5)4,)3,)2,)1,(,(,(,(6,)7)8)9)10)0",
+        "((((1,2)3,(4,5)6)7,)8,(,((9,10)11,(12,13)14)15)16)17",
+    ] {
+        let tree = BinaryNode::<i32>::from_newick(input).unwrap();
+        assert_disjoint(&tree.to_svg(&style));
+    }
+}
+
+#[test]
+fn n_ary_layout_is_disjoint() {
+    let style = SvgStyle::new().node_shape(NodeShape::Rect);
+    let input = "((a,bbbbbbbb,(c,d,e)f)g,(hhhhhhhhhhhh,(i)j)k,l,((m,n)o)p)root";
+    let tree = Node::<String>::from_newick(input).unwrap();
+    assert_disjoint(&tree.to_svg(&style));
+}
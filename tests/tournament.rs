@@ -0,0 +1,63 @@
+use gray_tree::tournament::kway_merge;
+
+#[test]
+fn merges_sorted_runs() {
+    let runs = vec![
+        vec![1, 4, 7, 10],
+        vec![],
+        vec![2, 5, 8],
+        vec![0, 3, 6, 9, 11, 12],
+        vec![13],
+    ];
+    let merge = kway_merge(runs.clone());
+    assert_eq!(merge.sources(), 5);
+    assert_eq!(merge.peek(), Some(&0));
+    assert_eq!(merge.size_hint(), (14, Some(14)));
+    assert!(merge.eq(0..14));
+
+    // Any number of sources, of any length.
+    for k in 0..20 {
+        let runs: Vec<Vec<usize>> = (0..k)
+            .map(|run| (0..run * 3 % 7).map(|i| i * k + run).collect())
+            .collect();
+        let mut expected: Vec<usize> = runs.iter().flatten().copied().collect();
+        expected.sort_unstable();
+        assert_eq!(kway_merge(runs).collect::<Vec<_>>(), expected);
+    }
+    assert_eq!(kway_merge(Vec::<Vec<u8>>::new()).next(), None);
+}
+
+#[test]
+fn stable_on_ties() {
+    // Items equal by key, tagged with their source.
+    #[derive(Debug)]
+    struct Tagged(u8, usize);
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Tagged {}
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+    let runs: Vec<Vec<Tagged>> = (0..6)
+        .map(|source| vec![Tagged(1, source), Tagged(2, source), Tagged(2, source)])
+        .collect();
+    let tags: Vec<(u8, usize)> = kway_merge(runs).map(|item| (item.0, item.1)).collect();
+    let mut expected: Vec<(u8, usize)> = (0..6).map(|source| (1, source)).collect();
+    expected.extend((0..6).flat_map(|source| vec![(2, source); 2]));
+    assert_eq!(tags, expected);
+
+    // Iterators, not only collections, and lazily.
+    let evens = (0..).step_by(2);
+    let odds = (1..).step_by(2);
+    assert!(kway_merge(vec![evens, odds]).take(1000).eq(0..1000));
+}
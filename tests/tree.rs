@@ -0,0 +1,120 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::tree::Node;
+use gray_tree::Error;
+
+/// Children lists of a random tree over `0..len`, every vertex below an
+/// earlier one.
+fn random_children(rng: &mut SplitMix64, len: usize) -> Vec<Vec<usize>> {
+    let mut children = vec![Vec::new(); len];
+    for vertex in 1..len {
+        children[(rng.next_u64() % vertex as u64) as usize].push(vertex);
+    }
+    children
+}
+
+fn build(children: &[Vec<usize>], vertex: usize) -> Node<usize> {
+    Node::builder()
+        .data(vertex)
+        .children(children[vertex].iter().map(|&child| build(children, child)))
+        .build()
+        .unwrap()
+}
+
+fn pre_order(children: &[Vec<usize>], vertex: usize, depth: usize, out: &mut Vec<(usize, usize)>) {
+    out.push((depth, vertex));
+    for &child in &children[vertex] {
+        pre_order(children, child, depth + 1, out);
+    }
+}
+
+fn post_order(children: &[Vec<usize>], vertex: usize, out: &mut Vec<usize>) {
+    for &child in &children[vertex] {
+        post_order(children, child, out);
+    }
+    out.push(vertex);
+}
+
+#[test]
+fn traversals_match_children_lists() {
+    let mut rng = SplitMix64::new(356);
+    for len in 1..60 {
+        let children = random_children(&mut rng, len);
+        let tree = build(&children, 0);
+
+        let mut expected = Vec::new();
+        pre_order(&children, 0, 0, &mut expected);
+        let pre: Vec<usize> = tree.pre_order_iter().copied().collect();
+        assert_eq!(pre, expected.iter().map(|&(_, v)| v).collect::<Vec<_>>());
+        let max_depth = len % 4;
+        let limited: Vec<(usize, usize)> = tree
+            .iter_to_depth(max_depth)
+            .map(|(depth, &v)| (depth, v))
+            .collect();
+        expected.retain(|&(depth, _)| depth <= max_depth);
+        assert_eq!(limited, expected);
+
+        let mut post = Vec::new();
+        post_order(&children, 0, &mut post);
+        assert_eq!(tree.post_order_iter().copied().collect::<Vec<_>>(), post);
+
+        let mut level = vec![(0, 0)];
+        let mut idx = 0;
+        while idx < level.len() {
+            let (depth, vertex) = level[idx];
+            level.extend(children[vertex].iter().map(|&child| (depth + 1, child)));
+            idx += 1;
+        }
+        let got: Vec<(usize, usize)> = tree.level_order_iter().map(|(d, &v)| (d, v)).collect();
+        assert_eq!(got, level);
+
+        let n = len / 2;
+        match tree.take_nodes(n) {
+            None => assert_eq!(n, 0),
+            Some(taken) => {
+                let got: Vec<(usize, usize)> =
+                    taken.level_order_iter().map(|(d, &v)| (d, v)).collect();
+                assert_eq!(got, level[..n]);
+            }
+        }
+
+        let mut seen = Vec::new();
+        let mapped = tree.clone().pre_order_map(|v| {
+            seen.push(v);
+            v * 2
+        });
+        assert_eq!(seen, pre);
+        let doubled: Vec<usize> = pre.iter().map(|v| v * 2).collect();
+        assert_eq!(
+            mapped.pre_order_iter().copied().collect::<Vec<_>>(),
+            doubled
+        );
+        let mut seen = Vec::new();
+        let mapped = tree.clone().post_order_map(|v| {
+            seen.push(v);
+            v + 1
+        });
+        assert_eq!(seen, post);
+        let shifted: Vec<usize> = post.iter().map(|v| v + 1).collect();
+        assert_eq!(
+            mapped.post_order_iter().copied().collect::<Vec<_>>(),
+            shifted
+        );
+        let (data, subtrees) = tree.into_parts();
+        assert_eq!(data, 0);
+        let roots: Vec<usize> = subtrees.iter().map(|node| *node.data()).collect();
+        assert_eq!(roots, children[0]);
+    }
+}
+
+#[test]
+fn builder_requires_data() {
+    let built = Node::<u8>::builder().child(Node::new(1)).build();
+    assert!(matches!(built, Err(Error::MissingDataField)));
+    let mut node = Node::builder().data(0).child(Node::new(1)).build().unwrap();
+    node.push_child(Node::new(2));
+    *node.child_mut(1).unwrap().data_mut() = 3;
+    assert_eq!(node.children().len(), 2);
+    assert_eq!(node.child(1).unwrap().data(), &3);
+    assert!(node.child(2).is_none());
+    assert!(!node.is_leaf() && node.child(0).unwrap().is_leaf());
+}
@@ -0,0 +1,524 @@
+use gray_tree::compare::{Natural, Reversed};
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::tree_map::{Entry, Treap, TreeMap};
+use gray_tree::tree_multiset::TreeMultiSet;
+use gray_tree::tree_set::TreeSet;
+use gray_tree::Error;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[test]
+fn matches_btree_map() {
+    let mut rng = SplitMix64::new(11);
+    let mut map = TreeMap::new();
+    let mut expected = BTreeMap::new();
+    for step in 0..20_000 {
+        let key = rng.next_u64() % 500;
+        if rng.next_u64().is_multiple_of(3) {
+            assert_eq!(map.remove(&key), expected.remove(&key));
+        } else {
+            assert_eq!(map.insert(key, step), expected.insert(key, step));
+        }
+        if step % 1000 == 0 {
+            map.validate().unwrap();
+        }
+    }
+    map.validate().unwrap();
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()));
+    assert!(map.height() <= 12);
+    assert_eq!(map.first_key_value(), expected.first_key_value());
+    assert_eq!(map.last_key_value(), expected.last_key_value());
+
+    for (lo, hi) in [(10, 20), (0, 0), (499, 600), (300, 100)] {
+        assert!(map.range(lo..hi).eq(expected.range(lo..hi.max(lo))));
+        assert!(map
+            .range(lo..=hi.max(lo))
+            .eq(expected.range(lo..=hi.max(lo))));
+    }
+    assert!(map.range(..250).eq(expected.range(..250)));
+    assert!(map.range(250..).eq(expected.range(250..)));
+
+    while let Some(entry) = map.pop_first() {
+        assert_eq!(Some(entry), expected.pop_first());
+    }
+    assert!(map.is_empty());
+    map.validate().unwrap();
+}
+
+#[test]
+fn treap_matches_btree_map() {
+    let mut rng = SplitMix64::new(14);
+    let mut map: TreeMap<u64, u64, Natural, Treap> = TreeMap::with_balance(Treap::with_seed(3));
+    let mut expected = BTreeMap::new();
+    for step in 0..20_000 {
+        let key = rng.next_u64() % 500;
+        match rng.next_u64() % 8 {
+            0..=2 => assert_eq!(map.insert(key, step), expected.insert(key, step)),
+            3 | 4 => assert_eq!(map.remove(&key), expected.remove(&key)),
+            5 => match map.entry(key) {
+                Entry::Occupied(entry) => assert_eq!(Some(entry.remove()), expected.remove(&key)),
+                Entry::Vacant(entry) => {
+                    entry.insert(step);
+                    expected.insert(key, step);
+                }
+            },
+            6 => assert_eq!(map.pop_first(), expected.pop_first()),
+            _ => {
+                let drained: Vec<_> = map.drain(key..key + 20).collect();
+                let removed: Vec<_> = expected
+                    .range(key..key + 20)
+                    .map(|(&k, &v)| (k, v))
+                    .collect();
+                removed.iter().for_each(|(key, _)| {
+                    expected.remove(key);
+                });
+                assert_eq!(drained, removed);
+            }
+        }
+        if step % 1000 == 0 {
+            map.validate().unwrap();
+        }
+    }
+    map.validate().unwrap();
+    assert!(map.iter().eq(expected.iter()));
+
+    map.retain(|key, _| key % 3 != 0);
+    expected.retain(|key, _| key % 3 != 0);
+    map.validate().unwrap();
+    assert!(map.iter().eq(expected.iter()));
+
+    let rest = map.split_off(&250);
+    let expected_rest = expected.split_off(&250);
+    map.validate().unwrap();
+    rest.validate().unwrap();
+    assert!(map.iter().eq(expected.iter()));
+    assert!(rest.iter().eq(expected_rest.iter()));
+
+    // Priorities do not depend on the keys, so sorted insertion does not
+    // degrade the tree into a chain.
+    let sorted: TreeMap<_, _, Natural, Treap> = (0..1000).map(|key| (key, key)).collect();
+    sorted.validate().unwrap();
+    assert!(sorted.height() <= 30);
+}
+
+#[test]
+fn treap_set_operations_match_btree_set() {
+    let mut rng = SplitMix64::new(15);
+    for _ in 0..50 {
+        let a: BTreeSet<u64> = (0..rng.next_u64() % 200)
+            .map(|_| rng.next_u64() % 300)
+            .collect();
+        let b: BTreeSet<u64> = (0..rng.next_u64() % 200)
+            .map(|_| rng.next_u64() % 300)
+            .collect();
+        let set = |values: &BTreeSet<u64>| -> TreeSet<u64, Natural, Treap> {
+            values.iter().copied().collect()
+        };
+        let union = set(&a) | set(&b);
+        let intersection = set(&a) & set(&b);
+        let difference = set(&a) - set(&b);
+        let symmetric = set(&a) ^ set(&b);
+        for (result, expected) in [
+            (&union, &a | &b),
+            (&intersection, &a & &b),
+            (&difference, &a - &b),
+            (&symmetric, &a ^ &b),
+        ] {
+            result.validate().unwrap();
+            assert!(result.iter().eq(expected.iter()));
+            assert_eq!(result.len(), expected.len());
+        }
+        assert!(set(&a).union(&set(&b)).eq((&a | &b).iter()));
+    }
+}
+
+#[test]
+fn map_api() {
+    let mut map: TreeMap<String, i32> = TreeMap::new();
+    map.insert("b".to_string(), 2);
+    map.insert("a".to_string(), 1);
+    map.insert("c".to_string(), 3);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map.get("z"), None);
+    *map.get_mut("b").unwrap() += 10;
+    map.values_mut().for_each(|value| *value *= 2);
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), [2, 24, 6]);
+    assert_eq!(map.keys().map(String::as_str).collect::<String>(), "abc");
+    assert_eq!(format!("{:?}", map), r#"{"a": 2, "b": 24, "c": 6}"#);
+    assert_eq!(map.pop_last(), Some(("c".to_string(), 6)));
+    let pairs: Vec<_> = map.into_iter().collect();
+    assert_eq!(pairs, [("a".to_string(), 2), ("b".to_string(), 24)]);
+
+    let sorted: TreeMap<_, _> = (0..1000).map(|key| (key, key)).collect();
+    assert!(sorted.height() <= 11);
+    sorted.validate().unwrap();
+}
+
+#[test]
+fn set_api() {
+    let mut set: TreeSet<_> = [5, 1, 4, 1, 3].iter().copied().collect();
+    assert_eq!(set.len(), 4);
+    assert!(!set.insert(4));
+    assert!(set.remove(&1));
+    assert!(!set.contains(&1));
+    assert_eq!(set.first(), Some(&3));
+    assert_eq!(set.range(4..).copied().collect::<Vec<_>>(), [4, 5]);
+    assert_eq!(format!("{:?}", set), "{3, 4, 5}");
+    assert_eq!(set.take(&5), Some(5));
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), [3, 4]);
+}
+
+#[test]
+fn entry_matches_btree_map() {
+    use gray_tree::tree_map::Entry;
+    use std::collections::btree_map;
+
+    let mut rng = SplitMix64::new(5);
+    let mut map = TreeMap::new();
+    let mut expected = BTreeMap::new();
+    for step in 0..20_000u64 {
+        let key = rng.next_u64() % 300;
+        match rng.next_u64() % 3 {
+            0 => {
+                *map.entry(key).and_modify(|v| *v += step).or_insert(step) += 1;
+                *expected
+                    .entry(key)
+                    .and_modify(|v| *v += step)
+                    .or_insert(step) += 1;
+            }
+            1 => {
+                let removed = match map.entry(key) {
+                    Entry::Occupied(entry) => Some(entry.remove_entry()),
+                    Entry::Vacant(_) => None,
+                };
+                let expected_removed = match expected.entry(key) {
+                    btree_map::Entry::Occupied(entry) => Some(entry.remove_entry()),
+                    btree_map::Entry::Vacant(_) => None,
+                };
+                assert_eq!(removed, expected_removed);
+            }
+            _ => assert_eq!(
+                *map.entry(key).or_insert_with_key(|key| key * 2),
+                *expected.entry(key).or_insert_with_key(|key| key * 2)
+            ),
+        }
+        if step % 1000 == 0 {
+            map.validate().unwrap();
+        }
+    }
+    map.validate().unwrap();
+    assert_eq!(map.len(), expected.len());
+    assert!(map.iter().eq(expected.iter()));
+}
+
+#[test]
+fn entry_api() {
+    use gray_tree::tree_map::Entry;
+
+    let mut counts: TreeMap<char, usize> = TreeMap::new();
+    for c in "abracadabra".chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    assert_eq!(
+        counts.iter().collect::<Vec<_>>(),
+        [(&'a', &5), (&'b', &2), (&'c', &1), (&'d', &1), (&'r', &2)]
+    );
+
+    match counts.entry('b') {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &'b');
+            assert_eq!(entry.insert(7), 2);
+            assert_eq!(
+                format!("{:?}", entry),
+                "OccupiedEntry { key: 'b', value: 7 }"
+            );
+            assert_eq!(entry.remove(), 7);
+        }
+        Entry::Vacant(_) => unreachable!(),
+    }
+    match counts.entry('z') {
+        Entry::Vacant(entry) => {
+            assert_eq!(format!("{:?}", entry), "VacantEntry('z')");
+            assert_eq!(entry.into_key(), 'z');
+        }
+        Entry::Occupied(_) => unreachable!(),
+    }
+    assert_eq!(counts.len(), 4);
+    assert!(!counts.contains_key(&'z'));
+    counts.validate().unwrap();
+}
+
+#[test]
+fn drain_ranges() {
+    let mut rng = SplitMix64::new(17);
+    for round in 0..50 {
+        let mut map: TreeMap<u64, u64> = TreeMap::new();
+        let mut expected = BTreeMap::new();
+        for _ in 0..rng.next_u64() % 400 {
+            let key = rng.next_u64() % 1000;
+            map.insert(key, round);
+            expected.insert(key, round);
+        }
+        let (a, b) = (rng.next_u64() % 1000, rng.next_u64() % 1000);
+        let (lo, hi) = (a.min(b), a.max(b));
+        let drained: Vec<_> = map.drain(lo..hi).collect();
+        let kept = expected.split_off(&hi);
+        let expected_drained = expected.split_off(&lo);
+        expected.extend(kept);
+        assert!(drained.into_iter().eq(expected_drained));
+        map.validate().unwrap();
+        assert!(map.iter().eq(expected.iter()));
+    }
+
+    let mut map: TreeMap<i32, ()> = (0..100).map(|key| (key, ())).collect();
+    assert_eq!(map.drain(..=9).len(), 10);
+    assert_eq!(map.drain(90..).len(), 10);
+    assert_eq!(map.drain(40..40).len(), 0);
+    assert_eq!(map.drain(..).len(), 80);
+    assert!(map.is_empty());
+    map.validate().unwrap();
+
+    let mut set: TreeSet<i32> = (0..10).collect();
+    assert_eq!(set.drain(3..7).collect::<Vec<_>>(), [3, 4, 5, 6]);
+    assert_eq!(set.iter().copied().collect::<Vec<_>>(), [0, 1, 2, 7, 8, 9]);
+    set.validate().unwrap();
+}
+
+#[test]
+fn split_off_matches_btree_map() {
+    let mut rng = SplitMix64::new(19);
+    for round in 0..60 {
+        let mut map: TreeMap<u64, u64> = TreeMap::new();
+        let mut expected = BTreeMap::new();
+        for _ in 0..rng.next_u64() % 400 {
+            let key = rng.next_u64() % 1000;
+            map.insert(key, round);
+            expected.insert(key, round);
+        }
+        let key = rng.next_u64() % 1100;
+        let height = map.height();
+        let rest = map.split_off(&key);
+        let expected_rest = expected.split_off(&key);
+        for (part, model) in [(&map, &expected), (&rest, &expected_rest)] {
+            part.validate().unwrap();
+            assert_eq!(part.len(), model.len());
+            assert!(part.iter().eq(model.iter()));
+            assert!(part.height() <= height);
+        }
+    }
+
+    // The comparator decides which side a key goes to.
+    let mut map: TreeMap<i32, (), Reversed> = (0..10).map(|key| (key, ())).collect();
+    let rest = map.split_off(&4);
+    assert!(map.keys().copied().eq([9, 8, 7, 6, 5]));
+    assert!(rest.keys().copied().eq([4, 3, 2, 1, 0]));
+
+    let mut set: TreeSet<i32> = (0..10).collect();
+    let rest = set.split_off(&7);
+    assert!(set.iter().copied().eq(0..7));
+    assert!(rest.iter().copied().eq(7..10));
+    assert!(set.split_off(&0).iter().copied().eq(0..7));
+    assert!(set.is_empty());
+    set.validate().unwrap();
+}
+
+#[test]
+fn set_operations_match_btree_set() {
+    let mut rng = SplitMix64::new(23);
+    for round in 0..80 {
+        // Sizes range from empty to far apart, where split and join pay off.
+        let (n, m) = (rng.next_u64() % 300, rng.next_u64() % (1 + round % 4 * 100));
+        let range = 1 + rng.next_u64() % 600;
+        let a: BTreeSet<u64> = (0..n).map(|_| rng.next_u64() % range).collect();
+        let b: BTreeSet<u64> = (0..m).map(|_| rng.next_u64() % range).collect();
+        let set = |values: &BTreeSet<u64>| values.iter().copied().collect::<TreeSet<u64>>();
+        let (x, y) = (set(&a), set(&b));
+
+        assert!(x.union(&y).eq(a.union(&b)));
+        assert!(x.intersection(&y).eq(a.intersection(&b)));
+        assert!(x.difference(&y).eq(a.difference(&b)));
+        assert!(x.symmetric_difference(&y).eq(a.symmetric_difference(&b)));
+
+        let eager = [
+            (set(&a) | set(&b), &a | &b),
+            (set(&a) & set(&b), &a & &b),
+            (set(&a) - set(&b), &a - &b),
+            (set(&b) - set(&a), &b - &a),
+            (set(&a) ^ set(&b), &a ^ &b),
+        ];
+        for (result, expected) in eager {
+            result.validate().unwrap();
+            assert_eq!(result.len(), expected.len());
+            assert!(result.iter().eq(expected.iter()));
+        }
+    }
+
+    // Both ways round follow the comparator of the sets.
+    let evens: TreeSet<i32, Reversed> = (0..10).filter(|v| v % 2 == 0).collect();
+    let small: TreeSet<i32, Reversed> = [3, 4].iter().copied().collect();
+    assert!(evens.union(&small).copied().eq([8, 6, 4, 3, 2, 0]));
+    assert!((small.clone() - evens.clone()).iter().copied().eq([3]));
+    assert!((evens ^ small).iter().copied().eq([8, 6, 3, 2, 0]));
+}
+
+#[test]
+fn retain_matches_btree_map() {
+    let mut rng = SplitMix64::new(23);
+    for modulus in 1..8 {
+        let mut map = TreeMap::new();
+        let mut expected = BTreeMap::new();
+        for _ in 0..2000 {
+            let key = rng.next_u64() % 5000;
+            map.insert(key, 0);
+            expected.insert(key, 0);
+        }
+        let len = map.len();
+        let mut visited = Vec::new();
+        map.retain(|&key, value| {
+            visited.push(key);
+            *value += 1;
+            key % modulus == 0
+        });
+        expected.retain(|&key, value| {
+            *value += 1;
+            key % modulus == 0
+        });
+        assert_eq!(visited.len(), len);
+        assert!(visited.windows(2).all(|pair| pair[0] < pair[1]));
+        map.validate().unwrap();
+        assert!(map.iter().eq(expected.iter()));
+    }
+
+    let mut set: TreeSet<i32> = (0..20).collect();
+    set.retain(|value| value % 3 == 0);
+    assert_eq!(
+        set.iter().copied().collect::<Vec<_>>(),
+        [0, 3, 6, 9, 12, 15, 18]
+    );
+    set.validate().unwrap();
+}
+
+#[test]
+fn paginate_from_a_key() {
+    use std::ops::Bound;
+
+    let map: TreeMap<u32, u32> = (0..1000).map(|key| (key * 3, key)).collect();
+    assert_eq!(map.iter_from(&301).next(), Some((&303, &101)));
+    assert_eq!(map.iter_from(&303).next(), Some((&303, &101)));
+    assert_eq!(map.iter_from(&3000).next(), None);
+    assert_eq!(map.iter_from(&0).count(), 1000);
+
+    let mut pages = Vec::new();
+    let mut last = None;
+    loop {
+        let page: Vec<_> = match last {
+            None => map.iter().take(64).map(|(&key, _)| key).collect(),
+            Some(last) => map
+                .range((Bound::Excluded(&last), Bound::Unbounded))
+                .take(64)
+                .map(|(&key, _)| key)
+                .collect(),
+        };
+        match page.last() {
+            Some(&key) => last = Some(key),
+            None => break,
+        }
+        pages.push(page);
+    }
+    assert_eq!(pages.len(), 16);
+    assert!(pages.concat().into_iter().eq(map.keys().copied()));
+
+    let set: TreeSet<String> = ["pear", "apple", "fig", "kiwi"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let rest: Vec<_> = set.iter_from("b").map(String::as_str).collect();
+    assert_eq!(rest, ["fig", "kiwi", "pear"]);
+}
+
+#[test]
+fn multiset_counts_duplicates() {
+    let mut bag: TreeMultiSet<&str> = ["b", "a", "b", "c", "b"].iter().copied().collect();
+    assert_eq!(bag.len(), 5);
+    assert_eq!(bag.distinct_len(), 3);
+    assert_eq!(bag.count("b"), 3);
+    assert_eq!(bag.count("z"), 0);
+    assert_eq!(
+        bag.iter().copied().collect::<Vec<_>>(),
+        ["a", "b", "b", "b", "c"]
+    );
+    assert_eq!(bag.iter().len(), 5);
+    assert_eq!(format!("{:?}", bag), r#"{"a", "b", "b", "b", "c"}"#);
+
+    assert!(bag.remove_one("b"));
+    assert_eq!(bag.count("b"), 2);
+    assert!(bag.remove_one("a"));
+    assert!(!bag.remove_one("a"));
+    assert!(!bag.contains("a"));
+    bag.insert_many("c", 4);
+    assert_eq!(bag.remove_all("c"), 5);
+    assert_eq!(bag.remove_all("c"), 0);
+    assert_eq!(bag.counts().collect::<Vec<_>>(), [(&"b", &2)]);
+    assert_eq!((bag.first(), bag.last()), (Some(&"b"), Some(&"b")));
+    assert_eq!(bag.len(), 2);
+    bag.validate().unwrap();
+
+    let mut rng = SplitMix64::new(3);
+    let mut bag = TreeMultiSet::new();
+    let mut expected = BTreeMap::new();
+    for _ in 0..5000 {
+        let value = rng.next_u64() % 50;
+        if rng.next_u64().is_multiple_of(3) {
+            let had = expected.contains_key(&value);
+            assert_eq!(bag.remove_one(&value), had);
+            if had {
+                *expected.get_mut(&value).unwrap() -= 1;
+                expected.retain(|_, count| *count > 0);
+            }
+        } else {
+            bag.insert(value);
+            *expected.entry(value).or_insert(0) += 1;
+        }
+    }
+    bag.validate().unwrap();
+    assert!(bag.counts().eq(expected.iter()));
+}
+
+#[test]
+fn std_conversions_and_bulk_loading() {
+    let std: BTreeMap<u32, u32> = (0..1000).map(|key| (key * 3, key)).collect();
+    let map = TreeMap::from(std.clone());
+    map.validate().unwrap();
+    assert_eq!(map.len(), 1000);
+    assert_eq!(map.height(), 10);
+    assert!(map.iter().eq(std.iter()));
+    assert_eq!(BTreeMap::from(map), std);
+
+    for len in 0..40 {
+        let map = TreeMap::from_sorted_iter((0..len).map(|key| (key, ()))).unwrap();
+        map.validate().unwrap();
+        assert_eq!(map.len(), len);
+    }
+    assert!(matches!(
+        TreeMap::from_sorted_iter(vec![(1, 'a'), (1, 'b')]),
+        Err(Error::Unsorted)
+    ));
+
+    let reversed =
+        TreeMap::from_sorted_iter_with_comparator(vec![(3, 'c'), (1, 'a')], Reversed(Natural))
+            .unwrap();
+    reversed.validate().unwrap();
+    assert_eq!(
+        BTreeMap::from(reversed).into_iter().collect::<Vec<_>>(),
+        [(1, 'a'), (3, 'c')]
+    );
+
+    let std: BTreeSet<_> = ["a", "b", "c"].iter().copied().collect();
+    let set = TreeSet::from(std.clone());
+    set.validate().unwrap();
+    assert_eq!(BTreeSet::from(set), std);
+    assert!(matches!(
+        TreeSet::from_sorted_iter(vec![2, 1]),
+        Err(Error::Unsorted)
+    ));
+}
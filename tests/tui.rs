@@ -0,0 +1,110 @@
+#![cfg(feature = "tui")]
+
+use gray_tree::explorer::Explorer;
+use gray_tree::tree::Node;
+use gray_tree::tui::{TreeView, TreeViewState};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use ratatui::widgets::{Block, Borders, StatefulWidget};
+
+fn tree() -> Node<&'static str> {
+    let mut src = Node::new("src");
+    let mut bin = Node::new("bin");
+    bin.push_child(Node::new("main.rs"));
+    src.push_child(bin);
+    src.push_child(Node::new("lib.rs"));
+    let mut root = Node::new(".");
+    root.push_child(src);
+    root.push_child(Node::new("Cargo.toml"));
+    root
+}
+
+fn render(view: TreeView<'_>, state: &mut TreeViewState, area: Rect) -> (Buffer, Vec<String>) {
+    let mut buf = Buffer::empty(area);
+    view.render(area, &mut buf, state);
+    let rows = (0..area.height)
+        .map(|y| {
+            let row: String = (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect();
+            row.trim_end().to_string()
+        })
+        .collect();
+    (buf, rows)
+}
+
+#[test]
+fn renders_visible_lines() {
+    let mut state = TreeViewState::new(Explorer::n_ary(&tree()));
+    let area = Rect::new(0, 0, 20, 4);
+    let (buf, rows) = render(TreeView::new(), &mut state, area);
+    assert_eq!(rows, ["▾ .", "├── ▸ src", "└── Cargo.toml", ""]);
+    assert!(buf[(0, 0)].modifier.contains(Modifier::REVERSED));
+    assert!(!buf[(0, 1)].modifier.contains(Modifier::REVERSED));
+
+    state.explorer_mut().go_down();
+    state.explorer_mut().expand();
+    let (buf, rows) = render(TreeView::new(), &mut state, area);
+    assert_eq!(
+        rows,
+        ["▾ .", "├── ▾ src", "│   ├── ▸ bin", "│   └── lib.rs"]
+    );
+    assert!(buf[(19, 1)].modifier.contains(Modifier::REVERSED));
+
+    let block = Block::default().borders(Borders::ALL);
+    let (_, rows) = render(
+        TreeView::new().block(block),
+        &mut state,
+        Rect::new(0, 0, 12, 3),
+    );
+    assert_eq!(rows, ["┌──────────┐", "│├── ▾ src │", "└──────────┘"]);
+}
+
+#[test]
+fn scrolls_to_the_cursor() {
+    let mut state = TreeViewState::new(Explorer::n_ary(&tree()));
+    state.explorer_mut().expand_all();
+    let area = Rect::new(0, 0, 24, 2);
+    for _ in 0..5 {
+        state.explorer_mut().go_down();
+    }
+    let (_, rows) = render(TreeView::new(), &mut state, area);
+    assert_eq!(state.offset(), 4);
+    assert_eq!(rows, ["│   └── lib.rs", "└── Cargo.toml"]);
+
+    state.explorer_mut().go_parent();
+    let (_, rows) = render(TreeView::new(), &mut state, area);
+    assert_eq!(state.offset(), 0);
+    assert_eq!(rows, ["▾ .", "├── ▾ src"]);
+
+    // Collapsing leaves no blank lines at the bottom.
+    state.explorer_mut().collapse_all();
+    let (_, rows) = render(TreeView::new(), &mut state, Rect::new(0, 0, 24, 5));
+    assert_eq!(rows[..3], ["▾ .", "├── ▸ src", "└── Cargo.toml"]);
+}
+
+#[test]
+fn search_highlights_matches() {
+    let mut state = TreeViewState::new(Explorer::n_ary(&tree()));
+    assert!(state.search(".rs"));
+    assert_eq!(state.query(), ".rs");
+    assert_eq!(
+        state.explorer().label(state.explorer().cursor()),
+        Some("main.rs")
+    );
+    let area = Rect::new(0, 0, 24, 6);
+    let (buf, rows) = render(TreeView::new(), &mut state, area);
+    assert_eq!(rows[3], "│   │   └── main.rs");
+    let bold = |x, y| buf[(x, y)].modifier.contains(Modifier::BOLD);
+    assert!(!bold(15, 3) && bold(16, 3) && bold(18, 3));
+    assert!(state.search_next());
+    assert_eq!(
+        state.explorer().label(state.explorer().cursor()),
+        Some("lib.rs")
+    );
+    state.clear_search();
+    assert!(!state.search_next());
+    let (buf, _) = render(TreeView::new(), &mut state, area);
+    assert!(!buf[(18, 3)].modifier.contains(Modifier::BOLD));
+}
@@ -0,0 +1,62 @@
+use gray_tree::binary_tree::bst::BinarySearchTree;
+use gray_tree::heap::TreeHeap;
+use gray_tree::Error;
+use std::cell::Cell;
+use std::cmp::Ordering;
+
+#[test]
+fn valid_containers() {
+    let bst: BinarySearchTree<_> = (0..20).collect();
+    assert!(bst.validate().is_ok());
+    assert!(BinarySearchTree::<i32>::new().validate().is_ok());
+    let mut heap = TreeHeap::<i32>::max();
+    heap.extend(&[5, 1, 9, 3, 7, 2]);
+    assert!(heap.validate().is_ok());
+}
+
+thread_local! {
+    static FLIPPED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Key whose ordering is reversed on demand, breaking the containers.
+#[derive(Debug, PartialEq, Eq)]
+struct Fickle(i32);
+
+impl PartialOrd for Fickle {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fickle {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ord = self.0.cmp(&other.0);
+        if FLIPPED.with(Cell::get) {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+#[test]
+fn inconsistent_ordering_is_reported() {
+    let bst: BinarySearchTree<_> = (0..3).map(Fickle).collect();
+    let mut heap = TreeHeap::min();
+    heap.extend((0..3).map(Fickle));
+    FLIPPED.with(|flipped| flipped.set(true));
+    let bst = bst.validate();
+    let heap = heap.validate();
+    FLIPPED.with(|flipped| flipped.set(false));
+    match bst {
+        Err(Error::InvariantViolation { path, .. }) => assert_eq!(path, Vec::<usize>::new()),
+        other => panic!("unexpected {:?}", other),
+    }
+    match heap {
+        Err(Error::InvariantViolation { path, reason }) => {
+            assert_eq!(path, [0]);
+            assert_eq!(reason, "item belongs above its parent");
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+}
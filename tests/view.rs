@@ -0,0 +1,86 @@
+use gray_tree::binary_tree::arc::ArcNode;
+use gray_tree::binary_tree::arena::Arena;
+use gray_tree::binary_tree::flat::FlatTree;
+use gray_tree::binary_tree::rc::RcNode;
+use gray_tree::binary_tree::view::{self, TreeNode};
+use gray_tree::binary_tree::Order;
+use gray_tree::gen::{self, SplitMix64};
+use std::convert::TryFrom;
+
+/// Data of a tree in every traversal order, with its size and height.
+#[derive(Debug, PartialEq)]
+struct Traversals {
+    pre: Vec<usize>,
+    mid: Vec<usize>,
+    post: Vec<usize>,
+    level: Vec<(usize, usize)>,
+    size: usize,
+    height: usize,
+}
+
+fn traverse<N: TreeNode<Data = usize>>(root: N) -> Traversals {
+    let data = |node: N| node.with_data(|data| *data);
+    Traversals {
+        pre: view::pre_order(root.clone()).map(data).collect(),
+        mid: view::mid_order(root.clone()).map(data).collect(),
+        post: view::post_order(root.clone()).map(data).collect(),
+        level: view::level_order(root.clone())
+            .map(|(level, node)| (level, data(node)))
+            .collect(),
+        size: view::size(root.clone()),
+        height: view::height(root),
+    }
+}
+
+#[test]
+fn every_storage_traverses_alike() {
+    let mut rng = SplitMix64::new(366);
+    for len in 1..25 {
+        let node = gen::uniform(len, &mut rng).unwrap();
+        // The boxed tree's own iterators are the reference.
+        let subtrees = |order| -> Vec<usize> {
+            node.subtrees(order)
+                .map(|subtree| *subtree.data())
+                .collect()
+        };
+        let expected = Traversals {
+            pre: subtrees(Order::Pre),
+            mid: node.iter().copied().collect(),
+            post: subtrees(Order::Post),
+            level: node.level_order_iter().map(|(l, &d)| (l, d)).collect(),
+            size: len,
+            height: node.stats().height(),
+        };
+        assert_eq!(expected.mid, (0..len).collect::<Vec<_>>());
+        assert_eq!(traverse(&node), expected);
+
+        let arc = ArcNode::from(node.clone());
+        assert_eq!(traverse(&arc), expected);
+        let rc = RcNode::from(node.clone());
+        assert_eq!(traverse(rc.clone()), expected);
+        let mut arena = Arena::new();
+        let root = arena.insert_tree(node.clone());
+        assert_eq!(traverse((&arena, root)), expected);
+        let flat = FlatTree::try_from(node.clone()).unwrap();
+        assert_eq!(traverse((&flat, 0)), expected);
+
+        // The storage-specific helpers go through the same traversals.
+        let arena_pre: Vec<usize> = arena
+            .pre_order(root)
+            .map(|id| arena.get(id).copied().unwrap())
+            .collect();
+        assert_eq!(arena_pre, expected.pre);
+        assert_eq!(arc.len(), len);
+        let mut seen = Vec::new();
+        arc.map(|&data| seen.push(data));
+        assert_eq!(seen, expected.pre);
+        for copy in [arc.to_node(), rc.to_node(), view::map(&node, |&data| data)] {
+            assert_eq!(traverse(&copy), expected);
+        }
+        let mut orders = [Vec::new(), Vec::new(), Vec::new()];
+        flat.clone().pre_order_map(|data| orders[0].push(data));
+        flat.clone().mid_order_map(|data| orders[1].push(data));
+        flat.clone().post_order_map(|data| orders[2].push(data));
+        assert_eq!(orders, [expected.pre, expected.mid, expected.post]);
+    }
+}
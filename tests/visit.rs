@@ -0,0 +1,72 @@
+use gray_tree::tree::Node;
+use gray_tree::visit::TraversalControl;
+
+#[test]
+fn binary_visit_prunes_and_stops() {
+    let tree = gray_tree::tree! {1 => {2 => {4, 5}, 3 => {6, 7}}};
+    let mut seen = Vec::new();
+    let stopped = tree.visit(|node, depth| {
+        seen.push((*node.data(), depth));
+        match node.data() {
+            2 => TraversalControl::SkipSubtree,
+            6 => TraversalControl::Stop,
+            _ => TraversalControl::Continue,
+        }
+    });
+    assert_eq!(seen, [(1, 0), (2, 1), (3, 1), (6, 2)]);
+    assert_eq!(stopped.map(|node| *node.data()), Some(6));
+    assert!(tree.visit(|_, _| TraversalControl::Continue).is_none());
+}
+
+#[test]
+fn nary_visit_prunes_and_stops() {
+    let tree: Node<String> = Node::from_newick("((d,e)b,(f)c)a;").unwrap();
+    let mut seen = Vec::new();
+    let stopped = tree.visit(|node, depth| {
+        seen.push((node.data().clone(), depth));
+        match node.data().as_str() {
+            "b" => TraversalControl::SkipSubtree,
+            "f" => TraversalControl::Stop,
+            _ => TraversalControl::Continue,
+        }
+    });
+    let names: Vec<_> = seen
+        .iter()
+        .map(|(name, depth)| (name.as_str(), *depth))
+        .collect();
+    assert_eq!(names, [("a", 0), ("b", 1), ("c", 1), ("f", 2)]);
+    assert!(stopped.is_some());
+}
+
+#[test]
+fn find_returns_paths() {
+    let mut tree = gray_tree::tree! {1 => {2 => {4, 5}, 3 => {6, 7}}};
+    let (path, node) = tree.find(|&x| x > 4).unwrap();
+    assert_eq!(path, [0, 1]);
+    assert_eq!(*node.data(), 5);
+    assert_eq!(tree.position(|&x| x == 1), Some(vec![]));
+    assert_eq!(tree.position(|&x| x == 7), Some(vec![1, 1]));
+    assert!(tree.position(|&x| x == 8).is_none());
+    let found = tree.find_map(|&x| if x % 3 == 0 { Some(x * 10) } else { None });
+    assert_eq!(found, Some((vec![1], 30)));
+
+    *tree.get_mut(&path).unwrap().data_mut() = 50;
+    assert_eq!(tree.get(&[0, 1]).map(|node| *node.data()), Some(50));
+    assert!(tree.get(&[0, 2]).is_none());
+    assert!(tree.get(&[0, 0, 0]).is_none());
+}
+
+#[test]
+fn nary_find_returns_paths() {
+    let mut tree: Node<String> = Node::from_newick("((d,e)b,(f)c)a;").unwrap();
+    let path = tree.position(|name| name == "f").unwrap();
+    assert_eq!(path, [1, 0]);
+    let (path, node) = tree.find(|name| name == "e").unwrap();
+    assert_eq!((path.as_slice(), node.data().as_str()), (&[0, 1][..], "e"));
+    *tree.get_mut(&path).unwrap().data_mut() = "g".to_string();
+    assert_eq!(tree.to_newick(), "((d,g)b,(f)c)a");
+    assert_eq!(
+        tree.find_map(|name| name.strip_prefix('c').map(str::len)),
+        Some((vec![1], 0))
+    );
+}
@@ -0,0 +1,60 @@
+use gray_tree::gen::{Rng, SplitMix64};
+use gray_tree::wavelet::WaveletTree;
+use gray_tree::Error;
+
+#[test]
+fn queries_match_a_scan() {
+    let mut rng = SplitMix64::new(355);
+    for (len, sigma) in [(0, 4), (1, 1), (50, 2), (200, 16), (300, 1000)] {
+        let values: Vec<u64> = (0..len).map(|_| rng.next_u64() % sigma).collect();
+        let tree = WaveletTree::new(&values);
+        assert_eq!(tree.len(), len);
+        assert_eq!(tree.is_empty(), len == 0);
+        for (index, &value) in values.iter().enumerate() {
+            assert_eq!(tree.get(index), Some(value));
+        }
+        assert_eq!(tree.get(len), None);
+        for value in (0..sigma.min(20)).chain(Some(sigma + 7)) {
+            let positions: Vec<usize> = (0..len).filter(|&i| values[i] == value).collect();
+            for end in 0..=len {
+                let count = positions.iter().filter(|&&i| i < end).count();
+                assert_eq!(tree.rank(value, end).unwrap(), count);
+            }
+            for (k, &position) in positions.iter().enumerate() {
+                assert_eq!(tree.select(value, k), Some(position));
+            }
+            assert_eq!(tree.select(value, positions.len()), None);
+        }
+        for _ in 0..200 {
+            let a = (rng.next_u64() % (len as u64 + 1)) as usize;
+            let b = (rng.next_u64() % (len as u64 + 1)) as usize;
+            let range = a.min(b)..a.max(b);
+            let mut sorted = values[range.clone()].to_vec();
+            sorted.sort_unstable();
+            let k = (rng.next_u64() % (sorted.len() as u64 + 2)) as usize;
+            assert_eq!(
+                tree.quantile(range.clone(), k).unwrap(),
+                sorted.get(k).copied()
+            );
+            let x = rng.next_u64() % (sigma + 2);
+            let y = rng.next_u64() % (sigma + 2);
+            let bounds = x.min(y)..x.max(y);
+            let count = sorted.iter().filter(|v| bounds.contains(v)).count();
+            assert_eq!(tree.range_count(range, bounds).unwrap(), count);
+        }
+    }
+}
+
+#[test]
+#[allow(clippy::reversed_empty_ranges)]
+fn rejects_out_of_range() {
+    let tree = WaveletTree::new(&[3, 1, 4, 1, 5]);
+    assert!(matches!(
+        tree.rank(1, 6),
+        Err(Error::IndexOutOfRange { index: 6, .. })
+    ));
+    assert!(tree.quantile(2..6, 0).is_err());
+    assert!(tree.quantile(3..2, 0).is_err());
+    assert!(tree.range_count(0..9, 0..4).is_err());
+    assert_eq!(tree.rank(1, 5).unwrap(), 2);
+}